@@ -0,0 +1,8 @@
+-- Track Claude OAuth token health so rotation can skip exhausted or revoked tokens,
+-- and count usage per token so admins can see whose subscription is being used.
+ALTER TABLE claude_oauth_tokens ADD COLUMN cooldown_until TEXT;
+ALTER TABLE claude_oauth_tokens ADD COLUMN invalidated_at TEXT;
+ALTER TABLE claude_oauth_tokens ADD COLUMN invalid_reason TEXT;
+ALTER TABLE claude_oauth_tokens ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE claude_oauth_tokens ADD COLUMN rate_limited_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE claude_oauth_tokens ADD COLUMN last_rate_limited_at TEXT;
@@ -0,0 +1,9 @@
+-- Per-project task-status workflow definition (JSON). Projects without a row
+-- use the built-in default workflow.
+CREATE TABLE project_workflows (
+    project_id  BLOB PRIMARY KEY,
+    definition  TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,30 @@
+-- Token usage per execution process and model, as reported by the agent's log normalizer
+CREATE TABLE execution_process_token_usage (
+    id                    BLOB PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL,
+    model                 TEXT NOT NULL,
+    input_tokens          INTEGER NOT NULL DEFAULT 0,
+    output_tokens         INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens     INTEGER NOT NULL DEFAULT 0,
+    cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+    cost_usd              REAL,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    UNIQUE (execution_process_id, model)
+);
+
+CREATE INDEX idx_execution_process_token_usage_created_at
+    ON execution_process_token_usage(created_at);
+
+-- Optional per-project spending limits. New executions are refused once a limit is reached.
+CREATE TABLE project_budgets (
+    project_id    BLOB PRIMARY KEY,
+    max_tokens    INTEGER,
+    max_cost_usd  REAL,
+    period        TEXT NOT NULL DEFAULT 'total'
+                  CHECK (period IN ('total', 'monthly')),
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
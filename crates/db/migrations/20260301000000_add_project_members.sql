@@ -0,0 +1,33 @@
+-- Server admins bypass project role checks and manage memberships. The earliest
+-- user becomes the first admin; on a fresh install the first user to sign in does.
+ALTER TABLE users ADD COLUMN is_admin INTEGER NOT NULL DEFAULT 0;
+
+UPDATE users SET is_admin = 1
+WHERE id = (SELECT id FROM users ORDER BY created_at ASC LIMIT 1);
+
+-- Per-project roles, ordered viewer < contributor < maintainer < owner
+CREATE TABLE project_members (
+    project_id  BLOB NOT NULL,
+    user_id     BLOB NOT NULL,
+    role        TEXT NOT NULL DEFAULT 'contributor'
+                   CHECK (role IN ('owner', 'maintainer', 'contributor', 'viewer')),
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, user_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_members_user_id ON project_members(user_id);
+
+-- Project creators own their projects
+INSERT INTO project_members (project_id, user_id, role)
+SELECT p.id, p.creator_user_id, 'owner'
+FROM projects p
+JOIN users u ON u.id = p.creator_user_id;
+
+-- Everyone else keeps day-to-day access to existing projects
+INSERT OR IGNORE INTO project_members (project_id, user_id, role)
+SELECT p.id, u.id, 'contributor'
+FROM projects p
+CROSS JOIN users u;
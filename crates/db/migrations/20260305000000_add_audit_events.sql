@@ -0,0 +1,33 @@
+-- Append-only record of who did what. Ids are kept without foreign keys so that
+-- events outlive the users, projects, tasks and workspaces they describe.
+CREATE TABLE audit_events (
+    id             BLOB PRIMARY KEY,
+    actor_user_id  BLOB,
+    source         TEXT NOT NULL DEFAULT 'api'
+                      CHECK (source IN ('api', 'mcp')),
+    action         TEXT NOT NULL,
+    project_id     BLOB,
+    task_id        BLOB,
+    workspace_id   BLOB,
+    target_id      BLOB,
+    details        TEXT NOT NULL DEFAULT '{}',
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_audit_events_created_at ON audit_events(created_at);
+CREATE INDEX idx_audit_events_actor_user_id ON audit_events(actor_user_id);
+CREATE INDEX idx_audit_events_project_id ON audit_events(project_id);
+CREATE INDEX idx_audit_events_task_id ON audit_events(task_id);
+CREATE INDEX idx_audit_events_workspace_id ON audit_events(workspace_id);
+
+CREATE TRIGGER audit_events_no_update
+BEFORE UPDATE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
+
+CREATE TRIGGER audit_events_no_delete
+BEFORE DELETE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
@@ -0,0 +1,18 @@
+-- Rules that answer agent tool approval requests automatically (JSON). A policy is
+-- scoped to a project, an executor (optionally one of its variants), or both; unset
+-- columns match anything and the most specific policy applies.
+CREATE TABLE approval_policies (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB,
+    executor    TEXT,
+    variant     TEXT,
+    definition  TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    CHECK (variant IS NULL OR executor IS NOT NULL)
+);
+
+CREATE UNIQUE INDEX idx_approval_policies_scope ON approval_policies(
+    ifnull(project_id, x''), ifnull(executor, ''), ifnull(variant, '')
+);
@@ -0,0 +1,24 @@
+-- Every tool approval an agent asked for and how it was answered: by a user, by an
+-- approval policy rule, by timing out, or left unanswered when the server stopped.
+CREATE TABLE tool_approvals (
+    id                   TEXT PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    tool_name            TEXT NOT NULL,
+    tool_call_id         TEXT NOT NULL,
+    tool_input           TEXT NOT NULL DEFAULT '{}',
+    status               TEXT NOT NULL DEFAULT 'pending'
+                         CHECK (status IN ('pending', 'approved', 'denied', 'timed_out', 'expired')),
+    reason               TEXT,
+    responded_by_user_id BLOB,
+    policy_rule          TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    timeout_at           TEXT NOT NULL,
+    responded_at         TEXT,
+    latency_ms           INTEGER,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (responded_by_user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_tool_approvals_execution_process_id ON tool_approvals(execution_process_id);
+CREATE INDEX idx_tool_approvals_responded_by_user_id ON tool_approvals(responded_by_user_id);
+CREATE INDEX idx_tool_approvals_status ON tool_approvals(status);
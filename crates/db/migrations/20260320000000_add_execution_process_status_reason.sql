@@ -0,0 +1,3 @@
+-- Why an execution ended the way it did, when the exit code alone doesn't say, e.g.
+-- the process sandbox stopped it for exceeding a resource limit.
+ALTER TABLE execution_processes ADD COLUMN status_reason TEXT;
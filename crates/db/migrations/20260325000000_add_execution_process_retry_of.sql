@@ -0,0 +1,5 @@
+-- Links an automatic retry of a failed coding agent run to the run it retries, so
+-- every attempt of a request can be followed within its session.
+ALTER TABLE execution_processes
+    ADD COLUMN retry_of_execution_process_id BLOB
+    REFERENCES execution_processes(id) ON DELETE SET NULL;
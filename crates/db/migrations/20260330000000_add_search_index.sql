@@ -0,0 +1,105 @@
+-- Full-text search over tasks, agent conversations and review comments. The FTS5
+-- tables index content stored elsewhere and are kept in sync by triggers.
+CREATE VIRTUAL TABLE tasks_fts USING fts5(
+    title,
+    description,
+    content = 'tasks',
+    content_rowid = 'rowid',
+    tokenize = 'porter unicode61'
+);
+
+INSERT INTO tasks_fts (rowid, title, description)
+SELECT rowid, title, description FROM tasks;
+
+CREATE TRIGGER tasks_fts_insert
+AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO tasks_fts (rowid, title, description)
+    VALUES (NEW.rowid, NEW.title, NEW.description);
+END;
+
+CREATE TRIGGER tasks_fts_delete
+AFTER DELETE ON tasks
+BEGIN
+    INSERT INTO tasks_fts (tasks_fts, rowid, title, description)
+    VALUES ('delete', OLD.rowid, OLD.title, OLD.description);
+END;
+
+CREATE TRIGGER tasks_fts_update
+AFTER UPDATE OF title, description ON tasks
+BEGIN
+    INSERT INTO tasks_fts (tasks_fts, rowid, title, description)
+    VALUES ('delete', OLD.rowid, OLD.title, OLD.description);
+    INSERT INTO tasks_fts (rowid, title, description)
+    VALUES (NEW.rowid, NEW.title, NEW.description);
+END;
+
+CREATE VIRTUAL TABLE review_messages_fts USING fts5(
+    content,
+    content = 'review_conversation_messages',
+    content_rowid = 'rowid',
+    tokenize = 'porter unicode61'
+);
+
+INSERT INTO review_messages_fts (rowid, content)
+SELECT rowid, content FROM review_conversation_messages;
+
+CREATE TRIGGER review_messages_fts_insert
+AFTER INSERT ON review_conversation_messages
+BEGIN
+    INSERT INTO review_messages_fts (rowid, content) VALUES (NEW.rowid, NEW.content);
+END;
+
+CREATE TRIGGER review_messages_fts_delete
+AFTER DELETE ON review_conversation_messages
+BEGIN
+    INSERT INTO review_messages_fts (review_messages_fts, rowid, content)
+    VALUES ('delete', OLD.rowid, OLD.content);
+END;
+
+CREATE TRIGGER review_messages_fts_update
+AFTER UPDATE OF content ON review_conversation_messages
+BEGIN
+    INSERT INTO review_messages_fts (review_messages_fts, rowid, content)
+    VALUES ('delete', OLD.rowid, OLD.content);
+    INSERT INTO review_messages_fts (rowid, content) VALUES (NEW.rowid, NEW.content);
+END;
+
+-- Normalized conversation entries are only ever held in memory, so the searchable
+-- text of each one is written here as a coding agent run produces it.
+CREATE TABLE search_conversation_entries (
+    id                    INTEGER PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    entry_index           INTEGER NOT NULL,
+    entry_type            TEXT NOT NULL,
+    content               TEXT NOT NULL,
+    UNIQUE (execution_process_id, entry_index)
+);
+
+CREATE VIRTUAL TABLE search_conversation_entries_fts USING fts5(
+    content,
+    content = 'search_conversation_entries',
+    content_rowid = 'id',
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER search_conversation_entries_fts_insert
+AFTER INSERT ON search_conversation_entries
+BEGIN
+    INSERT INTO search_conversation_entries_fts (rowid, content) VALUES (NEW.id, NEW.content);
+END;
+
+CREATE TRIGGER search_conversation_entries_fts_delete
+AFTER DELETE ON search_conversation_entries
+BEGIN
+    INSERT INTO search_conversation_entries_fts (search_conversation_entries_fts, rowid, content)
+    VALUES ('delete', OLD.id, OLD.content);
+END;
+
+CREATE TRIGGER search_conversation_entries_fts_update
+AFTER UPDATE OF content ON search_conversation_entries
+BEGIN
+    INSERT INTO search_conversation_entries_fts (search_conversation_entries_fts, rowid, content)
+    VALUES ('delete', OLD.id, OLD.content);
+    INSERT INTO search_conversation_entries_fts (rowid, content) VALUES (NEW.id, NEW.content);
+END;
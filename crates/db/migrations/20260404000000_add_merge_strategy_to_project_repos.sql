@@ -0,0 +1,5 @@
+-- Default strategy for merging a task branch into its target branch, per repository
+-- in a project. A merge request can still pick a different one.
+ALTER TABLE project_repos
+    ADD COLUMN merge_strategy TEXT NOT NULL DEFAULT 'squash'
+    CHECK (merge_strategy IN ('squash', 'merge_commit', 'rebase', 'fast_forward'));
@@ -0,0 +1,47 @@
+-- Repos can configure a script that must pass against the merged result of a
+-- workspace before it is merged or a pull request is opened for it.
+ALTER TABLE repos ADD COLUMN verify_script TEXT;
+
+-- Widen the run_reason CHECK with 'verifyscript', swapping in a new column
+ALTER TABLE execution_processes
+  ADD COLUMN run_reason_new TEXT NOT NULL DEFAULT 'setupscript'
+    CHECK (run_reason_new IN ('setupscript','codingagent','devserver','cleanupscript','verifyscript'));
+
+UPDATE execution_processes
+  SET run_reason_new = run_reason;
+
+DROP INDEX IF EXISTS idx_execution_processes_run_reason;
+DROP INDEX IF EXISTS idx_execution_processes_session_status_run_reason;
+DROP INDEX IF EXISTS idx_execution_processes_session_run_reason_created;
+
+ALTER TABLE execution_processes DROP COLUMN run_reason;
+
+ALTER TABLE execution_processes
+  RENAME COLUMN run_reason_new TO run_reason;
+
+CREATE INDEX idx_execution_processes_run_reason ON execution_processes(run_reason);
+
+CREATE INDEX idx_execution_processes_session_status_run_reason
+ON execution_processes (session_id, status, run_reason);
+
+CREATE INDEX idx_execution_processes_session_run_reason_created
+ON execution_processes (session_id, run_reason, created_at DESC);
+
+-- One row per verification run: the commits it merged and the temporary worktree
+-- holding the merge while the script runs
+CREATE TABLE merge_verifications (
+    execution_process_id  BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    task_commit           TEXT NOT NULL,
+    target_branch         TEXT NOT NULL,
+    target_commit         TEXT NOT NULL,
+    worktree_path         TEXT NOT NULL,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_merge_verifications_workspace_repo
+ON merge_verifications (workspace_id, repo_id, created_at DESC);
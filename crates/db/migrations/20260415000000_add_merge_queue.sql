@@ -0,0 +1,25 @@
+-- Workspaces waiting to be rebased, verified and merged into their target branch.
+-- Each repo and target branch has its own queue, worked through in position order;
+-- enqueueing takes the next position, so an entry that is queued again goes last.
+CREATE TABLE merge_queue_entries (
+    id                BLOB PRIMARY KEY,
+    workspace_id      BLOB NOT NULL,
+    repo_id           BLOB NOT NULL,
+    target_branch     TEXT NOT NULL,
+    merge_strategy    TEXT CHECK (merge_strategy IN ('squash', 'merge_commit', 'rebase', 'fast_forward')),
+    status            TEXT NOT NULL DEFAULT 'queued'
+                         CHECK (status IN ('queued', 'verifying', 'conflicted', 'failed')),
+    error_message     TEXT,
+    -- The agent run resolving a rebase conflict; the entry is queued again once it ends
+    resolution_execution_process_id BLOB,
+    position          INTEGER NOT NULL,
+    enqueued_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (resolution_execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL,
+    UNIQUE (workspace_id, repo_id)
+);
+
+CREATE INDEX idx_merge_queue_entries_order
+ON merge_queue_entries (repo_id, target_branch, position);
@@ -0,0 +1,21 @@
+-- Agent runs resolving the conflicts of a rebase, merge, cherry-pick or revert stopped
+-- in a workspace repo. When the run ends the operation is continued or rolled back,
+-- and the outcome recorded here.
+CREATE TABLE conflict_resolutions (
+    execution_process_id  BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    -- JSON array of the paths that were in conflict when the run started
+    conflicted_files      TEXT NOT NULL,
+    status                TEXT NOT NULL DEFAULT 'running'
+                             CHECK (status IN ('running', 'resolved', 'conflicted', 'rolled_back')),
+    error_message         TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_conflict_resolutions_workspace_repo
+ON conflict_resolutions (workspace_id, repo_id, created_at DESC);
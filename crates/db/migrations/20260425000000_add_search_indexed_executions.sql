@@ -0,0 +1,8 @@
+-- Coding agent runs whose whole conversation is in search_conversation_entries. Runs
+-- missing from here, such as those from before search existed or cut short by a
+-- restart, are indexed from their stored logs at startup.
+CREATE TABLE search_indexed_executions (
+    execution_process_id  BLOB PRIMARY KEY,
+    indexed_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- The worktree's HEAD when the run started, while the operation was stopped on
+-- conflicts. A finished operation leaves HEAD on top of it; an aborted one does not.
+ALTER TABLE conflict_resolutions ADD COLUMN start_head TEXT;
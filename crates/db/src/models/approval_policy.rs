@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+const APPROVAL_POLICY_COLUMNS: &str =
+    "id, project_id, executor, variant, definition, created_at, updated_at";
+
+#[derive(Debug, Error)]
+pub enum ApprovalPolicyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error("Invalid approval policy: {0}")]
+    Invalid(String),
+}
+
+/// What happens to a tool call a rule matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ApprovalDecision {
+    /// Run the tool without asking
+    Allow,
+    /// Refuse the tool call without asking
+    Deny,
+    /// Ask a human, as if there were no policy
+    #[default]
+    Ask,
+}
+
+/// Matches tool calls by name and input. Unset matchers match anything.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ApprovalRule {
+    /// Shown in the conversation next to each decision the rule makes
+    pub name: String,
+    pub decision: ApprovalDecision,
+    /// Regex the whole tool name must match, case-insensitively (e.g. `bash|shell`)
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    /// JSON pointer into the tool input that `input_pattern` is matched against
+    /// (e.g. `/command`). When unset, every string in the input is searched.
+    #[serde(default)]
+    pub input_field: Option<String>,
+    /// Regex searched for in the tool input (e.g. `rm\s+-rf` or `\.env\b`). Allow rules
+    /// must match a whole value instead (e.g. `cargo test( [\w-]+)*`), and never approve
+    /// one containing shell operators such as `;`, `|` or `$(`.
+    #[serde(default)]
+    pub input_pattern: Option<String>,
+    /// Reason given to the agent when the rule denies a call
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Auto-approval rules. Deny rules are evaluated first, then the rest in order; the
+/// first match wins and `default_decision` applies when none matches.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ApprovalPolicyDefinition {
+    pub rules: Vec<ApprovalRule>,
+    #[serde(default)]
+    pub default_decision: ApprovalDecision,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ApprovalPolicyRow {
+    id: Uuid,
+    project_id: Option<Uuid>,
+    executor: Option<String>,
+    variant: Option<String>,
+    definition: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// A stored policy and the executions it applies to. `None` scope fields match
+/// any project, executor or variant.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ApprovalPolicy {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>,
+    /// Executor the policy applies to (e.g. `CLAUDE_CODE`)
+    pub executor: Option<String>,
+    /// Executor profile variant (e.g. `PLAN`); requires `executor`
+    pub variant: Option<String>,
+    pub definition: ApprovalPolicyDefinition,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<ApprovalPolicyRow> for ApprovalPolicy {
+    type Error = ApprovalPolicyError;
+    fn try_from(r: ApprovalPolicyRow) -> Result<Self, ApprovalPolicyError> {
+        Ok(ApprovalPolicy {
+            id: r.id,
+            project_id: r.project_id,
+            executor: r.executor,
+            variant: r.variant,
+            definition: serde_json::from_str(&r.definition)?,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        })
+    }
+}
+
+/// Store a policy for a scope, replacing the one already stored for it
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertApprovalPolicy {
+    pub project_id: Option<Uuid>,
+    pub executor: Option<String>,
+    pub variant: Option<String>,
+    pub definition: ApprovalPolicyDefinition,
+}
+
+impl ApprovalPolicy {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, ApprovalPolicyError> {
+        let rows = sqlx::query_as::<_, ApprovalPolicyRow>(&format!(
+            "SELECT {APPROVAL_POLICY_COLUMNS} FROM approval_policies
+             ORDER BY project_id IS NOT NULL, executor IS NOT NULL, variant IS NOT NULL,
+                      created_at ASC"
+        ))
+        .fetch_all(pool)
+        .await?;
+        rows.into_iter().map(ApprovalPolicy::try_from).collect()
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<Self>, ApprovalPolicyError> {
+        let row = sqlx::query_as::<_, ApprovalPolicyRow>(&format!(
+            "SELECT {APPROVAL_POLICY_COLUMNS} FROM approval_policies WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+        row.map(ApprovalPolicy::try_from).transpose()
+    }
+
+    /// The most specific policy covering an execution of `executor`/`variant` in
+    /// `project_id`. A project match outranks an executor match, and a variant match
+    /// outranks an executor-wide one.
+    pub async fn find_for_execution(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        executor: &str,
+        variant: Option<&str>,
+    ) -> Result<Option<Self>, ApprovalPolicyError> {
+        let row = sqlx::query_as::<_, ApprovalPolicyRow>(&format!(
+            "SELECT {APPROVAL_POLICY_COLUMNS} FROM approval_policies
+             WHERE (project_id IS NULL OR project_id = $1)
+               AND (executor IS NULL OR executor = $2)
+               AND (variant IS NULL OR variant = $3)
+             ORDER BY project_id IS NOT NULL DESC, executor IS NOT NULL DESC,
+                      variant IS NOT NULL DESC
+             LIMIT 1"
+        ))
+        .bind(project_id)
+        .bind(executor)
+        .bind(variant)
+        .fetch_optional(pool)
+        .await?;
+        row.map(ApprovalPolicy::try_from).transpose()
+    }
+
+    /// Store (or replace) the policy for the data's scope
+    pub async fn upsert(
+        pool: &SqlitePool,
+        data: &UpsertApprovalPolicy,
+    ) -> Result<Self, ApprovalPolicyError> {
+        if data.variant.is_some() && data.executor.is_none() {
+            return Err(ApprovalPolicyError::Invalid(
+                "a variant can only be set together with an executor".to_string(),
+            ));
+        }
+        let json = serde_json::to_string(&data.definition)?;
+
+        let row = sqlx::query_as::<_, ApprovalPolicyRow>(&format!(
+            "UPDATE approval_policies
+             SET definition = $4, updated_at = datetime('now', 'subsec')
+             WHERE project_id IS $1 AND executor IS $2 AND variant IS $3
+             RETURNING {APPROVAL_POLICY_COLUMNS}"
+        ))
+        .bind(data.project_id)
+        .bind(&data.executor)
+        .bind(&data.variant)
+        .bind(&json)
+        .fetch_optional(pool)
+        .await?;
+        if let Some(row) = row {
+            return ApprovalPolicy::try_from(row);
+        }
+
+        let row = sqlx::query_as::<_, ApprovalPolicyRow>(&format!(
+            "INSERT INTO approval_policies (id, project_id, executor, variant, definition)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING {APPROVAL_POLICY_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(data.project_id)
+        .bind(&data.executor)
+        .bind(&data.variant)
+        .bind(&json)
+        .fetch_one(pool)
+        .await?;
+        ApprovalPolicy::try_from(row)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM approval_policies WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
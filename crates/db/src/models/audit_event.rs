@@ -0,0 +1,295 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+const AUDIT_EVENT_COLUMNS: &str = "id, actor_user_id, source, action, project_id, task_id, \
+     workspace_id, target_id, details, created_at";
+
+/// Largest page `AuditEvent::list` returns
+pub const MAX_AUDIT_PAGE_SIZE: i64 = 500;
+
+/// How the actor says it reached the server. The caller reports this itself and it is
+/// not verified, so it labels an event rather than proving where it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "audit_source", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum AuditSource {
+    /// The web UI or another HTTP client
+    Api,
+    /// The caller identified itself as the MCP task server
+    Mcp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "audit_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum AuditAction {
+    ProjectCreated,
+    ProjectUpdated,
+    ProjectDeleted,
+    ProjectRepositoryAdded,
+    ProjectRepositoryRemoved,
+    ProjectRepositoryUpdated,
+    ProjectWorkflowUpdated,
+    ProjectWorkflowReset,
+    ProjectBudgetUpdated,
+    ProjectBudgetDeleted,
+    ProjectExported,
+    ProjectImported,
+    TaskCreated,
+    TaskUpdated,
+    TaskDeleted,
+    TaskHoldPlaced,
+    TaskHoldReleased,
+    TaskApproved,
+    TaskUnapproved,
+    WorkspaceCreated,
+    WorkspaceUpdated,
+    WorkspaceDeleted,
+    WorkspaceMerged,
+    MergeQueued,
+    WorkspaceRebased,
+    BranchPushed,
+    BranchForcePushed,
+    BranchRenamed,
+    TargetBranchChanged,
+    ExecutionStopped,
+    ClaudeTokenUpserted,
+    ClaudeTokenDeleted,
+    ClaudeTokenKeyRotated,
+    ApprovalPolicyUpdated,
+    ApprovalPolicyDeleted,
+}
+
+/// One recorded action. `details` holds action-specific context; for updates it is a
+/// diff of the changed fields in the shape `{ "field": { "from": .., "to": .. } }`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    /// `null` on single-user servers and for actions taken by the server itself
+    pub actor_user_id: Option<Uuid>,
+    /// Self-reported by the caller and not verified
+    pub source: AuditSource,
+    pub action: AuditAction,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    /// The affected object when it is not the project, task or workspace itself,
+    /// e.g. a repository or the user whose hold was released
+    pub target_id: Option<Uuid>,
+    #[sqlx(json)]
+    pub details: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What an event is about. Unset ids are stored as `NULL`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AuditTarget {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+}
+
+impl AuditTarget {
+    pub fn project(project_id: Uuid) -> Self {
+        Self {
+            project_id: Some(project_id),
+            ..Default::default()
+        }
+    }
+
+    pub fn task(project_id: Uuid, task_id: Uuid) -> Self {
+        Self {
+            task_id: Some(task_id),
+            ..Self::project(project_id)
+        }
+    }
+
+    pub fn workspace(project_id: Uuid, task_id: Uuid, workspace_id: Uuid) -> Self {
+        Self {
+            workspace_id: Some(workspace_id),
+            ..Self::task(project_id, task_id)
+        }
+    }
+
+    pub fn with_target(self, target_id: Uuid) -> Self {
+        Self {
+            target_id: Some(target_id),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAuditEvent {
+    pub actor_user_id: Option<Uuid>,
+    pub source: AuditSource,
+    pub action: AuditAction,
+    pub target: AuditTarget,
+    pub details: Value,
+}
+
+/// Restricts which events are listed or exported. Unset fields do not filter.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditFilter {
+    pub actor_user_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub source: Option<AuditSource>,
+    /// Only events recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only events recorded before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// One page of events, newest first
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AuditEventPage {
+    pub events: Vec<AuditEvent>,
+    /// Number of events matching the filter across all pages
+    #[ts(type = "number")]
+    pub total: i64,
+    #[ts(type = "number")]
+    pub limit: i64,
+    #[ts(type = "number")]
+    pub offset: i64,
+}
+
+const AUDIT_FILTER: &str = "WHERE ($1 IS NULL OR actor_user_id = $1)
+       AND ($2 IS NULL OR project_id = $2)
+       AND ($3 IS NULL OR task_id = $3)
+       AND ($4 IS NULL OR workspace_id = $4)
+       AND ($5 IS NULL OR action = $5)
+       AND ($6 IS NULL OR source = $6)
+       AND ($7 IS NULL OR created_at >= datetime($7))
+       AND ($8 IS NULL OR created_at < datetime($8))";
+
+impl AuditEvent {
+    pub async fn create(pool: &SqlitePool, data: CreateAuditEvent) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, AuditEvent>(&format!(
+            "INSERT INTO audit_events (id, actor_user_id, source, action, project_id, task_id,
+                                       workspace_id, target_id, details)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             RETURNING {AUDIT_EVENT_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(data.actor_user_id)
+        .bind(data.source)
+        .bind(data.action)
+        .bind(data.target.project_id)
+        .bind(data.target.task_id)
+        .bind(data.target.workspace_id)
+        .bind(data.target.target_id)
+        .bind(sqlx::types::Json(&data.details))
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Events matching `filter`, newest first. `limit` is clamped to
+    /// `1..=MAX_AUDIT_PAGE_SIZE`.
+    pub async fn list(
+        pool: &SqlitePool,
+        filter: &AuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<AuditEventPage, sqlx::Error> {
+        let limit = limit.clamp(1, MAX_AUDIT_PAGE_SIZE);
+        let offset = offset.max(0);
+
+        let (total,) = Self::bind_filter(
+            sqlx::query_as::<_, (i64,)>(&format!(
+                "SELECT COUNT(*) FROM audit_events {AUDIT_FILTER}"
+            )),
+            filter,
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let events = Self::bind_filter(
+            sqlx::query_as::<_, AuditEvent>(&format!(
+                "SELECT {AUDIT_EVENT_COLUMNS} FROM audit_events {AUDIT_FILTER}
+                 ORDER BY created_at DESC, rowid DESC
+                 LIMIT $9 OFFSET $10"
+            )),
+            filter,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(AuditEventPage {
+            events,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Every event matching `filter`, oldest first, for exports
+    pub async fn find_all(
+        pool: &SqlitePool,
+        filter: &AuditFilter,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        Self::bind_filter(
+            sqlx::query_as::<_, AuditEvent>(&format!(
+                "SELECT {AUDIT_EVENT_COLUMNS} FROM audit_events {AUDIT_FILTER}
+                 ORDER BY created_at ASC, rowid ASC"
+            )),
+            filter,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    fn bind_filter<'q, O>(
+        query: sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+        filter: &AuditFilter,
+    ) -> sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>> {
+        query
+            .bind(filter.actor_user_id)
+            .bind(filter.project_id)
+            .bind(filter.task_id)
+            .bind(filter.workspace_id)
+            .bind(filter.action)
+            .bind(filter.source)
+            .bind(filter.since)
+            .bind(filter.until)
+    }
+
+    /// The fields that differ between two serialized versions of an object, as
+    /// `{ "field": { "from": .., "to": .. } }`. `updated_at` is left out since it changes
+    /// on every write. Objects that do not serialize to JSON objects are recorded whole.
+    pub fn diff<T: Serialize>(before: &T, after: &T) -> Value {
+        let before = serde_json::to_value(before).unwrap_or(Value::Null);
+        let after = serde_json::to_value(after).unwrap_or(Value::Null);
+        let (before, after) = match (before, after) {
+            (Value::Object(before), Value::Object(after)) => (before, after),
+            (before, after) => return serde_json::json!({ "from": before, "to": after }),
+        };
+
+        let mut changes = Map::new();
+        for (key, to) in after.iter().filter(|(key, _)| *key != "updated_at") {
+            let from = before.get(key).unwrap_or(&Value::Null);
+            if from != to {
+                changes.insert(key.clone(), serde_json::json!({ "from": from, "to": to }));
+            }
+        }
+        for (key, from) in &before {
+            if key != "updated_at" && !after.contains_key(key) {
+                changes.insert(key.clone(), serde_json::json!({ "from": from, "to": null }));
+            }
+        }
+        Value::Object(changes)
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -23,12 +23,17 @@ pub struct ClaudeOAuthToken {
     #[serde(skip_serializing)] // Never expose encrypted token to frontend
     pub encrypted_token: String,
     pub token_hint: Option<String>,
-    #[sqlx(rename = "created_at")]
     pub created_at: DateTime<Utc>,
-    #[sqlx(rename = "expires_at")]
     pub expires_at: Option<DateTime<Utc>>,
-    #[sqlx(rename = "last_used_at")]
     pub last_used_at: Option<DateTime<Utc>>,
+    /// Set when the subscription hit its usage limit; skipped by rotation until then
+    pub cooldown_until: Option<DateTime<Utc>>,
+    /// Set when Claude rejected the token; skipped by rotation until replaced
+    pub invalidated_at: Option<DateTime<Utc>>,
+    pub invalid_reason: Option<String>,
+    pub use_count: i64,
+    pub rate_limited_count: i64,
+    pub last_rate_limited_at: Option<DateTime<Utc>>,
 }
 
 /// Token status for frontend display (no sensitive data)
@@ -44,6 +49,19 @@ pub struct ClaudeOAuthTokenStatus {
     #[ts(type = "Date | null")]
     pub last_used_at: Option<DateTime<Utc>>,
     pub is_expired: bool,
+    /// When the token becomes usable again after hitting a usage limit
+    #[ts(type = "Date | null")]
+    pub cooldown_until: Option<DateTime<Utc>>,
+    pub is_invalid: bool,
+    pub invalid_reason: Option<String>,
+    /// Number of executions the token has been handed out for
+    #[ts(type = "number")]
+    pub use_count: i64,
+    /// Number of times the token hit a usage limit
+    #[ts(type = "number")]
+    pub rate_limited_count: i64,
+    #[ts(type = "Date | null")]
+    pub last_rate_limited_at: Option<DateTime<Utc>>,
 }
 
 /// Combined user and token status for admin view
@@ -71,7 +89,13 @@ impl ClaudeOAuthToken {
                       token_hint,
                       created_at as "created_at!: DateTime<Utc>",
                       expires_at as "expires_at: DateTime<Utc>",
-                      last_used_at as "last_used_at: DateTime<Utc>"
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      cooldown_until as "cooldown_until: DateTime<Utc>",
+                      invalidated_at as "invalidated_at: DateTime<Utc>",
+                      invalid_reason,
+                      use_count,
+                      rate_limited_count,
+                      last_rate_limited_at as "last_rate_limited_at: DateTime<Utc>"
                FROM claude_oauth_tokens
                WHERE user_id = $1"#,
             user_id
@@ -80,7 +104,33 @@ impl ClaudeOAuthToken {
         .await
     }
 
-    /// Create or update token for user
+    /// Find token by ID
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ClaudeOAuthToken,
+            r#"SELECT id as "id!: Uuid",
+                      user_id as "user_id!: Uuid",
+                      encrypted_token,
+                      token_hint,
+                      created_at as "created_at!: DateTime<Utc>",
+                      expires_at as "expires_at: DateTime<Utc>",
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      cooldown_until as "cooldown_until: DateTime<Utc>",
+                      invalidated_at as "invalidated_at: DateTime<Utc>",
+                      invalid_reason,
+                      use_count,
+                      rate_limited_count,
+                      last_rate_limited_at as "last_rate_limited_at: DateTime<Utc>"
+               FROM claude_oauth_tokens
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Create or update token for user.
+    /// Replacing a token clears its cooldown and invalid state but keeps usage counters.
     pub async fn upsert(
         pool: &SqlitePool,
         user_id: Uuid,
@@ -99,14 +149,23 @@ impl ClaudeOAuthToken {
                    token_hint = excluded.token_hint,
                    expires_at = excluded.expires_at,
                    created_at = datetime('now', 'subsec'),
-                   last_used_at = NULL
+                   last_used_at = NULL,
+                   cooldown_until = NULL,
+                   invalidated_at = NULL,
+                   invalid_reason = NULL
                RETURNING id as "id!: Uuid",
                          user_id as "user_id!: Uuid",
                          encrypted_token,
                          token_hint,
                          created_at as "created_at!: DateTime<Utc>",
                          expires_at as "expires_at: DateTime<Utc>",
-                         last_used_at as "last_used_at: DateTime<Utc>""#,
+                         last_used_at as "last_used_at: DateTime<Utc>",
+                         cooldown_until as "cooldown_until: DateTime<Utc>",
+                         invalidated_at as "invalidated_at: DateTime<Utc>",
+                         invalid_reason,
+                         use_count,
+                         rate_limited_count,
+                         last_rate_limited_at as "last_rate_limited_at: DateTime<Utc>""#,
             id,
             user_id,
             encrypted_token,
@@ -118,10 +177,11 @@ impl ClaudeOAuthToken {
     }
 
     /// Get next token using round-robin rotation (least recently used first)
-    /// Skips expired tokens
+    /// Skips expired, cooling down and invalidated tokens
     pub async fn get_next_for_rotation(
         pool: &SqlitePool,
     ) -> Result<Option<Self>, ClaudeOAuthTokenError> {
+        let now = Utc::now();
         let token = sqlx::query_as!(
             ClaudeOAuthToken,
             r#"SELECT id as "id!: Uuid",
@@ -130,11 +190,20 @@ impl ClaudeOAuthToken {
                       token_hint,
                       created_at as "created_at!: DateTime<Utc>",
                       expires_at as "expires_at: DateTime<Utc>",
-                      last_used_at as "last_used_at: DateTime<Utc>"
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      cooldown_until as "cooldown_until: DateTime<Utc>",
+                      invalidated_at as "invalidated_at: DateTime<Utc>",
+                      invalid_reason,
+                      use_count,
+                      rate_limited_count,
+                      last_rate_limited_at as "last_rate_limited_at: DateTime<Utc>"
                FROM claude_oauth_tokens
-               WHERE expires_at IS NULL OR expires_at > datetime('now')
+               WHERE (expires_at IS NULL OR expires_at > datetime('now'))
+                 AND invalidated_at IS NULL
+                 AND (cooldown_until IS NULL OR cooldown_until <= $1)
                ORDER BY last_used_at ASC NULLS FIRST, created_at ASC
-               LIMIT 1"#
+               LIMIT 1"#,
+            now
         )
         .fetch_optional(pool)
         .await?;
@@ -142,11 +211,12 @@ impl ClaudeOAuthToken {
         Ok(token)
     }
 
-    /// Update last_used_at timestamp for a token
+    /// Update last_used_at timestamp and usage counter for a token
     pub async fn mark_used(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query!(
             r#"UPDATE claude_oauth_tokens
-               SET last_used_at = datetime('now', 'subsec')
+               SET last_used_at = datetime('now', 'subsec'),
+                   use_count = use_count + 1
                WHERE id = $1"#,
             id
         )
@@ -156,6 +226,68 @@ impl ClaudeOAuthToken {
         Ok(())
     }
 
+    /// Take a token out of rotation until its usage limit resets
+    pub async fn mark_rate_limited(
+        pool: &SqlitePool,
+        id: Uuid,
+        cooldown_until: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE claude_oauth_tokens
+               SET cooldown_until = $1,
+                   rate_limited_count = rate_limited_count + 1,
+                   last_rate_limited_at = $2
+               WHERE id = $3"#,
+            cooldown_until,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Keep a token out of rotation until `cooldown_until` without recording a usage limit
+    pub async fn set_cooldown(
+        pool: &SqlitePool,
+        id: Uuid,
+        cooldown_until: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE claude_oauth_tokens SET cooldown_until = $1 WHERE id = $2"#,
+            cooldown_until,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Take a token out of rotation until the user replaces it
+    pub async fn mark_invalid(
+        pool: &SqlitePool,
+        id: Uuid,
+        reason: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE claude_oauth_tokens
+               SET invalidated_at = $1,
+                   invalid_reason = $2
+               WHERE id = $3"#,
+            now,
+            reason,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Delete token for user
     pub async fn delete_for_user(pool: &SqlitePool, user_id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query!(
@@ -168,8 +300,9 @@ impl ClaudeOAuthToken {
         Ok(())
     }
 
-    /// Get all valid (non-expired) tokens
+    /// Get all healthy tokens (not expired, cooling down or invalidated)
     pub async fn find_all_valid(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        let now = Utc::now();
         sqlx::query_as!(
             ClaudeOAuthToken,
             r#"SELECT id as "id!: Uuid",
@@ -178,21 +311,78 @@ impl ClaudeOAuthToken {
                       token_hint,
                       created_at as "created_at!: DateTime<Utc>",
                       expires_at as "expires_at: DateTime<Utc>",
-                      last_used_at as "last_used_at: DateTime<Utc>"
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      cooldown_until as "cooldown_until: DateTime<Utc>",
+                      invalidated_at as "invalidated_at: DateTime<Utc>",
+                      invalid_reason,
+                      use_count,
+                      rate_limited_count,
+                      last_rate_limited_at as "last_rate_limited_at: DateTime<Utc>"
+               FROM claude_oauth_tokens
+               WHERE (expires_at IS NULL OR expires_at > datetime('now'))
+                 AND invalidated_at IS NULL
+                 AND (cooldown_until IS NULL OR cooldown_until <= $1)
+               ORDER BY created_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Get all stored tokens, including expired ones
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ClaudeOAuthToken,
+            r#"SELECT id as "id!: Uuid",
+                      user_id as "user_id!: Uuid",
+                      encrypted_token,
+                      token_hint,
+                      created_at as "created_at!: DateTime<Utc>",
+                      expires_at as "expires_at: DateTime<Utc>",
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      cooldown_until as "cooldown_until: DateTime<Utc>",
+                      invalidated_at as "invalidated_at: DateTime<Utc>",
+                      invalid_reason,
+                      use_count,
+                      rate_limited_count,
+                      last_rate_limited_at as "last_rate_limited_at: DateTime<Utc>"
                FROM claude_oauth_tokens
-               WHERE expires_at IS NULL OR expires_at > datetime('now')
                ORDER BY created_at ASC"#
         )
         .fetch_all(pool)
         .await
     }
 
-    /// Count available (non-expired) tokens
+    /// Replace the stored ciphertext of a token without touching its usage metadata
+    pub async fn update_encrypted_token<'e, E>(
+        executor: E,
+        id: Uuid,
+        encrypted_token: &str,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query!(
+            r#"UPDATE claude_oauth_tokens SET encrypted_token = $1 WHERE id = $2"#,
+            encrypted_token,
+            id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count healthy tokens available for rotation
     pub async fn count_available(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        let now = Utc::now();
         let result = sqlx::query_scalar!(
             r#"SELECT COUNT(*) as "count!: i64"
                FROM claude_oauth_tokens
-               WHERE expires_at IS NULL OR expires_at > datetime('now')"#
+               WHERE (expires_at IS NULL OR expires_at > datetime('now'))
+                 AND invalidated_at IS NULL
+                 AND (cooldown_until IS NULL OR cooldown_until <= $1)"#,
+            now
         )
         .fetch_one(pool)
         .await?;
@@ -202,7 +392,8 @@ impl ClaudeOAuthToken {
 
     /// Convert to status for frontend display
     pub fn to_status(&self) -> ClaudeOAuthTokenStatus {
-        let is_expired = self.expires_at.map(|exp| exp < Utc::now()).unwrap_or(false);
+        let now = Utc::now();
+        let is_expired = self.expires_at.map(|exp| exp < now).unwrap_or(false);
 
         ClaudeOAuthTokenStatus {
             has_token: true,
@@ -211,6 +402,12 @@ impl ClaudeOAuthToken {
             expires_at: self.expires_at,
             last_used_at: self.last_used_at,
             is_expired,
+            cooldown_until: self.cooldown_until.filter(|until| *until > now),
+            is_invalid: self.invalidated_at.is_some(),
+            invalid_reason: self.invalid_reason.clone(),
+            use_count: self.use_count,
+            rate_limited_count: self.rate_limited_count,
+            last_rate_limited_at: self.last_rate_limited_at,
         }
     }
 }
@@ -225,6 +422,12 @@ impl ClaudeOAuthTokenStatus {
             expires_at: None,
             last_used_at: None,
             is_expired: false,
+            cooldown_until: None,
+            is_invalid: false,
+            invalid_reason: None,
+            use_count: 0,
+            rate_limited_count: 0,
+            last_rate_limited_at: None,
         }
     }
 }
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "conflict_resolution_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ConflictResolutionStatus {
+    /// The agent is working on the conflicts
+    Running,
+    /// No conflict markers remained and the operation was continued to completion
+    Resolved,
+    /// The operation was continued but stopped on the conflicts of a later commit,
+    /// which another run resolves
+    Conflicted,
+    /// The agent failed or left conflict markers, so the operation was aborted
+    RolledBack,
+}
+
+/// An agent run resolving the conflicts of a git operation stopped in a workspace repo
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ConflictResolution {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    /// The files in conflict when the run started
+    #[ts(type = "Array<string>")]
+    pub conflicted_files: Json<Vec<String>>,
+    /// The worktree's HEAD when the run started; `None` for runs from before it was
+    /// recorded
+    pub start_head: Option<String>,
+    pub status: ConflictResolutionStatus,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateConflictResolution {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub conflicted_files: Vec<String>,
+    pub start_head: String,
+}
+
+const SELECT_RESOLUTION: &str = "SELECT execution_process_id, workspace_id, repo_id,
+            conflicted_files, start_head, status, error_message, created_at, updated_at
+     FROM conflict_resolutions";
+
+impl ConflictResolution {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateConflictResolution,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO conflict_resolutions (execution_process_id, workspace_id, repo_id,
+                 conflicted_files, start_head)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(data.execution_process_id)
+        .bind(data.workspace_id)
+        .bind(data.repo_id)
+        .bind(Json(&data.conflicted_files))
+        .bind(&data.start_head)
+        .execute(pool)
+        .await?;
+
+        Self::find_by_execution_process_id(pool, data.execution_process_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ConflictResolution>(&format!(
+            "{SELECT_RESOLUTION} WHERE execution_process_id = $1"
+        ))
+        .bind(execution_process_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The most recent resolution of each of a workspace's repos
+    pub async fn find_latest_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ConflictResolution>(&format!(
+            "{SELECT_RESOLUTION} cr
+             WHERE cr.workspace_id = $1
+               AND cr.rowid = (SELECT rowid FROM conflict_resolutions
+                               WHERE workspace_id = cr.workspace_id AND repo_id = cr.repo_id
+                               ORDER BY created_at DESC, rowid DESC
+                               LIMIT 1)
+             ORDER BY cr.created_at"
+        ))
+        .bind(workspace_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_status(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        status: ConflictResolutionStatus,
+        error_message: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE conflict_resolutions
+             SET status = $1, error_message = $2, updated_at = datetime('now', 'subsec')
+             WHERE execution_process_id = $3",
+        )
+        .bind(status)
+        .bind(error_message)
+        .bind(execution_process_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
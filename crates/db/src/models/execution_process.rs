@@ -43,12 +43,33 @@ pub enum ExecutionProcessError {
 #[serde(rename_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
+    /// Waiting in the execution queue for a free slot
+    Queued,
     Running,
     Completed,
     Failed,
     Killed,
 }
 
+/// Why an execution failed or was stopped, when its status and exit code alone don't say
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(
+    type_name = "execution_process_status_reason",
+    rename_all = "snake_case"
+)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ExecutionProcessStatusReason {
+    /// The sandbox killed the process for exceeding its memory limit
+    MemoryLimitExceeded,
+    /// The sandbox refused to start more processes than its limit allows
+    PidsLimitExceeded,
+    /// Stopped for running longer than the executor profile's maximum runtime
+    MaxRuntimeExceeded,
+    /// Stopped for producing no output for longer than the executor profile allows
+    IdleTimeout,
+}
+
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
 #[sqlx(type_name = "execution_process_run_reason", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -57,6 +78,8 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    /// A repo's verify script, run against a temporary merge of the workspace branch
+    VerifyScript,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -501,6 +524,81 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    pub async fn set_status_reason(
+        pool: &SqlitePool,
+        id: Uuid,
+        reason: ExecutionProcessStatusReason,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE execution_processes SET status_reason = $1 WHERE id = $2")
+            .bind(reason)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// How many runs in the session were stopped for exceeding a runtime or idle limit
+    pub async fn count_timed_out_in_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM execution_processes
+             WHERE session_id = $1 AND status_reason IN ($2, $3)",
+        )
+        .bind(session_id)
+        .bind(ExecutionProcessStatusReason::MaxRuntimeExceeded)
+        .bind(ExecutionProcessStatusReason::IdleTimeout)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_status_reason(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<ExecutionProcessStatusReason>, sqlx::Error> {
+        sqlx::query_scalar::<_, Option<ExecutionProcessStatusReason>>(
+            "SELECT status_reason FROM execution_processes WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+    }
+
+    /// Record that `id` is an automatic retry of the failed `retry_of`
+    pub async fn set_retry_of(
+        pool: &SqlitePool,
+        id: Uuid,
+        retry_of: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE execution_processes SET retry_of_execution_process_id = $1 WHERE id = $2",
+        )
+        .bind(retry_of)
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Ids of the runs `id` retries and `id` itself, oldest first
+    pub async fn find_retry_chain(pool: &SqlitePool, id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            "WITH RECURSIVE chain(id, retry_of, depth) AS (
+                 SELECT id, retry_of_execution_process_id, 0
+                 FROM execution_processes WHERE id = $1
+                 UNION ALL
+                 SELECT ep.id, ep.retry_of_execution_process_id, chain.depth + 1
+                 FROM execution_processes ep JOIN chain ON ep.id = chain.retry_of
+             )
+             SELECT id FROM chain ORDER BY depth DESC",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+    }
+
     pub fn executor_action(&self) -> Result<&ExecutorAction, anyhow::Error> {
         match &self.executor_action.0 {
             ExecutorActionField::ExecutorAction(action) => Ok(action),
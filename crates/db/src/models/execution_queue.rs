@@ -0,0 +1,204 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessStatus;
+
+/// A queued coding agent execution waiting for a free slot
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutionQueueEntry {
+    pub execution_process_id: Uuid,
+    /// Higher priorities start first; equal priorities start in arrival order
+    #[ts(type = "number")]
+    pub priority: i64,
+    pub executor: Option<String>,
+    pub project_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A queue entry with the task it belongs to, for listing the queue
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QueuedExecution {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub entry: ExecutionQueueEntry,
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+}
+
+/// What a running or queued execution counts against: its executor, project and user
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct ExecutionSlot {
+    pub executor: Option<String>,
+    pub project_id: Uuid,
+    pub user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateQueuePriority {
+    #[ts(type = "number")]
+    pub priority: i64,
+}
+
+const QUEUE_COLUMNS: &str =
+    "q.execution_process_id, q.priority, q.executor, q.project_id, q.user_id, q.created_at";
+
+const QUEUE_ORDER: &str = "q.priority DESC, q.created_at ASC, q.rowid ASC";
+
+impl ExecutionQueueEntry {
+    pub fn slot(&self) -> ExecutionSlot {
+        ExecutionSlot {
+            executor: self.executor.clone(),
+            project_id: self.project_id,
+            user_id: self.user_id,
+        }
+    }
+}
+
+pub struct ExecutionQueue;
+
+impl ExecutionQueue {
+    /// Mark the execution as queued and add it to the back of its priority
+    pub async fn enqueue(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        slot: &ExecutionSlot,
+    ) -> Result<ExecutionQueueEntry, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("UPDATE execution_processes SET status = $1 WHERE id = $2")
+            .bind(ExecutionProcessStatus::Queued)
+            .bind(execution_process_id)
+            .execute(&mut *tx)
+            .await?;
+        let entry = sqlx::query_as::<_, ExecutionQueueEntry>(
+            "INSERT INTO execution_queue (execution_process_id, executor, project_id, user_id)
+             VALUES ($1, $2, $3, $4)
+             RETURNING execution_process_id, priority, executor, project_id, user_id, created_at",
+        )
+        .bind(execution_process_id)
+        .bind(&slot.executor)
+        .bind(slot.project_id)
+        .bind(slot.user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(entry)
+    }
+
+    /// Queue entries in the order they should be started
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<ExecutionQueueEntry>, sqlx::Error> {
+        sqlx::query_as::<_, ExecutionQueueEntry>(&format!(
+            "SELECT {QUEUE_COLUMNS} FROM execution_queue q ORDER BY {QUEUE_ORDER}"
+        ))
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<ExecutionQueueEntry>, sqlx::Error> {
+        sqlx::query_as::<_, ExecutionQueueEntry>(&format!(
+            "SELECT {QUEUE_COLUMNS} FROM execution_queue q WHERE q.execution_process_id = $1"
+        ))
+        .bind(execution_process_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Queue entries with their tasks, optionally limited to one project
+    pub async fn list(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<QueuedExecution>, sqlx::Error> {
+        sqlx::query_as::<_, QueuedExecution>(&format!(
+            "SELECT {QUEUE_COLUMNS}, ep.session_id, s.workspace_id,
+                    t.id AS task_id, t.title AS task_title
+             FROM execution_queue q
+             JOIN execution_processes ep ON ep.id = q.execution_process_id
+             JOIN sessions s ON s.id = ep.session_id
+             JOIN workspaces w ON w.id = s.workspace_id
+             JOIN tasks t ON t.id = w.task_id
+             WHERE ($1 IS NULL OR q.project_id = $1)
+             ORDER BY {QUEUE_ORDER}"
+        ))
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_priority(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        priority: i64,
+    ) -> Result<Option<ExecutionQueueEntry>, sqlx::Error> {
+        sqlx::query_as::<_, ExecutionQueueEntry>(
+            "UPDATE execution_queue SET priority = $1 WHERE execution_process_id = $2
+             RETURNING execution_process_id, priority, executor, project_id, user_id, created_at",
+        )
+        .bind(priority)
+        .bind(execution_process_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Take the execution off the queue and mark it running. Returns false if it was no
+    /// longer queued, e.g. because it was cancelled or another caller claimed it.
+    pub async fn claim(pool: &SqlitePool, execution_process_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let removed = sqlx::query("DELETE FROM execution_queue WHERE execution_process_id = $1")
+            .bind(execution_process_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if removed == 0 {
+            return Ok(false);
+        }
+        sqlx::query(
+            "UPDATE execution_processes
+             SET status = $1, started_at = datetime('now', 'subsec'),
+                 updated_at = datetime('now', 'subsec')
+             WHERE id = $2",
+        )
+        .bind(ExecutionProcessStatus::Running)
+        .bind(execution_process_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Remove the execution from the queue without starting it
+    pub async fn remove(pool: &SqlitePool, execution_process_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM execution_queue WHERE execution_process_id = $1")
+            .bind(execution_process_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Slots taken by coding agents that are running right now
+    pub async fn running_slots(pool: &SqlitePool) -> Result<Vec<ExecutionSlot>, sqlx::Error> {
+        sqlx::query_as::<_, ExecutionSlot>(
+            "SELECT json_extract(ep.executor_action, '$.typ.executor_profile_id.executor')
+                        AS executor,
+                    t.project_id,
+                    COALESCE(s.initiated_by_user_id, w.owner_user_id) AS user_id
+             FROM execution_processes ep
+             JOIN sessions s ON s.id = ep.session_id
+             JOIN workspaces w ON w.id = s.workspace_id
+             JOIN tasks t ON t.id = w.task_id
+             WHERE ep.status = 'running' AND ep.run_reason = 'codingagent'",
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -203,6 +203,19 @@ impl TaskImage {
         Ok(())
     }
 
+    /// The tasks `image_id` is attached to
+    pub async fn find_task_ids_by_image_id(
+        pool: &SqlitePool,
+        image_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT task_id AS "task_id!: Uuid" FROM task_images WHERE image_id = $1"#,
+            image_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn delete_by_task_id(pool: &SqlitePool, task_id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query!(r#"DELETE FROM task_images WHERE task_id = $1"#, task_id)
             .execute(pool)
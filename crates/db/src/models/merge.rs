@@ -6,6 +6,11 @@ use sqlx::{FromRow, SqlitePool, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::{
+    project_workflow::{ProjectWorkflow, ProjectWorkflowError},
+    task::TaskStatus,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, Type)]
 #[sqlx(type_name = "merge_status", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
@@ -16,6 +21,31 @@ pub enum MergeStatus {
     Unknown,
 }
 
+/// How a task branch is merged into its target branch locally
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "merge_strategy", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// One commit on the target branch with all of the task's changes
+    #[default]
+    Squash,
+    /// A merge commit that keeps every task commit, even when a fast-forward is possible
+    MergeCommit,
+    /// Replay the task's commits onto the target branch if it has moved on, then
+    /// fast-forward it
+    Rebase,
+    /// Move the target branch to the task branch; fails if the target has moved on
+    FastForward,
+}
+
+impl MergeStrategy {
+    /// Whether the merge creates a new commit, which gets the merge commit message.
+    /// Otherwise the task's own commits end up on the target branch unchanged.
+    pub fn creates_commit(self) -> bool {
+        matches!(self, MergeStrategy::Squash | MergeStrategy::MergeCommit)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Merge {
@@ -77,6 +107,15 @@ struct MergeRow {
     created_at: DateTime<Utc>,
 }
 
+/// A merged PR with the project and status of its task
+#[derive(FromRow)]
+struct MergedPrRow {
+    #[sqlx(flatten)]
+    merge: MergeRow,
+    project_id: Uuid,
+    task_status: TaskStatus,
+}
+
 impl Merge {
     pub fn merge_commit(&self) -> Option<String> {
         match self {
@@ -197,6 +236,51 @@ impl Merge {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    /// Get merged PRs whose task is still waiting on post-merge deploy checks, going by
+    /// the workflow of the task's project
+    pub async fn get_merged_prs_awaiting_deploy(
+        pool: &SqlitePool,
+    ) -> Result<Vec<PrMerge>, ProjectWorkflowError> {
+        let rows = sqlx::query_as::<_, MergedPrRow>(
+            r#"SELECT
+                m.id,
+                m.workspace_id,
+                m.repo_id,
+                m.merge_type,
+                m.merge_commit,
+                m.pr_number,
+                m.pr_url,
+                m.pr_status,
+                m.pr_merged_at,
+                m.pr_merge_commit_sha,
+                m.created_at,
+                m.target_branch_name,
+                t.project_id,
+                t.status AS task_status
+               FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE m.merge_type = 'pr' AND m.pr_status = 'merged'
+               ORDER BY m.created_at DESC"#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut workflows = HashMap::new();
+        let mut awaiting = Vec::new();
+        for row in rows {
+            if !workflows.contains_key(&row.project_id) {
+                let definition =
+                    ProjectWorkflow::definition_for_project(pool, row.project_id).await?;
+                workflows.insert(row.project_id, definition);
+            }
+            if workflows[&row.project_id].awaits_deploy(&row.task_status) {
+                awaiting.push(row.merge.into());
+            }
+        }
+        Ok(awaiting)
+    }
+
     /// Update PR status for a workspace
     pub async fn update_status(
         pool: &SqlitePool,
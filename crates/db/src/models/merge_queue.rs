@@ -0,0 +1,269 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::merge::MergeStrategy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "merge_queue_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum MergeQueueStatus {
+    /// Waiting for its turn, or being rebased and merged
+    Queued,
+    /// Rebased onto the target branch; waiting for the verify script to pass
+    Verifying,
+    /// The rebase hit conflicts; queued again once the agent has resolved them, or
+    /// failed if it could not
+    Conflicted,
+    /// Stopped with `error_message`; enqueue the workspace again to retry
+    Failed,
+}
+
+/// A workspace repo waiting to be merged into its target branch. Entries for the same
+/// repo and target branch are merged one at a time, in the order they were enqueued.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MergeQueueEntry {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    /// Defaults to the repository's merge strategy in the project
+    pub merge_strategy: Option<MergeStrategy>,
+    pub status: MergeQueueStatus,
+    pub error_message: Option<String>,
+    /// The agent run resolving the conflicts of a `conflicted` entry
+    pub resolution_execution_process_id: Option<Uuid>,
+    /// Lower positions merge first
+    #[ts(type = "number")]
+    pub position: i64,
+    pub enqueued_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A queue entry with the task it merges, for listing the queue
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QueuedMerge {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub entry: MergeQueueEntry,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub branch: String,
+}
+
+const ENTRY_COLUMNS: &str = "q.id, q.workspace_id, q.repo_id, q.target_branch, q.merge_strategy, \
+     q.status, q.error_message, q.resolution_execution_process_id, q.position, q.enqueued_at, \
+     q.updated_at";
+
+const RETURNING_ENTRY: &str = "RETURNING id, workspace_id, repo_id, target_branch, merge_strategy, \
+     status, error_message, resolution_execution_process_id, position, enqueued_at, updated_at";
+
+const QUEUE_ORDER: &str = "q.position ASC";
+
+const NEXT_POSITION: &str = "(SELECT COALESCE(MAX(position), 0) + 1 FROM merge_queue_entries)";
+
+impl MergeQueueEntry {
+    /// Add the workspace repo to the back of its target branch's queue. An existing
+    /// entry, e.g. one that failed, is reset and moved to the back.
+    pub async fn enqueue(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        target_branch: &str,
+        merge_strategy: Option<MergeStrategy>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, MergeQueueEntry>(&format!(
+            "INSERT INTO merge_queue_entries
+                 (id, workspace_id, repo_id, target_branch, merge_strategy, position)
+             VALUES ($1, $2, $3, $4, $5, {NEXT_POSITION})
+             ON CONFLICT (workspace_id, repo_id) DO UPDATE SET
+                 target_branch = excluded.target_branch,
+                 merge_strategy = excluded.merge_strategy,
+                 status = 'queued',
+                 error_message = NULL,
+                 resolution_execution_process_id = NULL,
+                 position = excluded.position,
+                 enqueued_at = datetime('now', 'subsec'),
+                 updated_at = datetime('now', 'subsec')
+             {RETURNING_ENTRY}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(workspace_id)
+        .bind(repo_id)
+        .bind(target_branch)
+        .bind(merge_strategy)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, MergeQueueEntry>(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM merge_queue_entries q
+             WHERE q.workspace_id = $1 AND q.repo_id = $2"
+        ))
+        .bind(workspace_id)
+        .bind(repo_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, MergeQueueEntry>(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM merge_queue_entries q
+             WHERE q.workspace_id = $1
+             ORDER BY {QUEUE_ORDER}"
+        ))
+        .bind(workspace_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The conflicted entry waiting on the given agent run to resolve its conflicts
+    pub async fn find_by_resolution_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, MergeQueueEntry>(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM merge_queue_entries q
+             WHERE q.resolution_execution_process_id = $1 AND q.status = 'conflicted'"
+        ))
+        .bind(execution_process_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The entry at the front of each queue. Conflicted and failed entries step aside,
+    /// so they do not hold up the entries behind them.
+    pub async fn find_heads(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, MergeQueueEntry>(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM merge_queue_entries q
+             WHERE q.rowid = (SELECT rowid FROM merge_queue_entries
+                              WHERE repo_id = q.repo_id
+                                AND target_branch = q.target_branch
+                                AND status IN ('queued', 'verifying')
+                              ORDER BY position ASC
+                              LIMIT 1)
+             ORDER BY {QUEUE_ORDER}"
+        ))
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Queue entries with their tasks, optionally limited to one project, grouped by
+    /// queue and in merge order within each
+    pub async fn list(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<QueuedMerge>, sqlx::Error> {
+        sqlx::query_as::<_, QueuedMerge>(&format!(
+            "SELECT {ENTRY_COLUMNS}, t.project_id, t.id AS task_id, t.title AS task_title,
+                    w.branch
+             FROM merge_queue_entries q
+             JOIN workspaces w ON w.id = q.workspace_id
+             JOIN tasks t ON t.id = w.task_id
+             WHERE ($1 IS NULL OR t.project_id = $1)
+             ORDER BY q.repo_id, q.target_branch, {QUEUE_ORDER}"
+        ))
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: MergeQueueStatus,
+        error_message: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE merge_queue_entries
+             SET status = $1, error_message = $2, updated_at = datetime('now', 'subsec')
+             WHERE id = $3",
+        )
+        .bind(status)
+        .bind(error_message)
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Step the entry aside while `resolution_execution_process_id`, if any, resolves
+    /// its rebase conflicts
+    pub async fn set_conflicted(
+        pool: &SqlitePool,
+        id: Uuid,
+        resolution_execution_process_id: Option<Uuid>,
+        error_message: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE merge_queue_entries
+             SET status = 'conflicted', error_message = $1,
+                 resolution_execution_process_id = $2,
+                 updated_at = datetime('now', 'subsec')
+             WHERE id = $3",
+        )
+        .bind(error_message)
+        .bind(resolution_execution_process_id)
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Put the entry whose conflicts the finished execution was resolving back at the
+    /// end of its queue. Returns the number of entries requeued.
+    pub async fn requeue_resolved(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(&format!(
+            "UPDATE merge_queue_entries
+             SET status = 'queued', error_message = NULL,
+                 resolution_execution_process_id = NULL,
+                 position = {NEXT_POSITION},
+                 enqueued_at = datetime('now', 'subsec'),
+                 updated_at = datetime('now', 'subsec')
+             WHERE resolution_execution_process_id = $1 AND status = 'conflicted'"
+        ))
+        .bind(execution_process_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM merge_queue_entries WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Whether the entry is waiting in, or at the front of, its queue
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.status,
+            MergeQueueStatus::Queued | MergeQueueStatus::Verifying
+        )
+    }
+
+    /// Whether the queue still has work to do for the entry, including taking it up
+    /// again once an agent has resolved its conflicts
+    pub fn is_pending(&self) -> bool {
+        self.status != MergeQueueStatus::Failed
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessStatus;
+
+/// A run of a repo's verify script against the merge of a workspace branch into
+/// its target branch
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MergeVerification {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    /// Head of the workspace branch that was merged
+    pub task_commit: String,
+    pub target_branch: String,
+    /// Head of the target branch it was merged into
+    pub target_commit: String,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub worktree_path: String,
+    /// Status of the script's execution process
+    pub status: ExecutionProcessStatus,
+    #[ts(type = "number | null")]
+    pub exit_code: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateMergeVerification {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub task_commit: String,
+    pub target_branch: String,
+    pub target_commit: String,
+    pub worktree_path: String,
+}
+
+const SELECT_VERIFICATION: &str = "SELECT mv.execution_process_id, mv.workspace_id, mv.repo_id,
+            mv.task_commit, mv.target_branch, mv.target_commit, mv.worktree_path,
+            ep.status, ep.exit_code, mv.created_at
+     FROM merge_verifications mv
+     JOIN execution_processes ep ON ep.id = mv.execution_process_id";
+
+impl MergeVerification {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateMergeVerification,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO merge_verifications (execution_process_id, workspace_id, repo_id,
+                 task_commit, target_branch, target_commit, worktree_path)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(data.execution_process_id)
+        .bind(data.workspace_id)
+        .bind(data.repo_id)
+        .bind(&data.task_commit)
+        .bind(&data.target_branch)
+        .bind(&data.target_commit)
+        .bind(&data.worktree_path)
+        .execute(pool)
+        .await?;
+
+        Self::find_by_execution_process_id(pool, data.execution_process_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, MergeVerification>(&format!(
+            "{SELECT_VERIFICATION} WHERE mv.execution_process_id = $1"
+        ))
+        .bind(execution_process_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The most recent verification of a workspace's repo
+    pub async fn find_latest(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, MergeVerification>(&format!(
+            "{SELECT_VERIFICATION}
+             WHERE mv.workspace_id = $1 AND mv.repo_id = $2
+             ORDER BY mv.created_at DESC, mv.rowid DESC
+             LIMIT 1"
+        ))
+        .bind(workspace_id)
+        .bind(repo_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The most recent verification of each of a workspace's repos
+    pub async fn find_latest_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, MergeVerification>(&format!(
+            "{SELECT_VERIFICATION}
+             WHERE mv.workspace_id = $1
+               AND mv.rowid = (SELECT rowid FROM merge_verifications
+                               WHERE workspace_id = mv.workspace_id AND repo_id = mv.repo_id
+                               ORDER BY created_at DESC, rowid DESC
+                               LIMIT 1)
+             ORDER BY mv.created_at"
+        ))
+        .bind(workspace_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether the script finished with exit code 0
+    pub fn passed(&self) -> bool {
+        self.status == ExecutionProcessStatus::Completed && self.exit_code == Some(0)
+    }
+}
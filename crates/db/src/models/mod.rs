@@ -1,20 +1,35 @@
+pub mod approval_policy;
+pub mod audit_event;
 pub mod auth_session;
 pub mod claude_oauth_token;
 pub mod coding_agent_turn;
+pub mod conflict_resolution;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_queue;
 pub mod image;
 pub mod merge;
+pub mod merge_queue;
+pub mod merge_verification;
 pub mod project;
+pub mod project_archive;
+pub mod project_budget;
+pub mod project_member;
 pub mod project_repo;
+pub mod project_workflow;
+pub mod queued_message;
 pub mod repo;
 pub mod review_conversation;
 pub mod scratch;
+pub mod search;
 pub mod session;
 pub mod tag;
 pub mod task;
 pub mod task_approval;
+pub mod task_dependency;
+pub mod token_usage;
+pub mod tool_approval;
 pub mod user;
 pub mod workspace;
 pub mod workspace_repo;
@@ -0,0 +1,801 @@
+//! Versioned archive of a whole project, for moving it between deployments or
+//! backing it up.
+//!
+//! Every row is restored under a fresh id so an archive can be imported next to the
+//! project it came from. Users are matched by GitHub id and are left out where the
+//! target has no such user; repos are matched by path on the target machine.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Sqlite, SqlitePool, Transaction};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_process_logs::ExecutionProcessLogs,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    image::{Image, TaskImage},
+    merge::MergeStrategy,
+    project::Project,
+    project_repo::ProjectRepo,
+    project_workflow::{ProjectWorkflow, ProjectWorkflowError, WorkflowDefinition},
+    repo::Repo,
+    review_conversation::{ReviewConversation, ReviewConversationMessage},
+    session::Session,
+    tag::Tag,
+    task::Task,
+    task_approval::TaskApproval,
+    task_dependency::TaskDependency,
+    user::User,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+
+/// Format version written by `ProjectArchive::collect`. Bump it when the archive
+/// changes in a way older servers can't read.
+pub const PROJECT_ARCHIVE_VERSION: u32 = 3;
+
+#[derive(Debug, Error)]
+pub enum ProjectArchiveError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Workflow(#[from] ProjectWorkflowError),
+    #[error("Project not found")]
+    ProjectNotFound,
+    #[error("Archive version {0} is newer than this server supports ({PROJECT_ARCHIVE_VERSION})")]
+    UnsupportedVersion(u32),
+    #[error("Archive references {0}, which it does not contain")]
+    MissingReference(Uuid),
+    #[error("No repository path given for archived repository {0}")]
+    MissingRepoPath(Uuid),
+}
+
+/// A user referenced by the archive, matched by GitHub id on import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedUser {
+    pub id: Uuid,
+    pub github_id: i64,
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectArchive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub project: Project,
+    /// The project's repos and any other repo its workspaces use
+    pub repos: Vec<Repo>,
+    /// Verify script of each archived repo that has one, keyed by repo id (since version 2)
+    #[serde(default)]
+    pub repo_verify_scripts: BTreeMap<Uuid, String>,
+    pub project_repos: Vec<ProjectRepo>,
+    /// Default merge strategy of each of the project's repos, keyed by repo id (since
+    /// version 3)
+    #[serde(default)]
+    pub repo_merge_strategies: BTreeMap<Uuid, MergeStrategy>,
+    /// Only present when the project overrides the default workflow
+    pub workflow: Option<WorkflowDefinition>,
+    pub users: Vec<ArchivedUser>,
+    /// Tags are shared by all projects; import adds the ones the target has no tag
+    /// of the same name for
+    pub tags: Vec<Tag>,
+    pub tasks: Vec<Task>,
+    pub task_dependencies: Vec<TaskDependency>,
+    pub task_approvals: Vec<TaskApproval>,
+    pub images: Vec<Image>,
+    /// Base64 contents of each image file, keyed by image id
+    #[serde(default)]
+    pub image_data: BTreeMap<Uuid, String>,
+    pub task_images: Vec<TaskImage>,
+    pub workspaces: Vec<Workspace>,
+    pub workspace_repos: Vec<WorkspaceRepo>,
+    pub sessions: Vec<Session>,
+    pub execution_processes: Vec<ExecutionProcess>,
+    pub execution_process_logs: Vec<ExecutionProcessLogs>,
+    pub execution_process_repo_states: Vec<ExecutionProcessRepoState>,
+    pub coding_agent_turns: Vec<CodingAgentTurn>,
+    pub review_conversations: Vec<ReviewConversation>,
+    pub review_conversation_messages: Vec<ReviewConversationMessage>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct ImportProject {
+    /// A file written by the export endpoint
+    #[ts(type = "unknown")]
+    pub archive: ProjectArchive,
+    /// Path on this machine for archived repos, keyed by their id in the archive.
+    /// Repos left out are expected at their archived path.
+    #[serde(default)]
+    pub repo_paths: HashMap<Uuid, String>,
+}
+
+/// Old ids from the archive mapped to the ids their rows get on import
+#[derive(Default)]
+struct IdMap(HashMap<Uuid, Uuid>);
+
+impl IdMap {
+    fn assign(&mut self, old: Uuid) -> Uuid {
+        *self.0.entry(old).or_insert_with(Uuid::new_v4)
+    }
+
+    fn get(&self, old: Uuid) -> Result<Uuid, ProjectArchiveError> {
+        self.0
+            .get(&old)
+            .copied()
+            .ok_or(ProjectArchiveError::MissingReference(old))
+    }
+
+    /// An optional reference, dropped when the archive doesn't contain its target
+    fn optional(&self, old: Option<Uuid>) -> Option<Uuid> {
+        old.and_then(|id| self.0.get(&id).copied())
+    }
+}
+
+const TASKS_OF_PROJECT: &str = "SELECT id FROM tasks WHERE project_id = $1";
+const WORKSPACES_OF_PROJECT: &str = "SELECT w.id FROM workspaces w
+     JOIN tasks t ON t.id = w.task_id WHERE t.project_id = $1";
+const SESSIONS_OF_PROJECT: &str = "SELECT s.id FROM sessions s
+     JOIN workspaces w ON w.id = s.workspace_id
+     JOIN tasks t ON t.id = w.task_id WHERE t.project_id = $1";
+const EXECUTIONS_OF_PROJECT: &str = "SELECT ep.id FROM execution_processes ep
+     JOIN sessions s ON s.id = ep.session_id
+     JOIN workspaces w ON w.id = s.workspace_id
+     JOIN tasks t ON t.id = w.task_id WHERE t.project_id = $1";
+
+async fn fetch_all_in<T>(
+    pool: &SqlitePool,
+    table: &str,
+    column: &str,
+    ids_query: &str,
+    project_id: Uuid,
+) -> Result<Vec<T>, sqlx::Error>
+where
+    T: for<'r> FromRow<'r, sqlx::sqlite::SqliteRow> + Send + Unpin,
+{
+    sqlx::query_as::<_, T>(&format!(
+        "SELECT * FROM {table} WHERE {column} IN ({ids_query}) ORDER BY rowid"
+    ))
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+impl ProjectArchive {
+    pub fn check_version(&self) -> Result<(), ProjectArchiveError> {
+        if self.version > PROJECT_ARCHIVE_VERSION {
+            return Err(ProjectArchiveError::UnsupportedVersion(self.version));
+        }
+        Ok(())
+    }
+
+    /// Everything stored for the project. `image_data` is left empty; the files live
+    /// outside the database.
+    pub async fn collect(pool: &SqlitePool, project_id: Uuid) -> Result<Self, ProjectArchiveError> {
+        let project = Project::find_by_id(pool, project_id)
+            .await?
+            .ok_or(ProjectArchiveError::ProjectNotFound)?;
+        let project_repos = ProjectRepo::find_by_project_id(pool, project_id).await?;
+        let workflow = ProjectWorkflow::find_by_project_id(pool, project_id)
+            .await?
+            .map(|workflow| workflow.definition);
+
+        let tasks: Vec<Task> =
+            sqlx::query_as("SELECT * FROM tasks WHERE project_id = $1 ORDER BY created_at, rowid")
+                .bind(project_id)
+                .fetch_all(pool)
+                .await?;
+        let task_dependencies: Vec<TaskDependency> = fetch_all_in(
+            pool,
+            "task_dependencies",
+            "task_id",
+            TASKS_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+        let task_approvals: Vec<TaskApproval> = fetch_all_in(
+            pool,
+            "task_approvals",
+            "task_id",
+            TASKS_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+        let task_images: Vec<TaskImage> =
+            fetch_all_in(pool, "task_images", "task_id", TASKS_OF_PROJECT, project_id).await?;
+        let images: Vec<Image> = sqlx::query_as(&format!(
+            "SELECT * FROM images WHERE id IN
+                 (SELECT image_id FROM task_images WHERE task_id IN ({TASKS_OF_PROJECT}))
+             ORDER BY rowid"
+        ))
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        let workspaces: Vec<Workspace> =
+            fetch_all_in(pool, "workspaces", "task_id", TASKS_OF_PROJECT, project_id).await?;
+        let workspace_repos: Vec<WorkspaceRepo> = fetch_all_in(
+            pool,
+            "workspace_repos",
+            "workspace_id",
+            WORKSPACES_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+        let review_conversations: Vec<ReviewConversation> = fetch_all_in(
+            pool,
+            "review_conversations",
+            "workspace_id",
+            WORKSPACES_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+        let review_conversation_messages: Vec<ReviewConversationMessage> =
+            sqlx::query_as(&format!(
+                "SELECT * FROM review_conversation_messages WHERE conversation_id IN
+                     (SELECT id FROM review_conversations WHERE workspace_id IN
+                         ({WORKSPACES_OF_PROJECT}))
+                 ORDER BY rowid"
+            ))
+            .bind(project_id)
+            .fetch_all(pool)
+            .await?;
+
+        let sessions: Vec<Session> = fetch_all_in(
+            pool,
+            "sessions",
+            "workspace_id",
+            WORKSPACES_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+        let execution_processes: Vec<ExecutionProcess> = fetch_all_in(
+            pool,
+            "execution_processes",
+            "session_id",
+            SESSIONS_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+        let execution_process_logs: Vec<ExecutionProcessLogs> = fetch_all_in(
+            pool,
+            "execution_process_logs",
+            "execution_id",
+            EXECUTIONS_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+        let execution_process_repo_states: Vec<ExecutionProcessRepoState> = fetch_all_in(
+            pool,
+            "execution_process_repo_states",
+            "execution_process_id",
+            EXECUTIONS_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+        let coding_agent_turns: Vec<CodingAgentTurn> = fetch_all_in(
+            pool,
+            "coding_agent_turns",
+            "execution_process_id",
+            EXECUTIONS_OF_PROJECT,
+            project_id,
+        )
+        .await?;
+
+        let mut repo_ids: Vec<Uuid> = project_repos.iter().map(|pr| pr.repo_id).collect();
+        for repo_id in workspace_repos.iter().map(|wr| wr.repo_id) {
+            if !repo_ids.contains(&repo_id) {
+                repo_ids.push(repo_id);
+            }
+        }
+        let repos = Repo::find_by_ids(pool, &repo_ids).await?;
+        let mut repo_verify_scripts = BTreeMap::new();
+        for repo in &repos {
+            if let Some(script) = Repo::find_verify_script(pool, repo.id).await? {
+                repo_verify_scripts.insert(repo.id, script);
+            }
+        }
+        let mut repo_merge_strategies = BTreeMap::new();
+        for project_repo in &project_repos {
+            let strategy =
+                ProjectRepo::find_merge_strategy(pool, project_id, project_repo.repo_id).await?;
+            repo_merge_strategies.insert(project_repo.repo_id, strategy);
+        }
+
+        let user_ids: HashSet<Uuid> = project
+            .creator_user_id
+            .into_iter()
+            .chain(tasks.iter().flat_map(|t| {
+                [t.creator_user_id, t.assignee_user_id, t.hold_user_id]
+                    .into_iter()
+                    .flatten()
+            }))
+            .chain(task_approvals.iter().map(|a| a.user_id))
+            .chain(workspaces.iter().filter_map(|w| w.owner_user_id))
+            .chain(sessions.iter().filter_map(|s| s.initiated_by_user_id))
+            .chain(
+                review_conversations
+                    .iter()
+                    .filter_map(|c| c.resolved_by_user_id),
+            )
+            .chain(
+                review_conversation_messages
+                    .iter()
+                    .filter_map(|m| m.user_id),
+            )
+            .collect();
+        let mut users = Vec::with_capacity(user_ids.len());
+        for id in user_ids {
+            if let Some(user) = User::find_by_id(pool, id).await? {
+                users.push(ArchivedUser {
+                    id: user.id,
+                    github_id: user.github_id,
+                    username: user.username,
+                });
+            }
+        }
+
+        Ok(Self {
+            version: PROJECT_ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            project,
+            repos,
+            repo_verify_scripts,
+            project_repos,
+            repo_merge_strategies,
+            workflow,
+            users,
+            tags: Tag::find_all(pool).await?,
+            tasks,
+            task_dependencies,
+            task_approvals,
+            images,
+            image_data: BTreeMap::new(),
+            task_images,
+            workspaces,
+            workspace_repos,
+            sessions,
+            execution_processes,
+            execution_process_logs,
+            execution_process_repo_states,
+            coding_agent_turns,
+            review_conversations,
+            review_conversation_messages,
+        })
+    }
+
+    /// Recreate the archived project as a new project owned by `creator_user_id`.
+    ///
+    /// `repo_paths` gives the path on this machine for every archived repo; repos
+    /// already registered at that path are reused as they are, new ones get the
+    /// archived scripts and settings. `image_ids` maps archived images to the images
+    /// their files were stored as; task images without an entry are left out.
+    ///
+    /// Workspaces come back without worktrees, and executions that were still
+    /// queued or running are marked as killed.
+    pub async fn restore(
+        &self,
+        pool: &SqlitePool,
+        repo_paths: &HashMap<Uuid, PathBuf>,
+        image_ids: &HashMap<Uuid, Uuid>,
+        creator_user_id: Option<Uuid>,
+    ) -> Result<Project, ProjectArchiveError> {
+        self.check_version()?;
+
+        let mut ids = IdMap::default();
+        for user in &self.users {
+            if let Some(local) = User::find_by_github_id(pool, user.github_id).await? {
+                ids.0.insert(user.id, local.id);
+            }
+        }
+        ids.0.extend(image_ids);
+        for id in self
+            .tasks
+            .iter()
+            .map(|t| t.id)
+            .chain(self.workspaces.iter().map(|w| w.id))
+            .chain(self.sessions.iter().map(|s| s.id))
+            .chain(self.execution_processes.iter().map(|ep| ep.id))
+            .chain(self.review_conversations.iter().map(|c| c.id))
+        {
+            ids.assign(id);
+        }
+
+        let mut tx = pool.begin().await?;
+        let project_id = ids.assign(self.project.id);
+        self.restore_project(&mut tx, project_id, creator_user_id)
+            .await?;
+        self.restore_repos(&mut tx, &mut ids, repo_paths, project_id)
+            .await?;
+        self.restore_tasks(&mut tx, &ids, project_id).await?;
+        self.restore_workspaces(&mut tx, &ids).await?;
+        self.restore_executions(&mut tx, &ids).await?;
+        tx.commit().await?;
+
+        Project::find_by_id(pool, project_id)
+            .await?
+            .ok_or(ProjectArchiveError::ProjectNotFound)
+    }
+
+    async fn restore_project(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        project_id: Uuid,
+        creator_user_id: Option<Uuid>,
+    ) -> Result<(), ProjectArchiveError> {
+        let project = &self.project;
+        sqlx::query(
+            "INSERT INTO projects (id, name, default_agent_working_dir, creator_user_id,
+                                   min_approvals_required, color, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(project_id)
+        .bind(&project.name)
+        .bind(&project.default_agent_working_dir)
+        .bind(creator_user_id)
+        .bind(project.min_approvals_required)
+        .bind(&project.color)
+        .bind(project.created_at)
+        .bind(project.updated_at)
+        .execute(&mut **tx)
+        .await?;
+
+        if let Some(workflow) = &self.workflow {
+            sqlx::query("INSERT INTO project_workflows (project_id, definition) VALUES ($1, $2)")
+                .bind(project_id)
+                .bind(serde_json::to_string(workflow).map_err(ProjectWorkflowError::from)?)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        for tag in &self.tags {
+            sqlx::query(
+                "INSERT INTO tags (id, tag_name, content, created_at, updated_at)
+                 SELECT $1, $2, $3, $4, $5
+                 WHERE NOT EXISTS (SELECT 1 FROM tags WHERE tag_name = $2)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(&tag.tag_name)
+            .bind(&tag.content)
+            .bind(tag.created_at)
+            .bind(tag.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn restore_repos(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        ids: &mut IdMap,
+        repo_paths: &HashMap<Uuid, PathBuf>,
+        project_id: Uuid,
+    ) -> Result<(), ProjectArchiveError> {
+        for repo in &self.repos {
+            let path = repo_paths
+                .get(&repo.id)
+                .ok_or(ProjectArchiveError::MissingRepoPath(repo.id))?
+                .to_string_lossy()
+                .to_string();
+            sqlx::query(
+                "INSERT INTO repos (id, path, name, display_name, setup_script, cleanup_script,
+                                    copy_files, parallel_setup_script, dev_server_script,
+                                    default_target_branch, verify_script)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                 ON CONFLICT(path) DO NOTHING",
+            )
+            .bind(Uuid::new_v4())
+            .bind(&path)
+            .bind(&repo.name)
+            .bind(&repo.display_name)
+            .bind(&repo.setup_script)
+            .bind(&repo.cleanup_script)
+            .bind(&repo.copy_files)
+            .bind(repo.parallel_setup_script)
+            .bind(&repo.dev_server_script)
+            .bind(&repo.default_target_branch)
+            .bind(self.repo_verify_scripts.get(&repo.id))
+            .execute(&mut **tx)
+            .await?;
+            let local_id: Uuid = sqlx::query_scalar("SELECT id FROM repos WHERE path = $1")
+                .bind(&path)
+                .fetch_one(&mut **tx)
+                .await?;
+            ids.0.insert(repo.id, local_id);
+        }
+
+        for project_repo in &self.project_repos {
+            sqlx::query(
+                "INSERT INTO project_repos (id, project_id, repo_id, merge_strategy)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT(project_id, repo_id) DO NOTHING",
+            )
+            .bind(Uuid::new_v4())
+            .bind(project_id)
+            .bind(ids.get(project_repo.repo_id)?)
+            .bind(
+                self.repo_merge_strategies
+                    .get(&project_repo.repo_id)
+                    .copied()
+                    .unwrap_or_default(),
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn restore_tasks(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        ids: &IdMap,
+        project_id: Uuid,
+    ) -> Result<(), ProjectArchiveError> {
+        for task in &self.tasks {
+            sqlx::query(
+                "INSERT INTO tasks (id, project_id, title, description, status,
+                                    parent_workspace_id, creator_user_id, assignee_user_id,
+                                    hold_user_id, hold_comment, hold_at, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+            )
+            .bind(ids.get(task.id)?)
+            .bind(project_id)
+            .bind(&task.title)
+            .bind(&task.description)
+            .bind(&task.status)
+            .bind(ids.optional(task.parent_workspace_id))
+            .bind(ids.optional(task.creator_user_id))
+            .bind(ids.optional(task.assignee_user_id))
+            .bind(ids.optional(task.hold_user_id))
+            .bind(&task.hold_comment)
+            .bind(task.hold_at)
+            .bind(task.created_at)
+            .bind(task.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for dependency in &self.task_dependencies {
+            sqlx::query(
+                "INSERT INTO task_dependencies (task_id, blocked_by_task_id, created_at)
+                 VALUES ($1, $2, $3)",
+            )
+            .bind(ids.get(dependency.task_id)?)
+            .bind(ids.get(dependency.blocked_by_task_id)?)
+            .bind(dependency.created_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for approval in &self.task_approvals {
+            // Approvals only count for users this server knows
+            let Some(user_id) = ids.optional(Some(approval.user_id)) else {
+                continue;
+            };
+            sqlx::query(
+                "INSERT INTO task_approvals (id, task_id, user_id, created_at)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(ids.get(approval.task_id)?)
+            .bind(user_id)
+            .bind(approval.created_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for task_image in &self.task_images {
+            let Some(image_id) = ids.optional(Some(task_image.image_id)) else {
+                continue;
+            };
+            sqlx::query(
+                "INSERT INTO task_images (id, task_id, image_id, created_at)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT(task_id, image_id) DO NOTHING",
+            )
+            .bind(Uuid::new_v4())
+            .bind(ids.get(task_image.task_id)?)
+            .bind(image_id)
+            .bind(task_image.created_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn restore_workspaces(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        ids: &IdMap,
+    ) -> Result<(), ProjectArchiveError> {
+        for workspace in &self.workspaces {
+            sqlx::query(
+                "INSERT INTO workspaces (id, task_id, branch, agent_working_dir, archived, pinned,
+                                         name, owner_user_id, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(ids.get(workspace.id)?)
+            .bind(ids.get(workspace.task_id)?)
+            .bind(&workspace.branch)
+            .bind(&workspace.agent_working_dir)
+            .bind(workspace.archived)
+            .bind(workspace.pinned)
+            .bind(&workspace.name)
+            .bind(ids.optional(workspace.owner_user_id))
+            .bind(workspace.created_at)
+            .bind(workspace.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for workspace_repo in &self.workspace_repos {
+            sqlx::query(
+                "INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch,
+                                              created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(ids.get(workspace_repo.workspace_id)?)
+            .bind(ids.get(workspace_repo.repo_id)?)
+            .bind(&workspace_repo.target_branch)
+            .bind(workspace_repo.created_at)
+            .bind(workspace_repo.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for conversation in &self.review_conversations {
+            sqlx::query(
+                "INSERT INTO review_conversations (id, workspace_id, file_path, line_number, side,
+                                                   code_line, is_resolved, resolved_at,
+                                                   resolved_by_user_id, resolution_summary,
+                                                   created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            )
+            .bind(ids.get(conversation.id)?)
+            .bind(ids.get(conversation.workspace_id)?)
+            .bind(&conversation.file_path)
+            .bind(conversation.line_number)
+            .bind(&conversation.side)
+            .bind(&conversation.code_line)
+            .bind(conversation.is_resolved)
+            .bind(conversation.resolved_at)
+            .bind(ids.optional(conversation.resolved_by_user_id))
+            .bind(&conversation.resolution_summary)
+            .bind(conversation.created_at)
+            .bind(conversation.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for message in &self.review_conversation_messages {
+            sqlx::query(
+                "INSERT INTO review_conversation_messages (id, conversation_id, user_id, content,
+                                                           created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(ids.get(message.conversation_id)?)
+            .bind(ids.optional(message.user_id))
+            .bind(&message.content)
+            .bind(message.created_at)
+            .bind(message.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for session in &self.sessions {
+            sqlx::query(
+                "INSERT INTO sessions (id, workspace_id, executor, initiated_by_user_id,
+                                       created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(ids.get(session.id)?)
+            .bind(ids.get(session.workspace_id)?)
+            .bind(&session.executor)
+            .bind(ids.optional(session.initiated_by_user_id))
+            .bind(session.created_at)
+            .bind(session.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn restore_executions(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        ids: &IdMap,
+    ) -> Result<(), ProjectArchiveError> {
+        for process in &self.execution_processes {
+            // Nothing is running for an imported project
+            let status = match process.status {
+                ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running => {
+                    ExecutionProcessStatus::Killed
+                }
+                ref status => status.clone(),
+            };
+            sqlx::query(
+                "INSERT INTO execution_processes (id, session_id, run_reason, executor_action,
+                                                  status, exit_code, dropped, started_at,
+                                                  completed_at, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            )
+            .bind(ids.get(process.id)?)
+            .bind(ids.get(process.session_id)?)
+            .bind(&process.run_reason)
+            .bind(&process.executor_action)
+            .bind(status)
+            .bind(process.exit_code)
+            .bind(process.dropped)
+            .bind(process.started_at)
+            .bind(process.completed_at)
+            .bind(process.created_at)
+            .bind(process.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for logs in &self.execution_process_logs {
+            sqlx::query(
+                "INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(ids.get(logs.execution_id)?)
+            .bind(&logs.logs)
+            .bind(logs.byte_size)
+            .bind(logs.inserted_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for state in &self.execution_process_repo_states {
+            sqlx::query(
+                "INSERT INTO execution_process_repo_states (id, execution_process_id, repo_id,
+                                                            before_head_commit, after_head_commit,
+                                                            merge_commit, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(ids.get(state.execution_process_id)?)
+            .bind(ids.get(state.repo_id)?)
+            .bind(&state.before_head_commit)
+            .bind(&state.after_head_commit)
+            .bind(&state.merge_commit)
+            .bind(state.created_at)
+            .bind(state.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for turn in &self.coding_agent_turns {
+            sqlx::query(
+                "INSERT INTO coding_agent_turns (id, execution_process_id, agent_session_id,
+                                                 agent_message_id, prompt, summary, seen,
+                                                 created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(ids.get(turn.execution_process_id)?)
+            .bind(&turn.agent_session_id)
+            .bind(&turn.agent_message_id)
+            .bind(&turn.prompt)
+            .bind(&turn.summary)
+            .bind(turn.seen)
+            .bind(turn.created_at)
+            .bind(turn.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::token_usage::{ExecutionTokenUsage, TokenUsageRollup, UsageFilter};
+
+#[derive(Debug, Error)]
+pub enum ProjectBudgetError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid budget: {0}")]
+    Invalid(String),
+}
+
+const PROJECT_BUDGET_COLUMNS: &str =
+    "project_id, max_tokens, max_cost_usd, period, created_at, updated_at";
+
+/// The window a budget's usage is counted over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "budget_period", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum BudgetPeriod {
+    /// All usage since the project was created
+    #[default]
+    Total,
+    /// Usage since the start of the current calendar month (UTC)
+    Monthly,
+}
+
+impl BudgetPeriod {
+    /// Start of the period containing `now`, or `None` for an unbounded period
+    pub fn start(self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            BudgetPeriod::Total => None,
+            BudgetPeriod::Monthly => Utc
+                .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+                .single(),
+        }
+    }
+}
+
+/// Spending limits for a project. Unset limits are not enforced.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectBudget {
+    pub project_id: Uuid,
+    #[ts(type = "number | null")]
+    pub max_tokens: Option<i64>,
+    pub max_cost_usd: Option<f64>,
+    pub period: BudgetPeriod,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectBudget {
+    #[ts(type = "number | null")]
+    pub max_tokens: Option<i64>,
+    pub max_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub period: BudgetPeriod,
+}
+
+/// A project's budget together with the usage counted against it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectBudgetStatus {
+    pub budget: ProjectBudget,
+    /// Usage in the budget's current period
+    pub usage: TokenUsageRollup,
+    /// Whether new executions are currently refused
+    pub exceeded: bool,
+}
+
+impl ProjectBudget {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ProjectBudget>(&format!(
+            "SELECT {PROJECT_BUDGET_COLUMNS} FROM project_budgets WHERE project_id = $1"
+        ))
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store (or replace) the project's budget
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectBudget,
+    ) -> Result<Self, ProjectBudgetError> {
+        if data.max_tokens.is_some_and(|max| max <= 0) {
+            return Err(ProjectBudgetError::Invalid(
+                "max_tokens must be positive".to_string(),
+            ));
+        }
+        if data
+            .max_cost_usd
+            .is_some_and(|max| !max.is_finite() || max <= 0.0)
+        {
+            return Err(ProjectBudgetError::Invalid(
+                "max_cost_usd must be positive".to_string(),
+            ));
+        }
+
+        Ok(sqlx::query_as::<_, ProjectBudget>(&format!(
+            "INSERT INTO project_budgets (project_id, max_tokens, max_cost_usd, period)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT(project_id) DO UPDATE SET
+                 max_tokens = excluded.max_tokens,
+                 max_cost_usd = excluded.max_cost_usd,
+                 period = excluded.period,
+                 updated_at = datetime('now', 'subsec')
+             RETURNING {PROJECT_BUDGET_COLUMNS}"
+        ))
+        .bind(project_id)
+        .bind(data.max_tokens)
+        .bind(data.max_cost_usd)
+        .bind(data.period)
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Remove the project's budget so executions are no longer limited
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM project_budgets WHERE project_id = $1")
+            .bind(project_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Whether `usage` reaches any of the budget's limits
+    pub fn is_exceeded_by(&self, usage: &TokenUsageRollup) -> bool {
+        self.max_tokens.is_some_and(|max| usage.total_tokens >= max)
+            || self
+                .max_cost_usd
+                .is_some_and(|max| usage.cost_usd.unwrap_or(0.0) >= max)
+    }
+
+    /// The project's budget and its usage in the current period, if a budget is set
+    pub async fn status_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<ProjectBudgetStatus>, sqlx::Error> {
+        let Some(budget) = Self::find_by_project_id(pool, project_id).await? else {
+            return Ok(None);
+        };
+        let filter = UsageFilter {
+            project_id: Some(project_id),
+            since: budget.period.start(Utc::now()),
+            ..Default::default()
+        };
+        let usage = ExecutionTokenUsage::totals(pool, &filter).await?;
+        let exceeded = budget.is_exceeded_by(&usage);
+        Ok(Some(ProjectBudgetStatus {
+            budget,
+            usage,
+            exceeded,
+        }))
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ProjectMemberError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("User not found")]
+    UserNotFound,
+    #[error("User is not a member of this project")]
+    NotAMember,
+    #[error("A project must keep at least one owner")]
+    LastOwner,
+}
+
+const PROJECT_MEMBER_COLUMNS: &str = "project_id, user_id, role, created_at, updated_at";
+
+/// A user's role in a project. Variants are declared from least to most privileged, so
+/// roles compare with `>=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "project_role", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ProjectRole {
+    /// Read-only access
+    Viewer,
+    /// Create and work on tasks, workspaces and agent sessions
+    Contributor,
+    /// Change project settings, repositories and scripts
+    Maintainer,
+    /// Everything, including deleting the project
+    Owner,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectMember {
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    pub role: ProjectRole,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A membership with enough of the user's profile to show in a member list
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectMemberWithUser {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub member: ProjectMember,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct SetProjectMemberRole {
+    pub role: ProjectRole,
+}
+
+impl ProjectMember {
+    pub async fn find_role(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<ProjectRole>, sqlx::Error> {
+        sqlx::query_scalar::<_, ProjectRole>(
+            "SELECT role FROM project_members WHERE project_id = $1 AND user_id = $2",
+        )
+        .bind(project_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<ProjectMemberWithUser>, sqlx::Error> {
+        sqlx::query_as::<_, ProjectMemberWithUser>(
+            "SELECT m.project_id, m.user_id, m.role, m.created_at, m.updated_at,
+                    u.username, u.display_name, u.avatar_url
+             FROM project_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.project_id = $1
+             ORDER BY m.created_at ASC, u.username ASC",
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Give `user_id` the `role` in `project_id`, replacing any existing role. Demoting
+    /// the last owner is rejected.
+    pub async fn set_role(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: Uuid,
+        role: ProjectRole,
+    ) -> Result<Self, ProjectMemberError> {
+        let user_exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+                .bind(user_id)
+                .fetch_one(pool)
+                .await?;
+        if !user_exists {
+            return Err(ProjectMemberError::UserNotFound);
+        }
+
+        if role != ProjectRole::Owner {
+            Self::ensure_not_last_owner(pool, project_id, user_id).await?;
+        }
+
+        Ok(Self::upsert(pool, project_id, user_id, role).await?)
+    }
+
+    /// Insert or replace a membership without any checks, e.g. for a project's creator
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: Uuid,
+        role: ProjectRole,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, ProjectMember>(&format!(
+            "INSERT INTO project_members (project_id, user_id, role)
+             VALUES ($1, $2, $3)
+             ON CONFLICT(project_id, user_id) DO UPDATE SET
+                 role = excluded.role,
+                 updated_at = datetime('now', 'subsec')
+             RETURNING {PROJECT_MEMBER_COLUMNS}"
+        ))
+        .bind(project_id)
+        .bind(user_id)
+        .bind(role)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Remove `user_id` from `project_id`. Removing the last owner is rejected.
+    pub async fn remove(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), ProjectMemberError> {
+        Self::ensure_not_last_owner(pool, project_id, user_id).await?;
+
+        let result =
+            sqlx::query("DELETE FROM project_members WHERE project_id = $1 AND user_id = $2")
+                .bind(project_id)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(ProjectMemberError::NotAMember);
+        }
+        Ok(())
+    }
+
+    async fn ensure_not_last_owner(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), ProjectMemberError> {
+        if Self::find_role(pool, project_id, user_id).await? != Some(ProjectRole::Owner) {
+            return Ok(());
+        }
+
+        let owners: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM project_members WHERE project_id = $1 AND role = 'owner'",
+        )
+        .bind(project_id)
+        .fetch_one(pool)
+        .await?;
+        if owners <= 1 {
+            return Err(ProjectMemberError::LastOwner);
+        }
+        Ok(())
+    }
+}
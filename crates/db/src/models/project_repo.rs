@@ -7,7 +7,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::{merge::MergeStrategy, repo::Repo};
 
 #[derive(Debug, Error)]
 pub enum ProjectRepoError {
@@ -26,6 +26,14 @@ pub struct ProjectRepo {
     pub repo_id: Uuid,
 }
 
+/// Merge settings of a repository within a project
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectRepoMergeSettings {
+    /// Used by local merges that don't ask for a strategy
+    pub merge_strategy: MergeStrategy,
+}
+
 #[derive(Debug, Clone, Deserialize, TS)]
 pub struct CreateProjectRepo {
     pub display_name: String,
@@ -183,4 +191,41 @@ impl ProjectRepo {
         .fetch_one(executor)
         .await
     }
+
+    /// The default merge strategy of a repository in a project; squash when the
+    /// repository is not in the project
+    pub async fn find_merge_strategy(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<MergeStrategy, sqlx::Error> {
+        let strategy = sqlx::query_scalar::<_, MergeStrategy>(
+            "SELECT merge_strategy FROM project_repos WHERE project_id = $1 AND repo_id = $2",
+        )
+        .bind(project_id)
+        .bind(repo_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(strategy.unwrap_or_default())
+    }
+
+    pub async fn set_merge_strategy(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        strategy: MergeStrategy,
+    ) -> Result<(), ProjectRepoError> {
+        let result = sqlx::query(
+            "UPDATE project_repos SET merge_strategy = $3 WHERE project_id = $1 AND repo_id = $2",
+        )
+        .bind(project_id)
+        .bind(repo_id)
+        .bind(strategy)
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ProjectRepoError::NotFound);
+        }
+        Ok(())
+    }
 }
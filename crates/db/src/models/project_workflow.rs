@@ -0,0 +1,314 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+#[derive(Debug, Error)]
+pub enum ProjectWorkflowError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error("Invalid workflow: {0}")]
+    Invalid(String),
+}
+
+/// Condition that must hold before a task may enter a status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum WorkflowGuard {
+    /// The task has at least the project's `min_approvals_required` approvals
+    Approvals,
+    /// The task is not on hold
+    NoHold,
+    /// Every review conversation on the task's workspaces is resolved
+    ConversationsResolved,
+}
+
+/// Something that happened to a task which can move it automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum WorkflowEvent {
+    /// A coding agent or script started running in one of the task's workspaces
+    AgentStarted,
+    /// The agent finished, failed or was stopped
+    AgentFinished,
+    /// The agent is waiting for a tool approval
+    ApprovalRequested,
+    /// A pending tool approval was answered
+    ApprovalResponded,
+    /// A pull request was opened for the task
+    PrOpened,
+    /// Checks started running on the task's open pull request
+    ChecksPending,
+    /// Checks on the open pull request passed (or there were none)
+    ChecksPassed,
+    /// Checks on the open pull request failed
+    ChecksFailed,
+    /// The pull request was merged and deploy checks on the merge commit are running
+    DeployPending,
+    /// Deploy checks on the merge commit failed
+    DeployFailed,
+    /// The pull request was merged and its deploy checks, if any, passed
+    PrMerged,
+    /// The task branch was merged directly into its target branch
+    BranchMerged,
+}
+
+/// A status change the workflow allows, with the conditions it requires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorkflowTransition {
+    /// Statuses the transition may start from; empty means any status
+    #[serde(default)]
+    pub from: Vec<TaskStatus>,
+    pub to: TaskStatus,
+    #[serde(default)]
+    pub guards: Vec<WorkflowGuard>,
+}
+
+impl WorkflowTransition {
+    fn matches(&self, from: &TaskStatus, to: &TaskStatus) -> bool {
+        &self.to == to && (self.from.is_empty() || self.from.contains(from))
+    }
+}
+
+/// A status change applied automatically when an event happens.
+/// It must still be allowed by a transition, whose guards apply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorkflowAutomation {
+    pub event: WorkflowEvent,
+    /// Statuses the automation applies to; empty means any status
+    #[serde(default)]
+    pub from: Vec<TaskStatus>,
+    pub to: TaskStatus,
+}
+
+/// A project's task-status workflow. Rules are evaluated in order; the first match wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorkflowDefinition {
+    pub transitions: Vec<WorkflowTransition>,
+    #[serde(default)]
+    pub automations: Vec<WorkflowAutomation>,
+}
+
+const ALL_STATUSES: [TaskStatus; 7] = [
+    TaskStatus::Todo,
+    TaskStatus::InProgress,
+    TaskStatus::InReview,
+    TaskStatus::Ci,
+    TaskStatus::Cd,
+    TaskStatus::Done,
+    TaskStatus::Cancelled,
+];
+
+impl Default for WorkflowDefinition {
+    /// Any status change is allowed, except that a task in review needs its approvals
+    /// to be done. Automations follow the agent, approval, PR and deploy lifecycle.
+    fn default() -> Self {
+        let mut transitions = vec![WorkflowTransition {
+            from: vec![TaskStatus::InReview],
+            to: TaskStatus::Done,
+            guards: vec![WorkflowGuard::Approvals],
+        }];
+        transitions.extend(ALL_STATUSES.into_iter().map(|to| WorkflowTransition {
+            from: vec![],
+            to,
+            guards: vec![],
+        }));
+
+        let automation = |event, from: &[TaskStatus], to| WorkflowAutomation {
+            event,
+            from: from.to_vec(),
+            to,
+        };
+        let automations = vec![
+            automation(WorkflowEvent::AgentStarted, &[], TaskStatus::InProgress),
+            automation(WorkflowEvent::AgentFinished, &[], TaskStatus::InReview),
+            automation(
+                WorkflowEvent::ApprovalRequested,
+                &[TaskStatus::InProgress],
+                TaskStatus::InReview,
+            ),
+            automation(
+                WorkflowEvent::ApprovalResponded,
+                &[TaskStatus::InReview],
+                TaskStatus::InProgress,
+            ),
+            automation(
+                WorkflowEvent::ChecksPending,
+                &[TaskStatus::InReview],
+                TaskStatus::Ci,
+            ),
+            automation(
+                WorkflowEvent::ChecksPassed,
+                &[TaskStatus::Ci],
+                TaskStatus::InReview,
+            ),
+            automation(
+                WorkflowEvent::ChecksFailed,
+                &[TaskStatus::Ci],
+                TaskStatus::InReview,
+            ),
+            automation(WorkflowEvent::DeployPending, &[], TaskStatus::Cd),
+            automation(WorkflowEvent::DeployFailed, &[], TaskStatus::InReview),
+            automation(WorkflowEvent::PrMerged, &[], TaskStatus::Done),
+            automation(WorkflowEvent::BranchMerged, &[], TaskStatus::Done),
+        ];
+
+        Self {
+            transitions,
+            automations,
+        }
+    }
+}
+
+impl WorkflowDefinition {
+    /// The first transition allowing `from` -> `to`, if any
+    pub fn find_transition(
+        &self,
+        from: &TaskStatus,
+        to: &TaskStatus,
+    ) -> Option<&WorkflowTransition> {
+        self.transitions.iter().find(|t| t.matches(from, to))
+    }
+
+    /// The first automation for `event` that applies to a task in `from`
+    pub fn find_automation(
+        &self,
+        event: WorkflowEvent,
+        from: &TaskStatus,
+    ) -> Option<&WorkflowAutomation> {
+        self.automations
+            .iter()
+            .find(|a| a.event == event && (a.from.is_empty() || a.from.contains(from)))
+    }
+
+    /// Whether a task in `status` is waiting on deploy checks: a DeployPending automation
+    /// moves tasks there and a PrMerged automation would move it on
+    pub fn awaits_deploy(&self, status: &TaskStatus) -> bool {
+        self.automations
+            .iter()
+            .any(|a| a.event == WorkflowEvent::DeployPending && &a.to == status)
+            && self
+                .find_automation(WorkflowEvent::PrMerged, status)
+                .is_some_and(|a| &a.to != status)
+    }
+
+    /// Reject definitions whose automations could never be applied
+    pub fn validate(&self) -> Result<(), ProjectWorkflowError> {
+        for automation in &self.automations {
+            let sources: &[TaskStatus] = if automation.from.is_empty() {
+                &ALL_STATUSES
+            } else {
+                &automation.from
+            };
+            let reachable = sources.iter().any(|from| {
+                from == &automation.to || self.find_transition(from, &automation.to).is_some()
+            });
+            if !reachable {
+                return Err(ProjectWorkflowError::Invalid(format!(
+                    "automation on {:?} moves tasks to {} but no transition allows it",
+                    automation.event, automation.to
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ProjectWorkflowRow {
+    project_id: Uuid,
+    definition: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// A workflow stored for a project, overriding the default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectWorkflow {
+    pub project_id: Uuid,
+    pub definition: WorkflowDefinition,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<ProjectWorkflowRow> for ProjectWorkflow {
+    type Error = ProjectWorkflowError;
+    fn try_from(r: ProjectWorkflowRow) -> Result<Self, ProjectWorkflowError> {
+        Ok(ProjectWorkflow {
+            project_id: r.project_id,
+            definition: serde_json::from_str(&r.definition)?,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        })
+    }
+}
+
+impl ProjectWorkflow {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, ProjectWorkflowError> {
+        let row = sqlx::query_as::<_, ProjectWorkflowRow>(
+            "SELECT project_id, definition, created_at, updated_at
+             FROM project_workflows WHERE project_id = $1",
+        )
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+        row.map(ProjectWorkflow::try_from).transpose()
+    }
+
+    /// The project's workflow, or the default one if none is stored
+    pub async fn definition_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<WorkflowDefinition, ProjectWorkflowError> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .map(|w| w.definition)
+            .unwrap_or_default())
+    }
+
+    /// Store (or replace) the project's workflow
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        definition: &WorkflowDefinition,
+    ) -> Result<Self, ProjectWorkflowError> {
+        definition.validate()?;
+        let json = serde_json::to_string(definition)?;
+        let row = sqlx::query_as::<_, ProjectWorkflowRow>(
+            "INSERT INTO project_workflows (project_id, definition)
+             VALUES ($1, $2)
+             ON CONFLICT(project_id) DO UPDATE SET
+                 definition = excluded.definition,
+                 updated_at = datetime('now', 'subsec')
+             RETURNING project_id, definition, created_at, updated_at",
+        )
+        .bind(project_id)
+        .bind(json)
+        .fetch_one(pool)
+        .await?;
+        ProjectWorkflow::try_from(row)
+    }
+
+    /// Remove the project's workflow so the default applies again
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM project_workflows WHERE project_id = $1")
+            .bind(project_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::scratch::DraftFollowUpData;
+
+#[derive(Debug, Error)]
+pub enum QueuedMessageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error("Queued message not found")]
+    NotFound,
+    #[error("Reorder must list every queued message of the session exactly once")]
+    InvalidOrder,
+}
+
+const QUEUED_MESSAGE_COLUMNS: &str =
+    "id, session_id, position, message, executor_profile_id, queued_at, updated_at";
+
+#[derive(Debug, Clone, FromRow)]
+struct QueuedMessageRow {
+    id: Uuid,
+    session_id: Uuid,
+    position: i64,
+    message: String,
+    executor_profile_id: String,
+    queued_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// A follow-up message waiting to run after the session's current execution
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    /// The session this message is queued for
+    pub session_id: Uuid,
+    /// Order within the session's queue; lower runs first
+    #[ts(type = "number")]
+    pub position: i64,
+    /// The follow-up data (message + variant)
+    pub data: DraftFollowUpData,
+    /// Timestamp when the message was queued
+    pub queued_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<QueuedMessageRow> for QueuedMessage {
+    type Error = QueuedMessageError;
+    fn try_from(r: QueuedMessageRow) -> Result<Self, QueuedMessageError> {
+        Ok(QueuedMessage {
+            id: r.id,
+            session_id: r.session_id,
+            position: r.position,
+            data: DraftFollowUpData {
+                message: r.message,
+                executor_profile_id: serde_json::from_str(&r.executor_profile_id)?,
+            },
+            queued_at: r.queued_at,
+            updated_at: r.updated_at,
+        })
+    }
+}
+
+impl QueuedMessage {
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<Self>, QueuedMessageError> {
+        let row = sqlx::query_as::<_, QueuedMessageRow>(&format!(
+            "SELECT {QUEUED_MESSAGE_COLUMNS} FROM queued_messages WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+        row.map(QueuedMessage::try_from).transpose()
+    }
+
+    /// All queued messages for a session, in execution order
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, QueuedMessageError> {
+        let rows = sqlx::query_as::<_, QueuedMessageRow>(&format!(
+            "SELECT {QUEUED_MESSAGE_COLUMNS} FROM queued_messages
+             WHERE session_id = $1
+             ORDER BY position ASC, queued_at ASC"
+        ))
+        .bind(session_id)
+        .fetch_all(pool)
+        .await?;
+        rows.into_iter().map(QueuedMessage::try_from).collect()
+    }
+
+    pub async fn exists_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM queued_messages WHERE session_id = $1)",
+        )
+        .bind(session_id)
+        .fetch_one(pool)
+        .await?;
+        Ok(exists)
+    }
+
+    /// Append a message to the end of the session's queue
+    pub async fn enqueue(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, QueuedMessageError> {
+        let executor_profile_id = serde_json::to_string(&data.executor_profile_id)?;
+        let row = sqlx::query_as::<_, QueuedMessageRow>(&format!(
+            "INSERT INTO queued_messages (id, session_id, position, message, executor_profile_id)
+             SELECT $1, $2, COALESCE(MAX(position), -1) + 1, $3, $4
+             FROM queued_messages WHERE session_id = $2
+             RETURNING {QUEUED_MESSAGE_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(session_id)
+        .bind(&data.message)
+        .bind(executor_profile_id)
+        .fetch_one(pool)
+        .await?;
+        QueuedMessage::try_from(row)
+    }
+
+    /// Replace the content of a queued message, keeping its position
+    pub async fn update_data(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, QueuedMessageError> {
+        let executor_profile_id = serde_json::to_string(&data.executor_profile_id)?;
+        let row = sqlx::query_as::<_, QueuedMessageRow>(&format!(
+            "UPDATE queued_messages
+             SET message = $2, executor_profile_id = $3, updated_at = datetime('now', 'subsec')
+             WHERE id = $1
+             RETURNING {QUEUED_MESSAGE_COLUMNS}"
+        ))
+        .bind(id)
+        .bind(&data.message)
+        .bind(executor_profile_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(QueuedMessageError::NotFound)?;
+        QueuedMessage::try_from(row)
+    }
+
+    /// Rewrite the positions of a session's queue. `ordered_ids` must contain every
+    /// queued message of the session exactly once.
+    pub async fn reorder(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        ordered_ids: &[Uuid],
+    ) -> Result<Vec<Self>, QueuedMessageError> {
+        let mut tx = pool.begin().await?;
+
+        let existing: Vec<Uuid> =
+            sqlx::query_scalar("SELECT id FROM queued_messages WHERE session_id = $1")
+                .bind(session_id)
+                .fetch_all(&mut *tx)
+                .await?;
+        let existing: HashSet<Uuid> = existing.into_iter().collect();
+        let requested: HashSet<Uuid> = ordered_ids.iter().copied().collect();
+        if requested.len() != ordered_ids.len() || requested != existing {
+            return Err(QueuedMessageError::InvalidOrder);
+        }
+
+        for (position, id) in ordered_ids.iter().enumerate() {
+            sqlx::query(
+                "UPDATE queued_messages
+                 SET position = $2, updated_at = datetime('now', 'subsec')
+                 WHERE id = $1",
+            )
+            .bind(id)
+            .bind(position as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_session_id(pool, session_id).await
+    }
+
+    /// Remove and return the first message in the session's queue
+    pub async fn take_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, QueuedMessageError> {
+        let row = sqlx::query_as::<_, QueuedMessageRow>(&format!(
+            "DELETE FROM queued_messages
+             WHERE id = (
+                 SELECT id FROM queued_messages
+                 WHERE session_id = $1
+                 ORDER BY position ASC, queued_at ASC
+                 LIMIT 1
+             )
+             RETURNING {QUEUED_MESSAGE_COLUMNS}"
+        ))
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await?;
+        row.map(QueuedMessage::try_from).transpose()
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM queued_messages WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM queued_messages WHERE session_id = $1")
+            .bind(session_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
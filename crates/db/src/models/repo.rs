@@ -92,6 +92,23 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub default_target_branch: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub verify_script: Option<Option<String>>,
+}
+
+/// Settings of a repo that are not part of [`Repo`]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RepoVerifySettings {
+    /// Script that must pass against the merge of a workspace branch into its target
+    /// branch before the workspace can be merged or a pull request opened
+    pub verify_script: Option<String>,
 }
 
 impl Repo {
@@ -227,6 +244,17 @@ impl Repo {
         Ok(result.rows_affected())
     }
 
+    pub async fn find_verify_script(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, Option<String>>("SELECT verify_script FROM repos WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map(Option::flatten)
+    }
+
     pub async fn list_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Repo,
@@ -289,6 +317,13 @@ impl Repo {
             None => existing.default_target_branch,
             Some(v) => v.clone(),
         };
+        if let Some(verify_script) = &payload.verify_script {
+            sqlx::query("UPDATE repos SET verify_script = $1 WHERE id = $2")
+                .bind(verify_script)
+                .bind(id)
+                .execute(pool)
+                .await?;
+        }
 
         sqlx::query_as!(
             Repo,
@@ -104,7 +104,7 @@ impl From<User> for ConversationUser {
 }
 
 /// A message with its author's information
-#[derive(Debug, Clone, Serialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct MessageWithAuthor {
     #[serde(flatten)]
@@ -113,7 +113,7 @@ pub struct MessageWithAuthor {
 }
 
 /// A conversation with all its messages and user info
-#[derive(Debug, Clone, Serialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ConversationWithMessages {
     #[serde(flatten)]
@@ -135,14 +135,14 @@ pub struct CreateConversation {
 }
 
 /// Request to add a message to a conversation
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct CreateMessage {
     pub content: String,
 }
 
 /// Request to resolve a conversation
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ResolveConversation {
     pub summary: String,
@@ -198,6 +198,22 @@ impl ReviewConversation {
         .await
     }
 
+    /// Count unresolved conversations across all workspaces of a task
+    pub async fn count_unresolved_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"SELECT COUNT(*)
+               FROM review_conversations rc
+               JOIN workspaces w ON w.id = rc.workspace_id
+               WHERE w.task_id = $1 AND rc.is_resolved = 0"#,
+        )
+        .bind(task_id)
+        .fetch_one(pool)
+        .await
+    }
+
     /// Find conversations by file path
     pub async fn find_by_file_path(
         pool: &SqlitePool,
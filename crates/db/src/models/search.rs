@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Largest number of hits `SearchHit::search` returns
+pub const MAX_SEARCH_RESULTS: i64 = 200;
+
+/// What a search hit matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "search_hit_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum SearchHitKind {
+    /// The task's title or description
+    Task,
+    /// A normalized entry of a coding agent conversation
+    ConversationEntry,
+    /// A comment in a review conversation
+    ReviewMessage,
+}
+
+/// One match, with the ids needed to link to it. Fields that do not apply to the
+/// hit's kind are `null`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Option<Uuid>,
+    pub session_id: Option<Uuid>,
+    pub execution_process_id: Option<Uuid>,
+    /// Index of the entry in the execution's normalized conversation
+    #[ts(type = "number | null")]
+    pub entry_index: Option<i64>,
+    /// `type` of the matched conversation entry, e.g. `assistant_message`
+    pub entry_type: Option<String>,
+    pub review_conversation_id: Option<Uuid>,
+    /// The matched text around the search terms
+    pub snippet: String,
+    /// Lower is a better match
+    pub rank: f64,
+}
+
+/// Restricts which hits are returned. Unset fields do not filter.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchScope {
+    pub project_id: Option<Uuid>,
+    /// Only projects this user is a member of
+    pub member_user_id: Option<Uuid>,
+}
+
+/// Turn free text into an FTS5 query that matches entries containing every word.
+/// Each word is quoted so that characters such as `-`, `:` and `*` in the input
+/// are searched for rather than parsed as query syntax; a trailing `*` on a word
+/// is kept as a prefix match. Returns `None` when there is nothing to search for.
+pub fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .filter_map(|word| {
+            let (word, prefix) = match word.strip_suffix('*') {
+                Some(stem) => (stem, true),
+                None => (word, false),
+            };
+            if !word.chars().any(char::is_alphanumeric) {
+                return None;
+            }
+            let quoted = format!("\"{}\"", word.replace('"', "\"\""));
+            Some(if prefix { quoted + "*" } else { quoted })
+        })
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+const SCOPE_FILTER: &str = "($2 IS NULL OR t.project_id = $2)
+       AND ($3 IS NULL OR t.project_id IN
+            (SELECT project_id FROM project_members WHERE user_id = $3))";
+
+impl SearchHit {
+    /// Hits for `text` across tasks, agent conversations and review comments, best
+    /// first. `limit` is clamped to `1..=MAX_SEARCH_RESULTS`.
+    pub async fn search(
+        pool: &SqlitePool,
+        text: &str,
+        scope: SearchScope,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let Some(query) = fts_query(text) else {
+            return Ok(Vec::new());
+        };
+
+        sqlx::query_as::<_, SearchHit>(&format!(
+            "SELECT 'task' AS kind, t.project_id, t.id AS task_id, t.title AS task_title,
+                    NULL AS workspace_id, NULL AS session_id, NULL AS execution_process_id,
+                    NULL AS entry_index, NULL AS entry_type, NULL AS review_conversation_id,
+                    snippet(tasks_fts, -1, '', '', '…', 16) AS snippet,
+                    bm25(tasks_fts) AS rank
+             FROM tasks_fts
+             JOIN tasks t ON t.rowid = tasks_fts.rowid
+             WHERE tasks_fts MATCH $1 AND {SCOPE_FILTER}
+             UNION ALL
+             SELECT 'conversation_entry', t.project_id, t.id, t.title,
+                    w.id, s.id, e.execution_process_id,
+                    e.entry_index, e.entry_type, NULL,
+                    snippet(search_conversation_entries_fts, 0, '', '', '…', 16),
+                    bm25(search_conversation_entries_fts)
+             FROM search_conversation_entries_fts
+             JOIN search_conversation_entries e ON e.id = search_conversation_entries_fts.rowid
+             JOIN execution_processes ep ON ep.id = e.execution_process_id
+             JOIN sessions s ON s.id = ep.session_id
+             JOIN workspaces w ON w.id = s.workspace_id
+             JOIN tasks t ON t.id = w.task_id
+             WHERE search_conversation_entries_fts MATCH $1 AND {SCOPE_FILTER}
+             UNION ALL
+             SELECT 'review_message', t.project_id, t.id, t.title,
+                    w.id, NULL, NULL,
+                    NULL, NULL, rc.id,
+                    snippet(review_messages_fts, 0, '', '', '…', 16),
+                    bm25(review_messages_fts)
+             FROM review_messages_fts
+             JOIN review_conversation_messages m ON m.rowid = review_messages_fts.rowid
+             JOIN review_conversations rc ON rc.id = m.conversation_id
+             JOIN workspaces w ON w.id = rc.workspace_id
+             JOIN tasks t ON t.id = w.task_id
+             WHERE review_messages_fts MATCH $1 AND {SCOPE_FILTER}
+             ORDER BY rank
+             LIMIT $4"
+        ))
+        .bind(query)
+        .bind(scope.project_id)
+        .bind(scope.member_user_id)
+        .bind(limit.clamp(1, MAX_SEARCH_RESULTS))
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// The searchable text of one normalized conversation entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversationEntryText {
+    pub entry_index: i64,
+    pub entry_type: String,
+    pub content: String,
+}
+
+pub struct ConversationSearchIndex;
+
+impl ConversationSearchIndex {
+    /// Add or replace the indexed text of `entries` of an execution
+    pub async fn upsert_entries(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entries: &[ConversationEntryText],
+    ) -> Result<(), sqlx::Error> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut tx = pool.begin().await?;
+        for entry in entries {
+            sqlx::query(
+                "INSERT INTO search_conversation_entries
+                     (execution_process_id, entry_index, entry_type, content)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (execution_process_id, entry_index) DO UPDATE
+                 SET entry_type = excluded.entry_type, content = excluded.content
+                 WHERE entry_type != excluded.entry_type OR content != excluded.content",
+            )
+            .bind(execution_process_id)
+            .bind(entry.entry_index)
+            .bind(&entry.entry_type)
+            .bind(&entry.content)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Drop the indexed text of entries that no longer have any
+    pub async fn remove_entries(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entry_indexes: &[i64],
+    ) -> Result<(), sqlx::Error> {
+        for entry_index in entry_indexes {
+            sqlx::query(
+                "DELETE FROM search_conversation_entries
+                 WHERE execution_process_id = $1 AND entry_index = $2",
+            )
+            .bind(execution_process_id)
+            .bind(entry_index)
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Replace everything indexed for an execution with `entries`, and record that its
+    /// whole conversation is indexed
+    pub async fn replace_entries(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entries: &[ConversationEntryText],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM search_conversation_entries WHERE execution_process_id = $1")
+            .bind(execution_process_id)
+            .execute(&mut *tx)
+            .await?;
+        for entry in entries {
+            sqlx::query(
+                "INSERT INTO search_conversation_entries
+                     (execution_process_id, entry_index, entry_type, content)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(execution_process_id)
+            .bind(entry.entry_index)
+            .bind(&entry.entry_type)
+            .bind(&entry.content)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(
+            "INSERT OR IGNORE INTO search_indexed_executions (execution_process_id) VALUES ($1)",
+        )
+        .bind(execution_process_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Record that the whole conversation of an execution is indexed
+    pub async fn mark_indexed(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO search_indexed_executions (execution_process_id) VALUES ($1)",
+        )
+        .bind(execution_process_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Coding agent executions that have stopped without their whole conversation
+    /// being indexed, oldest first
+    pub async fn find_unindexed_executions(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT ep.id
+             FROM execution_processes ep
+             WHERE ep.run_reason = 'codingagent'
+               AND ep.status NOT IN ('queued', 'running')
+               AND NOT EXISTS (
+                   SELECT 1 FROM search_indexed_executions sie
+                   WHERE sie.execution_process_id = ep.id
+               )
+             ORDER BY ep.created_at ASC",
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
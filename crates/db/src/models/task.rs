@@ -175,6 +175,9 @@ pub struct UpdateTask {
     pub image_ids: Option<Vec<Uuid>>,
     /// Set to Some(user_id) to assign, or None to not change, or Some(null) to unassign
     pub assignee_user_id: Option<Option<Uuid>>,
+    /// Move the task to this status; must be allowed by the project workflow
+    #[serde(default)]
+    pub status: Option<TaskStatus>,
 }
 
 impl Task {
@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    task::{Task, TaskStatus},
+    workspace_repo::CreateWorkspaceRepo,
+};
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("A task cannot be blocked by itself")]
+    SelfDependency,
+    #[error("Dependencies can only be added between tasks in the same project")]
+    DifferentProjects,
+    #[error("Adding this dependency would create a cycle")]
+    Cycle,
+    #[error("Auto-start requires at least one repository")]
+    NoRepos,
+}
+
+/// "`task_id` is blocked by `blocked_by_task_id`"
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub blocked_by_task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateTaskDependency {
+    pub blocked_by_task_id: Uuid,
+}
+
+/// The other side of a dependency, with enough detail to show whether it is resolved
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DependencyTask {
+    pub id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+}
+
+/// Workspace settings used to start a blocked task once its blockers are done
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskAutoStart {
+    pub task_id: Uuid,
+    #[sqlx(json)]
+    pub executor_profile_id: ExecutorProfileId,
+    #[sqlx(json)]
+    pub repos: Vec<CreateWorkspaceRepo>,
+    pub requested_by_user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertTaskAutoStart {
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<CreateWorkspaceRepo>,
+}
+
+/// Everything a task is blocked by or blocking, plus its auto-start settings
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskDependencies {
+    pub blocked_by: Vec<DependencyTask>,
+    pub blocking: Vec<DependencyTask>,
+    pub auto_start: Option<TaskAutoStart>,
+}
+
+const TASK_AUTO_START_COLUMNS: &str =
+    "task_id, executor_profile_id, repos, requested_by_user_id, created_at, updated_at";
+
+impl TaskDependency {
+    /// Tasks that block `task_id`
+    pub async fn find_blockers(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<DependencyTask>, sqlx::Error> {
+        sqlx::query_as::<_, DependencyTask>(
+            "SELECT t.id, t.title, t.status
+             FROM task_dependencies d
+             JOIN tasks t ON t.id = d.blocked_by_task_id
+             WHERE d.task_id = $1
+             ORDER BY t.created_at ASC",
+        )
+        .bind(task_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks that `task_id` blocks
+    pub async fn find_dependents(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<DependencyTask>, sqlx::Error> {
+        sqlx::query_as::<_, DependencyTask>(
+            "SELECT t.id, t.title, t.status
+             FROM task_dependencies d
+             JOIN tasks t ON t.id = d.task_id
+             WHERE d.blocked_by_task_id = $1
+             ORDER BY t.created_at ASC",
+        )
+        .bind(task_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Blockers of `task_id` that are not done yet
+    pub async fn find_unresolved_blockers(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<DependencyTask>, sqlx::Error> {
+        sqlx::query_as::<_, DependencyTask>(
+            "SELECT t.id, t.title, t.status
+             FROM task_dependencies d
+             JOIN tasks t ON t.id = d.blocked_by_task_id
+             WHERE d.task_id = $1 AND t.status != 'done'
+             ORDER BY t.created_at ASC",
+        )
+        .bind(task_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record that `task` is blocked by `blocked_by_task_id`. Both tasks must belong to
+    /// the same project and the new edge must not close a cycle.
+    pub async fn create(
+        pool: &SqlitePool,
+        task: &Task,
+        blocked_by_task_id: Uuid,
+    ) -> Result<Self, TaskDependencyError> {
+        if task.id == blocked_by_task_id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+        let blocker = Task::find_by_id(pool, blocked_by_task_id)
+            .await?
+            .ok_or(TaskDependencyError::TaskNotFound)?;
+        if blocker.project_id != task.project_id {
+            return Err(TaskDependencyError::DifferentProjects);
+        }
+
+        // A cycle exists if the blocker is already (transitively) blocked by the task
+        let creates_cycle: bool = sqlx::query_scalar(
+            "WITH RECURSIVE blockers(id) AS (
+                 SELECT blocked_by_task_id FROM task_dependencies WHERE task_id = $1
+                 UNION
+                 SELECT d.blocked_by_task_id
+                 FROM task_dependencies d
+                 JOIN blockers b ON d.task_id = b.id
+             )
+             SELECT EXISTS(SELECT 1 FROM blockers WHERE id = $2)",
+        )
+        .bind(blocked_by_task_id)
+        .bind(task.id)
+        .fetch_one(pool)
+        .await?;
+        if creates_cycle {
+            return Err(TaskDependencyError::Cycle);
+        }
+
+        Ok(sqlx::query_as::<_, TaskDependency>(
+            "INSERT INTO task_dependencies (task_id, blocked_by_task_id)
+             VALUES ($1, $2)
+             ON CONFLICT(task_id, blocked_by_task_id) DO UPDATE SET task_id = excluded.task_id
+             RETURNING task_id, blocked_by_task_id, created_at",
+        )
+        .bind(task.id)
+        .bind(blocked_by_task_id)
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        blocked_by_task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND blocked_by_task_id = $2",
+        )
+        .bind(task_id)
+        .bind(blocked_by_task_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Blockers, dependents and auto-start settings of `task_id`
+    pub async fn find_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<TaskDependencies, sqlx::Error> {
+        Ok(TaskDependencies {
+            blocked_by: Self::find_blockers(pool, task_id).await?,
+            blocking: Self::find_dependents(pool, task_id).await?,
+            auto_start: TaskAutoStart::find_by_task_id(pool, task_id).await?,
+        })
+    }
+}
+
+impl TaskAutoStart {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, TaskAutoStart>(&format!(
+            "SELECT {TASK_AUTO_START_COLUMNS} FROM task_auto_starts WHERE task_id = $1"
+        ))
+        .bind(task_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Auto-starts whose task has at least one blocker and every blocker is done
+    pub async fn find_ready(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, TaskAutoStart>(&format!(
+            "SELECT {TASK_AUTO_START_COLUMNS} FROM task_auto_starts a
+             WHERE EXISTS (SELECT 1 FROM task_dependencies d WHERE d.task_id = a.task_id)
+               AND NOT EXISTS (
+                   SELECT 1 FROM task_dependencies d
+                   JOIN tasks b ON b.id = d.blocked_by_task_id
+                   WHERE d.task_id = a.task_id AND b.status != 'done'
+               )
+             ORDER BY a.created_at ASC"
+        ))
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Store (or replace) the settings used to start `task_id` once it is unblocked
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &UpsertTaskAutoStart,
+        requested_by_user_id: Option<Uuid>,
+    ) -> Result<Self, TaskDependencyError> {
+        if data.repos.is_empty() {
+            return Err(TaskDependencyError::NoRepos);
+        }
+        Ok(sqlx::query_as::<_, TaskAutoStart>(&format!(
+            "INSERT INTO task_auto_starts
+                 (task_id, executor_profile_id, repos, requested_by_user_id)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT(task_id) DO UPDATE SET
+                 executor_profile_id = excluded.executor_profile_id,
+                 repos = excluded.repos,
+                 requested_by_user_id = excluded.requested_by_user_id,
+                 updated_at = datetime('now', 'subsec')
+             RETURNING {TASK_AUTO_START_COLUMNS}"
+        ))
+        .bind(task_id)
+        .bind(sqlx::types::Json(&data.executor_profile_id))
+        .bind(sqlx::types::Json(&data.repos))
+        .bind(requested_by_user_id)
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Remove the auto-start. Returns the number of rows removed.
+    pub async fn delete(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM task_auto_starts WHERE task_id = $1")
+            .bind(task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use executors::logs::ModelTokenUsage;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+const TOKEN_USAGE_COLUMNS: &str = "id, execution_process_id, model, input_tokens, output_tokens, \
+     cache_read_tokens, cache_creation_tokens, cost_usd, created_at, updated_at";
+
+/// Tokens one model consumed during an execution process
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutionTokenUsage {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub model: String,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_read_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_creation_tokens: i64,
+    /// Cost reported by the agent; `null` when the agent does not report costs
+    pub cost_usd: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How usage rollups are grouped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum UsageGroupBy {
+    Model,
+    Task,
+    Project,
+    User,
+    Day,
+}
+
+impl UsageGroupBy {
+    /// SQL for the group's id and label
+    fn columns(self) -> (&'static str, &'static str) {
+        match self {
+            UsageGroupBy::Model => ("NULL", "u.model"),
+            UsageGroupBy::Task => ("t.id", "t.title"),
+            UsageGroupBy::Project => ("p.id", "p.name"),
+            UsageGroupBy::User => ("s.initiated_by_user_id", "usr.username"),
+            UsageGroupBy::Day => ("NULL", "date(u.created_at)"),
+        }
+    }
+}
+
+/// Restricts which usage records a rollup covers. Unset fields do not filter.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UsageFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    /// Only usage recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only usage recorded before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Summed token usage for one group of a rollup
+#[derive(Debug, Clone, Default, PartialEq, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TokenUsageRollup {
+    /// Task, project or user id, depending on the grouping
+    pub group_id: Option<Uuid>,
+    /// Model name, task title, project name, username or day (`YYYY-MM-DD`)
+    pub label: Option<String>,
+    #[ts(type = "number")]
+    pub execution_count: i64,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_read_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_creation_tokens: i64,
+    #[ts(type = "number")]
+    pub total_tokens: i64,
+    /// Sum of the costs agents reported; `null` when none reported a cost
+    pub cost_usd: Option<f64>,
+}
+
+impl ExecutionTokenUsage {
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ExecutionTokenUsage>(&format!(
+            "SELECT {TOKEN_USAGE_COLUMNS} FROM execution_process_token_usage
+             WHERE execution_process_id = $1
+             ORDER BY model ASC"
+        ))
+        .bind(execution_process_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Store the execution's cumulative usage per model, replacing earlier reports
+    pub async fn record(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        usage: &[ModelTokenUsage],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for model_usage in usage {
+            sqlx::query(
+                "INSERT INTO execution_process_token_usage
+                     (id, execution_process_id, model, input_tokens, output_tokens,
+                      cache_read_tokens, cache_creation_tokens, cost_usd)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT(execution_process_id, model) DO UPDATE SET
+                     input_tokens = excluded.input_tokens,
+                     output_tokens = excluded.output_tokens,
+                     cache_read_tokens = excluded.cache_read_tokens,
+                     cache_creation_tokens = excluded.cache_creation_tokens,
+                     cost_usd = excluded.cost_usd,
+                     updated_at = datetime('now', 'subsec')",
+            )
+            .bind(Uuid::new_v4())
+            .bind(execution_process_id)
+            .bind(&model_usage.model)
+            .bind(model_usage.input_tokens as i64)
+            .bind(model_usage.output_tokens as i64)
+            .bind(model_usage.cache_read_tokens as i64)
+            .bind(model_usage.cache_creation_tokens as i64)
+            .bind(model_usage.cost_usd)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Usage matching `filter`, summed per group
+    pub async fn rollup(
+        pool: &SqlitePool,
+        filter: &UsageFilter,
+        group_by: UsageGroupBy,
+    ) -> Result<Vec<TokenUsageRollup>, sqlx::Error> {
+        let (id, label) = group_by.columns();
+        let group = if id == "NULL" {
+            label.to_string()
+        } else {
+            format!("{id}, {label}")
+        };
+        let order = match group_by {
+            UsageGroupBy::Day => "label ASC",
+            _ => "total_tokens DESC, label ASC",
+        };
+        let sql = format!(
+            "SELECT {id} AS group_id, {label} AS label, {ROLLUP_AGGREGATES} {ROLLUP_FROM}
+             GROUP BY {group} ORDER BY {order}"
+        );
+        sqlx::query_as::<_, TokenUsageRollup>(&sql)
+            .bind(filter.project_id)
+            .bind(filter.task_id)
+            .bind(filter.user_id)
+            .bind(filter.since)
+            .bind(filter.until)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Usage matching `filter`, summed into a single total
+    pub async fn totals(
+        pool: &SqlitePool,
+        filter: &UsageFilter,
+    ) -> Result<TokenUsageRollup, sqlx::Error> {
+        let sql =
+            format!("SELECT NULL AS group_id, NULL AS label, {ROLLUP_AGGREGATES} {ROLLUP_FROM}");
+        sqlx::query_as::<_, TokenUsageRollup>(&sql)
+            .bind(filter.project_id)
+            .bind(filter.task_id)
+            .bind(filter.user_id)
+            .bind(filter.since)
+            .bind(filter.until)
+            .fetch_one(pool)
+            .await
+    }
+}
+
+const ROLLUP_AGGREGATES: &str = "COUNT(DISTINCT u.execution_process_id) AS execution_count,
+     COALESCE(SUM(u.input_tokens), 0) AS input_tokens,
+     COALESCE(SUM(u.output_tokens), 0) AS output_tokens,
+     COALESCE(SUM(u.cache_read_tokens), 0) AS cache_read_tokens,
+     COALESCE(SUM(u.cache_creation_tokens), 0) AS cache_creation_tokens,
+     COALESCE(SUM(u.input_tokens + u.output_tokens + u.cache_read_tokens
+                  + u.cache_creation_tokens), 0) AS total_tokens,
+     SUM(u.cost_usd) AS cost_usd";
+
+const ROLLUP_FROM: &str = "FROM execution_process_token_usage u
+     JOIN execution_processes ep ON ep.id = u.execution_process_id
+     JOIN sessions s ON s.id = ep.session_id
+     JOIN workspaces w ON w.id = s.workspace_id
+     JOIN tasks t ON t.id = w.task_id
+     JOIN projects p ON p.id = t.project_id
+     LEFT JOIN users usr ON usr.id = s.initiated_by_user_id
+     WHERE ($1 IS NULL OR t.project_id = $1)
+       AND ($2 IS NULL OR t.id = $2)
+       AND ($3 IS NULL OR s.initiated_by_user_id = $3)
+       AND ($4 IS NULL OR u.created_at >= datetime($4))
+       AND ($5 IS NULL OR u.created_at < datetime($5))";
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+const TOOL_APPROVAL_COLUMNS: &str = "id, execution_process_id, tool_name, tool_call_id, \
+     tool_input, status, reason, responded_by_user_id, policy_rule, created_at, timeout_at, \
+     responded_at, latency_ms";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "tool_approval_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ToolApprovalStatus {
+    Pending,
+    Approved,
+    Denied,
+    TimedOut,
+    /// The server stopped before the approval was answered, so the agent that asked
+    /// for it could no longer receive an answer
+    Expired,
+}
+
+/// A tool approval an agent asked for and how it was answered
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ToolApproval {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub tool_call_id: String,
+    #[sqlx(json)]
+    pub tool_input: Value,
+    pub status: ToolApprovalStatus,
+    pub reason: Option<String>,
+    /// The user who answered; `null` for policy decisions, timeouts and single-user
+    /// servers
+    pub responded_by_user_id: Option<Uuid>,
+    /// The approval policy rule that answered without asking anyone
+    pub policy_rule: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+    /// Time from the request to its answer
+    #[ts(type = "number | null")]
+    pub latency_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateToolApproval {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub tool_call_id: String,
+    pub tool_input: Value,
+    pub created_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+}
+
+/// How a pending approval was answered
+#[derive(Debug, Clone)]
+pub struct ToolApprovalResponse {
+    pub status: ToolApprovalStatus,
+    pub reason: Option<String>,
+    pub responded_by_user_id: Option<Uuid>,
+    pub policy_rule: Option<String>,
+}
+
+impl ToolApproval {
+    pub async fn create(pool: &SqlitePool, data: &CreateToolApproval) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, ToolApproval>(&format!(
+            "INSERT INTO tool_approvals (id, execution_process_id, tool_name, tool_call_id,
+                                         tool_input, created_at, timeout_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {TOOL_APPROVAL_COLUMNS}"
+        ))
+        .bind(&data.id)
+        .bind(data.execution_process_id)
+        .bind(&data.tool_name)
+        .bind(&data.tool_call_id)
+        .bind(sqlx::types::Json(&data.tool_input))
+        .bind(data.created_at)
+        .bind(data.timeout_at)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record the answer to a pending approval. Returns `None` if the approval is
+    /// unknown or was already answered.
+    pub async fn respond(
+        pool: &SqlitePool,
+        id: &str,
+        response: &ToolApprovalResponse,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ToolApproval>(&format!(
+            "UPDATE tool_approvals
+             SET status = $2,
+                 reason = $3,
+                 responded_by_user_id = $4,
+                 policy_rule = $5,
+                 responded_at = datetime('now', 'subsec'),
+                 latency_ms = CAST(
+                     (julianday('now') - julianday(created_at)) * 86400000 AS INTEGER
+                 )
+             WHERE id = $1 AND status = 'pending'
+             RETURNING {TOOL_APPROVAL_COLUMNS}"
+        ))
+        .bind(id)
+        .bind(response.status)
+        .bind(&response.reason)
+        .bind(response.responded_by_user_id)
+        .bind(&response.policy_rule)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Mark every pending approval as expired. Called at startup, when no agent is
+    /// left waiting for an answer.
+    pub async fn expire_pending(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE tool_approvals
+             SET status = 'expired', responded_at = datetime('now', 'subsec')
+             WHERE status = 'pending'",
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ToolApproval>(&format!(
+            "SELECT {TOOL_APPROVAL_COLUMNS} FROM tool_approvals WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Approvals the execution asked for, in the order it asked
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ToolApproval>(&format!(
+            "SELECT {TOOL_APPROVAL_COLUMNS} FROM tool_approvals
+             WHERE execution_process_id = $1
+             ORDER BY created_at ASC, rowid ASC"
+        ))
+        .bind(execution_process_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Approvals the user answered, newest first
+    pub async fn find_by_responder(
+        pool: &SqlitePool,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ToolApproval>(&format!(
+            "SELECT {TOOL_APPROVAL_COLUMNS} FROM tool_approvals
+             WHERE responded_by_user_id = $1
+             ORDER BY responded_at DESC, rowid DESC
+             LIMIT $2 OFFSET $3"
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -11,6 +11,8 @@ pub enum UserError {
     Database(#[from] sqlx::Error),
     #[error("User not found")]
     UserNotFound,
+    #[error("The server must keep at least one admin")]
+    LastAdmin,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -139,4 +141,54 @@ impl User {
         .fetch_one(pool)
         .await
     }
+
+    /// Whether the user is a server admin. Admins bypass project role checks.
+    pub async fn is_admin(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar::<_, bool>("SELECT is_admin FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map(|is_admin| is_admin.unwrap_or(false))
+    }
+
+    /// IDs of every server admin
+    pub async fn find_admin_ids(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM users WHERE is_admin = 1 ORDER BY created_at ASC",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Grant or revoke server admin. Revoking the last admin is rejected.
+    pub async fn set_admin(pool: &SqlitePool, id: Uuid, is_admin: bool) -> Result<(), UserError> {
+        if !is_admin && Self::find_admin_ids(pool).await? == [id] {
+            return Err(UserError::LastAdmin);
+        }
+
+        let result = sqlx::query(
+            "UPDATE users SET is_admin = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+        )
+        .bind(id)
+        .bind(is_admin)
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(UserError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    /// Make the user an admin if the server has none yet, so the first user to sign in
+    /// on a fresh install can manage everyone else. Returns whether the user was promoted.
+    pub async fn claim_admin_if_none(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE users SET is_admin = 1
+             WHERE id = $1 AND NOT EXISTS (SELECT 1 FROM users WHERE is_admin = 1)",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
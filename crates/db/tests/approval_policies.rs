@@ -0,0 +1,132 @@
+mod common;
+
+use common::{create_project, setup_pool};
+use db::models::approval_policy::{
+    ApprovalDecision, ApprovalPolicy, ApprovalPolicyDefinition, ApprovalPolicyError,
+    UpsertApprovalPolicy,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Store a policy with no rules whose default decision identifies it
+async fn store(
+    pool: &SqlitePool,
+    project_id: Option<Uuid>,
+    executor: Option<&str>,
+    variant: Option<&str>,
+    default_decision: ApprovalDecision,
+) -> ApprovalPolicy {
+    ApprovalPolicy::upsert(
+        pool,
+        &UpsertApprovalPolicy {
+            project_id,
+            executor: executor.map(str::to_string),
+            variant: variant.map(str::to_string),
+            definition: ApprovalPolicyDefinition {
+                rules: vec![],
+                default_decision,
+            },
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_most_specific_policy_applies() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let other_project_id = create_project(&pool, "Other Project").await;
+
+    let global = store(&pool, None, None, None, ApprovalDecision::Ask).await;
+    let executor = store(
+        &pool,
+        None,
+        Some("CLAUDE_CODE"),
+        None,
+        ApprovalDecision::Deny,
+    )
+    .await;
+    let variant = store(
+        &pool,
+        None,
+        Some("CLAUDE_CODE"),
+        Some("PLAN"),
+        ApprovalDecision::Allow,
+    )
+    .await;
+    let project = store(&pool, Some(project_id), None, None, ApprovalDecision::Deny).await;
+
+    let find = |project_id, executor, variant| {
+        let pool = pool.clone();
+        async move {
+            ApprovalPolicy::find_for_execution(&pool, project_id, executor, variant)
+                .await
+                .unwrap()
+                .map(|policy| policy.id)
+        }
+    };
+
+    assert_eq!(
+        find(project_id, "CLAUDE_CODE", Some("PLAN")).await,
+        Some(project.id)
+    );
+    assert_eq!(
+        find(other_project_id, "CLAUDE_CODE", Some("PLAN")).await,
+        Some(variant.id)
+    );
+    assert_eq!(
+        find(other_project_id, "CLAUDE_CODE", None).await,
+        Some(executor.id)
+    );
+    assert_eq!(find(other_project_id, "AMP", None).await, Some(global.id));
+}
+
+#[tokio::test]
+async fn test_upsert_replaces_policy_for_same_scope() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+
+    let first = store(
+        &pool,
+        Some(project_id),
+        Some("CODEX"),
+        None,
+        ApprovalDecision::Ask,
+    )
+    .await;
+    let second = store(
+        &pool,
+        Some(project_id),
+        Some("CODEX"),
+        None,
+        ApprovalDecision::Deny,
+    )
+    .await;
+    assert_eq!(first.id, second.id);
+    assert_eq!(second.definition.default_decision, ApprovalDecision::Deny);
+    assert_eq!(ApprovalPolicy::find_all(&pool).await.unwrap().len(), 1);
+
+    let orphan_variant = ApprovalPolicy::upsert(
+        &pool,
+        &UpsertApprovalPolicy {
+            project_id: None,
+            executor: None,
+            variant: Some("PLAN".to_string()),
+            definition: ApprovalPolicyDefinition::default(),
+        },
+    )
+    .await;
+    assert!(matches!(
+        orphan_variant,
+        Err(ApprovalPolicyError::Invalid(_))
+    ));
+
+    assert_eq!(ApprovalPolicy::delete(&pool, second.id).await.unwrap(), 1);
+    assert!(
+        ApprovalPolicy::find_by_id(&pool, second.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
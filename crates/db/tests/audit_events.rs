@@ -0,0 +1,136 @@
+mod common;
+
+use common::setup_pool;
+use db::models::audit_event::{
+    AuditAction, AuditEvent, AuditFilter, AuditSource, AuditTarget, CreateAuditEvent,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+async fn record(
+    pool: &SqlitePool,
+    actor_user_id: Option<Uuid>,
+    source: AuditSource,
+    action: AuditAction,
+    target: AuditTarget,
+) -> AuditEvent {
+    AuditEvent::create(
+        pool,
+        CreateAuditEvent {
+            actor_user_id,
+            source,
+            action,
+            target,
+            details: json!({ "note": "test" }),
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_audit_events_are_append_only() {
+    let pool = setup_pool().await;
+    let event = record(
+        &pool,
+        None,
+        AuditSource::Api,
+        AuditAction::ProjectCreated,
+        AuditTarget::project(Uuid::new_v4()),
+    )
+    .await;
+    assert_eq!(event.details, json!({ "note": "test" }));
+
+    let update = sqlx::query("UPDATE audit_events SET action = 'task_deleted' WHERE id = $1")
+        .bind(event.id)
+        .execute(&pool)
+        .await;
+    assert!(update.is_err());
+
+    let delete = sqlx::query("DELETE FROM audit_events WHERE id = $1")
+        .bind(event.id)
+        .execute(&pool)
+        .await;
+    assert!(delete.is_err());
+}
+
+#[tokio::test]
+async fn test_list_filters_and_paginates() {
+    let pool = setup_pool().await;
+    let alice = Uuid::new_v4();
+    let bob = Uuid::new_v4();
+    let project_id = Uuid::new_v4();
+    let task_id = Uuid::new_v4();
+
+    record(
+        &pool,
+        Some(alice),
+        AuditSource::Api,
+        AuditAction::TaskCreated,
+        AuditTarget::task(project_id, task_id),
+    )
+    .await;
+    record(
+        &pool,
+        Some(alice),
+        AuditSource::Mcp,
+        AuditAction::TaskUpdated,
+        AuditTarget::task(project_id, task_id),
+    )
+    .await;
+    let released = record(
+        &pool,
+        Some(bob),
+        AuditSource::Api,
+        AuditAction::TaskHoldReleased,
+        AuditTarget::task(project_id, task_id).with_target(alice),
+    )
+    .await;
+    record(
+        &pool,
+        Some(bob),
+        AuditSource::Api,
+        AuditAction::ProjectCreated,
+        AuditTarget::project(Uuid::new_v4()),
+    )
+    .await;
+
+    let all = AuditEvent::list(&pool, &AuditFilter::default(), 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(all.total, 4);
+    assert_eq!(all.events[0].action, AuditAction::ProjectCreated);
+
+    let filter = AuditFilter {
+        project_id: Some(project_id),
+        ..Default::default()
+    };
+    let page = AuditEvent::list(&pool, &filter, 2, 0).await.unwrap();
+    assert_eq!(page.total, 3);
+    assert_eq!(page.events.len(), 2);
+    assert_eq!(page.events[0].id, released.id);
+    assert_eq!(page.events[0].target_id, Some(alice));
+    let next = AuditEvent::list(&pool, &filter, 2, 2).await.unwrap();
+    assert_eq!(next.events.len(), 1);
+    assert_eq!(next.events[0].action, AuditAction::TaskCreated);
+
+    let mcp = AuditFilter {
+        actor_user_id: Some(alice),
+        source: Some(AuditSource::Mcp),
+        ..Default::default()
+    };
+    let exported = AuditEvent::find_all(&pool, &mcp).await.unwrap();
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].action, AuditAction::TaskUpdated);
+}
+
+#[test]
+fn test_diff_records_changed_fields_only() {
+    let before = json!({ "title": "a", "status": "todo", "updated_at": "1" });
+    let after = json!({ "title": "a", "status": "done", "updated_at": "2" });
+    assert_eq!(
+        AuditEvent::diff(&before, &after),
+        json!({ "status": { "from": "todo", "to": "done" } })
+    );
+}
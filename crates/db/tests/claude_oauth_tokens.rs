@@ -0,0 +1,106 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{create_user, setup_pool};
+use db::models::claude_oauth_token::ClaudeOAuthToken;
+use sqlx::SqlitePool;
+
+async fn create_token(pool: &SqlitePool, username: &str) -> ClaudeOAuthToken {
+    let user = create_user(pool, username).await;
+    ClaudeOAuthToken::upsert(pool, user.id, "v1:dG9rZW4=", Some("...oken"), None)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_rotation_skips_cooling_down_tokens() {
+    let pool = setup_pool().await;
+    let first = create_token(&pool, "alice").await;
+    let second = create_token(&pool, "bob").await;
+
+    ClaudeOAuthToken::mark_rate_limited(&pool, first.id, Utc::now() + Duration::hours(1))
+        .await
+        .unwrap();
+
+    let next = ClaudeOAuthToken::get_next_for_rotation(&pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(next.id, second.id);
+    assert_eq!(ClaudeOAuthToken::count_available(&pool).await.unwrap(), 1);
+
+    let limited = ClaudeOAuthToken::find_by_id(&pool, first.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(limited.rate_limited_count, 1);
+    assert!(limited.to_status().cooldown_until.is_some());
+}
+
+#[tokio::test]
+async fn test_rate_limit_backoff_is_not_counted_as_usage_limit() {
+    let pool = setup_pool().await;
+    let token = create_token(&pool, "alice").await;
+
+    ClaudeOAuthToken::set_cooldown(&pool, token.id, Utc::now() + Duration::minutes(1))
+        .await
+        .unwrap();
+
+    assert_eq!(ClaudeOAuthToken::count_available(&pool).await.unwrap(), 0);
+    let token = ClaudeOAuthToken::find_by_id(&pool, token.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(token.rate_limited_count, 0);
+    assert!(token.to_status().cooldown_until.is_some());
+}
+
+#[tokio::test]
+async fn test_expired_cooldown_returns_token_to_rotation() {
+    let pool = setup_pool().await;
+    let token = create_token(&pool, "alice").await;
+
+    ClaudeOAuthToken::mark_rate_limited(&pool, token.id, Utc::now() - Duration::minutes(1))
+        .await
+        .unwrap();
+
+    assert_eq!(ClaudeOAuthToken::count_available(&pool).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_invalid_token_is_skipped_until_replaced() {
+    let pool = setup_pool().await;
+    let token = create_token(&pool, "alice").await;
+
+    ClaudeOAuthToken::mark_invalid(&pool, token.id, "OAuth token has expired")
+        .await
+        .unwrap();
+    assert!(
+        ClaudeOAuthToken::get_next_for_rotation(&pool)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let replaced = ClaudeOAuthToken::upsert(&pool, token.user_id, "v1:bmV3", None, None)
+        .await
+        .unwrap();
+    assert!(replaced.invalidated_at.is_none());
+    assert_eq!(ClaudeOAuthToken::count_available(&pool).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_mark_used_counts_uses() {
+    let pool = setup_pool().await;
+    let token = create_token(&pool, "alice").await;
+
+    ClaudeOAuthToken::mark_used(&pool, token.id).await.unwrap();
+    ClaudeOAuthToken::mark_used(&pool, token.id).await.unwrap();
+
+    let token = ClaudeOAuthToken::find_by_id(&pool, token.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(token.use_count, 2);
+    assert!(token.last_used_at.is_some());
+}
@@ -0,0 +1,138 @@
+//! Fixtures shared by the database integration tests.
+
+// Every test binary compiles this module but uses only some of the helpers
+#![allow(dead_code)]
+
+use db::models::{
+    session::{CreateSession, Session},
+    task::{CreateTask, Task},
+    user::User,
+    workspace::{CreateWorkspace, Workspace},
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Helper to set up an in-memory SQLite pool with all migrations applied
+pub async fn setup_pool() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
+
+/// Helper to create a project called `name`
+pub async fn create_project(pool: &SqlitePool, name: &str) -> Uuid {
+    let project_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO projects (id, name) VALUES ($1, $2)")
+        .bind(project_id)
+        .bind(name)
+        .execute(pool)
+        .await
+        .unwrap();
+    project_id
+}
+
+/// Helper to create a user called `username`
+pub async fn create_user(pool: &SqlitePool, username: &str) -> User {
+    sqlx::query_as::<_, User>(
+        r#"INSERT INTO users (id, github_id, username, email)
+           VALUES ($1, $2, $3, $4)
+           RETURNING id, github_id, username, email, display_name, avatar_url, created_at, updated_at"#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(Uuid::new_v4().as_u128() as i64)
+    .bind(username)
+    .bind(format!("{}@test.com", username))
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// Helper to create a task in `project_id`
+pub async fn create_task(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    title: &str,
+    description: Option<&str>,
+) -> Task {
+    Task::create(
+        pool,
+        &CreateTask::from_title_description(
+            project_id,
+            title.to_string(),
+            description.map(str::to_string),
+        ),
+        Uuid::new_v4(),
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+/// Helper to create a workspace for `task_id` on `branch`
+pub async fn create_workspace(pool: &SqlitePool, task_id: Uuid, branch: &str) -> Workspace {
+    Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: branch.to_string(),
+            agent_working_dir: None,
+        },
+        Uuid::new_v4(),
+        task_id,
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+/// Helper to create a session in `workspace_id`, started by `user_id`
+pub async fn create_session(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    user_id: Option<Uuid>,
+) -> Session {
+    Session::create(
+        pool,
+        &CreateSession { executor: None },
+        Uuid::new_v4(),
+        workspace_id,
+        user_id,
+    )
+    .await
+    .unwrap()
+}
+
+/// Helper to create a task titled `title` in `project_id`, with a workspace on
+/// `vk/<title>` and a session in it
+pub async fn create_task_session(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    title: &str,
+) -> (Task, Workspace, Session) {
+    let task = create_task(pool, project_id, title, None).await;
+    let branch = format!("vk/{}", title.to_lowercase().replace(' ', "-"));
+    let workspace = create_workspace(pool, task.id, &branch).await;
+    let session = create_session(pool, workspace.id, None).await;
+    (task, workspace, session)
+}
+
+/// Helper to create an execution process in `session_id` with an empty executor action
+pub async fn create_execution(
+    pool: &SqlitePool,
+    session_id: Uuid,
+    run_reason: &str,
+    status: &str,
+) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO execution_processes (id, session_id, run_reason, executor_action, status)
+         VALUES ($1, $2, $3, '{}', $4)",
+    )
+    .bind(id)
+    .bind(session_id)
+    .bind(run_reason)
+    .bind(status)
+    .execute(pool)
+    .await
+    .unwrap();
+    id
+}
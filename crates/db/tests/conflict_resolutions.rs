@@ -0,0 +1,137 @@
+mod common;
+
+use std::path::Path;
+
+use common::{create_execution, create_project, create_task_session, setup_pool};
+use db::models::{
+    conflict_resolution::{ConflictResolution, ConflictResolutionStatus, CreateConflictResolution},
+    merge_queue::{MergeQueueEntry, MergeQueueStatus},
+    repo::Repo,
+    session::Session,
+    workspace::Workspace,
+};
+use sqlx::SqlitePool;
+
+/// A workspace and the session its agent runs in
+async fn create_workspace(pool: &SqlitePool) -> (Workspace, Session) {
+    let project_id = create_project(pool, "Test Project").await;
+    let (_, workspace, session) = create_task_session(pool, project_id, "Add login").await;
+    (workspace, session)
+}
+
+/// Record an agent run resolving the conflicts in `files`
+async fn create_resolution(
+    pool: &SqlitePool,
+    session: &Session,
+    workspace: &Workspace,
+    repo: &Repo,
+    files: &[&str],
+) -> ConflictResolution {
+    let execution_process_id = create_execution(pool, session.id, "codingagent", "running").await;
+    ConflictResolution::create(
+        pool,
+        &CreateConflictResolution {
+            execution_process_id,
+            workspace_id: workspace.id,
+            repo_id: repo.id,
+            conflicted_files: files.iter().map(|file| file.to_string()).collect(),
+            start_head: "4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_string(),
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_latest_resolution_per_repo() {
+    let pool = setup_pool().await;
+    let (workspace, session) = create_workspace(&pool).await;
+    let app = Repo::find_or_create(&pool, Path::new("/src/app"), "App")
+        .await
+        .unwrap();
+    let api = Repo::find_or_create(&pool, Path::new("/src/api"), "Api")
+        .await
+        .unwrap();
+
+    let first = create_resolution(&pool, &session, &workspace, &app, &["src/a.rs"]).await;
+    assert_eq!(first.status, ConflictResolutionStatus::Running);
+    assert_eq!(first.conflicted_files.0, vec!["src/a.rs".to_string()]);
+    assert_eq!(
+        first.start_head.as_deref(),
+        Some("4b825dc642cb6eb9a060e54bf8d69288fbee4904")
+    );
+    ConflictResolution::set_status(
+        &pool,
+        first.execution_process_id,
+        ConflictResolutionStatus::Conflicted,
+        Some("git rebase --continue stopped on conflicts in: src/b.rs"),
+    )
+    .await
+    .unwrap();
+    let second = create_resolution(&pool, &session, &workspace, &app, &["src/b.rs"]).await;
+    create_resolution(&pool, &session, &workspace, &api, &["lib.rs"]).await;
+
+    let latest = ConflictResolution::find_latest_for_workspace(&pool, workspace.id)
+        .await
+        .unwrap();
+    assert_eq!(latest.len(), 2);
+    let latest_app = latest.iter().find(|r| r.repo_id == app.id).unwrap();
+    assert_eq!(latest_app.execution_process_id, second.execution_process_id);
+
+    let settled =
+        ConflictResolution::find_by_execution_process_id(&pool, first.execution_process_id)
+            .await
+            .unwrap()
+            .unwrap();
+    assert_eq!(settled.status, ConflictResolutionStatus::Conflicted);
+    assert!(settled.error_message.unwrap().contains("src/b.rs"));
+}
+
+#[tokio::test]
+async fn test_merge_queue_entry_is_found_by_its_resolution() {
+    let pool = setup_pool().await;
+    let (workspace, session) = create_workspace(&pool).await;
+    let app = Repo::find_or_create(&pool, Path::new("/src/app"), "App")
+        .await
+        .unwrap();
+    let resolution = create_resolution(&pool, &session, &workspace, &app, &["src/a.rs"]).await;
+
+    let entry = MergeQueueEntry::enqueue(&pool, workspace.id, app.id, "main", None)
+        .await
+        .unwrap();
+    MergeQueueEntry::set_conflicted(
+        &pool,
+        entry.id,
+        Some(resolution.execution_process_id),
+        "Conflicts",
+    )
+    .await
+    .unwrap();
+    let found = MergeQueueEntry::find_by_resolution_execution_process_id(
+        &pool,
+        resolution.execution_process_id,
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(found.id, entry.id);
+
+    // Only a conflicted entry waits on its resolution
+    MergeQueueEntry::set_status(
+        &pool,
+        entry.id,
+        MergeQueueStatus::Failed,
+        Some("Rolled back"),
+    )
+    .await
+    .unwrap();
+    assert!(
+        MergeQueueEntry::find_by_resolution_execution_process_id(
+            &pool,
+            resolution.execution_process_id
+        )
+        .await
+        .unwrap()
+        .is_none()
+    );
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::{create_project, create_task_session, setup_pool};
+use db::models::{
+    execution_queue::{ExecutionQueue, ExecutionSlot},
+    session::Session,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Helper to create a running coding agent execution for `executor` in `session`
+async fn create_test_execution(pool: &SqlitePool, session: &Session, executor: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO execution_processes (id, session_id, run_reason, executor_action, status)
+         VALUES ($1, $2, 'codingagent', $3, 'running')",
+    )
+    .bind(id)
+    .bind(session.id)
+    .bind(format!(
+        r#"{{"typ":{{"type":"CodingAgentInitialRequest","executor_profile_id":{{"executor":"{executor}"}}}}}}"#
+    ))
+    .execute(pool)
+    .await
+    .unwrap();
+    id
+}
+
+async fn status_of(pool: &SqlitePool, id: Uuid) -> String {
+    sqlx::query_scalar("SELECT status FROM execution_processes WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_queue_orders_by_priority_then_arrival() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let (_, _, session) = create_task_session(&pool, project_id, "task").await;
+    let slot = ExecutionSlot {
+        executor: Some("CODEX".to_string()),
+        project_id: project_id,
+        user_id: None,
+    };
+
+    let first = create_test_execution(&pool, &session, "CODEX").await;
+    let second = create_test_execution(&pool, &session, "CODEX").await;
+    let third = create_test_execution(&pool, &session, "CODEX").await;
+    for id in [first, second, third] {
+        ExecutionQueue::enqueue(&pool, id, &slot).await.unwrap();
+        assert_eq!(status_of(&pool, id).await, "queued");
+    }
+
+    let order: Vec<Uuid> = ExecutionQueue::find_all(&pool)
+        .await
+        .unwrap()
+        .iter()
+        .map(|entry| entry.execution_process_id)
+        .collect();
+    assert_eq!(order, vec![first, second, third]);
+
+    // Bumping the priority moves an entry ahead of earlier arrivals
+    let bumped = ExecutionQueue::set_priority(&pool, third, 5)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(bumped.priority, 5);
+    let listed = ExecutionQueue::list(&pool, Some(project_id)).await.unwrap();
+    let order: Vec<Uuid> = listed
+        .iter()
+        .map(|queued| queued.entry.execution_process_id)
+        .collect();
+    assert_eq!(order, vec![third, first, second]);
+    assert_eq!(listed[0].task_title, "task");
+    assert!(
+        ExecutionQueue::list(&pool, Some(Uuid::new_v4()))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_claim_and_running_slots() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let (_, _, session) = create_task_session(&pool, project_id, "task").await;
+    let slot = ExecutionSlot {
+        executor: Some("CLAUDE_CODE".to_string()),
+        project_id: project_id,
+        user_id: None,
+    };
+
+    create_test_execution(&pool, &session, "CODEX").await;
+    let queued = create_test_execution(&pool, &session, "CLAUDE_CODE").await;
+    let cancelled = create_test_execution(&pool, &session, "CLAUDE_CODE").await;
+    ExecutionQueue::enqueue(&pool, queued, &slot).await.unwrap();
+    ExecutionQueue::enqueue(&pool, cancelled, &slot)
+        .await
+        .unwrap();
+
+    // Queued executions do not take a slot
+    let running = ExecutionQueue::running_slots(&pool).await.unwrap();
+    assert_eq!(running.len(), 1);
+    assert_eq!(running[0].executor.as_deref(), Some("CODEX"));
+    assert_eq!(running[0].project_id, project_id);
+
+    assert!(ExecutionQueue::claim(&pool, queued).await.unwrap());
+    assert_eq!(status_of(&pool, queued).await, "running");
+    // A second claim finds nothing to take
+    assert!(!ExecutionQueue::claim(&pool, queued).await.unwrap());
+    let running = ExecutionQueue::running_slots(&pool).await.unwrap();
+    assert_eq!(running.len(), 2);
+    assert!(running.contains(&slot));
+
+    assert_eq!(ExecutionQueue::remove(&pool, cancelled).await.unwrap(), 1);
+    assert!(!ExecutionQueue::claim(&pool, cancelled).await.unwrap());
+    assert!(ExecutionQueue::find_all(&pool).await.unwrap().is_empty());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::{create_execution, create_project, create_task_session, setup_pool};
+use db::models::execution_process::ExecutionProcess;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Helper to create a session in a fresh project
+async fn create_test_session(pool: &SqlitePool) -> Uuid {
+    let project_id = create_project(pool, "Test Project").await;
+    let (_, _, session) = create_task_session(pool, project_id, "task").await;
+    session.id
+}
+
+async fn create_failed_execution(pool: &SqlitePool, session_id: Uuid) -> Uuid {
+    create_execution(pool, session_id, "codingagent", "failed").await
+}
+
+#[tokio::test]
+async fn test_retry_chain_lists_attempts_oldest_first() {
+    let pool = setup_pool().await;
+    let session_id = create_test_session(&pool).await;
+
+    let first = create_failed_execution(&pool, session_id).await;
+    let second = create_failed_execution(&pool, session_id).await;
+    let third = create_failed_execution(&pool, session_id).await;
+    let unrelated = create_failed_execution(&pool, session_id).await;
+    ExecutionProcess::set_retry_of(&pool, second, first)
+        .await
+        .unwrap();
+    ExecutionProcess::set_retry_of(&pool, third, second)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ExecutionProcess::find_retry_chain(&pool, third)
+            .await
+            .unwrap(),
+        vec![first, second, third]
+    );
+    assert_eq!(
+        ExecutionProcess::find_retry_chain(&pool, unrelated)
+            .await
+            .unwrap(),
+        vec![unrelated]
+    );
+}
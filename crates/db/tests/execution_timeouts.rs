@@ -0,0 +1,44 @@
+mod common;
+
+use common::{create_execution, create_project, create_task_session, setup_pool};
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessStatusReason};
+
+#[tokio::test]
+async fn test_count_timed_out_in_session() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let (_, _, session) = create_task_session(&pool, project_id, "task").await;
+    let (_, _, other_session) = create_task_session(&pool, project_id, "other").await;
+
+    let reasons = [
+        (
+            session.id,
+            Some(ExecutionProcessStatusReason::MaxRuntimeExceeded),
+        ),
+        (session.id, Some(ExecutionProcessStatusReason::IdleTimeout)),
+        (
+            session.id,
+            Some(ExecutionProcessStatusReason::MemoryLimitExceeded),
+        ),
+        (session.id, None),
+        (
+            other_session.id,
+            Some(ExecutionProcessStatusReason::IdleTimeout),
+        ),
+    ];
+    for (session_id, reason) in reasons {
+        let id = create_execution(&pool, session_id, "codingagent", "killed").await;
+        if let Some(reason) = reason {
+            ExecutionProcess::set_status_reason(&pool, id, reason)
+                .await
+                .unwrap();
+        }
+    }
+
+    assert_eq!(
+        ExecutionProcess::count_timed_out_in_session(&pool, session.id)
+            .await
+            .unwrap(),
+        2
+    );
+}
@@ -0,0 +1,168 @@
+mod common;
+
+use std::path::Path;
+
+use common::{
+    create_execution, create_project, create_session, create_task, create_workspace, setup_pool,
+};
+use db::models::{
+    merge::MergeStrategy,
+    merge_queue::{MergeQueueEntry, MergeQueueStatus},
+    repo::Repo,
+    workspace::Workspace,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+async fn create_task_workspace(pool: &SqlitePool, project_id: Uuid, title: &str) -> Workspace {
+    let task = create_task(pool, project_id, title, None).await;
+    let branch = format!("vk/{}", title.to_lowercase().replace(' ', "-"));
+    create_workspace(pool, task.id, &branch).await
+}
+
+/// A running coding agent in the workspace, e.g. one resolving rebase conflicts
+async fn create_agent_run(pool: &SqlitePool, workspace: &Workspace) -> Uuid {
+    let session = create_session(pool, workspace.id, None).await;
+    create_execution(pool, session.id, "codingagent", "running").await
+}
+
+fn ids(entries: &[MergeQueueEntry]) -> Vec<Uuid> {
+    entries.iter().map(|entry| entry.workspace_id).collect()
+}
+
+#[tokio::test]
+async fn test_each_target_branch_has_its_own_queue() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let app = Repo::find_or_create(&pool, Path::new("/src/app"), "App")
+        .await
+        .unwrap();
+    let login = create_task_workspace(&pool, project_id, "Add login").await;
+    let logout = create_task_workspace(&pool, project_id, "Add logout").await;
+    let hotfix = create_task_workspace(&pool, project_id, "Fix crash").await;
+
+    MergeQueueEntry::enqueue(&pool, login.id, app.id, "main", None)
+        .await
+        .unwrap();
+    MergeQueueEntry::enqueue(&pool, logout.id, app.id, "main", None)
+        .await
+        .unwrap();
+    let release = MergeQueueEntry::enqueue(
+        &pool,
+        hotfix.id,
+        app.id,
+        "release",
+        Some(MergeStrategy::FastForward),
+    )
+    .await
+    .unwrap();
+    assert_eq!(release.status, MergeQueueStatus::Queued);
+    assert_eq!(release.merge_strategy, Some(MergeStrategy::FastForward));
+
+    let heads = MergeQueueEntry::find_heads(&pool).await.unwrap();
+    assert_eq!(ids(&heads), vec![login.id, hotfix.id]);
+
+    let listed = MergeQueueEntry::list(&pool, Some(project_id))
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 3);
+    assert_eq!(listed[0].task_title, "Add login");
+    assert_eq!(listed[0].branch, "vk/add-login");
+    assert!(
+        MergeQueueEntry::list(&pool, Some(Uuid::new_v4()))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_conflicted_entry_steps_aside_and_requeues_at_the_back() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let app = Repo::find_or_create(&pool, Path::new("/src/app"), "App")
+        .await
+        .unwrap();
+    let login = create_task_workspace(&pool, project_id, "Add login").await;
+    let logout = create_task_workspace(&pool, project_id, "Add logout").await;
+    let signup = create_task_workspace(&pool, project_id, "Add signup").await;
+
+    let first = MergeQueueEntry::enqueue(&pool, login.id, app.id, "main", None)
+        .await
+        .unwrap();
+    MergeQueueEntry::enqueue(&pool, logout.id, app.id, "main", None)
+        .await
+        .unwrap();
+
+    let resolution = create_agent_run(&pool, &login).await;
+    MergeQueueEntry::set_conflicted(&pool, first.id, Some(resolution), "Conflicts")
+        .await
+        .unwrap();
+    let heads = MergeQueueEntry::find_heads(&pool).await.unwrap();
+    assert_eq!(ids(&heads), vec![logout.id]);
+
+    // Enqueued while the agent works, so it goes ahead of the requeued entry
+    MergeQueueEntry::enqueue(&pool, signup.id, app.id, "main", None)
+        .await
+        .unwrap();
+    assert_eq!(
+        MergeQueueEntry::requeue_resolved(&pool, Uuid::new_v4())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        MergeQueueEntry::requeue_resolved(&pool, resolution)
+            .await
+            .unwrap(),
+        1
+    );
+
+    let requeued = MergeQueueEntry::find(&pool, login.id, app.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(requeued.status, MergeQueueStatus::Queued);
+    assert_eq!(requeued.resolution_execution_process_id, None);
+    assert_eq!(requeued.error_message, None);
+    let listed = MergeQueueEntry::list(&pool, None).await.unwrap();
+    assert_eq!(
+        listed
+            .iter()
+            .map(|q| q.entry.workspace_id)
+            .collect::<Vec<_>>(),
+        vec![logout.id, signup.id, login.id]
+    );
+}
+
+#[tokio::test]
+async fn test_enqueue_again_resets_a_failed_entry() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let app = Repo::find_or_create(&pool, Path::new("/src/app"), "App")
+        .await
+        .unwrap();
+    let login = create_task_workspace(&pool, project_id, "Add login").await;
+
+    let entry = MergeQueueEntry::enqueue(&pool, login.id, app.id, "main", None)
+        .await
+        .unwrap();
+    MergeQueueEntry::set_status(
+        &pool,
+        entry.id,
+        MergeQueueStatus::Failed,
+        Some("The verify script of App failed"),
+    )
+    .await
+    .unwrap();
+    assert!(MergeQueueEntry::find_heads(&pool).await.unwrap().is_empty());
+
+    let again = MergeQueueEntry::enqueue(&pool, login.id, app.id, "develop", None)
+        .await
+        .unwrap();
+    assert_eq!(again.id, entry.id);
+    assert_eq!(again.target_branch, "develop");
+    assert_eq!(again.status, MergeQueueStatus::Queued);
+    assert_eq!(again.error_message, None);
+    assert!(again.is_active());
+}
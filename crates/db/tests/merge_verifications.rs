@@ -0,0 +1,146 @@
+mod common;
+
+use std::path::Path;
+
+use common::{create_project, create_task_session, setup_pool};
+use db::models::{
+    execution_process::ExecutionProcessStatus,
+    merge_verification::{CreateMergeVerification, MergeVerification},
+    repo::{Repo, UpdateRepo},
+    session::Session,
+    workspace::Workspace,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// A workspace and the session its verify scripts run in
+async fn create_workspace(pool: &SqlitePool) -> (Workspace, Session) {
+    let project_id = create_project(pool, "Test Project").await;
+    let (_, workspace, session) = create_task_session(pool, project_id, "Add login").await;
+    (workspace, session)
+}
+
+/// Record a finished verify script run
+async fn create_verification(
+    pool: &SqlitePool,
+    session: &Session,
+    workspace: &Workspace,
+    repo: &Repo,
+    target_commit: &str,
+    exit_code: i64,
+) -> MergeVerification {
+    let execution_process_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO execution_processes (id, session_id, run_reason, executor_action, status, exit_code)
+         VALUES ($1, $2, 'verifyscript', '{}', $3, $4)",
+    )
+    .bind(execution_process_id)
+    .bind(session.id)
+    .bind(if exit_code == 0 {
+        ExecutionProcessStatus::Completed
+    } else {
+        ExecutionProcessStatus::Failed
+    })
+    .bind(exit_code)
+    .execute(pool)
+    .await
+    .unwrap();
+    MergeVerification::create(
+        pool,
+        &CreateMergeVerification {
+            execution_process_id,
+            workspace_id: workspace.id,
+            repo_id: repo.id,
+            task_commit: "task".to_string(),
+            target_branch: "main".to_string(),
+            target_commit: target_commit.to_string(),
+            worktree_path: "/tmp/verify/app".to_string(),
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_verify_script_is_updated_separately_from_other_scripts() {
+    let pool = setup_pool().await;
+    let repo = Repo::find_or_create(&pool, Path::new("/src/app"), "App")
+        .await
+        .unwrap();
+    assert_eq!(
+        Repo::find_verify_script(&pool, repo.id).await.unwrap(),
+        None
+    );
+
+    let payload: UpdateRepo = serde_json::from_value(serde_json::json!({
+        "setup_script": "npm ci",
+        "verify_script": "npm test",
+    }))
+    .unwrap();
+    let updated = Repo::update(&pool, repo.id, &payload).await.unwrap();
+    assert_eq!(updated.setup_script.as_deref(), Some("npm ci"));
+    assert_eq!(
+        Repo::find_verify_script(&pool, repo.id)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("npm test")
+    );
+
+    // Leaving the field out keeps the script; null clears it
+    let payload: UpdateRepo =
+        serde_json::from_value(serde_json::json!({ "setup_script": null })).unwrap();
+    Repo::update(&pool, repo.id, &payload).await.unwrap();
+    assert!(
+        Repo::find_verify_script(&pool, repo.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    let payload: UpdateRepo =
+        serde_json::from_value(serde_json::json!({ "verify_script": null })).unwrap();
+    Repo::update(&pool, repo.id, &payload).await.unwrap();
+    assert_eq!(
+        Repo::find_verify_script(&pool, repo.id).await.unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_latest_verification_per_repo() {
+    let pool = setup_pool().await;
+    let (workspace, session) = create_workspace(&pool).await;
+    let app = Repo::find_or_create(&pool, Path::new("/src/app"), "App")
+        .await
+        .unwrap();
+    let api = Repo::find_or_create(&pool, Path::new("/src/api"), "Api")
+        .await
+        .unwrap();
+
+    assert!(
+        MergeVerification::find_latest(&pool, workspace.id, app.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let failed = create_verification(&pool, &session, &workspace, &app, "main-1", 1).await;
+    assert_eq!(failed.status, ExecutionProcessStatus::Failed);
+    assert!(!failed.passed());
+    let passed = create_verification(&pool, &session, &workspace, &app, "main-2", 0).await;
+    assert!(passed.passed());
+    create_verification(&pool, &session, &workspace, &api, "main-2", 0).await;
+
+    let latest = MergeVerification::find_latest(&pool, workspace.id, app.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.execution_process_id, passed.execution_process_id);
+    assert_eq!(latest.target_commit, "main-2");
+
+    let all = MergeVerification::find_latest_for_workspace(&pool, workspace.id)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all.iter().all(MergeVerification::passed));
+}
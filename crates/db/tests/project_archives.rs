@@ -0,0 +1,182 @@
+mod common;
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use common::{create_execution, create_project, create_task, create_workspace, setup_pool};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_process_logs::ExecutionProcessLogs,
+    merge::MergeStrategy,
+    project::Project,
+    project_archive::{ProjectArchive, ProjectArchiveError},
+    project_repo::ProjectRepo,
+    repo::Repo,
+    session::{CreateSession, Session},
+    task::Task,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// A project with one repo and a task whose agent is still running
+async fn create_project_with_history(pool: &SqlitePool) -> (Project, Repo, Uuid) {
+    let project_id = create_project(pool, "Source Project").await;
+    let repo = Repo::find_or_create(pool, Path::new("/src/app"), "App")
+        .await
+        .unwrap();
+    sqlx::query(
+        "UPDATE repos SET setup_script = 'npm ci', verify_script = 'cargo test' WHERE id = $1",
+    )
+    .bind(repo.id)
+    .execute(pool)
+    .await
+    .unwrap();
+    ProjectRepo::create(pool, project_id, repo.id)
+        .await
+        .unwrap();
+    ProjectRepo::set_merge_strategy(pool, project_id, repo.id, MergeStrategy::Rebase)
+        .await
+        .unwrap();
+
+    let task = create_task(pool, project_id, "Add login", Some("With GitHub")).await;
+    let workspace = create_workspace(pool, task.id, "vk/add-login").await;
+    sqlx::query(
+        "INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch)
+         VALUES ($1, $2, $3, 'main')",
+    )
+    .bind(Uuid::new_v4())
+    .bind(workspace.id)
+    .bind(repo.id)
+    .execute(pool)
+    .await
+    .unwrap();
+    let session = Session::create(
+        pool,
+        &CreateSession {
+            executor: Some("CLAUDE_CODE".to_string()),
+        },
+        Uuid::new_v4(),
+        workspace.id,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let execution_id = create_execution(pool, session.id, "codingagent", "running").await;
+    ExecutionProcessLogs::append_log_line(pool, execution_id, "{\"Stdout\":\"hello\"}\n")
+        .await
+        .unwrap();
+
+    let project = Project::find_by_id(pool, project_id)
+        .await
+        .unwrap()
+        .unwrap();
+    (project, repo, execution_id)
+}
+
+#[tokio::test]
+async fn test_restore_remaps_ids_and_repo_paths() {
+    let pool = setup_pool().await;
+    let (project, repo, execution_id) = create_project_with_history(&pool).await;
+
+    // Round-trip through JSON as the export and import endpoints do
+    let archive = ProjectArchive::collect(&pool, project.id).await.unwrap();
+    let archive: ProjectArchive =
+        serde_json::from_str(&serde_json::to_string(&archive).unwrap()).unwrap();
+    assert_eq!(archive.tasks.len(), 1);
+    assert_eq!(archive.execution_process_logs.len(), 1);
+
+    let repo_paths = HashMap::from([(repo.id, PathBuf::from("/dst/app"))]);
+    let imported = archive
+        .restore(&pool, &repo_paths, &HashMap::new(), None)
+        .await
+        .unwrap();
+    assert_ne!(imported.id, project.id);
+    assert_eq!(imported.name, "Source Project");
+
+    let repos = ProjectRepo::find_repos_for_project(&pool, imported.id)
+        .await
+        .unwrap();
+    assert_eq!(repos.len(), 1);
+    assert_ne!(repos[0].id, repo.id);
+    assert_eq!(repos[0].path, PathBuf::from("/dst/app"));
+    assert_eq!(repos[0].setup_script.as_deref(), Some("npm ci"));
+    assert_eq!(
+        Repo::find_verify_script(&pool, repos[0].id)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("cargo test")
+    );
+    assert_eq!(
+        ProjectRepo::find_merge_strategy(&pool, imported.id, repos[0].id)
+            .await
+            .unwrap(),
+        MergeStrategy::Rebase
+    );
+
+    let tasks: Vec<Task> = sqlx::query_as("SELECT * FROM tasks WHERE project_id = $1")
+        .bind(imported.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].title, "Add login");
+
+    let execution_ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT ep.id FROM execution_processes ep
+         JOIN sessions s ON s.id = ep.session_id
+         JOIN workspaces w ON w.id = s.workspace_id
+         WHERE w.task_id = $1",
+    )
+    .bind(tasks[0].id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(execution_ids.len(), 1);
+    assert_ne!(execution_ids[0], execution_id);
+
+    // Nothing runs for an imported project
+    let process = ExecutionProcess::find_by_id(&pool, execution_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(process.status, ExecutionProcessStatus::Killed);
+    let logs = ExecutionProcessLogs::find_by_execution_id(&pool, execution_ids[0])
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].logs, "{\"Stdout\":\"hello\"}\n");
+}
+
+#[tokio::test]
+async fn test_restore_rejects_newer_archives_and_missing_repo_paths() {
+    let pool = setup_pool().await;
+    let (project, _, _) = create_project_with_history(&pool).await;
+    let mut archive = ProjectArchive::collect(&pool, project.id).await.unwrap();
+
+    let result = archive
+        .restore(&pool, &HashMap::new(), &HashMap::new(), None)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ProjectArchiveError::MissingRepoPath(_))
+    ));
+    // The failed import left nothing behind
+    let project_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM projects")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(project_count, 1);
+
+    archive.version += 1;
+    let result = archive
+        .restore(&pool, &HashMap::new(), &HashMap::new(), None)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ProjectArchiveError::UnsupportedVersion(_))
+    ));
+}
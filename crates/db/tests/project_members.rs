@@ -0,0 +1,95 @@
+mod common;
+
+use common::{create_project, create_user, setup_pool};
+use db::models::{
+    project_member::{ProjectMember, ProjectMemberError, ProjectRole},
+    user::{User, UserError},
+};
+use uuid::Uuid;
+
+#[test]
+fn test_roles_are_ordered_by_privilege() {
+    assert!(ProjectRole::Owner > ProjectRole::Maintainer);
+    assert!(ProjectRole::Maintainer > ProjectRole::Contributor);
+    assert!(ProjectRole::Contributor > ProjectRole::Viewer);
+}
+
+#[tokio::test]
+async fn test_set_role_and_last_owner_protection() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let alice = create_user(&pool, "alice").await;
+    let bob = create_user(&pool, "bob").await;
+
+    assert_eq!(
+        ProjectMember::find_role(&pool, project_id, alice.id)
+            .await
+            .unwrap(),
+        None
+    );
+
+    ProjectMember::set_role(&pool, project_id, alice.id, ProjectRole::Owner)
+        .await
+        .unwrap();
+    ProjectMember::set_role(&pool, project_id, bob.id, ProjectRole::Viewer)
+        .await
+        .unwrap();
+    let members = ProjectMember::find_by_project(&pool, project_id)
+        .await
+        .unwrap();
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].username, "alice");
+    assert_eq!(members[0].member.role, ProjectRole::Owner);
+
+    // The only owner can be neither demoted nor removed
+    assert!(matches!(
+        ProjectMember::set_role(&pool, project_id, alice.id, ProjectRole::Maintainer).await,
+        Err(ProjectMemberError::LastOwner)
+    ));
+    assert!(matches!(
+        ProjectMember::remove(&pool, project_id, alice.id).await,
+        Err(ProjectMemberError::LastOwner)
+    ));
+
+    // Once there is a second owner, the first can step down
+    ProjectMember::set_role(&pool, project_id, bob.id, ProjectRole::Owner)
+        .await
+        .unwrap();
+    let demoted = ProjectMember::set_role(&pool, project_id, alice.id, ProjectRole::Contributor)
+        .await
+        .unwrap();
+    assert_eq!(demoted.role, ProjectRole::Contributor);
+    ProjectMember::remove(&pool, project_id, alice.id)
+        .await
+        .unwrap();
+    assert!(matches!(
+        ProjectMember::remove(&pool, project_id, alice.id).await,
+        Err(ProjectMemberError::NotAMember)
+    ));
+
+    assert!(matches!(
+        ProjectMember::set_role(&pool, project_id, Uuid::new_v4(), ProjectRole::Viewer).await,
+        Err(ProjectMemberError::UserNotFound)
+    ));
+}
+
+#[tokio::test]
+async fn test_first_user_claims_admin() {
+    let pool = setup_pool().await;
+    let alice = create_user(&pool, "alice").await;
+    let bob = create_user(&pool, "bob").await;
+
+    assert!(User::claim_admin_if_none(&pool, alice.id).await.unwrap());
+    assert!(!User::claim_admin_if_none(&pool, bob.id).await.unwrap());
+    assert!(User::is_admin(&pool, alice.id).await.unwrap());
+    assert!(!User::is_admin(&pool, bob.id).await.unwrap());
+
+    // The last admin cannot be revoked
+    assert!(matches!(
+        User::set_admin(&pool, alice.id, false).await,
+        Err(UserError::LastAdmin)
+    ));
+    User::set_admin(&pool, bob.id, true).await.unwrap();
+    User::set_admin(&pool, alice.id, false).await.unwrap();
+    assert_eq!(User::find_admin_ids(&pool).await.unwrap(), vec![bob.id]);
+}
@@ -0,0 +1,230 @@
+mod common;
+
+use common::{create_project, setup_pool};
+use db::models::{
+    merge::Merge,
+    project_workflow::{
+        ProjectWorkflow, ProjectWorkflowError, WorkflowAutomation, WorkflowDefinition,
+        WorkflowEvent, WorkflowGuard, WorkflowTransition,
+    },
+    task::TaskStatus,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Helper to create a task in `status` whose PR has been merged, returning the merge id
+async fn create_merged_pr(pool: &SqlitePool, project_id: Uuid, status: &str) -> Uuid {
+    let task_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO tasks (id, project_id, title, status) VALUES ($1, $2, 'Task', $3)")
+        .bind(task_id)
+        .bind(project_id)
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap();
+    let workspace_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO workspaces (id, task_id, branch) VALUES ($1, $2, 'vk/task')")
+        .bind(workspace_id)
+        .bind(task_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    let merge_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO merges (id, workspace_id, merge_type, pr_number, pr_url, pr_status,
+                             target_branch_name)
+         VALUES ($1, $2, 'pr', 1, 'https://github.com/o/r/pull/1', 'merged', 'main')",
+    )
+    .bind(merge_id)
+    .bind(workspace_id)
+    .execute(pool)
+    .await
+    .unwrap();
+    merge_id
+}
+
+/// Review -> Done needs approvals and resolved conversations; Todo -> InProgress is free.
+/// Agent events drive the task between InProgress and InReview.
+fn strict_workflow() -> WorkflowDefinition {
+    WorkflowDefinition {
+        transitions: vec![
+            WorkflowTransition {
+                from: vec![TaskStatus::Todo, TaskStatus::InReview],
+                to: TaskStatus::InProgress,
+                guards: vec![WorkflowGuard::NoHold],
+            },
+            WorkflowTransition {
+                from: vec![TaskStatus::InProgress],
+                to: TaskStatus::InReview,
+                guards: vec![],
+            },
+            WorkflowTransition {
+                from: vec![TaskStatus::InReview],
+                to: TaskStatus::Done,
+                guards: vec![
+                    WorkflowGuard::Approvals,
+                    WorkflowGuard::ConversationsResolved,
+                ],
+            },
+        ],
+        automations: vec![
+            WorkflowAutomation {
+                event: WorkflowEvent::AgentStarted,
+                from: vec![],
+                to: TaskStatus::InProgress,
+            },
+            WorkflowAutomation {
+                event: WorkflowEvent::AgentFinished,
+                from: vec![TaskStatus::InProgress],
+                to: TaskStatus::InReview,
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_default_workflow_matches_built_in_flow() {
+    let workflow = WorkflowDefinition::default();
+    assert!(workflow.validate().is_ok());
+
+    // Completing a reviewed task is gated on approvals, other moves are not
+    let review_to_done = workflow
+        .find_transition(&TaskStatus::InReview, &TaskStatus::Done)
+        .unwrap();
+    assert_eq!(review_to_done.guards, vec![WorkflowGuard::Approvals]);
+    let cd_to_done = workflow
+        .find_transition(&TaskStatus::Cd, &TaskStatus::Done)
+        .unwrap();
+    assert!(cd_to_done.guards.is_empty());
+
+    // Check results only move tasks that are waiting on them
+    assert_eq!(
+        workflow
+            .find_automation(WorkflowEvent::ChecksPending, &TaskStatus::InReview)
+            .map(|a| a.to.clone()),
+        Some(TaskStatus::Ci)
+    );
+    assert!(
+        workflow
+            .find_automation(WorkflowEvent::ChecksPending, &TaskStatus::Done)
+            .is_none()
+    );
+    assert!(
+        workflow
+            .find_automation(WorkflowEvent::PrOpened, &TaskStatus::InReview)
+            .is_none()
+    );
+}
+
+#[test]
+fn test_custom_workflow_rules() {
+    let workflow = strict_workflow();
+    assert!(workflow.validate().is_ok());
+
+    assert!(
+        workflow
+            .find_transition(&TaskStatus::Todo, &TaskStatus::Done)
+            .is_none()
+    );
+    assert!(
+        workflow
+            .find_transition(&TaskStatus::Todo, &TaskStatus::InProgress)
+            .is_some()
+    );
+    assert!(
+        workflow
+            .find_automation(WorkflowEvent::AgentFinished, &TaskStatus::Todo)
+            .is_none()
+    );
+
+    // An automation no transition allows is rejected
+    let mut invalid = strict_workflow();
+    invalid.automations.push(WorkflowAutomation {
+        event: WorkflowEvent::PrMerged,
+        from: vec![TaskStatus::InProgress],
+        to: TaskStatus::Done,
+    });
+    assert!(matches!(
+        invalid.validate(),
+        Err(ProjectWorkflowError::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_workflow_persistence() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+
+    // Projects without a stored workflow use the default
+    assert!(
+        ProjectWorkflow::find_by_project_id(&pool, project_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(
+        ProjectWorkflow::definition_for_project(&pool, project_id)
+            .await
+            .unwrap(),
+        WorkflowDefinition::default()
+    );
+
+    ProjectWorkflow::upsert(&pool, project_id, &WorkflowDefinition::default())
+        .await
+        .unwrap();
+    let stored = ProjectWorkflow::upsert(&pool, project_id, &strict_workflow())
+        .await
+        .unwrap();
+    assert_eq!(stored.definition, strict_workflow());
+    assert_eq!(
+        ProjectWorkflow::definition_for_project(&pool, project_id)
+            .await
+            .unwrap(),
+        strict_workflow()
+    );
+
+    assert_eq!(ProjectWorkflow::delete(&pool, project_id).await.unwrap(), 1);
+    assert_eq!(
+        ProjectWorkflow::definition_for_project(&pool, project_id)
+            .await
+            .unwrap(),
+        WorkflowDefinition::default()
+    );
+}
+
+#[tokio::test]
+async fn test_merged_prs_await_deploy_per_project_workflow() {
+    let pool = setup_pool().await;
+    let default_project_id = create_project(&pool, "Default Project").await;
+    let custom_project_id = create_project(&pool, "Custom Project").await;
+
+    // The custom workflow waits on deploy checks in CI instead of CD
+    let mut workflow = WorkflowDefinition::default();
+    for automation in &mut workflow.automations {
+        if automation.event == WorkflowEvent::DeployPending {
+            automation.to = TaskStatus::Ci;
+        }
+    }
+    assert!(workflow.awaits_deploy(&TaskStatus::Ci));
+    assert!(!workflow.awaits_deploy(&TaskStatus::Cd));
+    ProjectWorkflow::upsert(&pool, custom_project_id, &workflow)
+        .await
+        .unwrap();
+
+    let default_waiting = create_merged_pr(&pool, default_project_id, "cd").await;
+    create_merged_pr(&pool, default_project_id, "done").await;
+    create_merged_pr(&pool, default_project_id, "ci").await;
+    let custom_waiting = create_merged_pr(&pool, custom_project_id, "ci").await;
+    create_merged_pr(&pool, custom_project_id, "cd").await;
+
+    let mut awaiting: Vec<Uuid> = Merge::get_merged_prs_awaiting_deploy(&pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|pr_merge| pr_merge.id)
+        .collect();
+    awaiting.sort();
+    let mut expected = vec![default_waiting, custom_waiting];
+    expected.sort();
+    assert_eq!(awaiting, expected);
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use common::{create_project, create_task_session, setup_pool};
+use db::models::{
+    queued_message::{QueuedMessage, QueuedMessageError},
+    scratch::DraftFollowUpData,
+    session::Session,
+};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use sqlx::SqlitePool;
+
+/// Helper to create a session with its project, task and workspace
+async fn create_test_session(pool: &SqlitePool) -> Session {
+    let project_id = create_project(pool, "Test Project").await;
+    let (_, _, session) = create_task_session(pool, project_id, "Test Task").await;
+    session
+}
+
+fn follow_up(message: &str) -> DraftFollowUpData {
+    DraftFollowUpData {
+        message: message.to_string(),
+        executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+    }
+}
+
+fn messages(queue: &[QueuedMessage]) -> Vec<&str> {
+    queue.iter().map(|m| m.data.message.as_str()).collect()
+}
+
+#[tokio::test]
+async fn test_enqueue_appends_in_order() {
+    let pool = setup_pool().await;
+    let session = create_test_session(&pool).await;
+
+    for text in ["first", "second", "third"] {
+        QueuedMessage::enqueue(&pool, session.id, &follow_up(text))
+            .await
+            .unwrap();
+    }
+
+    let queue = QueuedMessage::find_by_session_id(&pool, session.id)
+        .await
+        .unwrap();
+    assert_eq!(messages(&queue), vec!["first", "second", "third"]);
+    assert_eq!(
+        queue.iter().map(|m| m.position).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    assert_eq!(
+        queue[0].data.executor_profile_id.executor,
+        BaseCodingAgent::ClaudeCode
+    );
+}
+
+#[tokio::test]
+async fn test_take_next_drains_one_at_a_time() {
+    let pool = setup_pool().await;
+    let session = create_test_session(&pool).await;
+    QueuedMessage::enqueue(&pool, session.id, &follow_up("first"))
+        .await
+        .unwrap();
+    QueuedMessage::enqueue(&pool, session.id, &follow_up("second"))
+        .await
+        .unwrap();
+
+    let next = QueuedMessage::take_next(&pool, session.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(next.data.message, "first");
+    assert!(
+        QueuedMessage::exists_for_session(&pool, session.id)
+            .await
+            .unwrap()
+    );
+
+    let next = QueuedMessage::take_next(&pool, session.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(next.data.message, "second");
+    assert!(
+        QueuedMessage::take_next(&pool, session.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_reorder_and_edit() {
+    let pool = setup_pool().await;
+    let session = create_test_session(&pool).await;
+    let a = QueuedMessage::enqueue(&pool, session.id, &follow_up("a"))
+        .await
+        .unwrap();
+    let b = QueuedMessage::enqueue(&pool, session.id, &follow_up("b"))
+        .await
+        .unwrap();
+    let c = QueuedMessage::enqueue(&pool, session.id, &follow_up("c"))
+        .await
+        .unwrap();
+
+    let queue = QueuedMessage::reorder(&pool, session.id, &[c.id, a.id, b.id])
+        .await
+        .unwrap();
+    assert_eq!(messages(&queue), vec!["c", "a", "b"]);
+
+    let edited = QueuedMessage::update_data(&pool, a.id, &follow_up("a2"))
+        .await
+        .unwrap();
+    assert_eq!(edited.position, 1);
+    assert_eq!(edited.data.message, "a2");
+
+    // Reorder must name every message exactly once
+    let err = QueuedMessage::reorder(&pool, session.id, &[c.id, a.id])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, QueuedMessageError::InvalidOrder));
+    let err = QueuedMessage::reorder(&pool, session.id, &[c.id, a.id, a.id])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, QueuedMessageError::InvalidOrder));
+}
+
+#[tokio::test]
+async fn test_delete_and_session_isolation() {
+    let pool = setup_pool().await;
+    let session = create_test_session(&pool).await;
+    let other = create_test_session(&pool).await;
+    let a = QueuedMessage::enqueue(&pool, session.id, &follow_up("a"))
+        .await
+        .unwrap();
+    QueuedMessage::enqueue(&pool, session.id, &follow_up("b"))
+        .await
+        .unwrap();
+    let other_msg = QueuedMessage::enqueue(&pool, other.id, &follow_up("other"))
+        .await
+        .unwrap();
+    assert_eq!(other_msg.position, 0);
+
+    assert_eq!(QueuedMessage::delete(&pool, a.id).await.unwrap(), 1);
+    let queue = QueuedMessage::find_by_session_id(&pool, session.id)
+        .await
+        .unwrap();
+    assert_eq!(messages(&queue), vec!["b"]);
+
+    assert_eq!(
+        QueuedMessage::delete_by_session_id(&pool, session.id)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(
+        !QueuedMessage::exists_for_session(&pool, session.id)
+            .await
+            .unwrap()
+    );
+    assert!(
+        QueuedMessage::exists_for_session(&pool, other.id)
+            .await
+            .unwrap()
+    );
+}
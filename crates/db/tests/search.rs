@@ -0,0 +1,287 @@
+mod common;
+
+use common::{
+    create_execution, create_project, create_session, create_task, create_workspace, setup_pool,
+};
+use db::models::{
+    project_member::{ProjectMember, ProjectRole},
+    review_conversation::{CreateConversation, DiffSide, ReviewConversation},
+    search::{
+        ConversationEntryText, ConversationSearchIndex, SearchHit, SearchHitKind, SearchScope,
+        fts_query,
+    },
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+struct Fixture {
+    project_id: Uuid,
+    task_id: Uuid,
+    workspace_id: Uuid,
+    session_id: Uuid,
+    execution_id: Uuid,
+}
+
+/// A project with one task whose workspace has a coding agent run
+async fn create_fixture(pool: &SqlitePool, title: &str, description: &str) -> Fixture {
+    let project_id = create_project(pool, "Test Project").await;
+    let task = create_task(pool, project_id, title, Some(description)).await;
+    let workspace = create_workspace(pool, task.id, "vk/search").await;
+    let session = create_session(pool, workspace.id, None).await;
+    let execution_id = create_execution(pool, session.id, "codingagent", "running").await;
+
+    Fixture {
+        project_id,
+        task_id: task.id,
+        workspace_id: workspace.id,
+        session_id: session.id,
+        execution_id,
+    }
+}
+
+fn entry(entry_index: i64, entry_type: &str, content: &str) -> ConversationEntryText {
+    ConversationEntryText {
+        entry_index,
+        entry_type: entry_type.to_string(),
+        content: content.to_string(),
+    }
+}
+
+async fn search(pool: &SqlitePool, text: &str, scope: SearchScope) -> Vec<SearchHit> {
+    SearchHit::search(pool, text, scope, 50).await.unwrap()
+}
+
+#[test]
+fn test_fts_query_quotes_terms() {
+    assert_eq!(
+        fts_query("OOM-killer crash").as_deref(),
+        Some("\"OOM-killer\" \"crash\"")
+    );
+    assert_eq!(
+        fts_query("deploy* \"x").as_deref(),
+        Some("\"deploy\"* \"\"\"x\"")
+    );
+    assert_eq!(fts_query("  - * "), None);
+}
+
+#[tokio::test]
+async fn test_search_links_hits_to_their_source() {
+    let pool = setup_pool().await;
+    let fixture = create_fixture(&pool, "Fix flaky login", "Tests time out on CI").await;
+
+    ConversationSearchIndex::upsert_entries(
+        &pool,
+        fixture.execution_id,
+        &[
+            entry(2, "tool_use", "cargo test -p auth"),
+            entry(5, "error_message", "login test timed out after 60s"),
+        ],
+    )
+    .await
+    .unwrap();
+    let conversation = ReviewConversation::create(
+        &pool,
+        fixture.workspace_id,
+        &CreateConversation {
+            file_path: "src/login.rs".to_string(),
+            line_number: 12,
+            side: DiffSide::New,
+            code_line: None,
+            initial_message: "Why does the login retry here?".to_string(),
+        },
+        None,
+    )
+    .await
+    .unwrap();
+
+    let hits = search(&pool, "login", SearchScope::default()).await;
+    assert_eq!(hits.len(), 3);
+    let task_hit = hits.iter().find(|h| h.kind == SearchHitKind::Task).unwrap();
+    assert_eq!(task_hit.task_id, fixture.task_id);
+    assert_eq!(task_hit.workspace_id, None);
+
+    let entry_hit = hits
+        .iter()
+        .find(|h| h.kind == SearchHitKind::ConversationEntry)
+        .unwrap();
+    assert_eq!(entry_hit.project_id, fixture.project_id);
+    assert_eq!(entry_hit.workspace_id, Some(fixture.workspace_id));
+    assert_eq!(entry_hit.session_id, Some(fixture.session_id));
+    assert_eq!(entry_hit.execution_process_id, Some(fixture.execution_id));
+    assert_eq!(entry_hit.entry_index, Some(5));
+    assert_eq!(entry_hit.entry_type.as_deref(), Some("error_message"));
+
+    let review_hit = hits
+        .iter()
+        .find(|h| h.kind == SearchHitKind::ReviewMessage)
+        .unwrap();
+    assert_eq!(review_hit.review_conversation_id, Some(conversation.id));
+
+    // Stemming matches other forms of a word: "time" and "timed"
+    let hits = search(&pool, "timing", SearchScope::default()).await;
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|h| h.kind != SearchHitKind::ReviewMessage));
+}
+
+#[tokio::test]
+async fn test_index_follows_task_and_entry_changes() {
+    let pool = setup_pool().await;
+    let fixture = create_fixture(&pool, "Add dark mode", "Toggle in settings").await;
+
+    sqlx::query("UPDATE tasks SET title = 'Add high contrast theme' WHERE id = $1")
+        .bind(fixture.task_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(
+        search(&pool, "dark", SearchScope::default())
+            .await
+            .is_empty()
+    );
+    assert_eq!(
+        search(&pool, "contrast", SearchScope::default())
+            .await
+            .len(),
+        1
+    );
+
+    ConversationSearchIndex::upsert_entries(
+        &pool,
+        fixture.execution_id,
+        &[entry(1, "assistant_message", "Updating the palette")],
+    )
+    .await
+    .unwrap();
+    ConversationSearchIndex::upsert_entries(
+        &pool,
+        fixture.execution_id,
+        &[entry(1, "assistant_message", "Updating the stylesheet")],
+    )
+    .await
+    .unwrap();
+    assert!(
+        search(&pool, "palette", SearchScope::default())
+            .await
+            .is_empty()
+    );
+    assert_eq!(
+        search(&pool, "stylesheet", SearchScope::default())
+            .await
+            .len(),
+        1
+    );
+
+    ConversationSearchIndex::remove_entries(&pool, fixture.execution_id, &[1])
+        .await
+        .unwrap();
+    assert!(
+        search(&pool, "stylesheet", SearchScope::default())
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_stopped_runs_are_indexed_until_marked() {
+    let pool = setup_pool().await;
+    let fixture = create_fixture(&pool, "Speed up builds", "").await;
+    let stopped = create_execution(&pool, fixture.session_id, "codingagent", "failed").await;
+    create_execution(&pool, fixture.session_id, "setupscript", "completed").await;
+
+    // Only stopped coding agent runs are picked up; the running one indexes itself
+    assert_eq!(
+        ConversationSearchIndex::find_unindexed_executions(&pool)
+            .await
+            .unwrap(),
+        vec![stopped]
+    );
+
+    // A run cut short leaves entries behind; indexing its stored logs replaces them
+    ConversationSearchIndex::upsert_entries(
+        &pool,
+        stopped,
+        &[
+            entry(0, "assistant_message", "Looking at sccache"),
+            entry(4, "tool_use", "cargo build --timings"),
+        ],
+    )
+    .await
+    .unwrap();
+    ConversationSearchIndex::replace_entries(
+        &pool,
+        stopped,
+        &[entry(0, "assistant_message", "Enabling incremental builds")],
+    )
+    .await
+    .unwrap();
+    assert!(
+        search(&pool, "sccache", SearchScope::default())
+            .await
+            .is_empty()
+    );
+    assert!(
+        search(&pool, "timings", SearchScope::default())
+            .await
+            .is_empty()
+    );
+    assert_eq!(
+        search(&pool, "incremental", SearchScope::default())
+            .await
+            .len(),
+        1
+    );
+    assert!(
+        ConversationSearchIndex::find_unindexed_executions(&pool)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_search_scope_limits_projects() {
+    let pool = setup_pool().await;
+    let member_of = create_fixture(&pool, "Migrate billing", "").await;
+    let other = create_fixture(&pool, "Migrate search", "").await;
+
+    let user_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO users (id, github_id, username) VALUES ($1, $2, $3)")
+        .bind(user_id)
+        .bind(1_i64)
+        .bind("alice")
+        .execute(&pool)
+        .await
+        .unwrap();
+    ProjectMember::upsert(&pool, member_of.project_id, user_id, ProjectRole::Viewer)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        search(&pool, "migrate", SearchScope::default()).await.len(),
+        2
+    );
+
+    let hits = search(
+        &pool,
+        "migrate",
+        SearchScope {
+            project_id: None,
+            member_user_id: Some(user_id),
+        },
+    )
+    .await;
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].project_id, member_of.project_id);
+
+    let hits = search(
+        &pool,
+        "migrate",
+        SearchScope {
+            project_id: Some(other.project_id),
+            member_user_id: None,
+        },
+    )
+    .await;
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].task_id, other.task_id);
+}
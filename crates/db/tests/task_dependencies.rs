@@ -0,0 +1,124 @@
+mod common;
+
+use common::{create_project, create_task, setup_pool};
+use db::models::{
+    task::{Task, TaskStatus},
+    task_dependency::{TaskAutoStart, TaskDependency, TaskDependencyError, UpsertTaskAutoStart},
+    workspace_repo::CreateWorkspaceRepo,
+};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_dependencies_reject_self_cycles_and_other_projects() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let other_project_id = create_project(&pool, "Other Project").await;
+    let a = create_task(&pool, project_id, "a", None).await;
+    let b = create_task(&pool, project_id, "b", None).await;
+    let c = create_task(&pool, project_id, "c", None).await;
+    let elsewhere = create_task(&pool, other_project_id, "elsewhere", None).await;
+
+    // c is blocked by b, which is blocked by a
+    TaskDependency::create(&pool, &b, a.id).await.unwrap();
+    TaskDependency::create(&pool, &c, b.id).await.unwrap();
+    // Adding an existing dependency again is a no-op
+    TaskDependency::create(&pool, &c, b.id).await.unwrap();
+
+    assert!(matches!(
+        TaskDependency::create(&pool, &a, a.id).await,
+        Err(TaskDependencyError::SelfDependency)
+    ));
+    assert!(matches!(
+        TaskDependency::create(&pool, &a, c.id).await,
+        Err(TaskDependencyError::Cycle)
+    ));
+    assert!(matches!(
+        TaskDependency::create(&pool, &a, elsewhere.id).await,
+        Err(TaskDependencyError::DifferentProjects)
+    ));
+    assert!(matches!(
+        TaskDependency::create(&pool, &a, Uuid::new_v4()).await,
+        Err(TaskDependencyError::TaskNotFound)
+    ));
+
+    let dependencies = TaskDependency::find_for_task(&pool, b.id).await.unwrap();
+    assert_eq!(dependencies.blocked_by.len(), 1);
+    assert_eq!(dependencies.blocked_by[0].id, a.id);
+    assert_eq!(dependencies.blocking.len(), 1);
+    assert_eq!(dependencies.blocking[0].id, c.id);
+
+    assert_eq!(TaskDependency::delete(&pool, c.id, b.id).await.unwrap(), 1);
+    // With c no longer depending on b, a can be blocked by c
+    TaskDependency::create(&pool, &a, c.id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unresolved_blockers_and_ready_auto_starts() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let first = create_task(&pool, project_id, "first", None).await;
+    let second = create_task(&pool, project_id, "second", None).await;
+    let blocked = create_task(&pool, project_id, "blocked", None).await;
+
+    TaskDependency::create(&pool, &blocked, first.id)
+        .await
+        .unwrap();
+    TaskDependency::create(&pool, &blocked, second.id)
+        .await
+        .unwrap();
+
+    let auto_start = UpsertTaskAutoStart {
+        executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+        repos: vec![CreateWorkspaceRepo {
+            repo_id: Uuid::new_v4(),
+            target_branch: "main".to_string(),
+        }],
+    };
+    TaskAutoStart::upsert(&pool, blocked.id, &auto_start, None)
+        .await
+        .unwrap();
+    assert!(matches!(
+        TaskAutoStart::upsert(
+            &pool,
+            blocked.id,
+            &UpsertTaskAutoStart {
+                repos: vec![],
+                ..auto_start.clone()
+            },
+            None,
+        )
+        .await,
+        Err(TaskDependencyError::NoRepos)
+    ));
+
+    let unresolved = TaskDependency::find_unresolved_blockers(&pool, blocked.id)
+        .await
+        .unwrap();
+    assert_eq!(unresolved.len(), 2);
+    assert!(TaskAutoStart::find_ready(&pool).await.unwrap().is_empty());
+
+    Task::update_status(&pool, first.id, TaskStatus::Done)
+        .await
+        .unwrap();
+    let unresolved = TaskDependency::find_unresolved_blockers(&pool, blocked.id)
+        .await
+        .unwrap();
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].id, second.id);
+    assert!(TaskAutoStart::find_ready(&pool).await.unwrap().is_empty());
+
+    Task::update_status(&pool, second.id, TaskStatus::Done)
+        .await
+        .unwrap();
+    let ready = TaskAutoStart::find_ready(&pool).await.unwrap();
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].task_id, blocked.id);
+    assert_eq!(ready[0].repos[0].target_branch, "main");
+    assert_eq!(ready[0].executor_profile_id, auto_start.executor_profile_id);
+
+    // Claiming the auto-start removes it so it only fires once
+    assert_eq!(TaskAutoStart::delete(&pool, blocked.id).await.unwrap(), 1);
+    assert_eq!(TaskAutoStart::delete(&pool, blocked.id).await.unwrap(), 0);
+    assert!(TaskAutoStart::find_ready(&pool).await.unwrap().is_empty());
+}
@@ -0,0 +1,252 @@
+mod common;
+
+use common::{create_project, create_session, create_task, create_workspace, setup_pool};
+use db::models::{
+    project_budget::{BudgetPeriod, ProjectBudget, ProjectBudgetError, UpsertProjectBudget},
+    session::Session,
+    task::Task,
+    token_usage::{ExecutionTokenUsage, UsageFilter, UsageGroupBy},
+};
+use executors::logs::ModelTokenUsage;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Helper to create a user to attribute sessions to
+async fn create_test_user(pool: &SqlitePool, github_id: i64, username: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO users (id, github_id, username) VALUES ($1, $2, $3)")
+        .bind(id)
+        .bind(github_id)
+        .bind(username)
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
+
+/// Helper to create a task with a workspace and a session in `project_id`
+async fn create_test_session(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    title: &str,
+    user_id: Option<Uuid>,
+) -> (Task, Session) {
+    let task = create_task(pool, project_id, title, None).await;
+    let workspace = create_workspace(pool, task.id, &format!("vk/{title}")).await;
+    let session = create_session(pool, workspace.id, user_id).await;
+    (task, session)
+}
+
+/// Helper to create a finished coding agent execution in `session`
+async fn create_test_execution(pool: &SqlitePool, session: &Session) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO execution_processes (id, session_id, run_reason, status)
+         VALUES ($1, $2, 'codingagent', 'completed')",
+    )
+    .bind(id)
+    .bind(session.id)
+    .execute(pool)
+    .await
+    .unwrap();
+    id
+}
+
+fn usage(model: &str, input: u64, output: u64, cost_usd: Option<f64>) -> ModelTokenUsage {
+    ModelTokenUsage {
+        model: model.to_string(),
+        input_tokens: input,
+        output_tokens: output,
+        cache_read_tokens: 10,
+        cache_creation_tokens: 0,
+        cost_usd,
+    }
+}
+
+#[tokio::test]
+async fn test_record_replaces_cumulative_usage() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let (_, session) = create_test_session(&pool, project_id, "task", None).await;
+    let exec_id = create_test_execution(&pool, &session).await;
+
+    ExecutionTokenUsage::record(&pool, exec_id, &[usage("sonnet", 100, 10, Some(0.5))])
+        .await
+        .unwrap();
+    // A later report for the same model is the new running total, not an increment
+    ExecutionTokenUsage::record(
+        &pool,
+        exec_id,
+        &[
+            usage("sonnet", 300, 30, Some(1.5)),
+            usage("haiku", 20, 2, None),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let stored = ExecutionTokenUsage::find_by_execution_process_id(&pool, exec_id)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 2);
+    assert_eq!(stored[0].model, "haiku");
+    assert_eq!(stored[0].cost_usd, None);
+    assert_eq!(stored[1].model, "sonnet");
+    assert_eq!(stored[1].input_tokens, 300);
+    assert_eq!(stored[1].cost_usd, Some(1.5));
+}
+
+#[tokio::test]
+async fn test_rollups_by_task_user_model_and_day() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let other_project_id = create_project(&pool, "Other Project").await;
+    let alice = create_test_user(&pool, 1, "alice").await;
+
+    let (task_a, session_a) = create_test_session(&pool, project_id, "a", Some(alice)).await;
+    let (task_b, session_b) = create_test_session(&pool, project_id, "b", None).await;
+    let (_, session_other) = create_test_session(&pool, other_project_id, "c", None).await;
+
+    for (session, usage) in [
+        (&session_a, usage("sonnet", 100, 10, Some(1.0))),
+        (&session_a, usage("sonnet", 50, 5, Some(0.5))),
+        (&session_b, usage("haiku", 10, 1, None)),
+        (&session_other, usage("sonnet", 1000, 100, Some(10.0))),
+    ] {
+        let exec_id = create_test_execution(&pool, session).await;
+        ExecutionTokenUsage::record(&pool, exec_id, &[usage])
+            .await
+            .unwrap();
+    }
+
+    let filter = UsageFilter {
+        project_id: Some(project_id),
+        ..Default::default()
+    };
+
+    let by_task = ExecutionTokenUsage::rollup(&pool, &filter, UsageGroupBy::Task)
+        .await
+        .unwrap();
+    assert_eq!(by_task.len(), 2);
+    assert_eq!(by_task[0].group_id, Some(task_a.id));
+    assert_eq!(by_task[0].label.as_deref(), Some("a"));
+    assert_eq!(by_task[0].execution_count, 2);
+    assert_eq!(by_task[0].input_tokens, 150);
+    assert_eq!(by_task[0].total_tokens, 150 + 15 + 20);
+    assert_eq!(by_task[0].cost_usd, Some(1.5));
+    assert_eq!(by_task[1].group_id, Some(task_b.id));
+    assert_eq!(by_task[1].cost_usd, None);
+
+    let by_user = ExecutionTokenUsage::rollup(&pool, &filter, UsageGroupBy::User)
+        .await
+        .unwrap();
+    assert_eq!(by_user.len(), 2);
+    assert_eq!(by_user[0].group_id, Some(alice));
+    assert_eq!(by_user[0].label.as_deref(), Some("alice"));
+    assert_eq!(by_user[1].group_id, None);
+
+    let by_model = ExecutionTokenUsage::rollup(&pool, &UsageFilter::default(), UsageGroupBy::Model)
+        .await
+        .unwrap();
+    let labels: Vec<_> = by_model.iter().map(|r| r.label.as_deref()).collect();
+    assert_eq!(labels, vec![Some("sonnet"), Some("haiku")]);
+    assert_eq!(by_model[0].execution_count, 3);
+
+    let by_day = ExecutionTokenUsage::rollup(&pool, &filter, UsageGroupBy::Day)
+        .await
+        .unwrap();
+    assert_eq!(by_day.len(), 1);
+    assert_eq!(by_day[0].execution_count, 3);
+
+    let task_totals = ExecutionTokenUsage::totals(
+        &pool,
+        &UsageFilter {
+            task_id: Some(task_b.id),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(task_totals.total_tokens, 21);
+
+    // Usage recorded before `since` is excluded
+    let future = ExecutionTokenUsage::totals(
+        &pool,
+        &UsageFilter {
+            since: Some(chrono::Utc::now() + chrono::Duration::days(1)),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(future.execution_count, 0);
+    assert_eq!(future.total_tokens, 0);
+}
+
+#[tokio::test]
+async fn test_project_budget_status() {
+    let pool = setup_pool().await;
+    let project_id = create_project(&pool, "Test Project").await;
+    let (_, session) = create_test_session(&pool, project_id, "task", None).await;
+
+    assert!(
+        ProjectBudget::status_for_project(&pool, project_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(matches!(
+        ProjectBudget::upsert(
+            &pool,
+            project_id,
+            &UpsertProjectBudget {
+                max_tokens: Some(0),
+                max_cost_usd: None,
+                period: BudgetPeriod::Total,
+            },
+        )
+        .await,
+        Err(ProjectBudgetError::Invalid(_))
+    ));
+
+    ProjectBudget::upsert(
+        &pool,
+        project_id,
+        &UpsertProjectBudget {
+            max_tokens: None,
+            max_cost_usd: Some(2.0),
+            period: BudgetPeriod::Monthly,
+        },
+    )
+    .await
+    .unwrap();
+
+    let exec_id = create_test_execution(&pool, &session).await;
+    ExecutionTokenUsage::record(&pool, exec_id, &[usage("sonnet", 100, 10, Some(1.0))])
+        .await
+        .unwrap();
+    let status = ProjectBudget::status_for_project(&pool, project_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(status.budget.period, BudgetPeriod::Monthly);
+    assert_eq!(status.usage.cost_usd, Some(1.0));
+    assert!(!status.exceeded);
+
+    ExecutionTokenUsage::record(&pool, exec_id, &[usage("sonnet", 200, 20, Some(2.5))])
+        .await
+        .unwrap();
+    let status = ProjectBudget::status_for_project(&pool, project_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(status.exceeded);
+
+    assert_eq!(ProjectBudget::delete(&pool, project_id).await.unwrap(), 1);
+    assert!(
+        ProjectBudget::status_for_project(&pool, project_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{create_execution, create_project, create_task_session, setup_pool};
+use db::models::tool_approval::{
+    CreateToolApproval, ToolApproval, ToolApprovalResponse, ToolApprovalStatus,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Helper to create a running coding agent execution in a fresh project
+async fn create_test_execution(pool: &SqlitePool) -> Uuid {
+    let project_id = create_project(pool, "Test Project").await;
+    let (_, _, session) = create_task_session(pool, project_id, "task").await;
+    create_execution(pool, session.id, "codingagent", "running").await
+}
+
+async fn request(pool: &SqlitePool, execution_process_id: Uuid, command: &str) -> ToolApproval {
+    let now = Utc::now();
+    ToolApproval::create(
+        pool,
+        &CreateToolApproval {
+            id: Uuid::new_v4().to_string(),
+            execution_process_id,
+            tool_name: "Bash".to_string(),
+            tool_call_id: Uuid::new_v4().to_string(),
+            tool_input: json!({ "command": command }),
+            created_at: now,
+            timeout_at: now + Duration::hours(1),
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_responses_are_recorded_once() {
+    let pool = setup_pool().await;
+    let execution_process_id = create_test_execution(&pool).await;
+    let user_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO users (id, github_id, username) VALUES ($1, $2, $3)")
+        .bind(user_id)
+        .bind(1_i64)
+        .bind("alice")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let approval = request(&pool, execution_process_id, "cargo test").await;
+    assert_eq!(approval.status, ToolApprovalStatus::Pending);
+    assert_eq!(approval.tool_input, json!({ "command": "cargo test" }));
+
+    let response = ToolApprovalResponse {
+        status: ToolApprovalStatus::Approved,
+        reason: None,
+        responded_by_user_id: Some(user_id),
+        policy_rule: None,
+    };
+    let answered = ToolApproval::respond(&pool, &approval.id, &response)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(answered.status, ToolApprovalStatus::Approved);
+    assert_eq!(answered.responded_by_user_id, Some(user_id));
+    assert!(answered.responded_at.is_some());
+    assert!(answered.latency_ms.is_some_and(|ms| ms >= 0));
+
+    let late = ToolApprovalResponse {
+        status: ToolApprovalStatus::TimedOut,
+        reason: None,
+        responded_by_user_id: None,
+        policy_rule: None,
+    };
+    assert!(
+        ToolApproval::respond(&pool, &approval.id, &late)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let history = ToolApproval::find_by_responder(&pool, user_id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].id, approval.id);
+}
+
+#[tokio::test]
+async fn test_expire_pending_closes_unanswered_approvals() {
+    let pool = setup_pool().await;
+    let execution_process_id = create_test_execution(&pool).await;
+
+    let answered = request(&pool, execution_process_id, "git status").await;
+    let denied = ToolApprovalResponse {
+        status: ToolApprovalStatus::Denied,
+        reason: Some("no".to_string()),
+        responded_by_user_id: None,
+        policy_rule: Some("no-git".to_string()),
+    };
+    ToolApproval::respond(&pool, &answered.id, &denied)
+        .await
+        .unwrap();
+    request(&pool, execution_process_id, "rm -rf /").await;
+
+    assert_eq!(ToolApproval::expire_pending(&pool).await.unwrap(), 1);
+
+    let history = ToolApproval::find_by_execution_process_id(&pool, execution_process_id)
+        .await
+        .unwrap();
+    let statuses: Vec<_> = history.iter().map(|a| a.status).collect();
+    assert_eq!(
+        statuses,
+        vec![ToolApprovalStatus::Denied, ToolApprovalStatus::Expired]
+    );
+    assert_eq!(history[0].policy_rule.as_deref(), Some("no-git"));
+}
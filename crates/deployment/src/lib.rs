@@ -34,6 +34,7 @@ use services::services::{
     queued_message::QueuedMessageService,
     repo::RepoService,
     share::SharePublisher,
+    token_encryption::TokenEncryptionError,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
@@ -73,6 +74,8 @@ pub enum DeploymentError {
     Event(#[from] EventError),
     #[error(transparent)]
     Config(#[from] ConfigError),
+    #[error(transparent)]
+    TokenEncryption(#[from] TokenEncryptionError),
     #[error("Remote client not configured")]
     RemoteClientNotConfigured,
     #[error(transparent)]
@@ -136,7 +139,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 user_id: self.user_id().to_string(),
                 analytics_service: analytics_service.clone(),
             });
-        PrMonitorService::spawn(db, analytics).await
+        let notifications = self.container().notification_service().clone();
+        PrMonitorService::spawn(db, self.config().clone(), analytics, notifications).await
     }
 
     async fn spawn_main_branch_sync_service(&self) -> tokio::task::JoinHandle<()> {
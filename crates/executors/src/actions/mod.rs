@@ -14,6 +14,7 @@ use crate::{
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild},
+    profile::ExecutorProfileId,
 };
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
@@ -69,6 +70,49 @@ impl ExecutorAction {
             ExecutorActionType::ScriptRequest(_) => None,
         }
     }
+
+    pub fn executor_profile_id(&self) -> Option<&ExecutorProfileId> {
+        match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Some(&request.executor_profile_id)
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Some(&request.executor_profile_id)
+            }
+            ExecutorActionType::ReviewRequest(request) => Some(&request.executor_profile_id),
+            ExecutorActionType::ScriptRequest(_) => None,
+        }
+    }
+
+    /// The same coding agent request run on another executor profile. An agent session
+    /// can't be resumed by a different executor, so a follow-up becomes a fresh run
+    /// with the same prompt.
+    pub fn with_executor_profile(&self, executor_profile_id: ExecutorProfileId) -> Option<Self> {
+        let typ = match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    executor_profile_id,
+                    ..request.clone()
+                })
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt: request.prompt.clone(),
+                    executor_profile_id,
+                    working_dir: request.working_dir.clone(),
+                })
+            }
+            ExecutorActionType::ReviewRequest(request) => {
+                ExecutorActionType::ReviewRequest(ReviewRequest {
+                    executor_profile_id,
+                    session_id: None,
+                    ..request.clone()
+                })
+            }
+            ExecutorActionType::ScriptRequest(_) => return None,
+        };
+        Some(Self::new(typ, self.next_action.clone()))
+    }
 }
 
 #[async_trait]
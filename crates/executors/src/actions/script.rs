@@ -3,7 +3,6 @@ use std::{path::Path, sync::Arc};
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::shell::get_shell_command;
 
@@ -25,6 +24,7 @@ pub enum ScriptContext {
     CleanupScript,
     DevServer,
     ToolInstallScript,
+    VerifyScript,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -33,7 +33,8 @@ pub struct ScriptRequest {
     pub language: ScriptRequestLanguage,
     pub context: ScriptContext,
     /// Optional relative path to execute the script in (relative to container_ref).
-    /// If None, uses the container_ref directory directly.
+    /// If None, uses the container_ref directory directly. An absolute path is used
+    /// as is.
     #[serde(default)]
     pub working_dir: Option<String>,
 }
@@ -53,7 +54,7 @@ impl Executable for ScriptRequest {
         };
 
         let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        let mut command = env.command(shell_cmd);
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
@@ -6,7 +6,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{executors::ExecutorError, retry::RetryPolicy, sandbox::SandboxNetwork};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -60,6 +60,42 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "Sandbox Network",
+        description = "Network access when the process sandbox is enabled; overrides the global setting"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox_network: Option<SandboxNetwork>,
+    #[schemars(
+        title = "Maximum Runtime (minutes)",
+        description = "Stop the agent once a single run has taken this long"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runtime_minutes: Option<u32>,
+    #[schemars(
+        title = "Maximum Idle Time (minutes)",
+        description = "Stop the agent when it has produced no output for this long. Time spent waiting for a tool approval does not count."
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_idle_minutes: Option<u32>,
+    #[schemars(
+        title = "Continue After Timeout",
+        description = "After stopping the agent for a timeout, send a follow-up asking it to continue"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continue_after_timeout: Option<bool>,
+    #[schemars(
+        title = "Maximum Timeout Continuations",
+        description = "How many continue follow-ups a session gets before the task is put on hold as timed out. Defaults to 1."
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_timeout_continuations: Option<u32>,
+    #[schemars(
+        title = "Retry Policy",
+        description = "Retry failed runs on this profile and then on fallback profiles"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -1,8 +1,8 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, ffi::OsStr, path::PathBuf};
 
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{command::CmdOverrides, sandbox::Sandbox};
 
 /// Repository context for executor operations
 #[derive(Debug, Clone, Default)]
@@ -34,6 +34,8 @@ pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
+    /// Sandbox the executor's processes are started in, if enabled
+    pub sandbox: Option<Sandbox>,
 }
 
 impl ExecutionEnv {
@@ -42,9 +44,15 @@ impl ExecutionEnv {
             vars: HashMap::new(),
             repo_context,
             commit_reminder,
+            sandbox: None,
         }
     }
 
+    pub fn with_sandbox(mut self, sandbox: Option<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
     /// Insert an environment variable
     pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
         self.vars.insert(key.into(), value.into());
@@ -62,8 +70,12 @@ impl ExecutionEnv {
         self
     }
 
-    /// Return a new env with profile env from CmdOverrides merged in.
-    pub fn with_profile(self, cmd: &CmdOverrides) -> Self {
+    /// Return a new env with profile env and sandbox settings from CmdOverrides
+    /// applied.
+    pub fn with_profile(mut self, cmd: &CmdOverrides) -> Self {
+        if let (Some(sandbox), Some(network)) = (self.sandbox.as_mut(), cmd.sandbox_network) {
+            sandbox.set_network(network);
+        }
         if let Some(ref profile_env) = cmd.env {
             self.with_overrides(profile_env)
         } else {
@@ -71,6 +83,14 @@ impl ExecutionEnv {
         }
     }
 
+    /// A command for `program`, run inside the sandbox when one is set
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        match &self.sandbox {
+            Some(sandbox) => sandbox.command(program),
+            None => Command::new(program),
+        }
+    }
+
     /// Apply all environment variables to a Command
     pub fn apply_to_command(&self, command: &mut Command) {
         for (key, value) in &self.vars {
@@ -9,7 +9,7 @@ use agent_client_protocol as proto;
 use agent_client_protocol::Agent as _;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
-use tokio::{io::AsyncWriteExt, process::Command, sync::mpsc};
+use tokio::{io::AsyncWriteExt, sync::mpsc};
 use tokio_util::{
     compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt},
     io::ReaderStream,
@@ -79,7 +79,8 @@ impl AcpAgentHarness {
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(cmd_overrides);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -90,9 +91,7 @@ impl AcpAgentHarness {
             .env("NODE_NO_WARNINGS", "1")
             .args(&args);
 
-        env.clone()
-            .with_profile(cmd_overrides)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
 
@@ -132,7 +131,8 @@ impl AcpAgentHarness {
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(cmd_overrides);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -143,9 +143,7 @@ impl AcpAgentHarness {
             .env("NODE_NO_WARNINGS", "1")
             .args(&args);
 
-        env.clone()
-            .with_profile(cmd_overrides)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
 
@@ -56,7 +56,8 @@ impl StandardCodingAgentExecutor for Amp {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(executable_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(executable_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -66,9 +67,7 @@ impl StandardCodingAgentExecutor for Amp {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
 
@@ -132,7 +131,8 @@ impl StandardCodingAgentExecutor for Amp {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(continue_program);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(continue_program);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -142,9 +142,7 @@ impl StandardCodingAgentExecutor for Amp {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&continue_args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
 
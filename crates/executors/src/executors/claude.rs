@@ -10,7 +10,6 @@ use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize};
-use tokio::process::Command;
 use tokio_util::sync::CancellationToken;
 use ts_rs::TS;
 use workspace_utils::{
@@ -32,8 +31,8 @@ use crate::{
         codex::client::LogWriter,
     },
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolStatus,
+        ActionType, FileChange, ModelTokenUsage, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, TodoItem, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{EntryIndexProvider, patch::ConversationPatch},
     },
@@ -264,7 +263,8 @@ impl ClaudeCode {
         let (program_path, args) = command_parts.into_resolved().await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -274,9 +274,7 @@ impl ClaudeCode {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         // Remove ANTHROPIC_API_KEY if disable_api_key is enabled
         if self.disable_api_key.unwrap_or(false) {
@@ -357,6 +355,39 @@ pub enum HistoryStrategy {
 /// Default context window for models (used until we get actual value from result)
 const DEFAULT_CLAUDE_CONTEXT_WINDOW: u32 = 200_000;
 
+/// Recognize Claude errors caused by the credentials rather than the task: usage limits
+/// (`Claude AI usage limit reached|<reset unix ts>`, `5-hour limit reached ∙ resets 3pm`),
+/// short-lived API rate limiting and rejected OAuth tokens.
+pub fn classify_claude_error(message: &str) -> Option<NormalizedEntryError> {
+    let lower = message.to_lowercase();
+
+    if lower.contains("usage limit reached")
+        || lower.contains("limit reached ∙ resets")
+        || lower.contains("limit reached · resets")
+    {
+        let resets_at = message
+            .split_once('|')
+            .and_then(|(_, ts)| ts.trim().parse::<i64>().ok());
+        return Some(NormalizedEntryError::UsageLimitReached { resets_at });
+    }
+
+    if lower.contains("rate_limit_error") {
+        return Some(NormalizedEntryError::RateLimited);
+    }
+
+    if lower.contains("invalid api key")
+        || lower.contains("oauth token has expired")
+        || lower.contains("oauth token has been revoked")
+        || lower.contains("authentication_error")
+        || lower.contains("please run /login")
+        || lower.contains("invalid bearer token")
+    {
+        return Some(NormalizedEntryError::AuthenticationFailed);
+    }
+
+    None
+}
+
 /// Handles log processing and interpretation for Claude executor
 pub struct ClaudeLogProcessor {
     model_name: Option<String>,
@@ -370,6 +401,8 @@ pub struct ClaudeLogProcessor {
     main_model_name: Option<String>,
     main_model_context_window: u32,
     context_tokens_used: u32,
+    // Per-model usage reported by the last result message
+    token_usage: Vec<ModelTokenUsage>,
 }
 
 impl ClaudeLogProcessor {
@@ -388,6 +421,7 @@ impl ClaudeLogProcessor {
             streaming_message_id: None,
             main_model_context_window: DEFAULT_CLAUDE_CONTEXT_WINDOW,
             context_tokens_used: 0,
+            token_usage: Vec::new(),
         }
     }
 
@@ -1244,20 +1278,56 @@ impl ClaudeLogProcessor {
             ClaudeJson::Result {
                 is_error,
                 model_usage,
+                usage,
+                result,
+                error,
                 ..
             } => {
                 // get the real model context window and correct the context usage entry
-                if let Some(context_window) = model_usage.as_ref().and_then(|model_usage| {
+                let context_window = model_usage.as_ref().and_then(|model_usage| {
                     self.main_model_name
                         .as_ref()
                         .and_then(|name| model_usage.get(name))
                         .and_then(|usage| usage.context_window)
-                }) {
+                });
+                if let Some(context_window) = context_window {
                     self.main_model_context_window = context_window;
+                }
+                let token_usage = Self::collect_token_usage(
+                    model_usage.as_ref(),
+                    usage.as_ref(),
+                    self.main_model_name.as_deref(),
+                );
+                let has_usage = !token_usage.is_empty();
+                if has_usage {
+                    self.token_usage = token_usage;
+                }
+                if context_window.is_some() || has_usage {
                     patches.push(self.add_token_usage_entry(entry_index_provider));
                 }
 
-                if matches!(self.strategy, HistoryStrategy::AmpResume) && is_error.unwrap_or(false)
+                let error_text = error
+                    .clone()
+                    .or_else(|| result.as_ref().and_then(|r| r.as_str()).map(str::to_string));
+                let classified = if is_error.unwrap_or(false) {
+                    error_text.as_deref().and_then(classify_claude_error)
+                } else {
+                    None
+                };
+
+                if let Some(error_type) = classified {
+                    // Usage-limit and auth failures are surfaced for every strategy so the
+                    // token rotation can react to them.
+                    let entry = NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::ErrorMessage { error_type },
+                        content: error_text.unwrap_or_default(),
+                        metadata: None,
+                    };
+                    let idx = entry_index_provider.next();
+                    patches.push(ConversationPatch::add_normalized_entry(idx, entry));
+                } else if matches!(self.strategy, HistoryStrategy::AmpResume)
+                    && is_error.unwrap_or(false)
                 {
                     let entry = NormalizedEntry {
                         timestamp: None,
@@ -1422,6 +1492,42 @@ impl ClaudeLogProcessor {
         }
     }
 
+    /// Per-model token usage from a result message. `modelUsage` covers subagents too;
+    /// the aggregate `usage` is attributed to the main model when it is missing.
+    fn collect_token_usage(
+        model_usage: Option<&HashMap<String, ClaudeModelUsage>>,
+        usage: Option<&ClaudeUsage>,
+        main_model_name: Option<&str>,
+    ) -> Vec<ModelTokenUsage> {
+        if let Some(model_usage) = model_usage.filter(|m| !m.is_empty()) {
+            let mut usage: Vec<ModelTokenUsage> = model_usage
+                .iter()
+                .map(|(model, usage)| ModelTokenUsage {
+                    model: model.clone(),
+                    input_tokens: usage.input_tokens.unwrap_or(0),
+                    output_tokens: usage.output_tokens.unwrap_or(0),
+                    cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                    cache_creation_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+                    cost_usd: usage.cost_usd,
+                })
+                .collect();
+            usage.sort_by(|a, b| a.model.cmp(&b.model));
+            return usage;
+        }
+        usage
+            .map(|usage| {
+                vec![ModelTokenUsage {
+                    model: main_model_name.unwrap_or("claude").to_string(),
+                    input_tokens: usage.input_tokens.unwrap_or(0),
+                    output_tokens: usage.output_tokens.unwrap_or(0),
+                    cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                    cache_creation_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+                    cost_usd: None,
+                }]
+            })
+            .unwrap_or_default()
+    }
+
     fn add_token_usage_entry(
         &mut self,
         entry_index_provider: &EntryIndexProvider,
@@ -1431,6 +1537,7 @@ impl ClaudeLogProcessor {
             entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
                 total_tokens: self.context_tokens_used,
                 model_context_window: self.main_model_context_window,
+                usage: self.token_usage.clone(),
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
@@ -1815,6 +1922,16 @@ pub struct ClaudeUsage {
 pub struct ClaudeModelUsage {
     #[serde(default)]
     pub context_window: Option<u32>,
+    #[serde(default)]
+    pub input_tokens: Option<u64>,
+    #[serde(default)]
+    pub output_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u64>,
+    #[serde(default, rename = "costUSD")]
+    pub cost_usd: Option<f64>,
 }
 
 /// Structured tool data for Claude tools based on real samples
@@ -2106,6 +2223,48 @@ mod tests {
         assert_eq!(entries.len(), 0); // Should be ignored like in old implementation
     }
 
+    #[test]
+    fn test_usage_limit_result_is_classified() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":true,"duration_ms":120,"result":"Claude AI usage limit reached|1760000000"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        let entries = normalize(&parsed, "");
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::UsageLimitReached {
+                    resets_at: Some(1760000000)
+                }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_classify_claude_error() {
+        assert_eq!(
+            classify_claude_error("5-hour limit reached ∙ resets 3pm"),
+            Some(NormalizedEntryError::UsageLimitReached { resets_at: None })
+        );
+        assert_eq!(
+            classify_claude_error("Invalid API key · Please run /login"),
+            Some(NormalizedEntryError::AuthenticationFailed)
+        );
+        assert_eq!(
+            classify_claude_error(
+                r#"API Error: 401 {"type":"error","error":{"type":"authentication_error","message":"OAuth token has expired."}}"#
+            ),
+            Some(NormalizedEntryError::AuthenticationFailed)
+        );
+        assert_eq!(
+            classify_claude_error(
+                r#"API Error: 429 {"type":"error","error":{"type":"rate_limit_error","message":"Number of requests has exceeded your rate limit"}}"#
+            ),
+            Some(NormalizedEntryError::RateLimited)
+        );
+        assert_eq!(classify_claude_error("Tests failed"), None);
+    }
+
     #[test]
     fn test_thinking_content() {
         let thinking_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Let me think about this..."}]}}"#;
@@ -2221,6 +2380,12 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                sandbox_network: None,
+                max_runtime_minutes: None,
+                max_idle_minutes: None,
+                continue_after_timeout: None,
+                max_timeout_continuations: None,
+                retry: None,
             },
             approvals_service: None,
             disable_api_key: None,
@@ -2271,6 +2436,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_result_token_usage_per_model() {
+        let mut processor = ClaudeLogProcessor::new();
+        let init_json = r#"{"type":"system","subtype":"init","model":"claude-sonnet-4"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(init_json).unwrap();
+        normalize_helper(&mut processor, &parsed, "");
+
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"result":"done","modelUsage":{"claude-sonnet-4":{"inputTokens":120,"outputTokens":40,"cacheReadInputTokens":900,"cacheCreationInputTokens":300,"costUSD":0.05,"contextWindow":200000},"claude-haiku":{"inputTokens":10,"outputTokens":5,"costUSD":0.001}}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+        let entries = normalize_helper(&mut processor, &parsed, "");
+        let info = entries
+            .iter()
+            .find_map(|entry| match &entry.entry_type {
+                NormalizedEntryType::TokenUsageInfo(info) => Some(info.clone()),
+                _ => None,
+            })
+            .expect("Expected a token usage entry");
+
+        assert_eq!(info.model_context_window, 200000);
+        assert_eq!(info.usage.len(), 2);
+        assert_eq!(info.usage[0].model, "claude-haiku");
+        assert_eq!(info.usage[0].cache_read_tokens, 0);
+        let sonnet = &info.usage[1];
+        assert_eq!(sonnet.model, "claude-sonnet-4");
+        assert_eq!(sonnet.input_tokens, 120);
+        assert_eq!(sonnet.output_tokens, 40);
+        assert_eq!(sonnet.cache_read_tokens, 900);
+        assert_eq!(sonnet.cache_creation_tokens, 300);
+        assert_eq!(sonnet.cost_usd, Some(0.05));
+
+        // Without a per-model breakdown the aggregate usage goes to the main model
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"result":"done","usage":{"input_tokens":7,"output_tokens":3}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+        let entries = normalize_helper(&mut processor, &parsed, "");
+        match &entries[0].entry_type {
+            NormalizedEntryType::TokenUsageInfo(info) => {
+                assert_eq!(info.usage.len(), 1);
+                assert_eq!(info.usage[0].model, "claude-sonnet-4");
+                assert_eq!(info.usage[0].input_tokens, 7);
+                assert_eq!(info.usage[0].cost_usd, None);
+            }
+            other => panic!("Expected TokenUsageInfo, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_amp_tool_aliases_create_file_and_edit_file() {
         // Amp "create_file" should deserialize into Write with alias field "path"
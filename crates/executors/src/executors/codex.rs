@@ -34,7 +34,6 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use strum_macros::AsRefStr;
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -348,7 +347,8 @@ impl Codex {
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
 
-        let mut process = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut process = env.command(program_path);
         process
             .kill_on_drop(true)
             .stdin(std::process::Stdio::piped())
@@ -361,9 +361,7 @@ impl Codex {
             .env("RUST_LOG", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut process);
+        env.apply_to_command(&mut process);
 
         let mut child = process.group_spawn()?;
 
@@ -17,7 +17,7 @@ use codex_protocol::{
         ErrorEvent, EventMsg, ExecApprovalRequestEvent, ExecCommandBeginEvent, ExecCommandEndEvent,
         ExecCommandOutputDeltaEvent, ExecOutputStream, FileChange as CodexProtoFileChange,
         McpInvocation, McpToolCallBeginEvent, McpToolCallEndEvent, PatchApplyBeginEvent,
-        PatchApplyEndEvent, StreamErrorEvent, ViewImageToolCallEvent, WarningEvent,
+        PatchApplyEndEvent, StreamErrorEvent, TokenUsage, ViewImageToolCallEvent, WarningEvent,
         WebSearchBeginEvent, WebSearchEndEvent,
     },
 };
@@ -34,9 +34,9 @@ use crate::{
     approvals::ToolCallMetadata,
     executors::codex::session::SessionHandler,
     logs::{
-        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
-        ToolStatus,
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, ModelTokenUsage,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult,
+        ToolResultValueType, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             ConversationPatch, EntryIndexProvider,
@@ -215,6 +215,9 @@ struct LogState {
     mcp_tools: HashMap<String, McpToolState>,
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
+    model: Option<String>,
+    /// Tokens used by this execution so far, per model
+    token_usage: Vec<ModelTokenUsage>,
 }
 
 enum StreamingTextKind {
@@ -232,9 +235,34 @@ impl LogState {
             mcp_tools: HashMap::new(),
             patches: HashMap::new(),
             web_searches: HashMap::new(),
+            model: None,
+            token_usage: Vec::new(),
         }
     }
 
+    /// Fold the usage of one model request into the execution's totals. Codex's
+    /// `total_token_usage` covers the whole session, including the turns of earlier
+    /// executions, so only each request's `last_token_usage` is added up.
+    fn add_token_usage(&mut self, last: &TokenUsage) -> Vec<ModelTokenUsage> {
+        let model = self.model.clone().unwrap_or_else(|| "codex".to_string());
+        // Cached input tokens are included in input_tokens
+        let usage = ModelTokenUsage {
+            input_tokens: (last.input_tokens - last.cached_input_tokens).max(0) as u64,
+            output_tokens: last.output_tokens.max(0) as u64,
+            cache_read_tokens: last.cached_input_tokens.max(0) as u64,
+            ..ModelTokenUsage::new(&model)
+        };
+        match self
+            .token_usage
+            .iter_mut()
+            .find(|entry| entry.model == model)
+        {
+            Some(entry) => entry.add(&usage),
+            None => self.token_usage.push(usage),
+        }
+        self.token_usage.clone()
+    }
+
     fn streaming_text_update(
         &mut self,
         content: String,
@@ -387,6 +415,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -425,6 +454,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -969,6 +999,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        let usage = state.add_token_usage(&info.last_token_usage);
                         add_normalized_entry(
                             &msg_store,
                             &entry_index,
@@ -981,6 +1012,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                             .model_context_window
                                             .unwrap_or_default()
                                             as u32,
+                                        usage,
                                     },
                                 ),
                                 content: format!(
@@ -1223,3 +1255,35 @@ impl ToNormalizedEntryOpt for Approval {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_usage_adds_up_requests_not_session_totals() {
+        let mut state = LogState::new(EntryIndexProvider::test_new());
+        state.model = Some("gpt-5-codex".to_string());
+
+        state.add_token_usage(&TokenUsage {
+            input_tokens: 1000,
+            cached_input_tokens: 400,
+            output_tokens: 200,
+            total_tokens: 1200,
+            ..Default::default()
+        });
+        let usage = state.add_token_usage(&TokenUsage {
+            input_tokens: 1500,
+            cached_input_tokens: 1000,
+            output_tokens: 100,
+            total_tokens: 1600,
+            ..Default::default()
+        });
+
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].model, "gpt-5-codex");
+        assert_eq!(usage[0].input_tokens, 1100);
+        assert_eq!(usage[0].cache_read_tokens, 1400);
+        assert_eq!(usage[0].output_tokens, 300);
+    }
+}
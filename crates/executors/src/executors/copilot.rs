@@ -14,7 +14,6 @@ use serde::{Deserialize, Serialize};
 use tokio::{
     fs,
     io::AsyncWriteExt,
-    process::Command,
     time::{interval, timeout},
 };
 use ts_rs::TS;
@@ -112,7 +111,8 @@ impl StandardCodingAgentExecutor for Copilot {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -123,9 +123,7 @@ impl StandardCodingAgentExecutor for Copilot {
             .env("NODE_NO_WARNINGS", "1")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
 
@@ -157,7 +155,8 @@ impl StandardCodingAgentExecutor for Copilot {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path);
 
         command
             .kill_on_drop(true)
@@ -169,9 +168,7 @@ impl StandardCodingAgentExecutor for Copilot {
             .env("NODE_NO_WARNINGS", "1")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
 
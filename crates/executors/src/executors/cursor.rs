@@ -6,7 +6,7 @@ use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use workspace_utils::{
     diff::{create_unified_diff, normalize_unified_diff},
@@ -85,7 +85,8 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(executable_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(executable_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -95,9 +96,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
 
@@ -126,7 +125,8 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(executable_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(executable_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -136,9 +136,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
 
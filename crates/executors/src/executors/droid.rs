@@ -5,7 +5,7 @@ use command_group::AsyncCommandGroup;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::AsRefStr;
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -114,7 +114,8 @@ async fn spawn_droid(
 ) -> Result<SpawnedChild, ExecutorError> {
     let (program_path, args) = command_parts.into_resolved().await?;
 
-    let mut command = Command::new(program_path);
+    let env = env.clone().with_profile(cmd_overrides);
+    let mut command = env.command(program_path);
     command
         .kill_on_drop(true)
         .stdin(Stdio::piped())
@@ -124,9 +125,7 @@ async fn spawn_droid(
         .env("NPM_CONFIG_LOGLEVEL", "error")
         .args(args);
 
-    env.clone()
-        .with_profile(cmd_overrides)
-        .apply_to_command(&mut command);
+    env.apply_to_command(&mut command);
 
     let mut child = command.group_spawn()?;
 
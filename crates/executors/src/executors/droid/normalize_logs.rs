@@ -12,8 +12,8 @@ use workspace_utils::{
 };
 
 use crate::logs::{
-    ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-    NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolStatus,
+    ActionType, CommandExitStatus, CommandRunResult, FileChange, ModelTokenUsage, NormalizedEntry,
+    NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo, ToolResult, ToolStatus,
     plain_text_processor::PlainTextLogProcessor,
     utils::{
         EntryIndexProvider,
@@ -83,6 +83,9 @@ pub fn normalize_logs(
             // Normalize JSON logs
             match droid_json {
                 DroidJson::System { model, .. } => {
+                    if state.model.is_none() {
+                        state.model = model.clone();
+                    }
                     if !state.model_reported
                         && let Some(model) = model
                     {
@@ -641,7 +644,9 @@ pub fn normalize_logs(
                     }
                 }
 
-                DroidJson::Completion { final_text, .. } => {
+                DroidJson::Completion {
+                    final_text, usage, ..
+                } => {
                     let entry = NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::AssistantMessage,
@@ -650,6 +655,25 @@ pub fn normalize_logs(
                     };
                     add_normalized_entry(&msg_store, &entry_index_provider, entry);
                     sent_completion = true;
+
+                    if let Some(usage) = usage {
+                        let usage = usage.to_model_usage(state.model.as_deref());
+                        let total_tokens = usage.input_tokens
+                            + usage.output_tokens
+                            + usage.cache_read_tokens
+                            + usage.cache_creation_tokens;
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
+                                total_tokens: total_tokens as u32,
+                                model_context_window: 0,
+                                usage: vec![usage],
+                            }),
+                            content: format!("Tokens used: {total_tokens}"),
+                            metadata: None,
+                        };
+                        add_normalized_entry(&msg_store, &entry_index_provider, entry);
+                    }
                 }
 
                 DroidJson::Error { message, .. } => {
@@ -861,9 +885,41 @@ pub enum DroidJson {
         #[serde(default)]
         timestamp: Option<u64>,
         session_id: String,
+        #[serde(default)]
+        usage: Option<DroidUsage>,
     },
 }
 
+/// Token usage reported with the completion event
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct DroidUsage {
+    #[serde(default, alias = "inputTokens")]
+    pub input_tokens: u64,
+    #[serde(default, alias = "outputTokens")]
+    pub output_tokens: u64,
+    #[serde(default, alias = "cacheReadInputTokens", alias = "cache_read_tokens")]
+    pub cache_read_input_tokens: u64,
+    #[serde(
+        default,
+        alias = "cacheCreationInputTokens",
+        alias = "cache_creation_tokens"
+    )]
+    pub cache_creation_input_tokens: u64,
+}
+
+impl DroidUsage {
+    fn to_model_usage(&self, model: Option<&str>) -> ModelTokenUsage {
+        ModelTokenUsage {
+            model: model.unwrap_or("droid").to_string(),
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            cache_read_tokens: self.cache_read_input_tokens,
+            cache_creation_tokens: self.cache_creation_input_tokens,
+            cost_usd: None,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 struct DroidErrorLog {
     pub level: String,
@@ -1236,6 +1292,7 @@ struct ToolCallStates {
     generic_tools: HashMap<String, GenericToolState>,
     pending_fifo: VecDeque<PendingToolCall>,
     model_reported: bool,
+    model: Option<String>,
 }
 
 impl ToolCallStates {
@@ -1251,6 +1308,7 @@ impl ToolCallStates {
             generic_tools: HashMap::new(),
             pending_fifo: VecDeque::new(),
             model_reported: false,
+            model: None,
         }
     }
 }
@@ -18,7 +18,7 @@ use crate::executors::qa_mock::QaMockExecutor;
 use crate::{
     actions::{ExecutorAction, review::RepoReviewContext},
     approvals::ExecutorApprovalService,
-    command::CommandBuildError,
+    command::{CmdOverrides, CommandBuildError},
     env::ExecutionEnv,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
@@ -96,7 +96,7 @@ pub enum ExecutorError {
 #[strum_discriminants(
     name(BaseCodingAgent),
     // Only add Hash; Eq/PartialEq are already provided by EnumDiscriminants.
-    derive(EnumString, Hash, strum_macros::Display, Serialize, Deserialize, TS, Type),
+    derive(EnumString, Hash, strum_macros::Display, Serialize, Deserialize, TS, Type, JsonSchema),
     strum(serialize_all = "SCREAMING_SNAKE_CASE"),
     ts(use_ts_enum),
     serde(rename_all = "SCREAMING_SNAKE_CASE"),
@@ -166,6 +166,23 @@ impl CodingAgent {
         }
     }
 
+    /// Command overrides from the executor's profile
+    pub fn cmd_overrides(&self) -> Option<&CmdOverrides> {
+        match self {
+            Self::ClaudeCode(executor) => Some(&executor.cmd),
+            Self::Amp(executor) => Some(&executor.cmd),
+            Self::Gemini(executor) => Some(&executor.cmd),
+            Self::Codex(executor) => Some(&executor.cmd),
+            Self::Opencode(executor) => Some(&executor.cmd),
+            Self::CursorAgent(executor) => Some(&executor.cmd),
+            Self::QwenCode(executor) => Some(&executor.cmd),
+            Self::Copilot(executor) => Some(&executor.cmd),
+            Self::Droid(executor) => Some(&executor.cmd),
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => None,
+        }
+    }
+
     pub fn supports_mcp(&self) -> bool {
         self.default_mcp_config_path().is_some()
     }
@@ -6,7 +6,7 @@ use derivative::Derivative;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Map;
-use tokio::{io::AsyncBufReadExt, process::Command};
+use tokio::io::AsyncBufReadExt;
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -78,7 +78,8 @@ impl Opencode {
 
         let server_password = generate_server_password();
 
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
@@ -91,9 +92,7 @@ impl Opencode {
             .env("OPENCODE_SERVER_PASSWORD", &server_password)
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn()?;
         let server_stdout = child.inner().stdout.take().ok_or_else(|| {
@@ -5,9 +5,12 @@ use std::{
 
 use serde_json::Value;
 
-use crate::executors::opencode::{
-    sdk::EventStreamContext,
-    types::{MessageRole, OpencodeExecutorEvent, ProviderListResponse, SdkEvent},
+use crate::{
+    executors::opencode::{
+        sdk::EventStreamContext,
+        types::{MessageRole, OpencodeExecutorEvent, ProviderListResponse, SdkEvent},
+    },
+    logs::ModelTokenUsage,
 };
 
 type ProviderId = String;
@@ -127,6 +130,19 @@ pub(super) async fn maybe_emit_token_usage(context: &EventStreamContext<'_>, eve
     let provider_id = message.provider_id();
     let model_id = message.model_id();
 
+    let usage = ModelTokenUsage {
+        model: match (provider_id, model_id) {
+            (Some(provider), Some(model)) => format!("{provider}/{model}"),
+            (_, Some(model)) => model.to_string(),
+            _ => "opencode".to_string(),
+        },
+        input_tokens: tokens.input as u64,
+        output_tokens: tokens.output as u64,
+        cache_read_tokens: tokens.cache.as_ref().map(|c| c.read).unwrap_or(0) as u64,
+        cache_creation_tokens: tokens.cache.as_ref().map(|c| c.write).unwrap_or(0) as u64,
+        cost_usd: message.cost,
+    };
+
     let model_context_window = match (provider_id, model_id) {
         (Some(provider), Some(model)) => {
             get_model_context_window(
@@ -142,15 +158,13 @@ pub(super) async fn maybe_emit_token_usage(context: &EventStreamContext<'_>, eve
         _ => 0,
     };
 
-    if model_context_window == 0 {
-        return;
-    }
-
     let _ = context
         .log_writer
         .log_event(&OpencodeExecutorEvent::TokenUsage {
             total_tokens,
             model_context_window,
+            message_id: Some(message.id.clone()),
+            usage: Some(usage),
         })
         .await;
 }
@@ -12,9 +12,9 @@ use super::types::{
 use crate::{
     approvals::ToolCallMetadata,
     logs::{
-        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo, ToolResult,
-        ToolStatus,
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, ModelTokenUsage,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo,
+        ToolResult, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -71,7 +71,10 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 OpencodeExecutorEvent::TokenUsage {
                     total_tokens,
                     model_context_window,
+                    message_id,
+                    usage,
                 } => {
+                    let usage = state.record_usage(message_id, usage);
                     add_normalized_entry(
                         &msg_store,
                         &entry_index,
@@ -80,6 +83,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
                                 total_tokens,
                                 model_context_window,
+                                usage,
                             }),
                             content: format!(
                                 "Tokens used: {} / Context window: {}",
@@ -151,6 +155,8 @@ struct LogState {
     todo_update_entry: Option<usize>,
     todo_update_fingerprint: Option<String>,
     retry_status_fingerprint: Option<String>,
+    // Latest usage reported for each assistant message
+    message_usage: HashMap<String, ModelTokenUsage>,
 }
 
 impl LogState {
@@ -167,9 +173,30 @@ impl LogState {
             todo_update_entry: None,
             todo_update_fingerprint: None,
             retry_status_fingerprint: None,
+            message_usage: HashMap::new(),
         }
     }
 
+    /// Record a message's usage and return the totals per model across all messages
+    fn record_usage(
+        &mut self,
+        message_id: Option<String>,
+        usage: Option<ModelTokenUsage>,
+    ) -> Vec<ModelTokenUsage> {
+        if let (Some(message_id), Some(usage)) = (message_id, usage) {
+            self.message_usage.insert(message_id, usage);
+        }
+        let mut totals: Vec<ModelTokenUsage> = Vec::new();
+        for usage in self.message_usage.values() {
+            match totals.iter_mut().find(|t| t.model == usage.model) {
+                Some(total) => total.add(usage),
+                None => totals.push(usage.clone()),
+            }
+        }
+        totals.sort_by(|a, b| a.model.cmp(&b.model));
+        totals
+    }
+
     fn handle_sdk_event(&mut self, raw: &Value, worktree_path: &Path, msg_store: &Arc<MsgStore>) {
         let Some(event) = SdkEvent::parse(raw) else {
             let raw_text = raw.to_string();
@@ -691,6 +718,7 @@ impl ToolCallState {
         if let Some(ApprovalStatus::Denied { reason }) = &self.approval {
             return ToolStatus::Denied {
                 reason: reason.clone(),
+                rule: None,
             };
         }
         if matches!(self.approval, Some(ApprovalStatus::TimedOut)) {
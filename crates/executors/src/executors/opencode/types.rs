@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::approvals::ApprovalStatus;
 
+use crate::logs::ModelTokenUsage;
+
 /// JSON log events emitted by the OpenCode SDK executor.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -18,6 +20,11 @@ pub enum OpencodeExecutorEvent {
     TokenUsage {
         total_tokens: u32,
         model_context_window: u32,
+        /// Assistant message the usage belongs to; opencode reports usage per message
+        #[serde(default)]
+        message_id: Option<String>,
+        #[serde(default)]
+        usage: Option<ModelTokenUsage>,
     },
     ApprovalResponse {
         tool_call_id: String,
@@ -121,6 +128,8 @@ pub(super) struct MessageInfo {
     pub(super) model_id: Option<String>,
     #[serde(default)]
     pub(super) tokens: Option<MessageTokens>,
+    #[serde(default)]
+    pub(super) cost: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,6 +145,8 @@ pub(super) struct MessageTokens {
 pub(super) struct MessageTokensCache {
     #[serde(default, deserialize_with = "deserialize_f64_as_u32")]
     pub(super) read: u32,
+    #[serde(default, deserialize_with = "deserialize_f64_as_u32")]
+    pub(super) write: u32,
 }
 
 fn deserialize_f64_as_u32<'de, D>(deserializer: D) -> Result<u32, D::Error>
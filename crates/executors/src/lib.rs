@@ -6,4 +6,6 @@ pub mod executors;
 pub mod logs;
 pub mod mcp_config;
 pub mod profile;
+pub mod retry;
+pub mod sandbox;
 pub mod stdout_dup;
@@ -67,6 +67,16 @@ pub struct NormalizedConversation {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum NormalizedEntryError {
     SetupRequired,
+    /// The account behind the credentials hit its usage limit.
+    /// `resets_at` is a unix timestamp (seconds) when the agent reported one.
+    UsageLimitReached {
+        #[ts(type = "number | null")]
+        resets_at: Option<i64>,
+    },
+    /// The API is throttling requests; unlike a usage limit this clears within minutes.
+    RateLimited,
+    /// The credentials were rejected, e.g. an expired or revoked OAuth token.
+    AuthenticationFailed,
     Other,
 }
 
@@ -102,6 +112,47 @@ pub enum NormalizedEntryType {
 pub struct TokenUsageInfo {
     pub total_tokens: u32,
     pub model_context_window: u32,
+    /// Tokens consumed by the execution so far, per model. Empty when the agent does not report it.
+    #[serde(default)]
+    pub usage: Vec<ModelTokenUsage>,
+}
+
+/// Cumulative token counts for one model within an execution
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ModelTokenUsage {
+    pub model: String,
+    /// Input tokens that were neither read from nor written to the prompt cache
+    #[ts(type = "number")]
+    pub input_tokens: u64,
+    #[ts(type = "number")]
+    pub output_tokens: u64,
+    #[ts(type = "number")]
+    pub cache_read_tokens: u64,
+    #[ts(type = "number")]
+    pub cache_creation_tokens: u64,
+    /// Cost reported by the agent, if it reports one
+    pub cost_usd: Option<f64>,
+}
+
+impl ModelTokenUsage {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Fold another report for the same model into this one
+    pub fn add(&mut self, other: &ModelTokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cost_usd = match (self.cost_usd, other.cost_usd) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -145,6 +196,10 @@ pub enum ToolStatus {
     Failed,
     Denied {
         reason: Option<String>,
+        /// The approval policy rule that denied the call without asking anyone
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        rule: Option<String>,
     },
     PendingApproval {
         approval_id: String,
@@ -152,6 +207,10 @@ pub enum ToolStatus {
         timeout_at: DateTime<Utc>,
     },
     TimedOut,
+    /// Approved by an approval policy rule without asking anyone
+    AutoApproved {
+        rule: String,
+    },
 }
 
 impl ToolStatus {
@@ -160,6 +219,7 @@ impl ToolStatus {
             ApprovalStatus::Approved => Some(ToolStatus::Created),
             ApprovalStatus::Denied { reason } => Some(ToolStatus::Denied {
                 reason: reason.clone(),
+                rule: None,
             }),
             ApprovalStatus::TimedOut => Some(ToolStatus::TimedOut),
             ApprovalStatus::Pending => None, // this should not happen
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::profile::ExecutorProfileId;
+
+/// Upper bound on the backoff between retries on the same profile
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+fn default_max_attempts() -> u32 {
+    1
+}
+
+/// How a failed coding agent run is retried: first on the same executor profile, then
+/// on each fallback profile in turn
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct RetryPolicy {
+    /// Runs per profile, counting the first; 1 moves straight on to the fallbacks
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before retrying on the same profile, doubled for each further retry
+    #[serde(default)]
+    pub backoff_seconds: u32,
+    /// Profiles to try, in order, once a profile's attempts are used up
+    #[serde(default)]
+    pub fallback_profiles: Vec<ExecutorProfileId>,
+}
+
+/// The run to start after a failed one
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryAttempt {
+    pub executor_profile_id: ExecutorProfileId,
+    pub delay: Duration,
+    /// Number of this run on its profile, starting at 1
+    pub attempt: u32,
+}
+
+impl RetryPolicy {
+    /// The run that follows `attempts`, the profiles of every run so far (oldest first)
+    /// that all failed, or `None` once the policy is used up
+    pub fn next_attempt(&self, attempts: &[ExecutorProfileId]) -> Option<RetryAttempt> {
+        let last = attempts.last()?;
+        let runs_on_last = attempts.iter().rev().take_while(|p| *p == last).count() as u32;
+
+        if runs_on_last < self.max_attempts.max(1) {
+            let factor = 1u64 << (runs_on_last - 1).min(16);
+            let delay = Duration::from_secs(u64::from(self.backoff_seconds).saturating_mul(factor));
+            return Some(RetryAttempt {
+                executor_profile_id: last.clone(),
+                delay: delay.min(MAX_BACKOFF),
+                attempt: runs_on_last + 1,
+            });
+        }
+
+        // Every change of profile so far moved one step down the fallback list
+        let switches = attempts.windows(2).filter(|w| w[0] != w[1]).count();
+        self.fallback_profiles
+            .get(switches)
+            .map(|next| RetryAttempt {
+                executor_profile_id: next.clone(),
+                delay: Duration::ZERO,
+                attempt: 1,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executors::BaseCodingAgent;
+
+    fn profile(executor: BaseCodingAgent) -> ExecutorProfileId {
+        ExecutorProfileId::new(executor)
+    }
+
+    #[test]
+    fn retries_same_profile_with_backoff_then_falls_back() {
+        let claude = profile(BaseCodingAgent::ClaudeCode);
+        let codex = profile(BaseCodingAgent::Codex);
+        let opencode = profile(BaseCodingAgent::Opencode);
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            backoff_seconds: 30,
+            fallback_profiles: vec![codex.clone(), opencode.clone()],
+        };
+
+        let retry = policy.next_attempt(&[claude.clone()]).unwrap();
+        assert_eq!(retry.executor_profile_id, claude);
+        assert_eq!(retry.delay, Duration::from_secs(30));
+        assert_eq!(retry.attempt, 2);
+
+        let fallback = policy
+            .next_attempt(&[claude.clone(), claude.clone()])
+            .unwrap();
+        assert_eq!(fallback.executor_profile_id, codex);
+        assert_eq!(fallback.delay, Duration::ZERO);
+
+        let attempts = [claude.clone(), claude.clone(), codex.clone(), codex.clone()];
+        let last = policy.next_attempt(&attempts).unwrap();
+        assert_eq!(last.executor_profile_id, opencode);
+
+        let attempts = [
+            claude.clone(),
+            claude,
+            codex.clone(),
+            codex,
+            opencode.clone(),
+            opencode,
+        ];
+        assert!(policy.next_attempt(&attempts).is_none());
+    }
+
+    #[test]
+    fn defaults_go_straight_to_fallbacks() {
+        let policy: RetryPolicy =
+            serde_json::from_str(r#"{"fallback_profiles": [{"executor": "CODEX"}]}"#).unwrap();
+        let retry = policy
+            .next_attempt(&[profile(BaseCodingAgent::ClaudeCode)])
+            .unwrap();
+        assert_eq!(retry.executor_profile_id, profile(BaseCodingAgent::Codex));
+        assert!(policy.next_attempt(&[]).is_none());
+    }
+}
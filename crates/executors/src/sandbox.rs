@@ -0,0 +1,448 @@
+//! Optional sandbox for agent, setup, cleanup and dev server processes (Linux only).
+//!
+//! Processes run under bubblewrap with the host filesystem mounted read-only, so
+//! they can only write inside their workspace and a few configured paths, and can
+//! optionally be cut off from the network. The server's own data directory, with its
+//! config, database and encryption key, is never writable. Each execution also gets its own cgroup
+//! v2 that caps memory, CPU and the number of processes.
+
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{assets::asset_dir, path::expand_tilde, shell::resolve_executable_path};
+
+/// Where execution cgroups are created when `cgroup_parent` is not set
+pub const DEFAULT_CGROUP_PARENT: &str = "/sys/fs/cgroup/vibe-kanban";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum SandboxNetwork {
+    #[default]
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SandboxConfig {
+    /// Run agent, setup, cleanup and dev server processes in the sandbox
+    #[serde(default)]
+    pub enabled: bool,
+    /// Network access for sandboxed processes; executor profiles can override it
+    #[serde(default)]
+    pub network: SandboxNetwork,
+    /// Memory limit per execution in MiB
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub memory_max_mb: Option<u64>,
+    /// CPU limit per execution as a percentage of one core, e.g. 200 for two cores
+    #[serde(default)]
+    pub cpu_max_percent: Option<u32>,
+    /// Maximum number of processes and threads per execution
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub pids_max: Option<u64>,
+    /// cgroup v2 directory delegated to the server, under which each execution gets
+    /// its own cgroup. Only used when a limit is set.
+    #[serde(default)]
+    pub cgroup_parent: Option<String>,
+    /// Paths outside the workspace that sandboxed processes may write to, such as
+    /// the directories agents keep their sessions and caches in. `~` is expanded.
+    /// Paths in the server's data directory are left read-only.
+    #[serde(default = "default_writable_paths")]
+    pub writable_paths: Vec<String>,
+}
+
+fn default_writable_paths() -> Vec<String> {
+    [
+        "~/.claude",
+        "~/.claude.json",
+        "~/.codex",
+        "~/.gemini",
+        "~/.qwen",
+        "~/.factory",
+        "~/.copilot",
+        "~/.cursor",
+        "~/.config/amp",
+        "~/.config/opencode",
+        "~/.local/share/amp",
+        "~/.local/share/opencode",
+        "~/.npm",
+        "~/.cargo/registry",
+        "~/.cargo/git",
+        "~/.cache/pip",
+        "~/.cache/go-build",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect()
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            network: SandboxNetwork::default(),
+            memory_max_mb: None,
+            cpu_max_percent: None,
+            pids_max: None,
+            cgroup_parent: None,
+            writable_paths: default_writable_paths(),
+        }
+    }
+}
+
+impl SandboxConfig {
+    fn has_limits(&self) -> bool {
+        self.memory_max_mb.is_some() || self.cpu_max_percent.is_some() || self.pids_max.is_some()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error("The process sandbox is only available on Linux")]
+    Unsupported,
+    #[error("The process sandbox needs bubblewrap (bwrap), which was not found on PATH")]
+    BubblewrapNotFound,
+    #[error(
+        "Failed to set up cgroup {path}: {source}. Resource limits need a cgroup v2 \
+         directory delegated to the server (see `cgroup_parent`)"
+    )]
+    Cgroup {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// The resource limit that stopped a sandboxed execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitExceeded {
+    Memory,
+    Pids,
+}
+
+/// The sandbox one execution runs in
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    bwrap: PathBuf,
+    workspace_dir: PathBuf,
+    writable_paths: Vec<PathBuf>,
+    /// Mounted read-only over the writable paths, so no configured path exposes them
+    read_only_paths: Vec<PathBuf>,
+    network: SandboxNetwork,
+    cgroup: Option<Cgroup>,
+}
+
+#[derive(Debug, Clone)]
+struct Cgroup {
+    path: PathBuf,
+    /// `cgroup.procs`, opened up front so the child can join the cgroup between
+    /// fork and exec without allocating
+    procs: Arc<std::fs::File>,
+}
+
+impl Sandbox {
+    /// Set up the sandbox for an execution in `workspace_dir`. `extra_writable`
+    /// paths (e.g. the git directories that worktrees commit into) are writable in
+    /// addition to the configured ones.
+    pub async fn prepare(
+        config: &SandboxConfig,
+        execution_id: Uuid,
+        workspace_dir: &Path,
+        extra_writable: Vec<PathBuf>,
+    ) -> Result<Self, SandboxError> {
+        if !cfg!(target_os = "linux") {
+            return Err(SandboxError::Unsupported);
+        }
+
+        let bwrap = resolve_executable_path("bwrap")
+            .await
+            .ok_or(SandboxError::BubblewrapNotFound)?;
+        let cgroup = if config.has_limits() {
+            Some(Cgroup::create(config, execution_id)?)
+        } else {
+            None
+        };
+
+        Ok(Self::new(
+            bwrap,
+            config,
+            workspace_dir,
+            extra_writable,
+            cgroup,
+        ))
+    }
+
+    fn new(
+        bwrap: PathBuf,
+        config: &SandboxConfig,
+        workspace_dir: &Path,
+        extra_writable: Vec<PathBuf>,
+        cgroup: Option<Cgroup>,
+    ) -> Self {
+        let read_only_paths = vec![asset_dir()];
+        let writable_paths = config
+            .writable_paths
+            .iter()
+            .map(|path| expand_tilde(path))
+            .chain(extra_writable)
+            .filter(|path| !read_only_paths.iter().any(|ro| path.starts_with(ro)))
+            .collect();
+
+        Self {
+            bwrap,
+            workspace_dir: workspace_dir.to_path_buf(),
+            writable_paths,
+            read_only_paths,
+            network: config.network,
+            cgroup,
+        }
+    }
+
+    pub fn set_network(&mut self, network: SandboxNetwork) {
+        self.network = network;
+    }
+
+    /// A command that runs `program` inside the sandbox. Arguments added to the
+    /// returned command are passed to `program`.
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let mut command = Command::new(&self.bwrap);
+        command
+            .args(["--die-with-parent", "--unshare-pid"])
+            .args(["--ro-bind", "/", "/"])
+            .args(["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"])
+            .arg("--bind")
+            .arg(&self.workspace_dir)
+            .arg(&self.workspace_dir);
+        for path in &self.writable_paths {
+            command.arg("--bind-try").arg(path).arg(path);
+        }
+        // Later mounts win, so these stay read-only even under a writable parent
+        for path in &self.read_only_paths {
+            command.arg("--ro-bind-try").arg(path).arg(path);
+        }
+        if self.network == SandboxNetwork::Deny {
+            command.arg("--unshare-net");
+        }
+        command.arg("--").arg(program);
+
+        if let Some(cgroup) = &self.cgroup {
+            cgroup.join_on_exec(&mut command);
+        }
+        command
+    }
+
+    /// Kill anything left in the execution's cgroup and remove it. Returns the
+    /// limit the execution ran into, if any.
+    pub async fn release(&self) -> Option<LimitExceeded> {
+        let cgroup = self.cgroup.as_ref()?;
+        let exceeded = cgroup.limit_exceeded();
+        cgroup.remove().await;
+        exceeded
+    }
+}
+
+impl Cgroup {
+    fn create(config: &SandboxConfig, execution_id: Uuid) -> Result<Self, SandboxError> {
+        let parent = PathBuf::from(
+            config
+                .cgroup_parent
+                .as_deref()
+                .unwrap_or(DEFAULT_CGROUP_PARENT),
+        );
+        let path = parent.join(format!("exec-{execution_id}"));
+        let cgroup_error = |source| SandboxError::Cgroup {
+            path: path.clone(),
+            source,
+        };
+
+        let mut controllers = Vec::new();
+        if config.memory_max_mb.is_some() {
+            controllers.push("+memory");
+        }
+        if config.cpu_max_percent.is_some() {
+            controllers.push("+cpu");
+        }
+        if config.pids_max.is_some() {
+            controllers.push("+pids");
+        }
+        std::fs::write(parent.join("cgroup.subtree_control"), controllers.join(" "))
+            .map_err(cgroup_error)?;
+        if let Err(e) = std::fs::create_dir(&path)
+            && e.kind() != std::io::ErrorKind::AlreadyExists
+        {
+            return Err(cgroup_error(e));
+        }
+
+        if let Some(mb) = config.memory_max_mb {
+            std::fs::write(path.join("memory.max"), (mb * 1024 * 1024).to_string())
+                .map_err(cgroup_error)?;
+            // Without swap accounting the file doesn't exist and there is nothing
+            // to turn off
+            let _ = std::fs::write(path.join("memory.swap.max"), "0");
+        }
+        if let Some(percent) = config.cpu_max_percent {
+            std::fs::write(
+                path.join("cpu.max"),
+                format!("{} 100000", u64::from(percent.max(1)) * 1000),
+            )
+            .map_err(cgroup_error)?;
+        }
+        if let Some(pids) = config.pids_max {
+            std::fs::write(path.join("pids.max"), pids.to_string()).map_err(cgroup_error)?;
+        }
+
+        let procs = std::fs::OpenOptions::new()
+            .write(true)
+            .open(path.join("cgroup.procs"))
+            .map_err(cgroup_error)?;
+        Ok(Self {
+            path,
+            procs: Arc::new(procs),
+        })
+    }
+
+    /// Move the child into this cgroup between fork and exec
+    #[cfg(unix)]
+    fn join_on_exec(&self, command: &mut Command) {
+        use std::io::Write;
+
+        let procs = self.procs.clone();
+        // SAFETY: the closure only writes to an already open file, which is
+        // async-signal-safe
+        unsafe {
+            command.pre_exec(move || (&*procs).write_all(b"0"));
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn join_on_exec(&self, _command: &mut Command) {}
+
+    fn limit_exceeded(&self) -> Option<LimitExceeded> {
+        if self.event_count("memory.events", "oom_kill") > 0 {
+            Some(LimitExceeded::Memory)
+        } else if self.event_count("pids.events", "max") > 0 {
+            Some(LimitExceeded::Pids)
+        } else {
+            None
+        }
+    }
+
+    /// Read a counter from a flat-keyed cgroup events file
+    fn event_count(&self, file: &str, key: &str) -> u64 {
+        std::fs::read_to_string(self.path.join(file))
+            .ok()
+            .and_then(|events| {
+                events.lines().find_map(|line| {
+                    let (name, count) = line.split_once(' ')?;
+                    (name == key).then(|| count.trim().parse().ok()).flatten()
+                })
+            })
+            .unwrap_or(0)
+    }
+
+    async fn remove(&self) {
+        let _ = std::fs::write(self.path.join("cgroup.kill"), "1");
+        // The cgroup can only be removed once the killed processes are gone
+        for _ in 0..10 {
+            match std::fs::remove_dir(&self.path) {
+                Ok(()) => return,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+                Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+            }
+        }
+        tracing::warn!("Failed to remove cgroup {}", self.path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_defaults_keep_agent_state_writable() {
+        let config: SandboxConfig = serde_json::from_str(r#"{"enabled": true}"#).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.network, SandboxNetwork::Allow);
+        assert!(!config.has_limits());
+        assert_eq!(
+            config.writable_paths,
+            SandboxConfig::default().writable_paths
+        );
+    }
+
+    #[test]
+    fn command_runs_program_after_bwrap_options() {
+        let sandbox = Sandbox {
+            bwrap: PathBuf::from("/usr/bin/bwrap"),
+            workspace_dir: PathBuf::from("/work"),
+            writable_paths: vec![PathBuf::from("/home/me/.claude")],
+            read_only_paths: Vec::new(),
+            network: SandboxNetwork::Deny,
+            cgroup: None,
+        };
+        let mut command = sandbox.command("claude");
+        command.arg("-p");
+
+        let std_command = command.as_std();
+        assert_eq!(std_command.get_program(), "/usr/bin/bwrap");
+        let args: Vec<_> = std_command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let separator = args.iter().position(|arg| arg == "--").unwrap();
+        assert_eq!(&args[separator + 1..], ["claude", "-p"]);
+        assert!(args[..separator].contains(&"--unshare-net".to_string()));
+        assert!(args.windows(3).any(|w| w == ["--bind", "/work", "/work"]));
+    }
+
+    #[test]
+    fn asset_dir_is_never_writable() {
+        let assets = asset_dir();
+        let parent = assets.parent().unwrap().to_path_buf();
+        for path in default_writable_paths() {
+            assert!(!assets.starts_with(expand_tilde(&path)), "{path}");
+        }
+
+        // Even when the user makes it, or a directory above it, writable
+        let config = SandboxConfig {
+            writable_paths: vec![
+                parent.to_string_lossy().into_owned(),
+                assets.to_string_lossy().into_owned(),
+            ],
+            ..SandboxConfig::default()
+        };
+        let sandbox = Sandbox::new(
+            PathBuf::from("/usr/bin/bwrap"),
+            &config,
+            Path::new("/work"),
+            vec![assets.join("db.sqlite")],
+            None,
+        );
+        assert_eq!(sandbox.writable_paths, [parent.clone()]);
+
+        let command = sandbox.command("sh");
+        let args: Vec<_> = command
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let mount = |option: &str, path: &Path| {
+            args.windows(3)
+                .position(|w| w[0] == option && w[1] == path.to_string_lossy())
+        };
+        assert_eq!(mount("--bind-try", &assets), None);
+        assert!(mount("--ro-bind-try", &assets).unwrap() > mount("--bind-try", &parent).unwrap());
+    }
+}
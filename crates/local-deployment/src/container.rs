@@ -1,8 +1,11 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Duration,
 };
 
@@ -15,12 +18,17 @@ use db::{
         coding_agent_turn::CodingAgentTurn,
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionProcessStatusReason,
         },
+        execution_process_logs::ExecutionProcessLogs,
         execution_process_repo_state::ExecutionProcessRepoState,
+        merge_queue::MergeQueueEntry,
+        project_workflow::WorkflowEvent,
         repo::Repo,
-        scratch::{DraftFollowUpData, Scratch, ScratchType},
+        scratch::{DraftFollowUpData, Scratch, ScratchPayload, ScratchType},
         session::{Session, SessionError},
-        task::{Task, TaskStatus},
+        task::Task,
+        token_usage::ExecutionTokenUsage,
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
     },
@@ -35,7 +43,14 @@ use executors::{
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::{ExecutionEnv, RepoContext},
     executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
-    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    logs::{
+        ModelTokenUsage, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        utils::{
+            ConversationPatch, EntryIndexProvider, patch::extract_normalized_entry_from_patch,
+        },
+    },
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    sandbox::{LimitExceeded, Sandbox, SandboxError},
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
 use serde_json::json;
@@ -43,17 +58,23 @@ use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
     claude_token_rotation::ClaudeTokenRotationService,
-    config::Config,
+    config::{Config, ExecutionLimits},
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
     git::{CoAuthor, CommitOptions, GitCli, GitIdentity, GitService},
     image::ImageService,
+    local_session::LocalSessionService,
+    merge_verification,
     notification::NotificationService,
     queued_message::QueuedMessageService,
     share::SharePublisher,
+    workflow,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{RwLock, broadcast},
+    task::JoinHandle,
+};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
@@ -64,6 +85,42 @@ use uuid::Uuid;
 
 use crate::{command, copy};
 
+/// Upper bound on how long an execution's MCP auth token stays valid. It is revoked as
+/// soon as the execution exits.
+const MCP_AUTH_TOKEN_LIFETIME: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Follow-up sent to an agent that was stopped for a timeout, when its profile asks for one
+const CONTINUE_AFTER_TIMEOUT_PROMPT: &str = "Your previous run was stopped because it hit a \
+     time limit. Review where you left off and continue with the task.";
+
+/// Runtime limits from the executor profile of a coding agent execution
+#[derive(Debug, Clone, Copy)]
+struct ExecutionTimeouts {
+    max_runtime: Option<Duration>,
+    max_idle: Option<Duration>,
+    /// How many times a session continues in a follow-up after a timeout; 0 to never
+    max_continuations: u32,
+}
+
+impl ExecutionTimeouts {
+    fn for_action(action: &ExecutorAction) -> Option<Self> {
+        let profile_id = action.executor_profile_id()?;
+        let agent = ExecutorConfigs::get_cached().get_coding_agent_or_default(profile_id);
+        let cmd = agent.cmd_overrides()?;
+        let minutes = |m: u32| Duration::from_secs(u64::from(m) * 60);
+        let timeouts = Self {
+            max_runtime: cmd.max_runtime_minutes.filter(|m| *m > 0).map(minutes),
+            max_idle: cmd.max_idle_minutes.filter(|m| *m > 0).map(minutes),
+            max_continuations: if cmd.continue_after_timeout.unwrap_or(false) {
+                cmd.max_timeout_continuations.unwrap_or(1)
+            } else {
+                0
+            },
+        };
+        (timeouts.max_runtime.is_some() || timeouts.max_idle.is_some()).then_some(timeouts)
+    }
+}
+
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
@@ -82,6 +139,17 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
     claude_token_rotation: ClaudeTokenRotationService,
+    /// Claude OAuth token handed to each running execution, keyed by execution process id
+    claude_token_assignments: Arc<RwLock<HashMap<Uuid, Uuid>>>,
+    /// Auth session minted for each running execution's MCP tools, keyed by execution
+    /// process id, so it can be revoked when the execution ends
+    mcp_auth_sessions: Arc<RwLock<HashMap<Uuid, Uuid>>>,
+    /// Set on shutdown so killing running processes does not start queued ones
+    shutting_down: Arc<AtomicBool>,
+    /// Process sandbox of each running execution, keyed by execution process id
+    sandboxes: Arc<RwLock<HashMap<Uuid, Sandbox>>>,
+    /// Executions stopped for a timeout whose profile asks for a follow-up to continue
+    timeout_continuations: Arc<RwLock<HashSet<Uuid>>>,
 }
 
 impl LocalContainerService {
@@ -102,7 +170,7 @@ impl LocalContainerService {
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
         let db_stream_handles = Arc::new(RwLock::new(HashMap::new()));
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
-        let notification_service = NotificationService::new(config.clone());
+        let notification_service = NotificationService::new(config.clone(), db.clone());
 
         let container = LocalContainerService {
             db,
@@ -119,6 +187,11 @@ impl LocalContainerService {
             queued_message_service,
             notification_service,
             claude_token_rotation,
+            claude_token_assignments: Arc::new(RwLock::new(HashMap::new())),
+            mcp_auth_sessions: Arc::new(RwLock::new(HashMap::new())),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            sandboxes: Arc::new(RwLock::new(HashMap::new())),
+            timeout_continuations: Arc::new(RwLock::new(HashSet::new())),
         };
 
         container.spawn_workspace_cleanup();
@@ -126,6 +199,41 @@ impl LocalContainerService {
         container
     }
 
+    /// Start queued executions that now fit under the limits, unless shutting down
+    async fn resume_execution_queue(&self) {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Err(e) = self.start_queued_executions().await {
+            tracing::error!("Failed to start queued executions: {}", e);
+        }
+    }
+
+    /// Start another conflict resolution if the one the finished execution settled
+    /// stopped on further conflicts, unless shutting down
+    async fn resume_conflict_resolution(&self, finished_exec_id: Uuid) {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Err(e) = self.follow_up_conflict_resolution(finished_exec_id).await {
+            tracing::error!("Failed to follow up conflict resolution: {}", e);
+        }
+    }
+
+    /// Queue the merge whose conflicts the finished execution was resolving again, and
+    /// advance the merge queues, which may have been waiting on it
+    async fn resume_merge_queue(&self, finished_exec_id: Uuid) {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Err(e) = MergeQueueEntry::requeue_resolved(&self.db.pool, finished_exec_id).await {
+            tracing::error!("Failed to requeue resolved merge: {}", e);
+        }
+        if let Err(e) = self.process_merge_queues().await {
+            tracing::error!("Failed to process merge queues: {}", e);
+        }
+    }
+
     pub async fn get_child_from_store(&self, id: &Uuid) -> Option<Arc<RwLock<AsyncGroupChild>>> {
         let map = self.child_store.read().await;
         map.get(id).cloned()
@@ -517,6 +625,13 @@ impl LocalContainerService {
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
 
+            let limit_exceeded = container.release_sandbox(exec_id).await;
+            if matches!(status, ExecutionProcessStatus::Failed)
+                && let Some(limit) = limit_exceeded
+            {
+                container.record_limit_exceeded(exec_id, limit).await;
+            }
+
             if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
                 && let Err(e) =
                     ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
@@ -524,16 +639,42 @@ impl LocalContainerService {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
 
+            container.revoke_mcp_auth_token(exec_id).await;
+
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::VerifyScript {
+                    merge_verification::remove_worktree(&db.pool, container.git(), exec_id).await;
+                }
+
+                // A run resolving conflicts hands its changes to the stopped git operation,
+                // which is continued or rolled back, instead of committing them
+                let resolving_conflicts = match container.settle_conflict_resolution(&ctx).await {
+                    Ok(resolving) => resolving,
+                    Err(e) => {
+                        tracing::error!("Failed to settle conflict resolution: {}", e);
+                        false
+                    }
+                };
+
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
-                let success = matches!(
-                    ctx.execution_process.status,
-                    ExecutionProcessStatus::Completed
-                ) && exit_code == Some(0);
+                container.record_token_usage(&exec_id).await;
+
+                // A turn that failed on an exhausted or rejected Claude token is retried with
+                // the next healthy token instead of being finalized
+                let retried = !resolving_conflicts
+                    && (container.handle_claude_token_failure(&ctx).await
+                        || container.retry_failed_execution(&ctx).await);
+
+                let success = !retried
+                    && matches!(
+                        ctx.execution_process.status,
+                        ExecutionProcessStatus::Completed
+                    )
+                    && exit_code == Some(0);
 
                 let cleanup_done = matches!(
                     ctx.execution_process.run_reason,
@@ -543,7 +684,7 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                if (success || cleanup_done) && !resolving_conflicts {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -584,7 +725,15 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                // An agent stopped for a timeout that continues in a follow-up keeps its
+                // queued messages; they run after the continuation
+                let continuing = container
+                    .timeout_continuations
+                    .write()
+                    .await
+                    .remove(&exec_id);
+
+                if !retried && !continuing && container.should_finalize(&ctx) {
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
                     let should_execute_queued = !matches!(
@@ -595,56 +744,62 @@ impl LocalContainerService {
                     // Also check if task is on hold - don't execute queued messages for held tasks
                     let task_on_hold = ctx.task.is_on_hold();
 
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
-                    {
-                        if task_on_hold {
-                            // Task is on hold - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} because task is on hold",
-                                ctx.session.id
-                            );
-                            container.finalize_task(&ctx).await;
-                        } else if should_execute_queued {
-                            tracing::info!(
-                                "Found queued message for session {}, starting follow-up execution",
-                                ctx.session.id
-                            );
-
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
-                                &db.pool,
+                    if task_on_hold || !should_execute_queued {
+                        // Task is on hold, or execution failed or was killed - discard the
+                        // remaining queue and finalize
+                        match container.queued_message_service.clear(ctx.session.id).await {
+                            Ok(0) => {}
+                            Ok(discarded) => tracing::info!(
+                                "Discarding {} queued message(s) for session {} (on hold: {}, status: {:?})",
+                                discarded,
                                 ctx.session.id,
-                                &ScratchType::DraftFollowUp,
-                            )
+                                task_on_hold,
+                                ctx.execution_process.status
+                            ),
+                            Err(e) => tracing::warn!(
+                                "Failed to clear queued messages for session {}: {}",
+                                ctx.session.id,
+                                e
+                            ),
+                        }
+                        container.finalize_task(&ctx).await;
+                    } else {
+                        match container
+                            .queued_message_service
+                            .take_next(ctx.session.id)
                             .await
-                            {
-                                tracing::warn!(
-                                    "Failed to delete scratch after consuming queued message: {}",
-                                    e
+                        {
+                            Ok(Some(queued_msg)) => {
+                                tracing::info!(
+                                    "Found queued message for session {}, starting follow-up execution",
+                                    ctx.session.id
                                 );
-                            }
 
-                            // Execute the queued follow-up
-                            if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
-                                .await
-                            {
-                                tracing::error!("Failed to start queued follow-up: {}", e);
-                                // Fall back to finalization if follow-up fails
+                                // Delete the draft if it is the message being consumed
+                                container
+                                    .clear_consumed_draft(ctx.session.id, &queued_msg.data)
+                                    .await;
+
+                                // Execute the queued follow-up
+                                if let Err(e) = container
+                                    .start_queued_follow_up(&ctx, &queued_msg.data)
+                                    .await
+                                {
+                                    tracing::error!("Failed to start queued follow-up: {}", e);
+                                    // Fall back to finalization if follow-up fails
+                                    container.finalize_task(&ctx).await;
+                                }
+                            }
+                            Ok(None) => container.finalize_task(&ctx).await,
+                            Err(e) => {
+                                tracing::error!(
+                                    "Failed to take queued message for session {}: {}",
+                                    ctx.session.id,
+                                    e
+                                );
                                 container.finalize_task(&ctx).await;
                             }
-                        } else {
-                            // Execution failed or was killed - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
-                                ctx.session.id,
-                                ctx.execution_process.status
-                            );
-                            container.finalize_task(&ctx).await;
                         }
-                    } else {
-                        container.finalize_task(&ctx).await;
                     }
                 }
 
@@ -682,6 +837,11 @@ impl LocalContainerService {
 
             // Cleanup child handle
             child_store.write().await.remove(&exec_id);
+
+            // The finished process may have freed a slot for a queued execution
+            container.resume_execution_queue().await;
+            container.resume_conflict_resolution(exec_id).await;
+            container.resume_merge_queue(exec_id).await;
         })
     }
 
@@ -793,6 +953,606 @@ impl LocalContainerService {
         None
     }
 
+    /// Set up the process sandbox for an execution when it is enabled. On Linux an
+    /// execution that cannot be sandboxed fails to start rather than running unconfined.
+    async fn prepare_sandbox(
+        &self,
+        exec_id: Uuid,
+        current_dir: &Path,
+        repos: &[Repo],
+    ) -> Result<Option<Sandbox>, ContainerError> {
+        let config = self.config.read().await.sandbox.clone();
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        // Worktrees commit into the git directory of their main repository
+        let mut extra_writable: Vec<PathBuf> =
+            repos.iter().map(|repo| repo.path.join(".git")).collect();
+        // Verify scripts run in temporary merges outside the workspace
+        extra_writable.push(utils::path::get_vibe_kanban_temp_dir().join("verify"));
+        match Sandbox::prepare(&config, exec_id, current_dir, extra_writable).await {
+            Ok(sandbox) => {
+                self.sandboxes
+                    .write()
+                    .await
+                    .insert(exec_id, sandbox.clone());
+                Ok(Some(sandbox))
+            }
+            Err(SandboxError::Unsupported) => {
+                tracing::warn!(
+                    "Process sandbox is enabled but only supported on Linux; running unsandboxed"
+                );
+                Ok(None)
+            }
+            Err(e) => Err(ContainerError::Other(anyhow!(e))),
+        }
+    }
+
+    /// Tear down the execution's sandbox, returning the resource limit it ran into, if any
+    async fn release_sandbox(&self, exec_id: Uuid) -> Option<LimitExceeded> {
+        let sandbox = self.sandboxes.write().await.remove(&exec_id)?;
+        sandbox.release().await
+    }
+
+    async fn record_limit_exceeded(&self, exec_id: Uuid, limit: LimitExceeded) {
+        let (reason, message) = match limit {
+            LimitExceeded::Memory => (
+                ExecutionProcessStatusReason::MemoryLimitExceeded,
+                "Process killed: the execution exceeded its sandbox memory limit",
+            ),
+            LimitExceeded::Pids => (
+                ExecutionProcessStatusReason::PidsLimitExceeded,
+                "Process failed: the execution reached its sandbox process limit",
+            ),
+        };
+        if let Err(e) = ExecutionProcess::set_status_reason(&self.db.pool, exec_id, reason).await {
+            tracing::error!("Failed to record status reason for {}: {}", exec_id, e);
+        }
+        if let Some(msg_store) = self.msg_stores.read().await.get(&exec_id) {
+            msg_store.push_stderr(message);
+        }
+    }
+
+    /// Stop the execution when it exceeds its profile's maximum runtime, or stays silent
+    /// for longer than the maximum idle time. Output is anything the agent writes to its
+    /// log; waiting on a tool approval is not idle.
+    fn spawn_execution_watchdog(&self, exec_id: Uuid, timeouts: ExecutionTimeouts) {
+        let container = self.clone();
+
+        tokio::spawn(async move {
+            let Some(msg_store) = container.msg_stores.read().await.get(&exec_id).cloned() else {
+                return;
+            };
+            let mut output = msg_store.get_receiver();
+            let started = tokio::time::Instant::now();
+            let mut last_output = started;
+
+            loop {
+                let runtime_deadline = timeouts.max_runtime.map(|max| started + max);
+                let idle_deadline = timeouts.max_idle.map(|max| last_output + max);
+                let Some(deadline) = runtime_deadline.into_iter().chain(idle_deadline).min() else {
+                    return;
+                };
+
+                tokio::select! {
+                    msg = output.recv() => match msg {
+                        Ok(LogMsg::Finished) | Err(broadcast::error::RecvError::Closed) => return,
+                        Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                            last_output = tokio::time::Instant::now();
+                        }
+                    },
+                    _ = tokio::time::sleep_until(deadline) => {
+                        let (reason, limit) = if runtime_deadline == Some(deadline) {
+                            (ExecutionProcessStatusReason::MaxRuntimeExceeded, timeouts.max_runtime)
+                        } else if container
+                            .approvals
+                            .get_pending_execution_process_ids(&[exec_id])
+                            .is_empty()
+                        {
+                            (ExecutionProcessStatusReason::IdleTimeout, timeouts.max_idle)
+                        } else {
+                            last_output = tokio::time::Instant::now();
+                            continue;
+                        };
+                        let minutes = limit.map(|d| d.as_secs() / 60).unwrap_or_default();
+                        container
+                            .stop_for_timeout(exec_id, reason, minutes, timeouts.max_continuations)
+                            .await;
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    async fn stop_for_timeout(
+        &self,
+        exec_id: Uuid,
+        reason: ExecutionProcessStatusReason,
+        minutes: u64,
+        max_continuations: u32,
+    ) {
+        let execution_process = match ExecutionProcess::find_by_id(&self.db.pool, exec_id).await {
+            Ok(Some(process)) if matches!(process.status, ExecutionProcessStatus::Running) => {
+                process
+            }
+            Ok(_) => return,
+            Err(e) => {
+                tracing::error!("Failed to load execution {} to stop it: {}", exec_id, e);
+                return;
+            }
+        };
+
+        let message = match reason {
+            ExecutionProcessStatusReason::MaxRuntimeExceeded => {
+                format!("Stopped: the agent ran for longer than its {minutes} minute limit.")
+            }
+            _ => format!("Stopped: the agent produced no output for {minutes} minutes."),
+        };
+        tracing::info!("Execution {}: {}", exec_id, message);
+
+        if let Err(e) = ExecutionProcess::set_status_reason(&self.db.pool, exec_id, reason).await {
+            tracing::error!("Failed to record status reason for {}: {}", exec_id, e);
+        }
+
+        // Every earlier timeout in the session used up one continuation
+        let continue_after = max_continuations > 0
+            && match ExecutionProcess::count_timed_out_in_session(
+                &self.db.pool,
+                execution_process.session_id,
+            )
+            .await
+            {
+                Ok(timed_out) => timed_out <= i64::from(max_continuations),
+                Err(e) => {
+                    tracing::error!("Failed to count timeouts for {}: {}", exec_id, e);
+                    false
+                }
+            };
+        let message = if max_continuations > 0 && !continue_after {
+            format!(
+                "{message} The task is on hold as timed out after {max_continuations} continue \
+                 follow-up(s)."
+            )
+        } else {
+            message
+        };
+        let error = NormalizedEntryType::ErrorMessage {
+            error_type: NormalizedEntryError::Other,
+        };
+        self.push_log_entry(exec_id, error, message).await;
+        if continue_after {
+            self.timeout_continuations.write().await.insert(exec_id);
+        } else if max_continuations > 0 {
+            self.hold_timed_out_task(exec_id, max_continuations).await;
+        }
+
+        if let Err(e) = self
+            .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
+            .await
+        {
+            tracing::error!("Failed to stop execution {} after timeout: {}", exec_id, e);
+            self.timeout_continuations.write().await.remove(&exec_id);
+            return;
+        }
+
+        if continue_after
+            && let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, exec_id).await
+        {
+            if ctx.task.is_on_hold() {
+                let _ = self.queued_message_service.clear(ctx.session.id).await;
+                self.finalize_task(&ctx).await;
+            } else if let Err(e) = self.continue_after_timeout(&ctx).await {
+                tracing::error!("Failed to continue after timeout: {}", e);
+                self.finalize_task(&ctx).await;
+            }
+        }
+    }
+
+    /// Put the task on hold once its agent keeps timing out, so nothing restarts it
+    /// until someone has looked at it
+    async fn hold_timed_out_task(&self, exec_id: Uuid, continuations: u32) {
+        let ctx = match ExecutionProcess::load_context(&self.db.pool, exec_id).await {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load execution {} to hold its task: {}",
+                    exec_id,
+                    e
+                );
+                return;
+            }
+        };
+        let comment = format!(
+            "Timed out: the agent hit its time limit again after {continuations} continue \
+             follow-up(s)"
+        );
+        if let Err(e) = Task::place_hold(&self.db.pool, ctx.task.id, None, comment).await {
+            tracing::error!("Failed to hold timed out task {}: {}", ctx.task.id, e);
+        }
+    }
+
+    /// Add an entry to the execution's normalized log, live and persisted
+    async fn push_log_entry(
+        &self,
+        exec_id: Uuid,
+        entry_type: NormalizedEntryType,
+        message: String,
+    ) {
+        let Some(msg_store) = self.msg_stores.read().await.get(&exec_id).cloned() else {
+            return;
+        };
+        let entry = NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: message,
+            metadata: None,
+        };
+        let index = EntryIndexProvider::start_from(&msg_store).next();
+        let patch = ConversationPatch::add_normalized_entry(index, entry);
+
+        if let Ok(json_line) = serde_json::to_string(&LogMsg::JsonPatch(patch.clone())) {
+            let _ = ExecutionProcessLogs::append_log_line(
+                &self.db.pool,
+                exec_id,
+                &format!("{json_line}\n"),
+            )
+            .await;
+        }
+        msg_store.push_patch(patch);
+    }
+
+    /// Start a follow-up asking an agent stopped for a timeout to carry on
+    async fn continue_after_timeout(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
+        let executor_profile_id = ctx
+            .execution_process
+            .executor_action()
+            .map_err(ContainerError::Other)?
+            .executor_profile_id()
+            .cloned()
+            .ok_or_else(|| ContainerError::Other(anyhow!("Execution has no executor profile")))?;
+        let data = DraftFollowUpData {
+            message: CONTINUE_AFTER_TIMEOUT_PROMPT.to_string(),
+            executor_profile_id,
+        };
+        self.start_queued_follow_up(ctx, &data).await?;
+        Ok(())
+    }
+
+    /// Whether a coding agent run failed: a non-zero exit or a failure to start, or an
+    /// agent that stopped on a usage limit or rejected credentials without failing
+    async fn is_failed_run(&self, ctx: &ExecutionContext) -> bool {
+        match ctx.execution_process.status {
+            ExecutionProcessStatus::Failed => true,
+            ExecutionProcessStatus::Completed => {
+                let exec_id = ctx.execution_process.id;
+                self.find_claude_token_error(&exec_id).await.is_some()
+                    || self.stderr_reports_rate_limit(&exec_id).await
+            }
+            _ => false,
+        }
+    }
+
+    async fn stderr_reports_rate_limit(&self, exec_id: &Uuid) -> bool {
+        const RATE_LIMIT_MARKERS: [&str; 5] = [
+            "rate limit",
+            "rate_limit",
+            "too many requests",
+            "quota exceeded",
+            "usage limit",
+        ];
+        let msg_stores = self.msg_stores.read().await;
+        let Some(msg_store) = msg_stores.get(exec_id) else {
+            return false;
+        };
+        msg_store.get_history().iter().any(|msg| match msg {
+            LogMsg::Stderr(line) => {
+                let line = line.to_lowercase();
+                RATE_LIMIT_MARKERS
+                    .iter()
+                    .any(|marker| line.contains(marker))
+            }
+            _ => false,
+        })
+    }
+
+    /// Profiles of every run in the failed execution's retry chain, oldest first
+    async fn retry_chain_profiles(
+        &self,
+        exec_id: Uuid,
+    ) -> Result<Option<Vec<ExecutorProfileId>>, ContainerError> {
+        let chain = ExecutionProcess::find_retry_chain(&self.db.pool, exec_id).await?;
+        let mut profiles = Vec::with_capacity(chain.len());
+        for id in chain {
+            let profile = ExecutionProcess::find_by_id(&self.db.pool, id)
+                .await?
+                .and_then(|process| {
+                    process
+                        .executor_action()
+                        .ok()
+                        .and_then(|action| action.executor_profile_id().cloned())
+                });
+            match profile {
+                Some(profile) => profiles.push(profile),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(profiles))
+    }
+
+    /// Start the scheduled retry of a failed run, unless the session has moved on
+    async fn start_retry(&self, failed_id: Uuid, action: ExecutorAction) {
+        let ctx = match ExecutionProcess::load_context(&self.db.pool, failed_id).await {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                tracing::error!("Failed to load execution {} to retry it: {}", failed_id, e);
+                return;
+            }
+        };
+
+        let latest = ExecutionProcess::find_latest_by_session_and_run_reason(
+            &self.db.pool,
+            ctx.session.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+        .ok()
+        .flatten();
+        if latest.is_some_and(|latest| latest.id != failed_id) || ctx.task.is_on_hold() {
+            tracing::info!("Dropping retry of {}: the session has moved on", failed_id);
+            self.finalize_task(&ctx).await;
+            return;
+        }
+
+        let executor = action
+            .executor_profile_id()
+            .map(|profile| profile.executor.to_string());
+        if let Some(executor) = executor
+            && ctx.session.executor.as_ref() != Some(&executor)
+            && let Err(e) = Session::update_executor(&self.db.pool, ctx.session.id, &executor).await
+        {
+            tracing::warn!(
+                "Failed to update executor of session {}: {}",
+                ctx.session.id,
+                e
+            );
+        }
+
+        match self
+            .start_execution_with_retry_of(
+                &ctx.workspace,
+                &ctx.session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+                Some(failed_id),
+            )
+            .await
+        {
+            Ok(retry) => tracing::info!("Retrying execution {} as {}", failed_id, retry.id),
+            Err(e) => {
+                // A retry that failed to start was already handed to the retry policy
+                tracing::error!("Failed to start retry of execution {}: {}", failed_id, e);
+            }
+        }
+    }
+
+    /// Find a usage-limit, rate-limit or authentication error reported by the Claude log
+    /// normalizer
+    async fn find_claude_token_error(&self, exec_id: &Uuid) -> Option<NormalizedEntryError> {
+        let msg_stores = self.msg_stores.read().await;
+        let msg_store = msg_stores.get(exec_id)?;
+
+        msg_store.get_history().iter().rev().find_map(|msg| {
+            let LogMsg::JsonPatch(patch) = msg else {
+                return None;
+            };
+            let (_, entry) = extract_normalized_entry_from_patch(patch)?;
+            match entry.entry_type {
+                NormalizedEntryType::ErrorMessage {
+                    error_type:
+                        error_type @ (NormalizedEntryError::UsageLimitReached { .. }
+                        | NormalizedEntryError::RateLimited
+                        | NormalizedEntryError::AuthenticationFailed),
+                } => Some(error_type),
+                _ => None,
+            }
+        })
+    }
+
+    /// On a multi-user server, give the execution a short-lived session for the user who
+    /// started it. The vibe-kanban MCP server sends it with every tool call, so agents are
+    /// held to the same project roles as that user.
+    async fn insert_mcp_auth_token(
+        &self,
+        env: &mut ExecutionEnv,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+    ) {
+        let Some(session_service) = LocalSessionService::from_env() else {
+            return;
+        };
+
+        let initiated_by =
+            match Session::find_by_id(&self.db.pool, execution_process.session_id).await {
+                Ok(session) => session.and_then(|s| s.initiated_by_user_id),
+                Err(e) => {
+                    tracing::warn!("Failed to load session for MCP auth token: {}", e);
+                    None
+                }
+            };
+        let Some(user_id) = initiated_by.or(workspace.owner_user_id) else {
+            tracing::warn!(
+                "No user to issue an MCP auth token for execution {}; MCP tool calls will be rejected",
+                execution_process.id
+            );
+            return;
+        };
+
+        let token = match session_service
+            .create_session_with_duration(&self.db.pool, user_id, MCP_AUTH_TOKEN_LIFETIME)
+            .await
+        {
+            Ok(token) => token,
+            Err(e) => {
+                tracing::error!("Failed to issue MCP auth token: {}", e);
+                return;
+            }
+        };
+        if let Ok(session_id) = session_service.extract_session_id(&token) {
+            self.mcp_auth_sessions
+                .write()
+                .await
+                .insert(execution_process.id, session_id);
+        }
+        env.insert("VK_AUTH_TOKEN", token);
+    }
+
+    async fn revoke_mcp_auth_token(&self, exec_id: Uuid) {
+        let Some(session_id) = self.mcp_auth_sessions.write().await.remove(&exec_id) else {
+            return;
+        };
+        let Some(session_service) = LocalSessionService::from_env() else {
+            return;
+        };
+        if let Err(e) = session_service
+            .revoke_session(&self.db.pool, session_id)
+            .await
+        {
+            tracing::warn!("Failed to revoke MCP auth token for {}: {}", exec_id, e);
+        }
+    }
+
+    /// If a Claude Code run failed because its OAuth token hit a usage or rate limit or was
+    /// rejected, take the token out of rotation and retry the same action with the next
+    /// healthy token. Returns true when a retry was started.
+    async fn handle_claude_token_failure(&self, ctx: &ExecutionContext) -> bool {
+        let Some(token_id) = self
+            .claude_token_assignments
+            .write()
+            .await
+            .remove(&ctx.execution_process.id)
+        else {
+            return false;
+        };
+
+        let Some(error_type) = self
+            .find_claude_token_error(&ctx.execution_process.id)
+            .await
+        else {
+            return false;
+        };
+
+        let marked = match error_type {
+            NormalizedEntryError::UsageLimitReached { resets_at } => {
+                self.claude_token_rotation
+                    .mark_rate_limited(token_id, resets_at)
+                    .await
+            }
+            NormalizedEntryError::RateLimited => {
+                self.claude_token_rotation.back_off(token_id).await
+            }
+            _ => {
+                self.claude_token_rotation
+                    .mark_invalid(token_id, "Claude rejected the OAuth token")
+                    .await
+            }
+        };
+        if let Err(e) = marked {
+            tracing::error!("Failed to update Claude OAuth token {}: {}", token_id, e);
+            return false;
+        }
+
+        if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed)
+            || !matches!(
+                ctx.execution_process.run_reason,
+                ExecutionProcessRunReason::CodingAgent
+            )
+        {
+            return false;
+        }
+
+        if !self.claude_token_rotation.has_available_tokens().await {
+            tracing::warn!(
+                "No healthy Claude OAuth tokens left, not retrying execution {}",
+                ctx.execution_process.id
+            );
+            return false;
+        }
+
+        let action = match ctx.execution_process.executor_action() {
+            Ok(action) => action.clone(),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load executor action for retry of {}: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+                return false;
+            }
+        };
+
+        match self
+            .start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await
+        {
+            Ok(retry) => {
+                tracing::info!(
+                    "Retrying execution {} as {} with the next Claude OAuth token",
+                    ctx.execution_process.id,
+                    retry.id
+                );
+                true
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to retry execution {} with another Claude OAuth token: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Find the latest per-model token usage reported by the executor's log normalizer
+    async fn extract_token_usage(&self, exec_id: &Uuid) -> Option<Vec<ModelTokenUsage>> {
+        let msg_stores = self.msg_stores.read().await;
+        let msg_store = msg_stores.get(exec_id)?;
+
+        msg_store.get_history().iter().rev().find_map(|msg| {
+            let LogMsg::JsonPatch(patch) = msg else {
+                return None;
+            };
+            let (_, entry) = extract_normalized_entry_from_patch(patch)?;
+            match entry.entry_type {
+                NormalizedEntryType::TokenUsageInfo(info) if !info.usage.is_empty() => {
+                    Some(info.usage)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Persist the execution's token usage for cost reporting and budgets (best-effort)
+    async fn record_token_usage(&self, exec_id: &Uuid) {
+        let Some(usage) = self.extract_token_usage(exec_id).await else {
+            return;
+        };
+        if let Err(e) = ExecutionTokenUsage::record(&self.db.pool, *exec_id, &usage).await {
+            tracing::warn!(
+                "Failed to record token usage for execution {}: {}",
+                exec_id,
+                e
+            );
+        }
+    }
+
     /// Update the coding agent turn summary with the final assistant message
     async fn update_executor_session_summary(&self, exec_id: &Uuid) -> Result<(), anyhow::Error> {
         // Check if there's a coding agent turn for this execution process
@@ -909,6 +1669,33 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Delete the session's draft follow-up if it still holds the message that was
+    /// just taken from the queue, so a draft for the next message is left alone.
+    async fn clear_consumed_draft(&self, session_id: Uuid, consumed: &DraftFollowUpData) {
+        let draft =
+            match Scratch::find_by_id(&self.db.pool, session_id, &ScratchType::DraftFollowUp).await
+            {
+                Ok(draft) => draft,
+                Err(e) => {
+                    tracing::warn!("Failed to load draft for session {}: {}", session_id, e);
+                    return;
+                }
+            };
+        let matches_consumed = matches!(
+            draft.map(|d| d.payload),
+            Some(ScratchPayload::DraftFollowUp(data)) if data.message == consumed.message
+        );
+        if matches_consumed
+            && let Err(e) =
+                Scratch::delete(&self.db.pool, session_id, &ScratchType::DraftFollowUp).await
+        {
+            tracing::warn!(
+                "Failed to delete scratch after consuming queued message: {}",
+                e
+            );
+        }
+    }
+
     /// Start a follow-up execution from a queued message
     async fn start_queued_follow_up(
         &self,
@@ -1026,6 +1813,10 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn execution_limits(&self) -> ExecutionLimits {
+        self.config.read().await.execution_limits.clone()
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1223,6 +2014,8 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_TASK_ID", task.id.to_string());
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
+        self.insert_mcp_auth_token(&mut env, workspace, execution_process)
+            .await;
 
         // Inject Claude OAuth token for rotation if this is a Claude Code executor
         if matches!(
@@ -1230,8 +2023,12 @@ impl ContainerService for LocalContainerService {
             Some(BaseCodingAgent::ClaudeCode)
         ) {
             match self.claude_token_rotation.get_next_token().await {
-                Ok(Some(token)) => {
-                    env.insert("CLAUDE_CODE_OAUTH_TOKEN", token);
+                Ok(Some(rotated)) => {
+                    env.insert("CLAUDE_CODE_OAUTH_TOKEN", rotated.token);
+                    self.claude_token_assignments
+                        .write()
+                        .await
+                        .insert(execution_process.id, rotated.id);
                     tracing::info!("Injected rotated Claude OAuth token for execution");
                 }
                 Ok(None) => {
@@ -1250,8 +2047,13 @@ impl ContainerService for LocalContainerService {
             }
         }
 
+        let sandbox = self
+            .prepare_sandbox(execution_process.id, &current_dir, &repos)
+            .await?;
+        let env = env.with_sandbox(sandbox);
+
         // Create the child and stream, add to execution tracker with timeout
-        let mut spawned = tokio::time::timeout(
+        let spawned = tokio::time::timeout(
             Duration::from_secs(30),
             executor_action.spawn(&current_dir, approvals_service, &env),
         )
@@ -1260,7 +2062,15 @@ impl ContainerService for LocalContainerService {
             ContainerError::Other(anyhow!(
                 "Timeout: process took more than 30 seconds to start"
             ))
-        })??;
+        })
+        .and_then(|result| result.map_err(ContainerError::from));
+        let mut spawned = match spawned {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                self.release_sandbox(execution_process.id).await;
+                return Err(e);
+            }
+        };
 
         self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
             .await;
@@ -1278,14 +2088,93 @@ impl ContainerService for LocalContainerService {
         let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
         self.add_exit_monitor_handle(execution_process.id, hn).await;
 
+        if let Some(timeouts) = ExecutionTimeouts::for_action(executor_action) {
+            self.spawn_execution_watchdog(execution_process.id, timeouts);
+        }
+
         Ok(())
     }
 
+    async fn retry_failed_execution(&self, ctx: &ExecutionContext) -> bool {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) || !self.is_failed_run(ctx).await
+        {
+            return false;
+        }
+        let exec_id = ctx.execution_process.id;
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return false;
+        };
+
+        let attempts = match self.retry_chain_profiles(exec_id).await {
+            Ok(Some(attempts)) if !attempts.is_empty() => attempts,
+            Ok(_) => return false,
+            Err(e) => {
+                tracing::error!("Failed to load retry chain of {}: {}", exec_id, e);
+                return false;
+            }
+        };
+        // The profile the request was first run on decides how it is retried
+        let Some(policy) = ExecutorConfigs::get_cached()
+            .get_coding_agent_or_default(&attempts[0])
+            .cmd_overrides()
+            .and_then(|cmd| cmd.retry.clone())
+        else {
+            return false;
+        };
+        let Some(next) = policy.next_attempt(&attempts) else {
+            tracing::info!("Retry policy exhausted for execution {}", exec_id);
+            return false;
+        };
+
+        let retry_action = if Some(&next.executor_profile_id) == attempts.last() {
+            Some(action.clone())
+        } else {
+            action.with_executor_profile(next.executor_profile_id.clone())
+        };
+        let Some(retry_action) = retry_action else {
+            return false;
+        };
+
+        let message = if next.delay.is_zero() {
+            format!(
+                "Run failed. Retrying with {} (attempt {}).",
+                next.executor_profile_id, next.attempt
+            )
+        } else {
+            format!(
+                "Run failed. Retrying with {} in {}s (attempt {}).",
+                next.executor_profile_id,
+                next.delay.as_secs(),
+                next.attempt
+            )
+        };
+        self.push_log_entry(exec_id, NormalizedEntryType::SystemMessage, message)
+            .await;
+
+        let container = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(next.delay).await;
+            container.start_retry(exec_id, retry_action).await;
+        });
+        true
+    }
+
     async fn stop_execution(
         &self,
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
+        // A queued execution has no process yet; taking it off the queue is enough
+        if self
+            .cancel_queued_execution(execution_process.id, status.clone())
+            .await?
+        {
+            return Ok(());
+        }
+
         let child = self
             .get_child_from_store(&execution_process.id)
             .await
@@ -1335,6 +2224,8 @@ impl ContainerService for LocalContainerService {
         }
         self.remove_child_from_store(&execution_process.id).await;
 
+        self.record_token_usage(&execution_process.id).await;
+
         // Mark the process finished in the MsgStore and wait for DB persistence
         let db_stream_handle = self.take_db_stream_handle(&execution_process.id).await;
         if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
@@ -1344,16 +2235,16 @@ impl ContainerService for LocalContainerService {
             let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
         }
 
-        // Update task status to InReview when execution is stopped
+        // Apply the workflow's agent-finished transition when execution is stopped
         if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
             && !matches!(
                 ctx.execution_process.run_reason,
                 ExecutionProcessRunReason::DevServer
             )
             && let Err(e) =
-                Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await
+                workflow::handle_event(&self.db.pool, &ctx.task, WorkflowEvent::AgentFinished).await
         {
-            tracing::error!("Failed to update task status to InReview: {e}");
+            tracing::error!("Failed to update task status after stopping execution: {e}");
         }
 
         tracing::debug!(
@@ -1364,6 +2255,8 @@ impl ContainerService for LocalContainerService {
         // Record after-head commit OID (best-effort)
         self.update_after_head_commits(execution_process.id).await;
 
+        self.resume_execution_queue().await;
+
         Ok(())
     }
 
@@ -1496,6 +2389,8 @@ impl ContainerService for LocalContainerService {
 
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
         tracing::info!("Killing all running processes");
+        // Queued executions stay queued and start after the next launch
+        self.shutting_down.store(true, Ordering::SeqCst);
         let running_processes = ExecutionProcess::find_running(&self.db.pool).await?;
 
         tracing::info!(
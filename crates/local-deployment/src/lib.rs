@@ -22,6 +22,7 @@ use services::services::{
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
     share::{ShareConfig, SharePublisher},
+    token_encryption::TokenCipher,
     worktree_manager::WorktreeManager,
 };
 use tokio::sync::RwLock;
@@ -127,8 +128,9 @@ impl Deployment for LocalDeployment {
             });
         }
 
-        let approvals = Approvals::new(msg_stores.clone());
-        let queued_message_service = QueuedMessageService::new();
+        let approvals = Approvals::new(db.pool.clone(), msg_stores.clone());
+        approvals.expire_orphaned().await;
+        let queued_message_service = QueuedMessageService::new(db.clone());
 
         let share_config = ShareConfig::from_env();
 
@@ -169,7 +171,41 @@ impl Deployment for LocalDeployment {
         let oauth_handoffs = Arc::new(RwLock::new(HashMap::new()));
 
         // Initialize Claude token rotation service
-        let claude_token_rotation = ClaudeTokenRotationService::new(Arc::new(db.clone()));
+        let token_cipher = {
+            let key_file = config
+                .read()
+                .await
+                .token_encryption_key_file
+                .as_deref()
+                .map(utils::path::expand_tilde);
+            TokenCipher::load(key_file.as_deref())?
+        };
+        let claude_token_rotation =
+            ClaudeTokenRotationService::new(Arc::new(db.clone()), token_cipher);
+        {
+            // Migrate legacy rows and rows sealed with retired keys to the current key
+            let claude_token_rotation = claude_token_rotation.clone();
+            tokio::spawn(async move {
+                match claude_token_rotation.reencrypt_all_tokens().await {
+                    Ok(report) => {
+                        if report.reencrypted > 0 {
+                            tracing::info!(
+                                "Re-encrypted {} Claude OAuth tokens",
+                                report.reencrypted
+                            );
+                        }
+                        if !report.failed.is_empty() {
+                            tracing::error!(
+                                "Could not decrypt {} Claude OAuth tokens: {:?}",
+                                report.failed.len(),
+                                report.failed
+                            );
+                        }
+                    }
+                    Err(e) => tracing::error!("Failed to re-encrypt Claude OAuth tokens: {}", e),
+                }
+            });
+        }
 
         // We need to make analytics accessible to the ContainerService
         // TODO: Handle this more gracefully
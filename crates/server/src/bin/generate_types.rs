@@ -19,6 +19,7 @@ fn generate_types_content() -> String {
         db::models::claude_oauth_token::ClaudeOAuthTokenStatus::decl(),
         db::models::claude_oauth_token::UserTokenStatus::decl(),
         server::routes::claude_tokens::UpsertClaudeTokenRequest::decl(),
+        server::routes::claude_tokens::ReencryptClaudeTokensResponse::decl(),
         db::models::project::Project::decl(),
         db::models::project::ProjectCreator::decl(),
         db::models::project::ProjectWithCreator::decl(),
@@ -26,10 +27,47 @@ fn generate_types_content() -> String {
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::project_archive::ImportProject::decl(),
         db::models::repo::Repo::decl(),
         db::models::repo::UpdateRepo::decl(),
+        db::models::repo::RepoVerifySettings::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
+        db::models::project_repo::ProjectRepoMergeSettings::decl(),
+        db::models::project_workflow::WorkflowGuard::decl(),
+        db::models::project_workflow::WorkflowEvent::decl(),
+        db::models::project_workflow::WorkflowTransition::decl(),
+        db::models::project_workflow::WorkflowAutomation::decl(),
+        db::models::project_workflow::WorkflowDefinition::decl(),
+        db::models::project_budget::BudgetPeriod::decl(),
+        db::models::project_budget::ProjectBudget::decl(),
+        db::models::project_budget::UpsertProjectBudget::decl(),
+        db::models::project_budget::ProjectBudgetStatus::decl(),
+        db::models::execution_queue::ExecutionQueueEntry::decl(),
+        db::models::execution_queue::QueuedExecution::decl(),
+        db::models::execution_queue::UpdateQueuePriority::decl(),
+        db::models::project_member::ProjectRole::decl(),
+        db::models::project_member::ProjectMember::decl(),
+        db::models::project_member::ProjectMemberWithUser::decl(),
+        db::models::project_member::SetProjectMemberRole::decl(),
+        server::routes::project_members::ProjectAccess::decl(),
+        server::routes::users::SetUserAdmin::decl(),
+        db::models::audit_event::AuditSource::decl(),
+        db::models::audit_event::AuditAction::decl(),
+        db::models::audit_event::AuditEvent::decl(),
+        db::models::audit_event::AuditEventPage::decl(),
+        db::models::search::SearchHitKind::decl(),
+        db::models::search::SearchHit::decl(),
+        db::models::approval_policy::ApprovalDecision::decl(),
+        db::models::approval_policy::ApprovalRule::decl(),
+        db::models::approval_policy::ApprovalPolicyDefinition::decl(),
+        db::models::approval_policy::ApprovalPolicy::decl(),
+        db::models::approval_policy::UpsertApprovalPolicy::decl(),
+        db::models::tool_approval::ToolApprovalStatus::decl(),
+        db::models::tool_approval::ToolApproval::decl(),
+        db::models::token_usage::ExecutionTokenUsage::decl(),
+        db::models::token_usage::UsageGroupBy::decl(),
+        db::models::token_usage::TokenUsageRollup::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
@@ -48,6 +86,12 @@ fn generate_types_content() -> String {
         db::models::task_approval::TaskApproval::decl(),
         db::models::task_approval::TaskApprovalUser::decl(),
         db::models::task_approval::TaskApprovalWithUser::decl(),
+        db::models::task_dependency::TaskDependency::decl(),
+        db::models::task_dependency::CreateTaskDependency::decl(),
+        db::models::task_dependency::DependencyTask::decl(),
+        db::models::task_dependency::TaskAutoStart::decl(),
+        db::models::task_dependency::UpsertTaskAutoStart::decl(),
+        db::models::task_dependency::TaskDependencies::decl(),
         server::routes::tasks::PlaceHoldRequest::decl(),
         server::routes::tasks::HoldResponse::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
@@ -70,12 +114,20 @@ fn generate_types_content() -> String {
         db::models::session::SessionWithInitiator::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
+        db::models::execution_process::ExecutionProcessStatusReason::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
+        db::models::merge_verification::MergeVerification::decl(),
+        db::models::merge_queue::MergeQueueStatus::decl(),
+        db::models::merge_queue::MergeQueueEntry::decl(),
+        db::models::merge_queue::QueuedMerge::decl(),
+        db::models::conflict_resolution::ConflictResolutionStatus::decl(),
+        db::models::conflict_resolution::ConflictResolution::decl(),
         db::models::merge::MergeStatus::decl(),
+        db::models::merge::MergeStrategy::decl(),
         db::models::merge::PullRequestInfo::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
@@ -130,12 +182,18 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
         server::routes::task_attempts::PushTaskAttemptRequest::decl(),
+        server::routes::task_attempts::MergeWorkspaceRequest::decl(),
+        server::routes::task_attempts::WorkspaceMergeError::decl(),
+        services::services::workspace_merge::PreflightProblem::decl(),
+        services::services::workspace_merge::MergedRepo::decl(),
+        services::services::workspace_merge::WorkspacePr::decl(),
         server::routes::task_attempts::RenameBranchRequest::decl(),
         server::routes::task_attempts::RenameBranchResponse::decl(),
         server::routes::sessions::review::StartReviewRequest::decl(),
         server::routes::sessions::review::ReviewError::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
+        server::routes::task_attempts::pr::CreateWorkspacePrsRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
@@ -145,9 +203,11 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::ResolveConflictsRequest::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::PrError::decl(),
+        server::routes::task_attempts::pr::WorkspacePrError::decl(),
         server::routes::task_attempts::RunScriptError::decl(),
         server::routes::task_attempts::pr::AttachPrResponse::decl(),
         server::routes::task_attempts::pr::AttachExistingPrRequest::decl(),
@@ -182,6 +242,14 @@ fn generate_types_content() -> String {
         services::services::file_search::SearchMode::decl(),
         services::services::config::Config::decl(),
         services::services::config::NotificationConfig::decl(),
+        services::services::config::NotificationChannelConfig::decl(),
+        services::services::config::NotificationChannelKind::decl(),
+        services::services::config::NotificationEvent::decl(),
+        services::services::config::SmtpSecurity::decl(),
+        services::services::config::ExecutionLimits::decl(),
+        services::services::config::GitHostsConfig::decl(),
+        executors::sandbox::SandboxConfig::decl(),
+        executors::sandbox::SandboxNetwork::decl(),
         services::services::config::ThemeMode::decl(),
         services::services::config::EditorConfig::decl(),
         services::services::config::EditorType::decl(),
@@ -190,7 +258,7 @@ fn generate_types_content() -> String {
         services::services::config::UiLanguage::decl(),
         services::services::config::SendMessageShortcut::decl(),
         services::services::git::GitBranch::decl(),
-        services::services::queued_message::QueuedMessage::decl(),
+        db::models::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
         services::services::git::ConflictOp::decl(),
         executors::actions::ExecutorAction::decl(),
@@ -204,6 +272,7 @@ fn generate_types_content() -> String {
         executors::executors::AvailabilityInfo::decl(),
         executors::command::CommandBuilder::decl(),
         executors::profile::ExecutorProfileId::decl(),
+        executors::retry::RetryPolicy::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::profile::ExecutorConfigs::decl(),
         executors::executors::BaseAgentCapability::decl(),
@@ -233,6 +302,7 @@ fn generate_types_content() -> String {
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::TokenUsageInfo::decl(),
+        executors::logs::ModelTokenUsage::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
         executors::logs::TodoItem::decl(),
@@ -5,9 +5,13 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError,
-    project_repo::ProjectRepoError, repo::RepoError, review_conversation::ReviewConversationError,
-    scratch::ScratchError, session::SessionError, workspace::WorkspaceError,
+    approval_policy::ApprovalPolicyError, execution_process::ExecutionProcessError,
+    project::ProjectError, project_archive::ProjectArchiveError,
+    project_budget::ProjectBudgetError, project_member::ProjectMemberError,
+    project_repo::ProjectRepoError, project_workflow::ProjectWorkflowError,
+    queued_message::QueuedMessageError, repo::RepoError,
+    review_conversation::ReviewConversationError, scratch::ScratchError, session::SessionError,
+    task_dependency::TaskDependencyError, user::UserError, workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::{command::CommandBuildError, executors::ExecutorError};
@@ -16,9 +20,10 @@ use local_deployment::pty::PtyError;
 use services::services::{
     config::ConfigError, container::ContainerError, git::GitServiceError, git_host::GitHostError,
     github_oauth::GitHubOAuthError, image::ImageError,
-    local_session::SessionError as LocalSessionError, project::ProjectServiceError,
-    remote_client::RemoteClientError, repo::RepoError as RepoServiceError,
-    worktree_manager::WorktreeError,
+    local_session::SessionError as LocalSessionError, merge_verification::MergeVerificationError,
+    project::ProjectServiceError, remote_client::RemoteClientError,
+    repo::RepoError as RepoServiceError, workflow::WorkflowError,
+    workspace_start::WorkspaceStartError, worktree_manager::WorktreeError,
 };
 use thiserror::Error;
 use utils::response::ApiResponse;
@@ -132,6 +137,9 @@ impl IntoResponse for ApiError {
             },
             ApiError::GitHost(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHostError"),
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
+            ApiError::Container(ContainerError::BudgetExceeded(_)) => {
+                (StatusCode::FORBIDDEN, "BudgetExceeded")
+            }
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::CommandBuilder(_) => (StatusCode::INTERNAL_SERVER_ERROR, "CommandBuildError"),
@@ -275,6 +283,7 @@ impl IntoResponse for ApiError {
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
             ApiError::Forbidden(msg) => msg.clone(),
+            ApiError::Container(err @ ContainerError::BudgetExceeded(_)) => err.to_string(),
             ApiError::GitHubOAuth(err) => format!("GitHub authentication failed: {}", err),
             ApiError::LocalSession(err) => match err {
                 LocalSessionError::InvalidToken => "Invalid session token.".to_string(),
@@ -320,6 +329,14 @@ impl From<ProjectServiceError> for ApiError {
             ProjectServiceError::RemoteClient(msg) => {
                 ApiError::BadRequest(format!("Remote client error: {}", msg))
             }
+            ProjectServiceError::Archive(ProjectArchiveError::Database(db_err)) => {
+                ApiError::Database(db_err)
+            }
+            ProjectServiceError::Archive(ProjectArchiveError::ProjectNotFound) => {
+                ApiError::Project(ProjectError::ProjectNotFound)
+            }
+            ProjectServiceError::Archive(e) => ApiError::BadRequest(e.to_string()),
+            ProjectServiceError::Image(img_err) => ApiError::Image(img_err),
         }
     }
 }
@@ -388,3 +405,111 @@ impl From<ReviewConversationError> for ApiError {
         }
     }
 }
+
+impl From<QueuedMessageError> for ApiError {
+    fn from(err: QueuedMessageError) -> Self {
+        match err {
+            QueuedMessageError::Database(db_err) => ApiError::Database(db_err),
+            QueuedMessageError::Serde(e) => {
+                ApiError::BadRequest(format!("Invalid queued message: {e}"))
+            }
+            QueuedMessageError::NotFound => {
+                ApiError::BadRequest("Queued message not found".to_string())
+            }
+            QueuedMessageError::InvalidOrder => ApiError::BadRequest(
+                "Reorder must list every queued message exactly once".to_string(),
+            ),
+        }
+    }
+}
+
+impl From<ProjectWorkflowError> for ApiError {
+    fn from(err: ProjectWorkflowError) -> Self {
+        match err {
+            ProjectWorkflowError::Database(db_err) => ApiError::Database(db_err),
+            ProjectWorkflowError::Serde(e) => {
+                ApiError::BadRequest(format!("Invalid workflow definition: {e}"))
+            }
+            ProjectWorkflowError::Invalid(msg) => ApiError::BadRequest(msg),
+        }
+    }
+}
+
+impl From<ApprovalPolicyError> for ApiError {
+    fn from(err: ApprovalPolicyError) -> Self {
+        match err {
+            ApprovalPolicyError::Database(db_err) => ApiError::Database(db_err),
+            ApprovalPolicyError::Serde(e) => {
+                ApiError::BadRequest(format!("Invalid approval policy definition: {e}"))
+            }
+            ApprovalPolicyError::Invalid(msg) => ApiError::BadRequest(msg),
+        }
+    }
+}
+
+impl From<ProjectBudgetError> for ApiError {
+    fn from(err: ProjectBudgetError) -> Self {
+        match err {
+            ProjectBudgetError::Database(db_err) => ApiError::Database(db_err),
+            ProjectBudgetError::Invalid(msg) => ApiError::BadRequest(msg),
+        }
+    }
+}
+
+impl From<TaskDependencyError> for ApiError {
+    fn from(err: TaskDependencyError) -> Self {
+        match err {
+            TaskDependencyError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<ProjectMemberError> for ApiError {
+    fn from(err: ProjectMemberError) -> Self {
+        match err {
+            ProjectMemberError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<UserError> for ApiError {
+    fn from(err: UserError) -> Self {
+        match err {
+            UserError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<MergeVerificationError> for ApiError {
+    fn from(err: MergeVerificationError) -> Self {
+        match err {
+            MergeVerificationError::Database(db_err) => ApiError::Database(db_err),
+            MergeVerificationError::GitService(git_err) => ApiError::GitService(git_err),
+            other => ApiError::Conflict(other.to_string()),
+        }
+    }
+}
+
+impl From<WorkflowError> for ApiError {
+    fn from(err: WorkflowError) -> Self {
+        match err {
+            WorkflowError::Database(db_err) => ApiError::Database(db_err),
+            WorkflowError::Definition(e) => e.into(),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<WorkspaceStartError> for ApiError {
+    fn from(err: WorkspaceStartError) -> Self {
+        match err {
+            WorkspaceStartError::Database(db_err) => ApiError::Database(db_err),
+            WorkspaceStartError::ClaudeTokenRequired => ApiError::ClaudeTokenRequired,
+            WorkspaceStartError::Forbidden(msg) => ApiError::Forbidden(msg),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
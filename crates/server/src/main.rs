@@ -67,11 +67,29 @@ async fn main() -> Result<(), VibeKanbanError> {
         .backfill_repo_names()
         .await
         .map_err(DeploymentError::from)?;
+    // Executions queued before the last shutdown start once slots allow
+    if let Err(e) = deployment.container().start_queued_executions().await {
+        tracing::warn!("Failed to start queued executions: {}", e);
+    }
+    // Merges queued before the last shutdown carry on without holding up startup
+    routes::merge_queue::spawn_process_merge_queues(&deployment);
     deployment.spawn_pr_monitor_service().await;
     deployment.spawn_main_branch_sync_service().await;
+    routes::task_dependencies::spawn_auto_start_scheduler(&deployment);
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
+    // Index conversations the search index is missing without holding up startup
+    let deployment_for_search = deployment.clone();
+    tokio::spawn(async move {
+        if let Err(e) = deployment_for_search
+            .container()
+            .backfill_search_index()
+            .await
+        {
+            tracing::warn!("Failed to backfill the search index: {}", e);
+        }
+    });
     // Pre-warm file search cache for most active projects
     let deployment_for_cache = deployment.clone();
     tokio::spawn(async move {
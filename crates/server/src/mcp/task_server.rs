@@ -1,8 +1,11 @@
 use std::{future::Future, str::FromStr};
 
 use db::models::{
+    execution_process::ExecutionProcess,
     project::Project,
     repo::Repo,
+    review_conversation::{ConversationWithMessages, CreateMessage, ResolveConversation},
+    session::Session,
     tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
     workspace::{Workspace, WorkspaceContext},
@@ -19,11 +22,19 @@ use rmcp::{
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use utils::diff::{Diff, create_unified_diff};
 use uuid::Uuid;
 
-use crate::routes::{
-    containers::ContainerQuery,
-    task_attempts::{CreateTaskAttemptBody, WorkspaceRepoInput},
+use crate::{
+    middleware::AUDIT_SOURCE_HEADER,
+    routes::{
+        containers::ContainerQuery,
+        sessions::CreateFollowUpAttempt,
+        task_attempts::{
+            CreateTaskAttemptBody, WorkspaceRepoInput,
+            review_conversations::{AddMessageResponse, ResolveConversationResponse},
+        },
+    },
 };
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -264,6 +275,10 @@ pub struct UpdateTaskRequest {
     pub title: Option<String>,
     #[schemars(description = "New description for the task")]
     pub description: Option<String>,
+    #[schemars(
+        description = "New status: 'todo', 'inprogress', 'inreview', 'ci', 'cd', 'done' or 'cancelled'. The project workflow must allow the change"
+    )]
+    pub status: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -321,6 +336,249 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListSessionsRequest {
+    #[schemars(description = "The ID of the workspace to list sessions for")]
+    pub workspace_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SessionSummary {
+    #[schemars(description = "The unique identifier of the session")]
+    pub id: String,
+    #[schemars(description = "The coding agent executor used by the session")]
+    pub executor: Option<String>,
+    #[schemars(description = "When the session was created")]
+    pub created_at: String,
+    #[schemars(description = "When the session was last updated")]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListSessionsResponse {
+    pub sessions: Vec<SessionSummary>,
+    pub count: usize,
+    pub workspace_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FollowUpRequest {
+    #[schemars(description = "The ID of the session to send the follow-up to")]
+    pub session_id: Uuid,
+    #[schemars(description = "The follow-up prompt for the coding agent")]
+    pub prompt: String,
+    #[schemars(
+        description = "Optional coding agent executor; defaults to the executor the session was started with"
+    )]
+    pub executor: Option<String>,
+    #[schemars(description = "Optional executor variant, if needed")]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct FollowUpResponse {
+    pub session_id: String,
+    #[schemars(description = "The execution process started for the follow-up")]
+    pub execution_process_id: String,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetWorkspaceDiffRequest {
+    #[schemars(description = "The ID of the workspace to diff against its target branches")]
+    pub workspace_id: Uuid,
+    #[schemars(
+        description = "Include a unified diff for each file (default: true). Set to false for per-file stats only"
+    )]
+    pub include_content: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct McpFileDiff {
+    #[schemars(description = "Path of the changed file")]
+    pub path: String,
+    #[schemars(
+        description = "Kind of change: added, deleted, modified, renamed, copied or permissionChange"
+    )]
+    pub change: String,
+    pub additions: Option<usize>,
+    pub deletions: Option<usize>,
+    pub repo_id: Option<String>,
+    #[schemars(description = "Unified diff of the file, when content was requested and available")]
+    pub unified_diff: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetWorkspaceDiffResponse {
+    pub workspace_id: String,
+    pub files: Vec<McpFileDiff>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListExecutionProcessesRequest {
+    #[schemars(description = "The ID of the session to list execution processes for")]
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ExecutionProcessSummary {
+    #[schemars(description = "The unique identifier of the execution process")]
+    pub id: String,
+    #[schemars(
+        description = "Why the process ran: setupscript, codingagent, cleanupscript or devserver"
+    )]
+    pub run_reason: String,
+    #[schemars(description = "Current status: running, completed, failed or killed")]
+    pub status: String,
+    pub exit_code: Option<i64>,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+}
+
+impl ExecutionProcessSummary {
+    fn from_process(process: ExecutionProcess) -> Self {
+        Self {
+            id: process.id.to_string(),
+            run_reason: serde_label(&process.run_reason),
+            status: serde_label(&process.status),
+            exit_code: process.exit_code,
+            started_at: process.started_at.to_rfc3339(),
+            completed_at: process.completed_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListExecutionProcessesResponse {
+    pub execution_processes: Vec<ExecutionProcessSummary>,
+    pub count: usize,
+    pub session_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetExecutionLogsRequest {
+    #[schemars(description = "The ID of the execution process to read logs from")]
+    pub execution_process_id: Uuid,
+    #[schemars(description = "Return only the last N log entries (default: 50)")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetExecutionLogsResponse {
+    pub execution_process_id: String,
+    #[schemars(description = "Total number of normalized entries produced by the process")]
+    pub total_entries: usize,
+    #[schemars(description = "The last `limit` normalized log entries, oldest first")]
+    pub entries: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StopExecutionRequest {
+    #[schemars(description = "The ID of the execution process to stop")]
+    pub execution_process_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct StopExecutionResponse {
+    pub stopped_execution_process_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListReviewConversationsRequest {
+    #[schemars(description = "The ID of the workspace whose review conversations to list")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "Only return unresolved conversations (default: false)")]
+    pub unresolved_only: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ReviewMessageSummary {
+    pub id: String,
+    #[schemars(description = "Username of the message author, if known")]
+    pub author: Option<String>,
+    pub content: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ReviewConversationSummary {
+    #[schemars(description = "The unique identifier of the conversation")]
+    pub id: String,
+    pub file_path: String,
+    pub line_number: i64,
+    #[schemars(description = "Side of the diff the conversation is anchored to: old or new")]
+    pub side: String,
+    pub code_line: Option<String>,
+    pub is_resolved: bool,
+    pub resolution_summary: Option<String>,
+    pub messages: Vec<ReviewMessageSummary>,
+}
+
+impl ReviewConversationSummary {
+    fn from_conversation(c: ConversationWithMessages) -> Self {
+        Self {
+            id: c.conversation.id.to_string(),
+            file_path: c.conversation.file_path,
+            line_number: c.conversation.line_number,
+            side: c.conversation.side,
+            code_line: c.conversation.code_line,
+            is_resolved: c.conversation.is_resolved,
+            resolution_summary: c.conversation.resolution_summary,
+            messages: c
+                .messages
+                .into_iter()
+                .map(|m| ReviewMessageSummary {
+                    id: m.message.id.to_string(),
+                    author: m.author.map(|a| a.username),
+                    content: m.message.content,
+                    created_at: m.message.created_at.to_rfc3339(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListReviewConversationsResponse {
+    pub conversations: Vec<ReviewConversationSummary>,
+    pub count: usize,
+    pub workspace_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ReplyToReviewConversationRequest {
+    #[schemars(description = "The ID of the workspace the conversation belongs to")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The ID of the conversation to reply to")]
+    pub conversation_id: Uuid,
+    #[schemars(description = "The reply content")]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ResolveReviewConversationRequest {
+    #[schemars(description = "The ID of the workspace the conversation belongs to")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The ID of the conversation to resolve")]
+    pub conversation_id: Uuid,
+    #[schemars(description = "Summary of how the conversation was addressed")]
+    pub summary: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ReviewConversationResponse {
+    pub conversation: ReviewConversationSummary,
+}
+
+/// Serialized form of a unit enum (e.g. `ExecutionProcessStatus::Running` -> "running")
+fn serde_label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => "unknown".to_string(),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
@@ -355,13 +613,42 @@ pub struct McpContext {
 impl TaskServer {
     pub fn new(base_url: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: Self::build_client(),
             base_url: base_url.to_string(),
             tool_router: Self::tool_router(),
             context: None,
         }
     }
 
+    /// On multi-user servers the agent is given a session token for the user who started
+    /// it, so tool calls pass the same project role checks as that user's own requests.
+    /// Every call is also marked as coming from MCP so the audit log can tell agent
+    /// actions apart from the user's own.
+    fn build_client() -> reqwest::Client {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            AUDIT_SOURCE_HEADER,
+            reqwest::header::HeaderValue::from_static("mcp"),
+        );
+        if let Ok(token) = std::env::var("VK_AUTH_TOKEN")
+            && let Ok(mut value) =
+                reqwest::header::HeaderValue::from_str(&format!("Bearer {token}"))
+        {
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to build HTTP client, falling back to defaults: {}",
+                    e
+                );
+                reqwest::Client::new()
+            })
+    }
+
     pub async fn init(mut self) -> Self {
         let context = self.fetch_context_at_startup().await;
 
@@ -519,6 +806,42 @@ impl TaskServer {
         Ok(())
     }
 
+    /// Parse an executor name ('CLAUDE_CODE', 'claude-code', ...) and optional variant
+    fn parse_executor_profile(
+        executor: &str,
+        variant: Option<String>,
+    ) -> Result<ExecutorProfileId, CallToolResult> {
+        let executor_trimmed = executor.trim();
+        if executor_trimmed.is_empty() {
+            return Err(
+                Self::err("Executor must not be empty.".to_string(), None::<String>).unwrap(),
+            );
+        }
+
+        let normalized_executor = executor_trimmed.replace('-', "_").to_ascii_uppercase();
+        let base_executor = BaseCodingAgent::from_str(&normalized_executor).map_err(|_| {
+            Self::err(
+                format!("Unknown executor '{executor_trimmed}'."),
+                None::<String>,
+            )
+            .unwrap()
+        })?;
+
+        let variant = variant.and_then(|v| {
+            let trimmed = v.trim();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        });
+
+        Ok(ExecutorProfileId {
+            executor: base_executor,
+            variant,
+        })
+    }
+
     fn url(&self, path: &str) -> String {
         format!(
             "{}/{}",
@@ -869,34 +1192,9 @@ impl TaskServer {
             );
         }
 
-        let executor_trimmed = executor.trim();
-        if executor_trimmed.is_empty() {
-            return Self::err("Executor must not be empty.".to_string(), None::<String>);
-        }
-
-        let normalized_executor = executor_trimmed.replace('-', "_").to_ascii_uppercase();
-        let base_executor = match BaseCodingAgent::from_str(&normalized_executor) {
-            Ok(exec) => exec,
-            Err(_) => {
-                return Self::err(
-                    format!("Unknown executor '{executor_trimmed}'."),
-                    None::<String>,
-                );
-            }
-        };
-
-        let variant = variant.and_then(|v| {
-            let trimmed = v.trim();
-            if trimmed.is_empty() {
-                None
-            } else {
-                Some(trimmed.to_string())
-            }
-        });
-
-        let executor_profile_id = ExecutorProfileId {
-            executor: base_executor,
-            variant,
+        let executor_profile_id = match Self::parse_executor_profile(&executor, variant) {
+            Ok(profile) => profile,
+            Err(e) => return Ok(e),
         };
 
         let workspace_repos: Vec<WorkspaceRepoInput> = repos
@@ -929,7 +1227,7 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Update an existing task/ticket's title or description. `task_id` is required. `title` and `description` are optional."
+        description = "Update an existing task/ticket's title, description or status. `task_id` is required. `title`, `description` and `status` are optional."
     )]
     async fn update_task(
         &self,
@@ -937,8 +1235,22 @@ impl TaskServer {
             task_id,
             title,
             description,
+            status,
         }): Parameters<UpdateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        let status = match status {
+            Some(ref status_str) => match TaskStatus::from_str(status_str) {
+                Ok(s) => Some(s),
+                Err(_) => {
+                    return Self::err(
+                        "Invalid status. Valid values: 'todo', 'inprogress', 'inreview', 'ci', 'cd', 'done', 'cancelled'".to_string(),
+                        Some(status_str.to_string()),
+                    );
+                }
+            },
+            None => None,
+        };
+
         // Expand @tagname references in description
         let expanded_description = match description {
             Some(desc) => Some(self.expand_tags(&desc).await),
@@ -951,6 +1263,7 @@ impl TaskServer {
             parent_workspace_id: None,
             image_ids: None,
             assignee_user_id: None, // Don't change assignee through MCP
+            status,
         };
         let url = self.url(&format!("/api/tasks/{}", task_id));
         let updated_task: Task = match self.send_json(self.client.put(&url).json(&payload)).await {
@@ -998,12 +1311,339 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+    #[tool(
+        description = "List the sessions of a workspace. Use the returned `session_id` with `follow_up` and `list_execution_processes`. `workspace_id` is required."
+    )]
+    async fn list_sessions(
+        &self,
+        Parameters(ListSessionsRequest { workspace_id }): Parameters<ListSessionsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/sessions?workspace_id={}", workspace_id));
+        let sessions: Vec<Session> = match self.send_json(self.client.get(&url)).await {
+            Ok(s) => s,
+            Err(e) => return Ok(e),
+        };
+
+        let summaries: Vec<SessionSummary> = sessions
+            .into_iter()
+            .map(|s| SessionSummary {
+                id: s.id.to_string(),
+                executor: s.executor,
+                created_at: s.created_at.to_rfc3339(),
+                updated_at: s.updated_at.to_rfc3339(),
+            })
+            .collect();
+
+        TaskServer::success(&ListSessionsResponse {
+            count: summaries.len(),
+            sessions: summaries,
+            workspace_id: workspace_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Send a follow-up prompt to the coding agent of an existing session. `session_id` and `prompt` are required. The executor defaults to the one the session was started with."
+    )]
+    async fn follow_up(
+        &self,
+        Parameters(FollowUpRequest {
+            session_id,
+            prompt,
+            executor,
+            variant,
+        }): Parameters<FollowUpRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if prompt.trim().is_empty() {
+            return Self::err("Prompt must not be empty.".to_string(), None::<String>);
+        }
+
+        let executor = match executor {
+            Some(executor) => executor,
+            None => {
+                let url = self.url(&format!("/api/sessions/{}", session_id));
+                let session: Session = match self.send_json(self.client.get(&url)).await {
+                    Ok(s) => s,
+                    Err(e) => return Ok(e),
+                };
+                match session.executor {
+                    Some(executor) => executor,
+                    None => {
+                        return Self::err(
+                            "Session has no executor; pass `executor` explicitly.".to_string(),
+                            None::<String>,
+                        );
+                    }
+                }
+            }
+        };
+        let executor_profile_id = match Self::parse_executor_profile(&executor, variant) {
+            Ok(profile) => profile,
+            Err(e) => return Ok(e),
+        };
+
+        let payload = CreateFollowUpAttempt {
+            prompt: self.expand_tags(&prompt).await,
+            executor_profile_id,
+            retry_process_id: None,
+            force_when_dirty: None,
+            perform_git_reset: None,
+        };
+        let url = self.url(&format!("/api/sessions/{}/follow-up", session_id));
+        let process: ExecutionProcess =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(p) => p,
+                Err(e) => return Ok(e),
+            };
+
+        TaskServer::success(&FollowUpResponse {
+            session_id: session_id.to_string(),
+            execution_process_id: process.id.to_string(),
+            status: serde_label(&process.status),
+        })
+    }
+
+    #[tool(
+        description = "Get the current diff of a workspace against the target branch of each of its repositories. `workspace_id` is required."
+    )]
+    async fn get_workspace_diff(
+        &self,
+        Parameters(GetWorkspaceDiffRequest {
+            workspace_id,
+            include_content,
+        }): Parameters<GetWorkspaceDiffRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let include_content = include_content.unwrap_or(true);
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/diff?stats_only={}",
+            workspace_id, !include_content
+        ));
+        let diffs: Vec<Diff> = match self.send_json(self.client.get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        let files: Vec<McpFileDiff> = diffs
+            .into_iter()
+            .map(|diff| {
+                let path = diff
+                    .new_path
+                    .clone()
+                    .or_else(|| diff.old_path.clone())
+                    .unwrap_or_default();
+                let unified_diff = if include_content && !diff.content_omitted {
+                    Some(create_unified_diff(
+                        &path,
+                        diff.old_content.as_deref().unwrap_or(""),
+                        diff.new_content.as_deref().unwrap_or(""),
+                    ))
+                } else {
+                    None
+                };
+                McpFileDiff {
+                    change: serde_label(&diff.change),
+                    additions: diff.additions,
+                    deletions: diff.deletions,
+                    repo_id: diff.repo_id.map(|id| id.to_string()),
+                    unified_diff,
+                    path,
+                }
+            })
+            .collect();
+
+        TaskServer::success(&GetWorkspaceDiffResponse {
+            workspace_id: workspace_id.to_string(),
+            count: files.len(),
+            files,
+        })
+    }
+
+    #[tool(
+        description = "List the execution processes (agent runs and scripts) of a session, oldest first. `session_id` is required."
+    )]
+    async fn list_execution_processes(
+        &self,
+        Parameters(ListExecutionProcessesRequest { session_id }): Parameters<
+            ListExecutionProcessesRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/execution-processes?session_id={}",
+            session_id
+        ));
+        let processes: Vec<ExecutionProcess> = match self.send_json(self.client.get(&url)).await {
+            Ok(p) => p,
+            Err(e) => return Ok(e),
+        };
+
+        let summaries: Vec<ExecutionProcessSummary> = processes
+            .into_iter()
+            .map(ExecutionProcessSummary::from_process)
+            .collect();
+
+        TaskServer::success(&ListExecutionProcessesResponse {
+            count: summaries.len(),
+            execution_processes: summaries,
+            session_id: session_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Get the normalized logs (messages, tool calls, errors) of an execution process. Works while the process is still running. `execution_process_id` is required."
+    )]
+    async fn get_execution_logs(
+        &self,
+        Parameters(GetExecutionLogsRequest {
+            execution_process_id,
+            limit,
+        }): Parameters<GetExecutionLogsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/execution-processes/{}/normalized-logs",
+            execution_process_id
+        ));
+        let mut entries: Vec<serde_json::Value> = match self.send_json(self.client.get(&url)).await
+        {
+            Ok(e) => e,
+            Err(e) => return Ok(e),
+        };
+
+        let total_entries = entries.len();
+        let limit = limit.unwrap_or(50);
+        let entries = entries.split_off(total_entries.saturating_sub(limit));
+
+        TaskServer::success(&GetExecutionLogsResponse {
+            execution_process_id: execution_process_id.to_string(),
+            total_entries,
+            entries,
+        })
+    }
+
+    #[tool(description = "Stop a running execution process. `execution_process_id` is required.")]
+    async fn stop_execution(
+        &self,
+        Parameters(StopExecutionRequest {
+            execution_process_id,
+        }): Parameters<StopExecutionRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/execution-processes/{}/stop",
+            execution_process_id
+        ));
+        if let Err(e) = self.send_empty_json(self.client.post(&url)).await {
+            return Ok(e);
+        }
+
+        TaskServer::success(&StopExecutionResponse {
+            stopped_execution_process_id: execution_process_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "List the review conversations (line comments on the diff) of a workspace with their messages. `workspace_id` is required."
+    )]
+    async fn list_review_conversations(
+        &self,
+        Parameters(ListReviewConversationsRequest {
+            workspace_id,
+            unresolved_only,
+        }): Parameters<ListReviewConversationsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let path = if unresolved_only.unwrap_or(false) {
+            format!(
+                "/api/task-attempts/{}/conversations/unresolved",
+                workspace_id
+            )
+        } else {
+            format!("/api/task-attempts/{}/conversations", workspace_id)
+        };
+        let url = self.url(&path);
+        let conversations: Vec<ConversationWithMessages> =
+            match self.send_json(self.client.get(&url)).await {
+                Ok(c) => c,
+                Err(e) => return Ok(e),
+            };
+
+        let summaries: Vec<ReviewConversationSummary> = conversations
+            .into_iter()
+            .map(ReviewConversationSummary::from_conversation)
+            .collect();
+
+        TaskServer::success(&ListReviewConversationsResponse {
+            count: summaries.len(),
+            conversations: summaries,
+            workspace_id: workspace_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Reply to a review conversation. `workspace_id`, `conversation_id` and `content` are required."
+    )]
+    async fn reply_to_review_conversation(
+        &self,
+        Parameters(ReplyToReviewConversationRequest {
+            workspace_id,
+            conversation_id,
+            content,
+        }): Parameters<ReplyToReviewConversationRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if content.trim().is_empty() {
+            return Self::err("Reply must not be empty.".to_string(), None::<String>);
+        }
+
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/conversations/{}/messages",
+            workspace_id, conversation_id
+        ));
+        let response: AddMessageResponse = match self
+            .send_json(self.client.post(&url).json(&CreateMessage { content }))
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&ReviewConversationResponse {
+            conversation: ReviewConversationSummary::from_conversation(response.conversation),
+        })
+    }
+
+    #[tool(
+        description = "Resolve a review conversation once its feedback has been addressed. `workspace_id`, `conversation_id` and `summary` are required."
+    )]
+    async fn resolve_review_conversation(
+        &self,
+        Parameters(ResolveReviewConversationRequest {
+            workspace_id,
+            conversation_id,
+            summary,
+        }): Parameters<ResolveReviewConversationRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/conversations/{}/resolve",
+            workspace_id, conversation_id
+        ));
+        let response: ResolveConversationResponse = match self
+            .send_json(
+                self.client
+                    .post(&url)
+                    .json(&ResolveConversation { summary }),
+            )
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&ReviewConversationResponse {
+            conversation: ReviewConversationSummary::from_conversation(response.conversation),
+        })
+    }
 }
 
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'get_repo', 'update_setup_script', 'update_cleanup_script', 'update_dev_server_script'. Make sure to pass `project_id`, `task_id`, or `repo_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'get_repo', 'update_setup_script', 'update_cleanup_script', 'update_dev_server_script', 'list_sessions', 'follow_up', 'get_workspace_diff', 'list_execution_processes', 'get_execution_logs', 'stop_execution', 'list_review_conversations', 'reply_to_review_conversation', 'resolve_review_conversation'. Make sure to pass `project_id`, `task_id`, `repo_id`, `workspace_id`, `session_id` or `execution_process_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
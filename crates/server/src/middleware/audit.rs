@@ -0,0 +1,46 @@
+use axum::http::HeaderMap;
+use db::models::audit_event::{
+    AuditAction, AuditEvent, AuditSource, AuditTarget, CreateAuditEvent,
+};
+use deployment::Deployment;
+use serde_json::Value;
+
+use super::auth::get_user_id_from_headers;
+use crate::DeploymentImpl;
+
+/// Header the MCP task server sends so its calls are audited as agent actions
+pub const AUDIT_SOURCE_HEADER: &str = "x-vk-source";
+
+/// The source the caller claims through [`AUDIT_SOURCE_HEADER`]. Any client can send the
+/// header, so the result is recorded as self-reported and must not be used for access
+/// decisions.
+fn audit_source(headers: &HeaderMap) -> AuditSource {
+    match headers
+        .get(AUDIT_SOURCE_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(source) if source.eq_ignore_ascii_case("mcp") => AuditSource::Mcp,
+        _ => AuditSource::Api,
+    }
+}
+
+/// Append an audit event for the caller identified by `headers`. The action has already
+/// happened by the time this runs, so a failed write is logged rather than returned.
+pub async fn record_audit_event(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    action: AuditAction,
+    target: AuditTarget,
+    details: Value,
+) {
+    let event = CreateAuditEvent {
+        actor_user_id: get_user_id_from_headers(deployment, headers).await,
+        source: audit_source(headers),
+        action,
+        target,
+        details,
+    };
+    if let Err(e) = AuditEvent::create(&deployment.db().pool, event).await {
+        tracing::error!("Failed to record audit event {:?}: {}", action, e);
+    }
+}
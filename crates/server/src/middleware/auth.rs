@@ -1,7 +1,7 @@
 use axum::http::HeaderMap;
 use db::models::user::User;
 use deployment::Deployment;
-use services::services::local_session::LocalSessionService;
+use services::services::{local_session::LocalSessionService, workspace_start};
 use uuid::Uuid;
 
 fn extract_bearer_token(headers: &HeaderMap) -> Option<String> {
@@ -54,12 +54,8 @@ pub async fn user_has_valid_claude_token<D: Deployment>(
     };
 
     // Check if user has a valid token
-    match deployment
-        .claude_token_rotation()
-        .get_user_token_status(user.id)
-        .await
-    {
-        Ok(status) => status.has_token && !status.is_expired,
+    match workspace_start::user_has_valid_claude_token(&deployment.db().pool, user.id).await {
+        Ok(valid) => valid,
         Err(e) => {
             tracing::warn!(
                 "Failed to check Claude token status for user {}: {}",
@@ -0,0 +1,299 @@
+//! Project role checks for the multi-user local server.
+//!
+//! Without local auth configured the server is single-user and every check passes.
+//! Otherwise a request needs a valid session token; server admins pass every check and
+//! everyone else needs a high enough role in the project the route touches. MCP tool
+//! calls go through the same HTTP routes with the initiating user's token, so they are
+//! checked the same way.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+use db::models::{
+    execution_process::ExecutionProcess,
+    project::Project,
+    project_member::{ProjectMember, ProjectRole},
+    project_repo::ProjectRepo,
+    session::Session,
+    task::Task,
+    user::User,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use services::services::local_session::LocalSessionService;
+use uuid::Uuid;
+
+use super::auth::try_get_authenticated_user;
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Who is making a request
+pub enum Caller {
+    /// Local auth is not configured, so there is only one user
+    SingleUser,
+    User {
+        user: User,
+        is_admin: bool,
+    },
+}
+
+impl Caller {
+    pub fn user(&self) -> Option<&User> {
+        match self {
+            Caller::SingleUser => None,
+            Caller::User { user, .. } => Some(user),
+        }
+    }
+
+    pub fn is_admin(&self) -> bool {
+        match self {
+            Caller::SingleUser => true,
+            Caller::User { is_admin, .. } => *is_admin,
+        }
+    }
+}
+
+pub async fn get_caller(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+) -> Result<Caller, ApiError> {
+    if LocalSessionService::from_env().is_none() {
+        return Ok(Caller::SingleUser);
+    }
+
+    let user = try_get_authenticated_user(deployment, headers)
+        .await
+        .ok_or(ApiError::Unauthorized)?;
+    let is_admin = User::is_admin(&deployment.db().pool, user.id).await?;
+    Ok(Caller::User { user, is_admin })
+}
+
+/// Require the caller to be a server admin
+pub async fn require_admin(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+) -> Result<Caller, ApiError> {
+    let caller = get_caller(deployment, headers).await?;
+    if !caller.is_admin() {
+        return Err(ApiError::Forbidden(
+            "Only server admins can do this".to_string(),
+        ));
+    }
+    Ok(caller)
+}
+
+/// Require the caller to have at least `min_role` in `project_id`
+pub async fn require_project_role(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    project_id: Uuid,
+    min_role: ProjectRole,
+) -> Result<Caller, ApiError> {
+    let caller = get_caller(deployment, headers).await?;
+    check_project_role(deployment, &caller, project_id, min_role).await?;
+    Ok(caller)
+}
+
+/// Require the caller to have at least `min_role` in the project that owns `task_id`
+pub async fn require_task_role(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    task_id: Uuid,
+    min_role: ProjectRole,
+) -> Result<Caller, ApiError> {
+    let task = Task::find_by_id(&deployment.db().pool, task_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Task not found".to_string()))?;
+    require_project_role(deployment, headers, task.project_id, min_role).await
+}
+
+/// Require the caller to have at least `min_role` in the project that owns `workspace_id`
+pub async fn require_workspace_role(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    workspace_id: Uuid,
+    min_role: ProjectRole,
+) -> Result<Caller, ApiError> {
+    let workspace = Workspace::find_by_id(&deployment.db().pool, workspace_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Workspace not found".to_string()))?;
+    require_task_role(deployment, headers, workspace.task_id, min_role).await
+}
+
+/// Require the caller to have at least `min_role` in every project that uses
+/// `repo_id`, since repository settings such as scripts are shared between them. A
+/// repository no project uses yet can only be changed by an admin.
+pub async fn require_repo_role(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    repo_id: Uuid,
+    min_role: ProjectRole,
+) -> Result<Caller, ApiError> {
+    let project_repos = ProjectRepo::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    if project_repos.is_empty() {
+        return require_admin(deployment, headers).await;
+    }
+
+    let caller = get_caller(deployment, headers).await?;
+    for project_repo in project_repos {
+        check_project_role(deployment, &caller, project_repo.project_id, min_role).await?;
+    }
+    Ok(caller)
+}
+
+async fn check_project_role(
+    deployment: &DeploymentImpl,
+    caller: &Caller,
+    project_id: Uuid,
+    min_role: ProjectRole,
+) -> Result<(), ApiError> {
+    let user = match caller {
+        Caller::SingleUser | Caller::User { is_admin: true, .. } => return Ok(()),
+        Caller::User { user, .. } => user,
+    };
+
+    let role = ProjectMember::find_role(&deployment.db().pool, project_id, user.id).await?;
+    match role {
+        Some(role) if role >= min_role => Ok(()),
+        Some(role) => Err(ApiError::Forbidden(format!(
+            "This requires the {} role in the project, you are a {}",
+            role_name(min_role),
+            role_name(role)
+        ))),
+        None => Err(ApiError::Forbidden(
+            "You are not a member of this project".to_string(),
+        )),
+    }
+}
+
+fn role_name(role: ProjectRole) -> &'static str {
+    match role {
+        ProjectRole::Viewer => "viewer",
+        ProjectRole::Contributor => "contributor",
+        ProjectRole::Maintainer => "maintainer",
+        ProjectRole::Owner => "owner",
+    }
+}
+
+/// What a request targets, taken from the models the loader middleware inserted
+enum RequestTarget {
+    Project(Uuid),
+    Workspace(Uuid),
+    Session(Uuid),
+}
+
+impl RequestTarget {
+    fn from_request(request: &Request) -> Option<Self> {
+        let extensions = request.extensions();
+        if let Some(project) = extensions.get::<Project>() {
+            Some(Self::Project(project.id))
+        } else if let Some(task) = extensions.get::<Task>() {
+            Some(Self::Project(task.project_id))
+        } else if let Some(workspace) = extensions.get::<Workspace>() {
+            Some(Self::Workspace(workspace.id))
+        } else if let Some(session) = extensions.get::<Session>() {
+            Some(Self::Workspace(session.workspace_id))
+        } else {
+            extensions
+                .get::<ExecutionProcess>()
+                .map(|process| Self::Session(process.session_id))
+        }
+    }
+
+    async fn project_id(self, deployment: &DeploymentImpl) -> Result<Option<Uuid>, ApiError> {
+        let pool = &deployment.db().pool;
+        let workspace_id = match self {
+            Self::Project(project_id) => return Ok(Some(project_id)),
+            Self::Workspace(workspace_id) => workspace_id,
+            Self::Session(session_id) => match Session::find_by_id(pool, session_id).await? {
+                Some(session) => session.workspace_id,
+                None => return Ok(None),
+            },
+        };
+        let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? else {
+            return Ok(None);
+        };
+        Ok(workspace
+            .parent_task(pool)
+            .await?
+            .map(|task| task.project_id))
+    }
+}
+
+/// Check `request` against its project. Everything needed is copied out of the request
+/// up front, since the body is not `Sync` and cannot be borrowed across an `.await`.
+fn authorize_request(
+    deployment: &DeploymentImpl,
+    request: &Request,
+    min_role: ProjectRole,
+) -> impl Future<Output = Result<(), ApiError>> + Send + use<> {
+    let deployment = deployment.clone();
+    let headers = request.headers().clone();
+    let target = RequestTarget::from_request(request);
+    let uri = request.uri().clone();
+
+    async move {
+        let caller = get_caller(&deployment, &headers).await?;
+        let project_id = match target {
+            Some(target) => target.project_id(&deployment).await?,
+            None => None,
+        };
+        let Some(project_id) = project_id else {
+            tracing::error!("No project to authorize {} against", uri);
+            return Err(ApiError::Forbidden(
+                "Could not determine the project for this request".to_string(),
+            ));
+        };
+        check_project_role(&deployment, &caller, project_id, min_role).await
+    }
+}
+
+fn is_read(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Writes need the contributor role in the request's project; reads are not restricted.
+/// Layer this inside a model loader so the project can be resolved.
+pub async fn authorize_project_writes(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if !is_read(request.method()) {
+        authorize_request(&deployment, &request, ProjectRole::Contributor).await?;
+    }
+    Ok(next.run(request).await)
+}
+
+/// Route layer for project settings, repositories and scripts
+pub async fn require_project_maintainer(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    authorize_request(&deployment, &request, ProjectRole::Maintainer).await?;
+    Ok(next.run(request).await)
+}
+
+/// Route layer for destructive project operations
+pub async fn require_project_owner(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    authorize_request(&deployment, &request, ProjectRole::Owner).await?;
+    Ok(next.run(request).await)
+}
+
+/// Route layer for server-wide settings and membership management
+pub async fn require_server_admin(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let headers = request.headers().clone();
+    require_admin(&deployment, &headers).await?;
+    Ok(next.run(request).await)
+}
@@ -1,7 +1,11 @@
+pub mod audit;
 pub mod auth;
+pub mod authorization;
 pub mod model_loaders;
 pub mod origin;
 
+pub use audit::*;
 pub use auth::*;
+pub use authorization::*;
 pub use model_loaders::*;
 pub use origin::*;
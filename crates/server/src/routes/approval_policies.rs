@@ -0,0 +1,157 @@
+//! API routes for the policies that answer agent tool approvals automatically.
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::{
+    approval_policy::{ApprovalPolicy, UpsertApprovalPolicy},
+    audit_event::{AuditAction, AuditEvent, AuditTarget},
+    project_member::ProjectRole,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::approval_policy;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{record_audit_event, require_admin, require_project_role},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalPolicyQuery {
+    pub project_id: Option<Uuid>,
+}
+
+/// Project maintainers manage their project's policies; policies that apply across
+/// projects are for server admins
+async fn authorize_policy_scope(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    project_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    match project_id {
+        Some(project_id) => {
+            require_project_role(deployment, headers, project_id, ProjectRole::Maintainer).await?;
+        }
+        None => {
+            require_admin(deployment, headers).await?;
+        }
+    }
+    Ok(())
+}
+
+fn audit_target(policy: &ApprovalPolicy) -> AuditTarget {
+    let target = match policy.project_id {
+        Some(project_id) => AuditTarget::project(project_id),
+        None => AuditTarget::default(),
+    };
+    target.with_target(policy.id)
+}
+
+/// Stored policies; with `project_id`, only that project's
+pub async fn list_approval_policies(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Query(query): Query<ApprovalPolicyQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalPolicy>>>, ApiError> {
+    authorize_policy_scope(&deployment, &headers, query.project_id).await?;
+
+    let mut policies = ApprovalPolicy::find_all(&deployment.db().pool).await?;
+    if let Some(project_id) = query.project_id {
+        policies.retain(|policy| policy.project_id == Some(project_id));
+    }
+    Ok(ResponseJson(ApiResponse::success(policies)))
+}
+
+/// Store the policy for a project/executor scope, replacing any existing one
+pub async fn upsert_approval_policy(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Json(payload): Json<UpsertApprovalPolicy>,
+) -> Result<ResponseJson<ApiResponse<ApprovalPolicy>>, ApiError> {
+    authorize_policy_scope(&deployment, &headers, payload.project_id).await?;
+    approval_policy::validate(&payload.definition)?;
+
+    let pool = &deployment.db().pool;
+    let before = ApprovalPolicy::find_all(pool).await?.into_iter().find(|p| {
+        p.project_id == payload.project_id
+            && p.executor == payload.executor
+            && p.variant == payload.variant
+    });
+    let policy = ApprovalPolicy::upsert(pool, &payload).await?;
+
+    let mut details = AuditEvent::diff(
+        &before.map(|p| p.definition).unwrap_or_default(),
+        &policy.definition,
+    );
+    details["executor"] = serde_json::json!(policy.executor);
+    details["variant"] = serde_json::json!(policy.variant);
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ApprovalPolicyUpdated,
+        audit_target(&policy),
+        details,
+    )
+    .await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_policy_updated",
+            serde_json::json!({
+                "project_scoped": policy.project_id.is_some(),
+                "executor_scoped": policy.executor.is_some(),
+                "rule_count": policy.definition.rules.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+pub async fn delete_approval_policy(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path(policy_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let policy = ApprovalPolicy::find_by_id(pool, policy_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Approval policy not found".to_string()))?;
+    authorize_policy_scope(&deployment, &headers, policy.project_id).await?;
+
+    ApprovalPolicy::delete(pool, policy.id).await?;
+
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ApprovalPolicyDeleted,
+        audit_target(&policy),
+        serde_json::json!({
+            "executor": policy.executor,
+            "variant": policy.variant,
+            "definition": policy.definition,
+        }),
+    )
+    .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/approval-policies",
+            get(list_approval_policies).put(upsert_approval_policy),
+        )
+        .route(
+            "/approval-policies/{policy_id}",
+            delete(delete_approval_policy),
+        )
+}
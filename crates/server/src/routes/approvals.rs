@@ -1,26 +1,59 @@
 use axum::{
     Router,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::HeaderMap,
     response::Json as ResponseJson,
-    routing::post,
+    routing::{get, post},
+};
+use db::models::{
+    execution_process::ExecutionProcess, project_member::ProjectRole, tool_approval::ToolApproval,
 };
 use deployment::Deployment;
+use serde::Deserialize;
 use utils::{
     approvals::{ApprovalResponse, ApprovalStatus},
     response::ApiResponse,
 };
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{get_caller, get_user_id_from_headers, require_project_role},
+};
 
-use crate::DeploymentImpl;
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalHistoryQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(id): Path<String>,
     ResponseJson(request): ResponseJson<ApprovalResponse>,
-) -> Result<ResponseJson<ApiResponse<ApprovalStatus>>, StatusCode> {
+) -> Result<ResponseJson<ApiResponse<ApprovalStatus>>, ApiError> {
     let service = deployment.approvals();
 
-    match service.respond(&deployment.db().pool, &id, request).await {
+    // Answering a tool approval steers the agent, so it needs the same role as a follow-up
+    if let Some(execution_process_id) = service.pending_execution_process_id(&id) {
+        let context =
+            ExecutionProcess::load_context(&deployment.db().pool, execution_process_id).await?;
+        require_project_role(
+            &deployment,
+            &headers,
+            context.project.id,
+            ProjectRole::Contributor,
+        )
+        .await?;
+    }
+
+    let responded_by = get_user_id_from_headers(&deployment, &headers).await;
+    match service.respond(&id, request, responded_by).await {
         Ok((status, context)) => {
             deployment
                 .track_if_analytics_allowed(
@@ -38,11 +71,43 @@ pub async fn respond_to_approval(
         }
         Err(e) => {
             tracing::error!("Failed to respond to approval: {:?}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(ApiError::BadRequest(format!(
+                "Failed to respond to approval: {e}"
+            )))
         }
     }
 }
 
+/// Tool approvals a user answered, newest first. Users can read their own history;
+/// server admins can read anyone's.
+pub async fn get_user_approvals(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<ApprovalHistoryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ToolApproval>>>, ApiError> {
+    let caller = get_caller(&deployment, &headers).await?;
+    if !caller.is_admin() && caller.user().map(|user| user.id) != Some(user_id) {
+        return Err(ApiError::Forbidden(
+            "Only server admins can read other users' approvals".to_string(),
+        ));
+    }
+
+    let approvals = ToolApproval::find_by_responder(
+        &deployment.db().pool,
+        user_id,
+        query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE),
+        query.offset.unwrap_or(0).max(0),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
+    Router::new()
+        .route("/approvals/{id}/respond", post(respond_to_approval))
+        .route("/users/{user_id}/approvals", get(get_user_approvals))
 }
@@ -0,0 +1,124 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    http::{HeaderMap, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    audit_event::{AuditAction, AuditEvent, AuditEventPage, AuditFilter, AuditSource},
+    project_member::ProjectRole,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{require_admin, require_project_role},
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub actor_user_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub source: Option<AuditSource>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl AuditQuery {
+    fn filter(&self) -> AuditFilter {
+        AuditFilter {
+            actor_user_id: self.actor_user_id,
+            project_id: self.project_id,
+            task_id: self.task_id,
+            workspace_id: self.workspace_id,
+            action: self.action,
+            source: self.source,
+            since: self.since,
+            until: self.until,
+        }
+    }
+}
+
+/// Server admins can read the whole log; project maintainers can read their project's
+async fn authorize_audit_read(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    query: &AuditQuery,
+) -> Result<(), ApiError> {
+    match query.project_id {
+        Some(project_id) => {
+            require_project_role(deployment, headers, project_id, ProjectRole::Maintainer).await?;
+        }
+        None => {
+            require_admin(deployment, headers).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Audit events matching the query, newest first
+pub async fn list_audit_events(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Query(query): Query<AuditQuery>,
+) -> Result<ResponseJson<ApiResponse<AuditEventPage>>, ApiError> {
+    authorize_audit_read(&deployment, &headers, &query).await?;
+
+    let page = AuditEvent::list(
+        &deployment.db().pool,
+        &query.filter(),
+        query.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        query.offset.unwrap_or(0),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(page)))
+}
+
+/// Every audit event matching the query as JSON Lines, oldest first. Paging parameters
+/// are ignored.
+pub async fn export_audit_events(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Query(query): Query<AuditQuery>,
+) -> Result<Response, ApiError> {
+    authorize_audit_read(&deployment, &headers, &query).await?;
+
+    let events = AuditEvent::find_all(&deployment.db().pool, &query.filter()).await?;
+    let mut body = String::new();
+    for event in &events {
+        body.push_str(&serde_json::to_string(event).map_err(std::io::Error::from)?);
+        body.push('\n');
+    }
+
+    let file_name = format!("audit-{}.jsonl", Utc::now().format("%Y%m%dT%H%M%SZ"));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/audit", get(list_audit_events))
+        .route("/audit/export", get(export_audit_events))
+}
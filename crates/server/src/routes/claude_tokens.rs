@@ -7,13 +7,21 @@ use axum::{
     response::Json as ResponseJson,
     routing::{delete, get, post},
 };
-use db::models::claude_oauth_token::{ClaudeOAuthTokenStatus, UserTokenStatus};
+use db::models::{
+    audit_event::{AuditAction, AuditTarget},
+    claude_oauth_token::{ClaudeOAuthTokenStatus, UserTokenStatus},
+};
 use deployment::Deployment;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::try_get_authenticated_user};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{record_audit_event, require_admin, try_get_authenticated_user},
+};
 
 /// Request body for adding/updating a Claude OAuth token
 #[derive(Debug, Deserialize, TS)]
@@ -23,6 +31,16 @@ pub struct UpsertClaudeTokenRequest {
     pub token: String,
 }
 
+/// Response for re-encrypting stored tokens
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct ReencryptClaudeTokensResponse {
+    /// Number of stored tokens that were rewritten with the current key
+    pub reencrypted: usize,
+    /// Tokens that could not be decrypted and were left as they are
+    pub failed_token_ids: Vec<Uuid>,
+}
+
 /// Get current user's token status
 pub async fn get_my_token_status(
     State(deployment): State<DeploymentImpl>,
@@ -69,6 +87,15 @@ pub async fn upsert_token(
         .await
         .map_err(|e| ApiError::BadRequest(format!("Failed to save token: {e}")))?;
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ClaudeTokenUpserted,
+        AuditTarget::default().with_target(user.id),
+        serde_json::json!({ "expires_at": status.expires_at }),
+    )
+    .await;
+
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
@@ -87,18 +114,24 @@ pub async fn delete_my_token(
         .await
         .map_err(|e| ApiError::BadRequest(format!("Failed to delete token: {e}")))?;
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ClaudeTokenDeleted,
+        AuditTarget::default().with_target(user.id),
+        serde_json::json!({}),
+    )
+    .await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
-/// Get all users' token statuses (for admin view / dashboard)
+/// Get all users' token statuses (admin only)
 pub async fn get_all_token_statuses(
     State(deployment): State<DeploymentImpl>,
     headers: HeaderMap,
 ) -> Result<ResponseJson<ApiResponse<Vec<UserTokenStatus>>>, ApiError> {
-    // Require authentication
-    let _user = try_get_authenticated_user(&deployment, &headers)
-        .await
-        .ok_or(ApiError::Unauthorized)?;
+    require_admin(&deployment, &headers).await?;
 
     let statuses = deployment
         .claude_token_rotation()
@@ -109,10 +142,44 @@ pub async fn get_all_token_statuses(
     Ok(ResponseJson(ApiResponse::success(statuses)))
 }
 
+/// Generate a new encryption key and re-encrypt every stored token with it (admin only)
+pub async fn rotate_encryption_key(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<ApiResponse<ReencryptClaudeTokensResponse>>, ApiError> {
+    require_admin(&deployment, &headers).await?;
+
+    let report = deployment
+        .claude_token_rotation()
+        .rotate_encryption_key()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to rotate encryption key: {e}")))?;
+
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ClaudeTokenKeyRotated,
+        AuditTarget::default(),
+        serde_json::json!({
+            "reencrypted": report.reencrypted,
+            "failed_token_ids": report.failed,
+        }),
+    )
+    .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        ReencryptClaudeTokensResponse {
+            reencrypted: report.reencrypted,
+            failed_token_ids: report.failed,
+        },
+    )))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/claude-tokens/me", get(get_my_token_status))
         .route("/claude-tokens", post(upsert_token))
         .route("/claude-tokens/me", delete(delete_my_token))
         .route("/claude-tokens/all", get(get_all_token_statuses))
+        .route("/claude-tokens/rotate-key", post(rotate_encryption_key))
 }
@@ -4,7 +4,7 @@ use axum::{
     Json, Router,
     body::Body,
     extract::{Path, Query, State},
-    http,
+    http::{self, HeaderMap},
     response::{Json as ResponseJson, Response},
     routing::{get, put},
 };
@@ -18,12 +18,17 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{Config, ConfigError, SoundFile, save_config_to_file};
+use services::services::{
+    config::{
+        Config, ConfigError, SoundFile, keep_redacted_secrets, redact_secrets, save_config_to_file,
+    },
+    container::ContainerService,
+};
 use tokio::fs;
 use ts_rs::TS;
 use utils::{api::oauth::LoginStatus, assets::config_path, response::ApiResponse};
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::require_admin};
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
@@ -82,7 +87,7 @@ async fn get_user_system_info(
     let login_status = deployment.get_login_status().await;
 
     let user_system_info = UserSystemInfo {
-        config: config.clone(),
+        config: redact_secrets(&config),
         analytics_user_id: deployment.user_id().to_string(),
         login_status,
         profiles: ExecutorConfigs::get_cached(),
@@ -104,21 +109,26 @@ async fn get_user_system_info(
 
 async fn update_config(
     State(deployment): State<DeploymentImpl>,
-    Json(new_config): Json<Config>,
-) -> ResponseJson<ApiResponse<Config>> {
+    headers: HeaderMap,
+    Json(mut new_config): Json<Config>,
+) -> Result<ResponseJson<ApiResponse<Config>>, ApiError> {
+    // The config is shared by every user of the server
+    require_admin(&deployment, &headers).await?;
+
     let config_path = config_path();
 
     // Validate git branch prefix
     if !utils::git::is_valid_branch_prefix(&new_config.git_branch_prefix) {
-        return ResponseJson(ApiResponse::error(
+        return Ok(ResponseJson(ApiResponse::error(
             "Invalid git branch prefix. Must be a valid git branch name component without slashes.",
-        ));
+        )));
     }
 
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
+    keep_redacted_secrets(&mut new_config, &old_config);
 
-    match save_config_to_file(&new_config, &config_path).await {
+    Ok(match save_config_to_file(&new_config, &config_path).await {
         Ok(_) => {
             let mut config = deployment.config().write().await;
             *config = new_config.clone();
@@ -127,10 +137,10 @@ async fn update_config(
             // Track config events when fields transition from false → true and run side effects
             handle_config_events(&deployment, &old_config, &new_config).await;
 
-            ResponseJson(ApiResponse::success(new_config))
+            ResponseJson(ApiResponse::success(redact_secrets(&new_config)))
         }
         Err(e) => ResponseJson(ApiResponse::error(&format!("Failed to save config: {}", e))),
-    }
+    })
 }
 
 /// Track config events when fields transition from false → true
@@ -175,6 +185,16 @@ async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &C
             deployment_clone.trigger_auto_project_setup().await;
         });
     }
+
+    if old.execution_limits != new.execution_limits {
+        // Raised limits may let queued executions start right away
+        let deployment_clone = deployment.clone();
+        tokio::spawn(async move {
+            if let Err(e) = deployment_clone.container().start_queued_executions().await {
+                tracing::error!("Failed to start queued executions: {}", e);
+            }
+        });
+    }
 }
 
 async fn get_sound(Path(sound): Path<SoundFile>) -> Result<Response, ApiError> {
@@ -244,10 +264,14 @@ async fn get_mcp_servers(
 }
 
 async fn update_mcp_servers(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Query(query): Query<McpServerQuery>,
     Json(payload): Json<UpdateMcpServersBody>,
 ) -> Result<ResponseJson<ApiResponse<String>>, ApiError> {
+    // Agent MCP servers are shared by every user of the server
+    require_admin(&deployment, &headers).await?;
+
     let profiles = ExecutorConfigs::get_cached();
     let agent = profiles
         .get_coding_agent(&ExecutorProfileId::new(query.executor))
@@ -398,11 +422,15 @@ async fn get_profiles(
 }
 
 async fn update_profiles(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     body: String,
-) -> ResponseJson<ApiResponse<String>> {
+) -> Result<ResponseJson<ApiResponse<String>>, ApiError> {
+    // Executor profiles are shared by every user of the server
+    require_admin(&deployment, &headers).await?;
+
     // Try to parse as ExecutorProfileConfigs format
-    match serde_json::from_str::<ExecutorConfigs>(&body) {
+    Ok(match serde_json::from_str::<ExecutorConfigs>(&body) {
         Ok(executor_profiles) => {
             // Save the profiles to file
             match executor_profiles.save_overrides() {
@@ -427,7 +455,7 @@ async fn update_profiles(
             "Invalid executor profiles format: {}",
             e
         ))),
-    }
+    })
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -10,8 +10,13 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
-    execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
+    execution_process::{
+        ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus,
+        ExecutionProcessStatusReason,
+    },
     execution_process_repo_state::ExecutionProcessRepoState,
+    token_usage::ExecutionTokenUsage,
+    tool_approval::ToolApproval,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
@@ -20,7 +25,11 @@ use services::services::container::ContainerService;
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{authorize_project_writes, load_execution_process_middleware},
+};
 
 #[derive(Debug, Deserialize)]
 pub struct SessionExecutionProcessQuery {
@@ -30,6 +39,19 @@ pub struct SessionExecutionProcessQuery {
     pub show_soft_deleted: Option<bool>,
 }
 
+pub async fn get_execution_processes(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SessionExecutionProcessQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>>>, ApiError> {
+    let processes = ExecutionProcess::find_by_session_id(
+        &deployment.db().pool,
+        query.session_id,
+        query.show_soft_deleted.unwrap_or(false),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(processes)))
+}
+
 pub async fn get_execution_process_by_id(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(_deployment): State<DeploymentImpl>,
@@ -120,6 +142,19 @@ async fn handle_raw_logs_ws(
     Ok(())
 }
 
+/// Snapshot of the normalized conversation entries produced so far
+pub async fn get_normalized_logs(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<serde_json::Value>>>, ApiError> {
+    let entries = deployment
+        .container()
+        .normalized_logs_snapshot(&execution_process.id)
+        .await
+        .unwrap_or_default();
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
 pub async fn stream_normalized_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -243,19 +278,63 @@ pub async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+/// Tokens the execution consumed, per model
+pub async fn get_execution_process_token_usage(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionTokenUsage>>>, ApiError> {
+    let usage = ExecutionTokenUsage::find_by_execution_process_id(
+        &deployment.db().pool,
+        execution_process.id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+/// Why the execution failed when its exit code alone doesn't say, e.g. a sandbox
+/// resource limit
+pub async fn get_execution_process_status_reason(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutionProcessStatusReason>>>, ApiError> {
+    let reason =
+        ExecutionProcess::find_status_reason(&deployment.db().pool, execution_process.id).await?;
+    Ok(ResponseJson(ApiResponse::success(reason)))
+}
+
+/// Tool approvals the execution asked for and how each was answered
+pub async fn get_execution_process_approvals(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ToolApproval>>>, ApiError> {
+    let approvals =
+        ToolApproval::find_by_execution_process_id(&deployment.db().pool, execution_process.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/token-usage", get(get_execution_process_token_usage))
+        .route("/status-reason", get(get_execution_process_status_reason))
+        .route("/approvals", get(get_execution_process_approvals))
+        .route("/normalized-logs", get(get_normalized_logs))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authorize_project_writes,
+        ))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_execution_process_middleware,
         ));
 
     let workspaces_router = Router::new()
+        .route("/", get(get_execution_processes))
         .route(
             "/stream/session/ws",
             get(stream_execution_processes_by_session_ws),
@@ -0,0 +1,69 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    execution_queue::{ExecutionQueue, ExecutionQueueEntry, QueuedExecution, UpdateQueuePriority},
+    project_member::ProjectRole,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::require_project_role};
+
+#[derive(Debug, Deserialize)]
+pub struct ExecutionQueueQuery {
+    pub project_id: Option<Uuid>,
+}
+
+/// Queued executions in the order they will start. Cancel one with the execution
+/// process's stop endpoint.
+pub async fn get_execution_queue(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExecutionQueueQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedExecution>>>, ApiError> {
+    let queue = ExecutionQueue::list(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
+/// Move a queued execution ahead of (or behind) others by changing its priority
+pub async fn update_queue_priority(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path(execution_process_id): Path<Uuid>,
+    Json(payload): Json<UpdateQueuePriority>,
+) -> Result<ResponseJson<ApiResponse<ExecutionQueueEntry>>, ApiError> {
+    let queued = ExecutionQueue::find(&deployment.db().pool, execution_process_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Execution is not queued".to_string()))?;
+    require_project_role(
+        &deployment,
+        &headers,
+        queued.project_id,
+        ProjectRole::Maintainer,
+    )
+    .await?;
+
+    let entry = ExecutionQueue::set_priority(
+        &deployment.db().pool,
+        execution_process_id,
+        payload.priority,
+    )
+    .await?
+    .ok_or_else(|| ApiError::BadRequest("Execution is not queued".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/execution-queue", get(get_execution_queue))
+        .route(
+            "/execution-queue/{execution_process_id}/priority",
+            put(update_queue_priority),
+        )
+}
@@ -4,13 +4,14 @@ use axum::{
     Router,
     body::Body,
     extract::{DefaultBodyLimit, Multipart, Path, Query, State},
-    http::{StatusCode, header},
+    http::{HeaderMap, StatusCode, header},
     response::{Json as ResponseJson, Response},
     routing::{delete, get, post},
 };
 use chrono::{DateTime, Utc};
 use db::models::{
     image::{Image, TaskImage},
+    project_member::ProjectRole,
     task::Task,
 };
 use deployment::Deployment;
@@ -23,7 +24,11 @@ use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{get_caller, require_task_role},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ImageResponse {
@@ -76,8 +81,11 @@ pub struct ImageMetadata {
 
 pub async fn upload_image(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     multipart: Multipart,
 ) -> Result<ResponseJson<ApiResponse<ImageResponse>>, ApiError> {
+    get_caller(&deployment, &headers).await?;
+
     let image_response = process_image_upload(&deployment, multipart, None).await?;
     Ok(ResponseJson(ApiResponse::success(image_response)))
 }
@@ -130,11 +138,13 @@ pub(crate) async fn process_image_upload(
 pub async fn upload_task_image(
     Path(task_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     multipart: Multipart,
 ) -> Result<ResponseJson<ApiResponse<ImageResponse>>, ApiError> {
     Task::find_by_id(&deployment.db().pool, task_id)
         .await?
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    require_task_role(&deployment, &headers, task_id, ProjectRole::Contributor).await?;
 
     let image_response = process_image_upload(&deployment, multipart, Some(task_id)).await?;
     Ok(ResponseJson(ApiResponse::success(image_response)))
@@ -177,7 +187,14 @@ pub async fn serve_image(
 pub async fn delete_image(
     Path(image_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    // An image attached to tasks can only be removed by someone who can edit all of them
+    get_caller(&deployment, &headers).await?;
+    for task_id in TaskImage::find_task_ids_by_image_id(&deployment.db().pool, image_id).await? {
+        require_task_role(&deployment, &headers, task_id, ProjectRole::Contributor).await?;
+    }
+
     let image_service = deployment.image();
     image_service.delete_image(image_id).await?;
     Ok(ResponseJson(ApiResponse::success(())))
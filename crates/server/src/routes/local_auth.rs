@@ -182,6 +182,9 @@ async fn github_auth_callback(
     // Upsert user in database
     let pool = state.deployment.db().pool.clone();
     let user = User::upsert_from_github(&pool, &profile).await?;
+    if User::claim_admin_if_none(&pool, user.id).await? {
+        tracing::info!("User {} is the first server admin", user.username);
+    }
 
     // Create session and get JWT token
     let jwt_token = session_service.create_session(&pool, user.id).await?;
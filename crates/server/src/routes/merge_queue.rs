@@ -0,0 +1,43 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::merge_queue::{MergeQueueEntry, QueuedMerge};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::container::ContainerService;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct MergeQueueQuery {
+    pub project_id: Option<Uuid>,
+}
+
+/// Queued merges, grouped by repo and target branch and in merge order within each.
+/// Enqueue and dequeue workspaces through their task attempt endpoints.
+pub async fn get_merge_queue(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MergeQueueQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedMerge>>>, ApiError> {
+    let queue = MergeQueueEntry::list(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
+/// Advance the merge queues without holding up the caller
+pub fn spawn_process_merge_queues(deployment: &DeploymentImpl) {
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        if let Err(e) = deployment.container().process_merge_queues().await {
+            tracing::error!("Failed to process merge queues: {}", e);
+        }
+    });
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/merge-queue", get(get_merge_queue))
+}
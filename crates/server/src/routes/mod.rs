@@ -6,7 +6,9 @@ use tower_http::validate_request::ValidateRequestHeaderLayer;
 
 use crate::{DeploymentImpl, middleware};
 
+pub mod approval_policies;
 pub mod approvals;
+pub mod audit;
 pub mod claude_tokens;
 pub mod config;
 pub mod containers;
@@ -14,21 +16,27 @@ pub mod filesystem;
 // pub mod github;
 pub mod events;
 pub mod execution_processes;
+pub mod execution_queue;
 pub mod frontend;
 pub mod health;
 pub mod images;
 pub mod local_auth;
+pub mod merge_queue;
 pub mod oauth;
 pub mod organizations;
+pub mod project_members;
 pub mod projects;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod sessions;
 pub mod tags;
 pub mod task_approvals;
 pub mod task_attempts;
+pub mod task_dependencies;
 pub mod tasks;
 pub mod terminal;
+pub mod usage;
 pub mod users;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
@@ -38,9 +46,12 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(config::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
+        .merge(project_members::router())
         .merge(tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
+        .merge(execution_queue::router())
+        .merge(merge_queue::router())
         .merge(tags::router(&deployment))
         .merge(oauth::router())
         .merge(local_auth::router(&deployment))
@@ -49,11 +60,15 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(repo::router())
         .merge(events::router(&deployment))
         .merge(approvals::router())
+        .merge(approval_policies::router())
         .merge(scratch::router(&deployment))
         .merge(sessions::router(&deployment))
         .merge(terminal::router())
         .merge(users::router())
+        .merge(usage::router())
         .merge(claude_tokens::router())
+        .merge(audit::router())
+        .merge(search::router())
         .nest("/images", images::routes())
         .layer(ValidateRequestHeaderLayer::custom(
             middleware::validate_origin,
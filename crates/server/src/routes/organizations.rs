@@ -1,7 +1,7 @@
 use axum::{
     Router,
     extract::{Json, Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::Json as ResponseJson,
     routing::{delete, get, patch, post},
 };
@@ -18,7 +18,7 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::require_admin};
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
@@ -72,8 +72,12 @@ async fn get_organization(
 
 async fn create_organization(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(request): Json<CreateOrganizationRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateOrganizationResponse>>, ApiError> {
+    // Organization changes are made with the server's own remote account
+    require_admin(&deployment, &headers).await?;
+
     let client = deployment.remote_client()?;
 
     let response = client.create_organization(&request).await?;
@@ -92,9 +96,13 @@ async fn create_organization(
 
 async fn update_organization(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(id): Path<Uuid>,
     Json(request): Json<UpdateOrganizationRequest>,
 ) -> Result<ResponseJson<ApiResponse<Organization>>, ApiError> {
+    // Organization changes are made with the server's own remote account
+    require_admin(&deployment, &headers).await?;
+
     let client = deployment.remote_client()?;
 
     let response = client.update_organization(id, &request).await?;
@@ -104,8 +112,12 @@ async fn update_organization(
 
 async fn delete_organization(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, ApiError> {
+    // Organization changes are made with the server's own remote account
+    require_admin(&deployment, &headers).await?;
+
     let client = deployment.remote_client()?;
 
     client.delete_organization(id).await?;
@@ -115,9 +127,13 @@ async fn delete_organization(
 
 async fn create_invitation(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(org_id): Path<Uuid>,
     Json(request): Json<CreateInvitationRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateInvitationResponse>>, ApiError> {
+    // Organization changes are made with the server's own remote account
+    require_admin(&deployment, &headers).await?;
+
     let client = deployment.remote_client()?;
 
     let response = client.create_invitation(org_id, &request).await?;
@@ -160,9 +176,13 @@ async fn get_invitation(
 
 async fn revoke_invitation(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(org_id): Path<Uuid>,
     Json(payload): Json<RevokeInvitationRequest>,
 ) -> Result<StatusCode, ApiError> {
+    // Organization changes are made with the server's own remote account
+    require_admin(&deployment, &headers).await?;
+
     let client = deployment.remote_client()?;
 
     client
@@ -174,8 +194,12 @@ async fn revoke_invitation(
 
 async fn accept_invitation(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(invitation_token): Path<String>,
 ) -> Result<ResponseJson<ApiResponse<AcceptInvitationResponse>>, ApiError> {
+    // Organization changes are made with the server's own remote account
+    require_admin(&deployment, &headers).await?;
+
     let client = deployment.remote_client()?;
 
     let response = client.accept_invitation(&invitation_token).await?;
@@ -196,8 +220,12 @@ async fn list_members(
 
 async fn remove_member(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path((org_id, user_id)): Path<(Uuid, Uuid)>,
 ) -> Result<StatusCode, ApiError> {
+    // Organization changes are made with the server's own remote account
+    require_admin(&deployment, &headers).await?;
+
     let client = deployment.remote_client()?;
 
     client.remove_member(org_id, user_id).await?;
@@ -207,9 +235,13 @@ async fn remove_member(
 
 async fn update_member_role(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path((org_id, user_id)): Path<(Uuid, Uuid)>,
     Json(request): Json<UpdateMemberRoleRequest>,
 ) -> Result<ResponseJson<ApiResponse<UpdateMemberRoleResponse>>, ApiError> {
+    // Organization changes are made with the server's own remote account
+    require_admin(&deployment, &headers).await?;
+
     let client = deployment.remote_client()?;
 
     let response = client.update_member_role(org_id, user_id, &request).await?;
@@ -0,0 +1,118 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    project::Project,
+    project_member::{ProjectMember, ProjectMemberWithUser, ProjectRole, SetProjectMemberRole},
+};
+use deployment::Deployment;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{Caller, get_caller, require_admin},
+};
+
+/// What the caller may do in a project
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectAccess {
+    /// The caller's role, or null if they are not a member
+    pub role: Option<ProjectRole>,
+    /// Server admins pass every role check and manage memberships
+    pub is_admin: bool,
+}
+
+async fn ensure_project_exists(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<(), ApiError> {
+    Project::find_by_id(&deployment.db().pool, project_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Project not found".to_string()))?;
+    Ok(())
+}
+
+pub async fn list_project_members(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectMemberWithUser>>>, ApiError> {
+    ensure_project_exists(&deployment, project_id).await?;
+    let members = ProjectMember::find_by_project(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(members)))
+}
+
+/// The caller's own access to a project, so the UI can hide actions they cannot take
+pub async fn get_project_access(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ProjectAccess>>, ApiError> {
+    ensure_project_exists(&deployment, project_id).await?;
+    let access = match get_caller(&deployment, &headers).await? {
+        Caller::SingleUser => ProjectAccess {
+            role: Some(ProjectRole::Owner),
+            is_admin: true,
+        },
+        Caller::User { user, is_admin } => ProjectAccess {
+            role: ProjectMember::find_role(&deployment.db().pool, project_id, user.id).await?,
+            is_admin,
+        },
+    };
+    Ok(ResponseJson(ApiResponse::success(access)))
+}
+
+/// Add a member or change their role (admin only)
+pub async fn set_project_member_role(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path((project_id, user_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<SetProjectMemberRole>,
+) -> Result<ResponseJson<ApiResponse<ProjectMember>>, ApiError> {
+    require_admin(&deployment, &headers).await?;
+    ensure_project_exists(&deployment, project_id).await?;
+
+    let member =
+        ProjectMember::set_role(&deployment.db().pool, project_id, user_id, payload.role).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_member_role_set",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "role": payload.role,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(member)))
+}
+
+/// Remove a member from a project (admin only)
+pub async fn remove_project_member(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path((project_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    require_admin(&deployment, &headers).await?;
+    ProjectMember::remove(&deployment.db().pool, project_id, user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/projects/{project_id}/members", get(list_project_members))
+        .route("/projects/{project_id}/access", get(get_project_access))
+        .route(
+            "/projects/{project_id}/members/{user_id}",
+            put(set_project_member_role).delete(remove_project_member),
+        )
+}
@@ -2,19 +2,25 @@ use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Path, Query, State,
+        DefaultBodyLimit, Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, StatusCode, header},
     middleware::from_fn_with_state,
-    response::{IntoResponse, Json as ResponseJson},
-    routing::get,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{delete, get, post, put},
 };
+use chrono::Utc;
 use db::models::{
+    audit_event::{AuditAction, AuditEvent, AuditTarget},
     project::{
         CreateProject, Project, ProjectError, ProjectWithCreator, SearchResult, UpdateProject,
     },
-    project_repo::{CreateProjectRepo, ProjectRepo},
+    project_archive::ImportProject,
+    project_budget::{ProjectBudget, ProjectBudgetStatus, UpsertProjectBudget},
+    project_member::ProjectRole,
+    project_repo::{CreateProjectRepo, ProjectRepo, ProjectRepoMergeSettings},
+    project_workflow::{ProjectWorkflow, WorkflowDefinition},
     repo::Repo,
 };
 use deployment::Deployment;
@@ -26,7 +32,10 @@ use uuid::Uuid;
 use crate::{
     DeploymentImpl,
     error::ApiError,
-    middleware::{get_user_id, load_project_middleware, try_get_authenticated_user},
+    middleware::{
+        authorize_project_writes, get_caller, load_project_middleware, record_audit_event,
+        require_project_maintainer, require_project_owner, require_project_role,
+    },
 };
 
 pub async fn get_projects(
@@ -94,8 +103,8 @@ pub async fn create_project(
     tracing::debug!("Creating project '{}'", payload.name);
     let repo_count = payload.repositories.len();
 
-    let authenticated_user = try_get_authenticated_user(&deployment, &headers).await;
-    let creator_user_id = get_user_id(&authenticated_user);
+    let caller = get_caller(&deployment, &headers).await?;
+    let creator_user_id = caller.user().map(|user| user.id);
 
     match deployment
         .project()
@@ -108,6 +117,15 @@ pub async fn create_project(
         .await
     {
         Ok(project) => {
+            record_audit_event(
+                &deployment,
+                &headers,
+                AuditAction::ProjectCreated,
+                AuditTarget::project(project.id),
+                serde_json::json!({ "name": project.name, "repository_count": repo_count }),
+            )
+            .await;
+
             // Track project creation event
             deployment
                 .track_if_analytics_allowed(
@@ -142,9 +160,105 @@ pub async fn create_project(
     }
 }
 
+/// Largest archive `import_project` accepts; archives carry every execution log
+const MAX_IMPORT_BYTES: usize = 512 * 1024 * 1024;
+
+/// The whole project, its history included, as an archive for `import_project`
+pub async fn export_project(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let archive = deployment
+        .project()
+        .export_project(&deployment.db().pool, deployment.image(), project.id)
+        .await?;
+    let body = serde_json::to_vec(&archive).map_err(std::io::Error::from)?;
+
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ProjectExported,
+        AuditTarget::project(project.id),
+        serde_json::json!({
+            "task_count": archive.tasks.len(),
+            "execution_count": archive.execution_processes.len(),
+        }),
+    )
+    .await;
+
+    let file_name = format!(
+        "project-{}-{}.json",
+        project.id,
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+pub async fn import_project(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Json(payload): Json<ImportProject>,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    let source_project_id = payload.archive.project.id;
+    let task_count = payload.archive.tasks.len();
+    tracing::debug!("Importing project '{}'", payload.archive.project.name);
+
+    let caller = get_caller(&deployment, &headers).await?;
+    let creator_user_id = caller.user().map(|user| user.id);
+
+    let project = deployment
+        .project()
+        .import_project(
+            &deployment.db().pool,
+            deployment.repo(),
+            deployment.image(),
+            payload,
+            creator_user_id,
+        )
+        .await?;
+
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ProjectImported,
+        AuditTarget::project(project.id),
+        serde_json::json!({
+            "name": project.name,
+            "source_project_id": source_project_id,
+            "task_count": task_count,
+        }),
+    )
+    .await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_imported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "task_count": task_count,
+                "creator_user_id": creator_user_id.map(|id| id.to_string()),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(project)))
+}
+
 pub async fn update_project(
     Extension(existing_project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
     match deployment
@@ -152,7 +266,18 @@ pub async fn update_project(
         .update_project(&deployment.db().pool, &existing_project, payload)
         .await
     {
-        Ok(project) => Ok(ResponseJson(ApiResponse::success(project))),
+        Ok(project) => {
+            record_audit_event(
+                &deployment,
+                &headers,
+                AuditAction::ProjectUpdated,
+                AuditTarget::project(project.id),
+                AuditEvent::diff(&existing_project, &project),
+            )
+            .await;
+
+            Ok(ResponseJson(ApiResponse::success(project)))
+        }
         Err(e) => {
             tracing::error!("Failed to update project: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -163,6 +288,7 @@ pub async fn update_project(
 pub async fn delete_project(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
 ) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
     match deployment
         .project()
@@ -173,6 +299,15 @@ pub async fn delete_project(
             if rows_affected == 0 {
                 Err(StatusCode::NOT_FOUND)
             } else {
+                record_audit_event(
+                    &deployment,
+                    &headers,
+                    AuditAction::ProjectDeleted,
+                    AuditTarget::project(project.id),
+                    serde_json::json!({ "name": project.name }),
+                )
+                .await;
+
                 deployment
                     .track_if_analytics_allowed(
                         "project_deleted",
@@ -246,6 +381,7 @@ pub async fn get_project_repositories(
 pub async fn add_project_repository(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<CreateProjectRepo>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
     tracing::debug!(
@@ -266,6 +402,18 @@ pub async fn add_project_repository(
         .await
     {
         Ok(repository) => {
+            record_audit_event(
+                &deployment,
+                &headers,
+                AuditAction::ProjectRepositoryAdded,
+                AuditTarget::project(project.id).with_target(repository.id),
+                serde_json::json!({
+                    "name": repository.name,
+                    "path": repository.path,
+                }),
+            )
+            .await;
+
             deployment
                 .track_if_analytics_allowed(
                     "project_repository_added",
@@ -329,8 +477,11 @@ pub async fn add_project_repository(
 
 pub async fn delete_project_repository(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    require_project_role(&deployment, &headers, project_id, ProjectRole::Maintainer).await?;
+
     tracing::debug!(
         "Removing repository {} from project {}",
         repo_id,
@@ -343,6 +494,15 @@ pub async fn delete_project_repository(
         .await
     {
         Ok(()) => {
+            record_audit_event(
+                &deployment,
+                &headers,
+                AuditAction::ProjectRepositoryRemoved,
+                AuditTarget::project(project_id).with_target(repo_id),
+                serde_json::json!({}),
+            )
+            .await;
+
             deployment
                 .track_if_analytics_allowed(
                     "project_repository_removed",
@@ -380,17 +540,220 @@ pub async fn get_project_repository(
     }
 }
 
+pub async fn get_project_repository_merge_settings(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<ProjectRepoMergeSettings>>, ApiError> {
+    require_project_role(&deployment, &headers, project_id, ProjectRole::Viewer).await?;
+
+    let pool = &deployment.db().pool;
+    if ProjectRepo::find_by_project_and_repo(pool, project_id, repo_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest(
+            "Repository not found in project".to_string(),
+        ));
+    }
+    let merge_strategy = ProjectRepo::find_merge_strategy(pool, project_id, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        ProjectRepoMergeSettings { merge_strategy },
+    )))
+}
+
+pub async fn update_project_repository_merge_settings(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ProjectRepoMergeSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectRepoMergeSettings>>, ApiError> {
+    require_project_role(&deployment, &headers, project_id, ProjectRole::Maintainer).await?;
+
+    let pool = &deployment.db().pool;
+    let before = ProjectRepoMergeSettings {
+        merge_strategy: ProjectRepo::find_merge_strategy(pool, project_id, repo_id).await?,
+    };
+    ProjectRepo::set_merge_strategy(pool, project_id, repo_id, payload.merge_strategy).await?;
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ProjectRepositoryUpdated,
+        AuditTarget::project(project_id).with_target(repo_id),
+        AuditEvent::diff(&before, &payload),
+    )
+    .await;
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
+/// The project's task-status workflow (the default one if none was saved)
+pub async fn get_project_workflow(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkflowDefinition>>, ApiError> {
+    let definition =
+        ProjectWorkflow::definition_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(definition)))
+}
+
+pub async fn update_project_workflow(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Json(payload): Json<WorkflowDefinition>,
+) -> Result<ResponseJson<ApiResponse<WorkflowDefinition>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let before = ProjectWorkflow::definition_for_project(pool, project.id).await?;
+    let workflow = ProjectWorkflow::upsert(pool, project.id, &payload).await?;
+
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ProjectWorkflowUpdated,
+        AuditTarget::project(project.id),
+        AuditEvent::diff(&before, &workflow.definition),
+    )
+    .await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_workflow_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "transition_count": workflow.definition.transitions.len(),
+                "automation_count": workflow.definition.automations.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(workflow.definition)))
+}
+
+/// Drop the project's saved workflow and go back to the default one
+pub async fn reset_project_workflow(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<ApiResponse<WorkflowDefinition>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let before = ProjectWorkflow::definition_for_project(pool, project.id).await?;
+    ProjectWorkflow::delete(pool, project.id).await?;
+
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ProjectWorkflowReset,
+        AuditTarget::project(project.id),
+        AuditEvent::diff(&before, &WorkflowDefinition::default()),
+    )
+    .await;
+    Ok(ResponseJson(ApiResponse::success(
+        WorkflowDefinition::default(),
+    )))
+}
+
+/// The project's budget and its usage in the current period, if a budget is set
+pub async fn get_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectBudgetStatus>>>, ApiError> {
+    let status = ProjectBudget::status_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn update_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Json(payload): Json<UpsertProjectBudget>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectBudgetStatus>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let before = ProjectBudget::find_by_project_id(pool, project.id).await?;
+    let budget = ProjectBudget::upsert(pool, project.id, &payload).await?;
+
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::ProjectBudgetUpdated,
+        AuditTarget::project(project.id),
+        AuditEvent::diff(&before, &Some(budget)),
+    )
+    .await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_budget_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "has_token_limit": payload.max_tokens.is_some(),
+                "has_cost_limit": payload.max_cost_usd.is_some(),
+            }),
+        )
+        .await;
+
+    let status = ProjectBudget::status_for_project(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Remove the project's budget so executions are no longer limited
+pub async fn delete_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let before = ProjectBudget::find_by_project_id(pool, project.id).await?;
+    if ProjectBudget::delete(pool, project.id).await? > 0 {
+        record_audit_event(
+            &deployment,
+            &headers,
+            AuditAction::ProjectBudgetDeleted,
+            AuditTarget::project(project.id),
+            AuditEvent::diff(&before, &None),
+        )
+        .await;
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let maintainer = || from_fn_with_state(deployment.clone(), require_project_maintainer);
+
     let project_id_router = Router::new()
+        .route("/", get(get_project))
+        .route("/", put(update_project).route_layer(maintainer()))
         .route(
             "/",
-            get(get_project).put(update_project).delete(delete_project),
+            delete(delete_project).route_layer(from_fn_with_state(
+                deployment.clone(),
+                require_project_owner,
+            )),
         )
         .route("/search", get(search_project_files))
+        .route("/export", get(export_project).route_layer(maintainer()))
+        .route("/workflow", get(get_project_workflow))
+        .route(
+            "/workflow",
+            put(update_project_workflow)
+                .delete(reset_project_workflow)
+                .route_layer(maintainer()),
+        )
+        .route("/budget", get(get_project_budget))
+        .route(
+            "/budget",
+            put(update_project_budget)
+                .delete(delete_project_budget)
+                .route_layer(maintainer()),
+        )
+        .route("/repositories", get(get_project_repositories))
         .route(
             "/repositories",
-            get(get_project_repositories).post(add_project_repository),
+            post(add_project_repository).route_layer(maintainer()),
         )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authorize_project_writes,
+        ))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -398,10 +761,19 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let projects_router = Router::new()
         .route("/", get(get_projects).post(create_project))
+        .route(
+            "/import",
+            post(import_project).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
         .route(
             "/{project_id}/repositories/{repo_id}",
             get(get_project_repository).delete(delete_project_repository),
         )
+        .route(
+            "/{project_id}/repositories/{repo_id}/merge-settings",
+            get(get_project_repository_merge_settings)
+                .put(update_project_repository_merge_settings),
+        )
         .route("/stream/ws", get(stream_projects_ws))
         .nest("/{id}", project_id_router);
 
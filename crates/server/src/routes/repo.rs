@@ -1,13 +1,14 @@
 use axum::{
     Router,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::Json as ResponseJson,
     routing::{get, post},
 };
 use db::models::{
     project::SearchResult,
-    repo::{Repo, UpdateRepo},
+    project_member::ProjectRole,
+    repo::{Repo, RepoError, RepoVerifySettings, UpdateRepo},
 };
 use deployment::Deployment;
 use serde::Deserialize;
@@ -16,7 +17,11 @@ use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{get_caller, require_repo_role},
+};
 
 #[derive(Debug, Deserialize, TS)]
 #[ts(export)]
@@ -49,8 +54,12 @@ pub struct CloneRepoRequest {
 
 pub async fn register_repo(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     ResponseJson(payload): ResponseJson<RegisterRepoRequest>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    // Any signed-in user may add a repository, as creating a project does
+    get_caller(&deployment, &headers).await?;
+
     let repo = deployment
         .repo()
         .register(
@@ -65,8 +74,12 @@ pub async fn register_repo(
 
 pub async fn init_repo(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     ResponseJson(payload): ResponseJson<InitRepoRequest>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    // Any signed-in user may add a repository, as creating a project does
+    get_caller(&deployment, &headers).await?;
+
     let repo = deployment
         .repo()
         .init_repo(
@@ -82,8 +95,12 @@ pub async fn init_repo(
 
 pub async fn clone_repo(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     ResponseJson(payload): ResponseJson<CloneRepoRequest>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    // Any signed-in user may add a repository, as creating a project does
+    get_caller(&deployment, &headers).await?;
+
     let repo = deployment
         .repo()
         .clone_repo(
@@ -144,11 +161,29 @@ pub async fn get_repo(
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
 
+pub async fn get_repo_verify_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoVerifySettings>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let verify_script = Repo::find_verify_script(pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(RepoVerifySettings {
+        verify_script,
+    })))
+}
+
 pub async fn update_repo(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(repo_id): Path<Uuid>,
     ResponseJson(payload): ResponseJson<UpdateRepo>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    // Scripts run in every project that uses the repo
+    require_repo_role(&deployment, &headers, repo_id, ProjectRole::Maintainer).await?;
+
     let repo = Repo::update(&deployment.db().pool, repo_id, &payload).await?;
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
@@ -197,5 +232,9 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/repos/batch", post(get_repos_batch))
         .route("/repos/{repo_id}", get(get_repo).put(update_repo))
         .route("/repos/{repo_id}/branches", get(get_repo_branches))
+        .route(
+            "/repos/{repo_id}/verify-settings",
+            get(get_repo_verify_settings),
+        )
         .route("/repos/{repo_id}/search", get(search_repo))
 }
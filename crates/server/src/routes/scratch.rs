@@ -4,17 +4,27 @@ use axum::{
         Path, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
+    http::HeaderMap,
     response::{IntoResponse, Json as ResponseJson},
     routing::get,
 };
-use db::models::scratch::{CreateScratch, Scratch, ScratchType, UpdateScratch};
+use db::models::{
+    project_member::ProjectRole,
+    scratch::{CreateScratch, Scratch, ScratchType, UpdateScratch},
+    session::Session,
+    workspace::Workspace,
+};
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{get_caller, require_workspace_role},
+};
 
 /// Path parameters for scratch routes with composite key
 #[derive(Deserialize)]
@@ -23,6 +33,39 @@ pub struct ScratchPath {
     id: Uuid,
 }
 
+/// Require the caller to be a contributor to the workspace a scratch belongs to. Drafts
+/// keyed by a session or workspace check that workspace; the rest, and drafts for a
+/// session that does not exist yet, only need a signed-in user.
+async fn authorize_scratch_write(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    scratch_type: ScratchType,
+    id: Uuid,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace_id = match scratch_type {
+        ScratchType::DraftFollowUp => match Session::find_by_id(pool, id).await? {
+            Some(session) => Some(session.workspace_id),
+            None => Workspace::find_by_id(pool, id).await?.map(|w| w.id),
+        },
+        ScratchType::PreviewSettings | ScratchType::WorkspaceNotes => {
+            Workspace::find_by_id(pool, id).await?.map(|w| w.id)
+        }
+        ScratchType::DraftTask | ScratchType::DraftWorkspace => None,
+    };
+
+    match workspace_id {
+        Some(workspace_id) => {
+            require_workspace_role(deployment, headers, workspace_id, ProjectRole::Contributor)
+                .await?;
+        }
+        None => {
+            get_caller(deployment, headers).await?;
+        }
+    }
+    Ok(())
+}
+
 pub async fn list_scratch(
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Scratch>>>, ApiError> {
@@ -42,17 +85,11 @@ pub async fn get_scratch(
 
 pub async fn create_scratch(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
     Json(payload): Json<CreateScratch>,
 ) -> Result<ResponseJson<ApiResponse<Scratch>>, ApiError> {
-    // Reject edits to draft_follow_up if a message is queued for this task attempt
-    if matches!(scratch_type, ScratchType::DraftFollowUp)
-        && deployment.queued_message_service().has_queued(id)
-    {
-        return Err(ApiError::BadRequest(
-            "Cannot edit scratch while a message is queued".to_string(),
-        ));
-    }
+    authorize_scratch_write(&deployment, &headers, scratch_type, id).await?;
 
     // Validate that payload type matches URL type
     payload
@@ -66,17 +103,11 @@ pub async fn create_scratch(
 
 pub async fn update_scratch(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
     Json(payload): Json<UpdateScratch>,
 ) -> Result<ResponseJson<ApiResponse<Scratch>>, ApiError> {
-    // Reject edits to draft_follow_up if a message is queued for this task attempt
-    if matches!(scratch_type, ScratchType::DraftFollowUp)
-        && deployment.queued_message_service().has_queued(id)
-    {
-        return Err(ApiError::BadRequest(
-            "Cannot edit scratch while a message is queued".to_string(),
-        ));
-    }
+    authorize_scratch_write(&deployment, &headers, scratch_type, id).await?;
 
     // Validate that payload type matches URL type
     payload
@@ -91,8 +122,11 @@ pub async fn update_scratch(
 
 pub async fn delete_scratch(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    authorize_scratch_write(&deployment, &headers, scratch_type, id).await?;
+
     let rows = Scratch::delete(&deployment.db().pool, id, &scratch_type).await?;
     if rows == 0 {
         return Err(ApiError::BadRequest("Scratch not found".to_string()));
@@ -0,0 +1,72 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    project_member::ProjectRole,
+    search::{SearchHit, SearchScope},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{get_caller, require_project_role},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+/// Tasks, agent conversation entries and review comments matching `q`, best first.
+/// Without a project, server admins search every project and other users the
+/// projects they are a member of.
+pub async fn search(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Query(query): Query<SearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchHit>>>, ApiError> {
+    let scope = match query.project_id {
+        Some(project_id) => {
+            require_project_role(&deployment, &headers, project_id, ProjectRole::Viewer).await?;
+            SearchScope {
+                project_id: Some(project_id),
+                member_user_id: None,
+            }
+        }
+        None => {
+            let caller = get_caller(&deployment, &headers).await?;
+            SearchScope {
+                project_id: None,
+                member_user_id: caller
+                    .user()
+                    .filter(|_| !caller.is_admin())
+                    .map(|user| user.id),
+            }
+        }
+    };
+
+    let hits = SearchHit::search(
+        &deployment.db().pool,
+        &query.q,
+        scope,
+        query.limit.unwrap_or(DEFAULT_LIMIT),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(hits)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/search", get(search))
+}
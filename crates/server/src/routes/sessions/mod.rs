@@ -12,6 +12,7 @@ use axum::{
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    project_member::ProjectRole,
     scratch::{Scratch, ScratchType},
     session::{CreateSession, Session, SessionError, SessionWithInitiator},
     workspace::{Workspace, WorkspaceError},
@@ -24,7 +25,7 @@ use executors::{
     },
     profile::ExecutorProfileId,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
 use ts_rs::TS;
 use utils::response::ApiResponse;
@@ -34,8 +35,8 @@ use crate::{
     DeploymentImpl,
     error::ApiError,
     middleware::{
-        get_user_id, load_session_middleware, try_get_authenticated_user,
-        user_has_valid_claude_token,
+        authorize_project_writes, get_user_id, load_session_middleware, require_workspace_role,
+        try_get_authenticated_user, user_has_valid_claude_token,
     },
     routes::task_attempts::util::restore_worktrees_to_process,
 };
@@ -86,6 +87,13 @@ pub async fn create_session(
         .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
             "Workspace not found".to_string(),
         )))?;
+    require_workspace_role(
+        &deployment,
+        &headers,
+        payload.workspace_id,
+        ProjectRole::Contributor,
+    )
+    .await?;
 
     // Get authenticated user for session attribution
     let authenticated_user = try_get_authenticated_user(&deployment, &headers).await;
@@ -105,7 +113,7 @@ pub async fn create_session(
     Ok(ResponseJson(ApiResponse::success(session)))
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CreateFollowUpAttempt {
     pub prompt: String,
     pub executor_profile_id: ExecutorProfileId,
@@ -275,6 +283,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
         .route("/review", post(review::start_review))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authorize_project_writes,
+        ))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -1,16 +1,28 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{
+        Path, State, WebSocketUpgrade,
+        ws::{Message, WebSocket},
+    },
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Json as ResponseJson},
+    routing::{get, put},
 };
 use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
+use futures_util::{SinkExt, StreamExt};
 use serde::Deserialize;
 use services::services::queued_message::QueueStatus;
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{authorize_project_writes, load_session_middleware},
+};
 
 /// Request body for queueing a follow-up message
 #[derive(Debug, Deserialize, TS)]
@@ -19,7 +31,14 @@ pub struct QueueMessageRequest {
     pub executor_profile_id: ExecutorProfileId,
 }
 
-/// Queue a follow-up message to be executed when the current execution finishes
+/// Request body for reordering the queue
+#[derive(Debug, Deserialize, TS)]
+pub struct ReorderQueueRequest {
+    /// Every queued message id, in the desired execution order
+    pub message_ids: Vec<Uuid>,
+}
+
+/// Append a follow-up message to be executed when the current execution finishes
 pub async fn queue_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -30,9 +49,10 @@ pub async fn queue_message(
         executor_profile_id: payload.executor_profile_id,
     };
 
-    let queued = deployment
+    let status = deployment
         .queued_message_service()
-        .queue_message(session.id, data);
+        .queue_message(session.id, data)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -44,19 +64,18 @@ pub async fn queue_message(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Cancel a queued follow-up message
+/// Cancel every queued follow-up message
 pub async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
     deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .clear(session.id)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -76,11 +95,121 @@ pub async fn get_queue_status(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let status = deployment.queued_message_service().get_status(session.id);
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+/// Edit a queued message without changing its position
+pub async fn update_queued_message(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_session_id, message_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<QueueMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let data = DraftFollowUpData {
+        message: payload.message,
+        executor_profile_id: payload.executor_profile_id,
+    };
+
+    let status = deployment
+        .queued_message_service()
+        .update_message(session.id, message_id, data)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Remove a single queued message
+pub async fn delete_queued_message(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_session_id, message_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let status = deployment
+        .queued_message_service()
+        .remove_message(session.id, message_id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Set the execution order of the queued messages
+pub async fn reorder_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let status = deployment
+        .queued_message_service()
+        .reorder(session.id, &payload.message_ids)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Stream the session's queue: the current status first, then every change
+pub async fn stream_queue_ws(
+    ws: WebSocketUpgrade,
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let session_id = session.id;
+    Ok(ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_queue_ws(socket, deployment.clone(), session_id).await {
+            tracing::warn!("queue WS closed: {}", e);
+        }
+        deployment
+            .queued_message_service()
+            .release(session_id)
+            .await;
+    }))
+}
+
+async fn handle_queue_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    session_id: Uuid,
+) -> anyhow::Result<()> {
+    let service = deployment.queued_message_service();
+    // Subscribe before loading the snapshot so no change is missed in between
+    let mut rx = service.subscribe(session_id).await;
+    let initial = service.get_status(session_id).await?;
+    let (mut sender, mut receiver) = socket.split();
+
+    sender
+        .send(Message::Text(serde_json::to_string(&initial)?.into()))
+        .await?;
+
+    loop {
+        tokio::select! {
+            msg = rx.recv() => {
+                let status = match msg {
+                    Ok(status) => status,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::debug!("Queue WS client lagged by {} messages", n);
+                        service.get_status(session_id).await?
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                let json = serde_json::to_string(&status)?;
+                if sender.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
+            msg = receiver.next() => {
+                if msg.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .route(
@@ -89,6 +218,16 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .post(queue_message)
                 .delete(cancel_queued_message),
         )
+        .route("/ws", get(stream_queue_ws))
+        .route("/reorder", put(reorder_queue))
+        .route(
+            "/{message_id}",
+            put(update_queued_message).delete(delete_queued_message),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authorize_project_writes,
+        ))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
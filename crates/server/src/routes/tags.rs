@@ -1,6 +1,7 @@
 use axum::{
     Extension, Json, Router,
     extract::{Query, State},
+    http::HeaderMap,
     middleware::from_fn_with_state,
     response::Json as ResponseJson,
     routing::{get, put},
@@ -11,7 +12,11 @@ use serde::Deserialize;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_tag_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{load_tag_middleware, require_admin},
+};
 
 #[derive(Deserialize, TS)]
 pub struct TagSearchParams {
@@ -36,8 +41,12 @@ pub async fn get_tags(
 
 pub async fn create_tag(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<CreateTag>,
 ) -> Result<ResponseJson<ApiResponse<Tag>>, ApiError> {
+    // Tags are shared by every user of the server
+    require_admin(&deployment, &headers).await?;
+
     let tag = Tag::create(&deployment.db().pool, &payload).await?;
 
     deployment
@@ -56,8 +65,12 @@ pub async fn create_tag(
 pub async fn update_tag(
     Extension(tag): Extension<Tag>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateTag>,
 ) -> Result<ResponseJson<ApiResponse<Tag>>, ApiError> {
+    // Tags are shared by every user of the server
+    require_admin(&deployment, &headers).await?;
+
     let updated_tag = Tag::update(&deployment.db().pool, tag.id, &payload).await?;
 
     deployment
@@ -76,7 +89,11 @@ pub async fn update_tag(
 pub async fn delete_tag(
     Extension(tag): Extension<Tag>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    // Tags are shared by every user of the server
+    require_admin(&deployment, &headers).await?;
+
     let rows_affected = Tag::delete(&deployment.db().pool, tag.id).await?;
     if rows_affected == 0 {
         Err(ApiError::Database(sqlx::Error::RowNotFound))
@@ -6,13 +6,18 @@ use axum::{
     routing::{delete, get, post},
 };
 use db::models::{
+    audit_event::{AuditAction, AuditTarget},
     task::Task,
     task_approval::{TaskApproval, TaskApprovalWithUser},
 };
 use deployment::Deployment;
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::try_get_authenticated_user};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{record_audit_event, try_get_authenticated_user},
+};
 
 pub async fn list_task_approvals(
     Extension(task): Extension<Task>,
@@ -44,6 +49,15 @@ pub async fn approve_task(
 
     let approval = TaskApproval::create(pool, task.id, user.id).await?;
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::TaskApproved,
+        AuditTarget::task(task.project_id, task.id),
+        serde_json::json!({ "task_status": task.status }),
+    )
+    .await;
+
     let approval_with_user = TaskApprovalWithUser {
         approval,
         user: user.into(),
@@ -72,6 +86,15 @@ pub async fn unapprove_task(
         ));
     }
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::TaskUnapproved,
+        AuditTarget::task(task.project_id, task.id),
+        serde_json::json!({ "task_status": task.status }),
+    )
+    .await;
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -21,16 +21,23 @@ use axum::{
     http::{HeaderMap, StatusCode},
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
 };
 use db::models::{
+    audit_event::{AuditAction, AuditEvent, AuditTarget},
     coding_agent_turn::CodingAgentTurn,
+    conflict_resolution::ConflictResolution,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
-    merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    merge::{Merge, MergeStatus, MergeStrategy, PrMerge, PullRequestInfo},
+    merge_queue::MergeQueueEntry,
+    merge_verification::MergeVerification,
     project::SearchResult,
+    project_member::ProjectRole,
+    project_repo::ProjectRepo,
+    project_workflow::WorkflowEvent,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
-    task::{Task, TaskRelationships, TaskStatus},
+    task::{Task, TaskRelationships},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
 };
@@ -48,22 +55,28 @@ use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
     file_search::SearchQuery,
-    git::{ConflictOp, GitCliError, GitServiceError},
+    git::{ConflictOp, DiffTarget, GitCliError, GitServiceError},
+    merge_queue, merge_verification, workflow,
     workspace_manager::WorkspaceManager,
+    workspace_merge::{self, MergedRepo, PlannedMerge, PreflightProblem},
+    workspace_start,
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{diff::Diff, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
     error::ApiError,
     middleware::{
-        get_user_id, load_workspace_middleware, try_get_authenticated_user,
-        user_has_valid_claude_token,
+        authorize_project_writes, get_user_id, load_workspace_middleware, record_audit_event,
+        require_project_role, try_get_authenticated_user, user_has_valid_claude_token,
+    },
+    routes::{
+        merge_queue::spawn_process_merge_queues, task_attempts::gh_cli_setup::GhCliSetupError,
+        task_dependencies,
     },
-    routes::task_attempts::gh_cli_setup::GhCliSetupError,
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -78,6 +91,11 @@ pub struct AbortConflictsRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -115,6 +133,30 @@ pub struct UpdateWorkspace {
     pub name: Option<String>,
 }
 
+/// Record an audit event about `workspace`, filling in its task and project
+async fn record_workspace_event(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+    workspace: &Workspace,
+    action: AuditAction,
+    details: serde_json::Value,
+) {
+    let project_id = match workspace.parent_task(&deployment.db().pool).await {
+        Ok(task) => task.map(|task| task.project_id),
+        Err(e) => {
+            tracing::warn!("Failed to find task for workspace {}: {}", workspace.id, e);
+            None
+        }
+    };
+    let target = AuditTarget {
+        project_id,
+        task_id: Some(workspace.task_id),
+        workspace_id: Some(workspace.id),
+        target_id: None,
+    };
+    record_audit_event(deployment, headers, action, target, details).await;
+}
+
 pub async fn get_task_attempts(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskAttemptQuery>,
@@ -141,6 +183,7 @@ pub async fn get_task_attempt(
 pub async fn update_workspace(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(request): Json<UpdateWorkspace>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -155,6 +198,16 @@ pub async fn update_workspace(
     let updated = Workspace::find_by_id(pool, workspace.id)
         .await?
         .ok_or(WorkspaceError::TaskNotFound)?;
+
+    record_workspace_event(
+        &deployment,
+        &headers,
+        &updated,
+        AuditAction::WorkspaceUpdated,
+        AuditEvent::diff(&workspace, &updated),
+    )
+    .await;
+
     Ok(ResponseJson(ApiResponse::success(updated)))
 }
 
@@ -207,15 +260,16 @@ pub async fn create_task_attempt(
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
+    require_project_role(
+        &deployment,
+        &headers,
+        task.project_id,
+        ProjectRole::Contributor,
+    )
+    .await?;
 
-    // Block workspace session creation if task is on hold
-    if task.is_on_hold() {
-        let hold_comment = task.hold_comment.as_deref().unwrap_or("No reason given");
-        return Err(ApiError::BadRequest(format!(
-            "Task is on hold: {}. Release the hold before starting a new workspace session.",
-            hold_comment
-        )));
-    }
+    // Block workspace session creation while the task is on hold or blocked
+    workspace_start::ensure_task_startable(pool, &task).await?;
 
     // Compute agent_working_dir based on repo count:
     // - Single repo: use repo name as working dir (agent runs in repo directory)
@@ -257,6 +311,20 @@ pub async fn create_task_attempt(
         .collect();
 
     WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
+
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::WorkspaceCreated,
+        AuditTarget::workspace(task.project_id, task.id, workspace.id),
+        serde_json::json!({
+            "branch": workspace.branch,
+            "executor_profile_id": executor_profile_id,
+            "repos": workspace_repos,
+        }),
+    )
+    .await;
+
     if let Err(err) = deployment
         .container()
         .start_workspace(&workspace, executor_profile_id.clone())
@@ -315,6 +383,57 @@ pub async fn run_agent_setup(
     Ok(ResponseJson(ApiResponse::success(RunAgentSetupResponse {})))
 }
 
+/// Current diff of every repository in the workspace against its target branch
+pub async fn get_task_attempt_diff(
+    Query(params): Query<DiffStreamQuery>,
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Diff>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+
+    let mut all_diffs = Vec::new();
+    for repo_with_branch in workspace_repos {
+        let repo = repo_with_branch.repo;
+        let repo_id = repo.id;
+        let repo_path = repo.path.clone();
+        let worktree_path = PathBuf::from(&container_ref).join(&repo.name);
+        let git = deployment.git().clone();
+        let branch = workspace.branch.clone();
+        let target_branch = repo_with_branch.target_branch;
+
+        let diffs = tokio::task::spawn_blocking(move || {
+            let base_commit = git.get_base_commit(&repo_path, &branch, &target_branch)?;
+            git.get_diffs(
+                DiffTarget::Worktree {
+                    worktree_path: &worktree_path,
+                    base_commit: &base_commit,
+                },
+                None,
+            )
+        })
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to compute diff: {e}")))??;
+
+        all_diffs.extend(diffs.into_iter().map(|mut diff| {
+            diff.repo_id = Some(repo_id);
+            if params.stats_only {
+                diff.old_content = None;
+                diff.new_content = None;
+                diff.content_omitted = true;
+            }
+            diff
+        }));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(all_diffs)))
+}
+
 #[axum::debug_handler]
 pub async fn stream_task_attempt_diff_ws(
     ws: WebSocketUpgrade,
@@ -437,6 +556,36 @@ async fn handle_workspaces_ws(
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct MergeTaskAttemptRequest {
     pub repo_id: Uuid,
+    /// Defaults to the repository's merge strategy in the project
+    #[serde(default)]
+    #[ts(optional)]
+    pub merge_strategy: Option<MergeStrategy>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct MergeWorkspaceRequest {
+    /// Defaults to each repository's merge strategy in the project
+    #[serde(default)]
+    #[ts(optional)]
+    pub merge_strategy: Option<MergeStrategy>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum WorkspaceMergeError {
+    /// No repo has commits its target branch lacks
+    NoChanges,
+    /// Nothing was merged, as these repos are not ready
+    PreflightFailed { problems: Vec<PreflightProblem> },
+    /// Merging `repo_name` failed, and the repos merged before it were rolled back
+    MergeFailed {
+        repo_id: Uuid,
+        repo_name: String,
+        message: String,
+        rolled_back: Vec<String>,
+        not_rolled_back: Vec<String>,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -448,6 +597,7 @@ pub struct PushTaskAttemptRequest {
 pub async fn merge_task_attempt(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(request): Json<MergeTaskAttemptRequest>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -477,49 +627,42 @@ pub async fn merge_task_attempt(
         .ensure_container_exists(&workspace)
         .await?;
     let workspace_path = Path::new(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
+    let worktree_path = workspace_path.join(&repo.name);
 
     let task = workspace
         .parent_task(pool)
         .await?
         .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
 
-    // Check approval gate: task must have sufficient approvals before merge
-    if task.status == TaskStatus::InReview {
-        let project = db::models::project::Project::find_by_id(pool, task.project_id)
-            .await?
-            .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    // Check the workflow gate before merging: the merge must be allowed to move the task
+    workflow::check_event(pool, &task, WorkflowEvent::BranchMerged).await?;
 
-        let approval_count =
-            db::models::task_approval::TaskApproval::count_by_task_id(pool, task.id).await?;
+    // The merge queue must not rebase or merge into the same branches meanwhile
+    let queue_guard = merge_queue::MERGE_QUEUE_LOCK.lock().await;
+    ensure_not_in_merge_queue(pool, workspace.id, workspace_repo.repo_id).await?;
+    merge_verification::check_verified(
+        pool,
+        deployment.git(),
+        &workspace,
+        &repo,
+        &workspace_repo.target_branch,
+    )
+    .await?;
 
-        if approval_count < project.min_approvals_required {
-            return Err(ApiError::BadRequest(format!(
-                "Task requires {} approval(s) to merge, but has {}",
-                project.min_approvals_required, approval_count
-            )));
+    let commit_message = merge_queue::merge_commit_message(&task);
+    let merge_strategy = match request.merge_strategy {
+        Some(strategy) => strategy,
+        None => {
+            ProjectRepo::find_merge_strategy(pool, task.project_id, workspace_repo.repo_id).await?
         }
-    }
-
-    let task_uuid_str = task.id.to_string();
-    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
-
-    let mut commit_message = format!("{} (vibe-kanban {})", task.title, first_uuid_section);
-
-    // Add description on next line if it exists
-    if let Some(description) = &task.description
-        && !description.trim().is_empty()
-    {
-        commit_message.push_str("\n\n");
-        commit_message.push_str(description);
-    }
-
-    let merge_commit_id = deployment.git().merge_changes(
+    };
+    let merge_commit_id = deployment.git().merge_changes_with_strategy(
         &repo.path,
         &worktree_path,
         &workspace.branch,
         &workspace_repo.target_branch,
         &commit_message,
+        merge_strategy,
     )?;
 
     Merge::create_direct(
@@ -530,7 +673,69 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
-    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    // A direct merge supersedes a failed queue entry
+    if let Some(entry) = MergeQueueEntry::find(pool, workspace.id, workspace_repo.repo_id).await? {
+        MergeQueueEntry::delete(pool, entry.id).await?;
+    }
+    drop(queue_guard);
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::WorkspaceMerged,
+        AuditTarget::workspace(task.project_id, task.id, workspace.id)
+            .with_target(workspace_repo.repo_id),
+        serde_json::json!({
+            "branch": workspace.branch,
+            "target_branch": workspace_repo.target_branch,
+            "merge_commit": merge_commit_id,
+            "merge_strategy": merge_strategy,
+        }),
+    )
+    .await;
+    finish_merged_workspace(&deployment, &workspace, &task).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_merged",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Fail if the merge queue is still working on the workspace's repo: a direct merge
+/// would race its rebase, its verify run or the agent resolving its conflicts. Only a
+/// failed entry may be superseded. Call with [`merge_queue::MERGE_QUEUE_LOCK`] held.
+async fn ensure_not_in_merge_queue(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    repo_id: Uuid,
+) -> Result<(), ApiError> {
+    if let Some(entry) = MergeQueueEntry::find(pool, workspace_id, repo_id).await?
+        && entry.is_pending()
+    {
+        return Err(ApiError::Conflict(
+            "The workspace is in the merge queue; take it out of the queue to merge directly"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Move the task on once the workspace has been merged: apply the workflow, start the
+/// tasks it unblocked, archive the workspace unless it is pinned and stop its dev servers
+async fn finish_merged_workspace(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    task: &Task,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    workflow::handle_event(pool, task, WorkflowEvent::BranchMerged).await?;
+    task_dependencies::spawn_start_unblocked_tasks(deployment);
     if !workspace.pinned {
         Workspace::set_archived(pool, workspace.id, true).await?;
     }
@@ -559,23 +764,243 @@ pub async fn merge_task_attempt(
             );
         }
     }
+    Ok(())
+}
+
+/// Merge the workspace branch into the target branch of every repo with changes. Every
+/// repo is checked first, and if one still fails to merge, the repos merged before it
+/// are rolled back, so either all of them are merged or none is.
+#[axum::debug_handler]
+pub async fn merge_workspace(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Json(request): Json<MergeWorkspaceRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergedRepo>, WorkspaceMergeError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    workflow::check_event(pool, &task, WorkflowEvent::BranchMerged).await?;
+
+    // The merge queue must not rebase or merge into the same branches meanwhile
+    let queue_guard = merge_queue::MERGE_QUEUE_LOCK.lock().await;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let changes = workspace_merge::repos_with_changes(
+        deployment.git(),
+        &workspace.branch,
+        Path::new(&container_ref),
+        repos,
+    )?;
+    if changes.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            WorkspaceMergeError::NoChanges,
+        )));
+    }
+    let problems =
+        workspace_merge::preflight(pool, deployment.git(), &workspace, &changes, true).await;
+    if !problems.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            WorkspaceMergeError::PreflightFailed { problems },
+        )));
+    }
+
+    let mut merges = Vec::with_capacity(changes.len());
+    for change in changes {
+        let strategy = match request.merge_strategy {
+            Some(strategy) => strategy,
+            None => ProjectRepo::find_merge_strategy(pool, task.project_id, change.repo.id).await?,
+        };
+        merges.push(PlannedMerge {
+            repo_id: change.repo.id,
+            repo_name: change.repo.display_name,
+            repo_path: change.repo.path,
+            worktree_path: change.worktree_path,
+            target_branch: change.target_branch,
+            strategy,
+        });
+    }
+
+    let commit_message = merge_queue::merge_commit_message(&task);
+    let merged = match workspace_merge::merge_all(
+        deployment.git(),
+        &workspace.branch,
+        &commit_message,
+        &merges,
+    ) {
+        Ok(merged) => merged,
+        Err(e) => {
+            tracing::error!("Failed to merge workspace {}: {}", workspace.id, e);
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                WorkspaceMergeError::MergeFailed {
+                    repo_id: e.repo_id,
+                    message: e.error.to_string(),
+                    repo_name: e.repo_name,
+                    rolled_back: e.rolled_back,
+                    not_rolled_back: e.not_rolled_back,
+                },
+            )));
+        }
+    };
+
+    for repo in &merged {
+        Merge::create_direct(
+            pool,
+            workspace.id,
+            repo.repo_id,
+            &repo.target_branch,
+            &repo.merge_commit,
+        )
+        .await?;
+        // A direct merge supersedes a failed queue entry
+        if let Some(entry) = MergeQueueEntry::find(pool, workspace.id, repo.repo_id).await? {
+            MergeQueueEntry::delete(pool, entry.id).await?;
+        }
+        record_audit_event(
+            &deployment,
+            &headers,
+            AuditAction::WorkspaceMerged,
+            AuditTarget::workspace(task.project_id, task.id, workspace.id)
+                .with_target(repo.repo_id),
+            serde_json::json!({
+                "branch": workspace.branch,
+                "target_branch": repo.target_branch,
+                "merge_commit": repo.merge_commit,
+                "merge_strategy": repo.merge_strategy,
+                "repo_count": merged.len(),
+            }),
+        )
+        .await;
+    }
+    drop(queue_guard);
+    finish_merged_workspace(&deployment, &workspace, &task).await?;
 
     deployment
         .track_if_analytics_allowed(
-            "task_attempt_merged",
+            "task_attempt_workspace_merged",
             serde_json::json!({
                 "task_id": task.id.to_string(),
                 "workspace_id": workspace.id.to_string(),
+                "repo_count": merged.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(merged)))
+}
+
+/// Add the workspace's branch in a repo to the merge queue of its target branch. The
+/// queue rebases it onto the target branch, runs the repo's verify script if it has one
+/// and merges it, after the workspaces enqueued before it.
+#[axum::debug_handler]
+pub async fn enqueue_merge(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Json(request): Json<MergeTaskAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let target_branch_type = deployment
+        .git()
+        .find_branch_type(&repo.path, &workspace_repo.target_branch)?;
+    if target_branch_type == BranchType::Remote {
+        return Err(ApiError::BadRequest(
+            "Cannot merge directly into a remote branch. Please create a pull request instead."
+                .to_string(),
+        ));
+    }
+    if let Some(entry) = MergeQueueEntry::find(pool, workspace.id, repo.id).await?
+        && entry.is_active()
+    {
+        return Err(ApiError::Conflict(
+            "The workspace is already in the merge queue".to_string(),
+        ));
+    }
+
+    let entry = MergeQueueEntry::enqueue(
+        pool,
+        workspace.id,
+        repo.id,
+        &workspace_repo.target_branch,
+        request.merge_strategy,
+    )
+    .await?;
+    record_workspace_event(
+        &deployment,
+        &headers,
+        &workspace,
+        AuditAction::MergeQueued,
+        serde_json::json!({
+            "repo_id": repo.id,
+            "target_branch": entry.target_branch,
+            "merge_strategy": entry.merge_strategy,
+        }),
+    )
+    .await;
+    spawn_process_merge_queues(&deployment);
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_merge_queued",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
             }),
         )
         .await;
 
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+/// The workspace's merge queue entries, one per enqueued repo
+pub async fn get_workspace_merge_queue(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueueEntry>>>, ApiError> {
+    let entries =
+        MergeQueueEntry::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+/// Take the workspace's repo out of the merge queue. A verify script or agent already
+/// running for it is left to finish.
+pub async fn dequeue_merge(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    axum::extract::Path((_id, repo_id)): axum::extract::Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let entry = MergeQueueEntry::find(pool, workspace.id, repo_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest("The workspace is not in the merge queue".to_string())
+        })?;
+    MergeQueueEntry::delete(pool, entry.id).await?;
+    // The entries behind it may be able to go now
+    spawn_process_merge_queues(&deployment);
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
 pub async fn push_task_attempt_branch(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(request): Json<PushTaskAttemptRequest>,
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -600,7 +1025,17 @@ pub async fn push_task_attempt_branch(
         .git()
         .push_to_remote(&worktree_path, &workspace.branch, false)
     {
-        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(_) => {
+            record_workspace_event(
+                &deployment,
+                &headers,
+                &workspace,
+                AuditAction::BranchPushed,
+                serde_json::json!({ "repo_id": repo.id, "branch": workspace.branch }),
+            )
+            .await;
+            Ok(ResponseJson(ApiResponse::success(())))
+        }
         Err(GitServiceError::GitCLI(GitCliError::PushRejected(_))) => Ok(ResponseJson(
             ApiResponse::error_with_data(PushError::ForcePushRequired),
         )),
@@ -611,6 +1046,7 @@ pub async fn push_task_attempt_branch(
 pub async fn force_push_task_attempt_branch(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(request): Json<PushTaskAttemptRequest>,
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -634,6 +1070,16 @@ pub async fn force_push_task_attempt_branch(
     deployment
         .git()
         .push_to_remote(&worktree_path, &workspace.branch, true)?;
+
+    record_workspace_event(
+        &deployment,
+        &headers,
+        &workspace,
+        AuditAction::BranchForcePushed,
+        serde_json::json!({ "repo_id": repo.id, "branch": workspace.branch }),
+    )
+    .await;
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -859,6 +1305,7 @@ pub enum RenameBranchError {
 pub async fn change_target_branch(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<ChangeTargetBranchRequest>,
 ) -> Result<ResponseJson<ApiResponse<ChangeTargetBranchResponse>>, ApiError> {
     let repo_id = payload.repo_id;
@@ -882,8 +1329,24 @@ pub async fn change_target_branch(
         )));
     };
 
+    let old_target_branch =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+            .await?
+            .map(|workspace_repo| workspace_repo.target_branch);
     WorkspaceRepo::update_target_branch(pool, workspace.id, repo_id, &new_target_branch).await?;
 
+    record_workspace_event(
+        &deployment,
+        &headers,
+        &workspace,
+        AuditAction::TargetBranchChanged,
+        serde_json::json!({
+            "repo_id": repo_id,
+            "target_branch": { "from": old_target_branch, "to": new_target_branch },
+        }),
+    )
+    .await;
+
     let status =
         deployment
             .git()
@@ -912,6 +1375,7 @@ pub async fn change_target_branch(
 pub async fn rename_branch(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<RenameBranchRequest>,
 ) -> Result<ResponseJson<ApiResponse<RenameBranchResponse, RenameBranchError>>, ApiError> {
     let new_branch_name = payload.new_branch_name.trim();
@@ -1017,6 +1481,14 @@ pub async fn rename_branch(
     }
 
     Workspace::update_branch_name(pool, workspace.id, new_branch_name).await?;
+    record_workspace_event(
+        &deployment,
+        &headers,
+        &workspace,
+        AuditAction::BranchRenamed,
+        serde_json::json!({ "branch": { "from": old_branch, "to": new_branch_name } }),
+    )
+    .await;
     // What will become of me?
     let updated_children_count = WorkspaceRepo::update_target_branch_for_children_of_workspace(
         pool,
@@ -1052,6 +1524,7 @@ pub async fn rename_branch(
 pub async fn rebase_task_attempt(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<RebaseTaskAttemptRequest>,
 ) -> Result<ResponseJson<ApiResponse<(), GitOperationError>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -1136,6 +1609,19 @@ pub async fn rebase_task_attempt(
         };
     }
 
+    record_workspace_event(
+        &deployment,
+        &headers,
+        &workspace,
+        AuditAction::WorkspaceRebased,
+        serde_json::json!({
+            "repo_id": payload.repo_id,
+            "old_base_branch": old_base_branch,
+            "new_base_branch": new_base_branch,
+        }),
+    )
+    .await;
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_rebased",
@@ -1173,6 +1659,76 @@ pub async fn abort_conflicts_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Ask the workspace's agent to resolve the conflicts of the git operation stopped in
+/// the repo's worktree. When the agent finishes the operation is continued, or rolled
+/// back if conflict markers remain.
+#[axum::debug_handler]
+pub async fn resolve_conflicts_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running process to finish before resolving conflicts".to_string(),
+        ));
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+    if deployment
+        .git()
+        .get_conflicted_files(&worktree_path)?
+        .is_empty()
+    {
+        return Err(ApiError::BadRequest("No conflicts to resolve".to_string()));
+    }
+
+    let execution_process = deployment
+        .container()
+        .start_conflict_resolution(&workspace, &repo)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest(
+                "Start a coding agent in this workspace before resolving conflicts with it"
+                    .to_string(),
+            )
+        })?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_conflict_resolution_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+/// The latest agent conflict resolution of each of the workspace's repos
+pub async fn get_conflict_resolutions(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ConflictResolution>>>, ApiError> {
+    let resolutions =
+        ConflictResolution::find_latest_for_workspace(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(resolutions)))
+}
+
 #[axum::debug_handler]
 pub async fn start_dev_server(
     Extension(workspace): Extension<Workspace>,
@@ -1323,9 +1879,19 @@ pub async fn get_task_attempt_children(
 pub async fn stop_task_attempt_execution(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     deployment.container().try_stop(&workspace, false).await;
 
+    record_workspace_event(
+        &deployment,
+        &headers,
+        &workspace,
+        AuditAction::ExecutionStopped,
+        serde_json::json!({}),
+    )
+    .await;
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_stopped",
@@ -1506,6 +2072,90 @@ pub async fn run_cleanup_script(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// Run the verify scripts of the workspace's repos against a temporary merge of the
+/// workspace branch into each repo's target branch. Merging and opening pull requests
+/// are blocked until they pass.
+#[axum::debug_handler]
+pub async fn run_verify_script(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>, RunScriptError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    // Check if any non-dev-server processes are already running for this workspace
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            RunScriptError::ProcessAlreadyRunning,
+        )));
+    }
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let mut has_script = false;
+    for repo in &repos {
+        has_script |= Repo::find_verify_script(pool, repo.id).await?.is_some();
+    }
+    if !has_script {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            RunScriptError::NoScriptConfigured,
+        )));
+    }
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    // Get or create a session for the verify scripts (system-initiated)
+    let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+        Some(s) => s,
+        None => {
+            Session::create(
+                pool,
+                &CreateSession { executor: None },
+                Uuid::new_v4(),
+                workspace.id,
+                None, // System-initiated session
+            )
+            .await?
+        }
+    };
+
+    let execution_processes = deployment
+        .container()
+        .start_merge_verification(&workspace, &session, None)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "verify_script_executed",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_processes)))
+}
+
+/// The latest verify script run of each of the workspace's repos
+pub async fn get_merge_verifications(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeVerification>>>, ApiError> {
+    let verifications =
+        MergeVerification::find_latest_for_workspace(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(verifications)))
+}
+
 #[axum::debug_handler]
 pub async fn gh_cli_setup_handler(
     Extension(workspace): Extension<Workspace>,
@@ -1606,6 +2256,7 @@ pub async fn get_first_user_message(
 pub async fn delete_workspace(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
 ) -> Result<(StatusCode, ResponseJson<ApiResponse<()>>), ApiError> {
     let pool = &deployment.db().pool;
 
@@ -1665,6 +2316,19 @@ pub async fn delete_workspace(
         return Err(ApiError::Database(SqlxError::RowNotFound));
     }
 
+    record_workspace_event(
+        &deployment,
+        &headers,
+        &workspace,
+        AuditAction::WorkspaceDeleted,
+        serde_json::json!({
+            "branch": workspace.branch,
+            "owner_user_id": workspace.owner_user_id,
+            "repo_ids": repositories.iter().map(|repo| repo.id).collect::<Vec<_>>(),
+        }),
+    )
+    .await;
+
     deployment
         .track_if_analytics_allowed(
             "workspace_deleted",
@@ -1732,14 +2396,26 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/start-dev-server", post(start_dev_server))
         .route("/run-setup-script", post(run_setup_script))
         .route("/run-cleanup-script", post(run_cleanup_script))
+        .route("/run-verify-script", post(run_verify_script))
+        .route("/verifications", get(get_merge_verifications))
         .route("/branch-status", get(get_task_attempt_branch_status))
+        .route("/diff", get(get_task_attempt_diff))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
+        .route("/merge/all", post(merge_workspace))
+        .route(
+            "/merge-queue",
+            get(get_workspace_merge_queue).post(enqueue_merge),
+        )
+        .route("/merge-queue/{repo_id}", delete(dequeue_merge))
         .route("/push", post(push_task_attempt_branch))
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+        .route("/conflicts/resolve", post(resolve_conflicts_task_attempt))
+        .route("/conflicts/resolutions", get(get_conflict_resolutions))
         .route("/pr", post(pr::create_pr))
+        .route("/pr/all", post(pr::create_workspace_prs))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
         .route("/children", get(get_task_attempt_children))
@@ -1751,6 +2427,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/first-message", get(get_first_user_message))
         .route("/mark-seen", put(mark_seen))
         .nest("/conversations", review_conversations::router())
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authorize_project_writes,
+        ))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
@@ -21,7 +21,7 @@ use uuid::Uuid;
 use crate::{
     DeploymentImpl,
     error::ApiError,
-    middleware::load_workspace_middleware,
+    middleware::{authorize_project_writes, load_workspace_middleware},
     routes::images::{ImageMetadata, ImageResponse, process_image_upload},
 };
 
@@ -245,6 +245,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/upload",
             post(upload_image).layer(DefaultBodyLimit::max(20 * 1024 * 1024)), // 20MB limit
         )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authorize_project_writes,
+        ))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
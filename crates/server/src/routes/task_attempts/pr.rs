@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use axum::{
     Extension, Json,
@@ -8,10 +8,10 @@ use axum::{
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
-    merge::{Merge, MergeStatus},
+    merge::{Merge, MergeStatus, PullRequestInfo},
+    project_workflow::WorkflowEvent,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
-    task::{Task, TaskStatus},
     workspace::{Workspace, WorkspaceError},
     workspace_repo::WorkspaceRepo,
 };
@@ -27,12 +27,14 @@ use services::services::{
     git_host::{
         self, CreatePrRequest, GitHostError, GitHostProvider, ProviderKind, UnifiedPrComment,
     },
+    merge_verification, workflow,
+    workspace_merge::{self, PreflightProblem, WorkspacePr},
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, routes::task_dependencies};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreatePrApiRequest {
@@ -57,6 +59,30 @@ pub enum PrError {
     UnsupportedProvider,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateWorkspacePrsRequest {
+    pub title: String,
+    pub body: Option<String>,
+    pub draft: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum WorkspacePrError {
+    /// No repo has commits its target branch lacks
+    NoChanges,
+    /// No branch was pushed, as these repos are not ready
+    PreflightFailed { problems: Vec<PreflightProblem> },
+    /// Opening the PR of `repo_name` failed; `opened` lists the PRs opened before it
+    PrFailed {
+        repo_id: Uuid,
+        repo_name: String,
+        error: PrError,
+        opened: Vec<WorkspacePr>,
+    },
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct AttachPrResponse {
     pub pr_attached: bool,
@@ -209,13 +235,15 @@ pub async fn create_pr(
         .await?
         .ok_or(RepoError::NotFound)?;
 
-    let repo_path = repo.path.clone();
     let target_branch = if let Some(branch) = request.target_branch {
         branch
     } else {
         workspace_repo.target_branch.clone()
     };
 
+    merge_verification::check_verified(pool, deployment.git(), &workspace, &repo, &target_branch)
+        .await?;
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -223,75 +251,154 @@ pub async fn create_pr(
     let workspace_path = PathBuf::from(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    let opened = match push_and_open_pr(
+        &deployment,
+        &workspace,
+        &repo,
+        &worktree_path,
+        &target_branch,
+        &request.title,
+        request.body.clone(),
+        request.draft,
+    )
+    .await?
+    {
+        Ok(opened) => opened,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error_with_data(e))),
+    };
+    let pr_info = opened.info;
+
+    // Update the workspace with PR information
+    if let Err(e) = Merge::create_pr(
+        pool,
+        workspace.id,
+        workspace_repo.repo_id,
+        &opened.base_branch,
+        pr_info.number,
+        &pr_info.url,
+    )
+    .await
+    {
+        tracing::error!("Failed to update workspace PR status: {}", e);
+    }
+
+    if let Ok(Some(task)) = workspace.parent_task(pool).await
+        && let Err(e) = workflow::handle_event(pool, &task, WorkflowEvent::PrOpened).await
+    {
+        tracing::warn!("Task {} status not updated on PR creation: {}", task.id, e);
+    }
+
+    // Auto-open PR in browser
+    if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
+        tracing::warn!("Failed to open PR in browser: {}", e);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_created",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "provider": format!("{:?}", opened.provider),
+            }),
+        )
+        .await;
+
+    // Trigger auto-description follow-up if enabled
+    if request.auto_generate_description
+        && let Err(e) =
+            trigger_pr_description_follow_up(&deployment, &workspace, pr_info.number, &pr_info.url)
+                .await
+    {
+        tracing::warn!(
+            "Failed to trigger PR description follow-up for attempt {}: {}",
+            workspace.id,
+            e
+        );
+    }
+
+    Ok(ResponseJson(ApiResponse::success(pr_info.url)))
+}
+
+/// A PR opened by [`push_and_open_pr`]
+struct OpenedPr {
+    info: PullRequestInfo,
+    /// The target branch without its remote
+    base_branch: String,
+    provider: ProviderKind,
+    git_host: git_host::GitHostService,
+}
+
+/// Push the workspace branch of `repo` and open a PR for it into `target_branch`.
+/// Problems the user can fix, such as a missing CLI or target branch, are returned as
+/// the inner error.
+#[allow(clippy::too_many_arguments)]
+async fn push_and_open_pr(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo: &Repo,
+    worktree_path: &Path,
+    target_branch: &str,
+    title: &str,
+    body: Option<String>,
+    draft: Option<bool>,
+) -> Result<Result<OpenedPr, PrError>, ApiError> {
+    let repo_path = &repo.path;
     let git = deployment.git();
-    let push_remote = git.resolve_remote_name_for_branch(&repo_path, &workspace.branch)?;
+    let push_remote = git.resolve_remote_name_for_branch(repo_path, &workspace.branch)?;
 
     // Try to get the remote from the branch name (works for remote-tracking branches like "upstream/main").
     // Fall back to push_remote if the branch doesn't exist locally or isn't a remote-tracking branch.
     let (target_remote, base_branch) =
-        match git.get_remote_name_from_branch_name(&repo_path, &target_branch) {
+        match git.get_remote_name_from_branch_name(repo_path, target_branch) {
             Ok(remote) => {
                 let branch = target_branch
                     .strip_prefix(&format!("{remote}/"))
-                    .unwrap_or(&target_branch);
+                    .unwrap_or(target_branch);
                 (remote, branch.to_string())
             }
-            Err(_) => (push_remote.clone(), target_branch.clone()),
+            Err(_) => (push_remote.clone(), target_branch.to_string()),
         };
 
-    let push_remote_url = git.get_remote_url(&repo_path, &push_remote)?;
-    let target_remote_url = git.get_remote_url(&repo_path, &target_remote)?;
+    let push_remote_url = git.get_remote_url(repo_path, &push_remote)?;
+    let target_remote_url = git.get_remote_url(repo_path, &target_remote)?;
 
-    match git.check_remote_branch_exists(&repo_path, &target_remote_url, &base_branch) {
+    match git.check_remote_branch_exists(repo_path, &target_remote_url, &base_branch) {
         Ok(false) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::TargetBranchNotFound {
-                    branch: target_branch.clone(),
-                },
-            )));
+            return Ok(Err(PrError::TargetBranchNotFound {
+                branch: target_branch.to_string(),
+            }));
         }
         Err(GitServiceError::GitCLI(GitCliError::AuthFailed(_))) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::GitCliNotLoggedIn,
-            )));
+            return Ok(Err(PrError::GitCliNotLoggedIn));
         }
         Err(GitServiceError::GitCLI(GitCliError::NotAvailable)) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::GitCliNotInstalled,
-            )));
+            return Ok(Err(PrError::GitCliNotInstalled));
         }
         Err(e) => return Err(ApiError::GitService(e)),
         Ok(true) => {}
     }
 
-    if let Err(e) = git.push_to_remote(&worktree_path, &workspace.branch, false) {
+    if let Err(e) = git.push_to_remote(worktree_path, &workspace.branch, false) {
         tracing::error!("Failed to push branch to remote: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    PrError::GitCliNotLoggedIn,
-                )));
+                return Ok(Err(PrError::GitCliNotLoggedIn));
             }
             GitServiceError::GitCLI(GitCliError::NotAvailable) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    PrError::GitCliNotInstalled,
-                )));
+                return Ok(Err(PrError::GitCliNotInstalled));
             }
             _ => return Err(ApiError::GitService(e)),
         }
     }
 
-    let git_host = match git_host::GitHostService::from_url(&target_remote_url) {
+    let git_hosts_config = deployment.config().read().await.git_hosts.clone();
+    let git_host = match git_host::GitHostService::from_url(&target_remote_url, &git_hosts_config) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::UnsupportedProvider,
-            )));
+            return Ok(Err(PrError::UnsupportedProvider));
         }
         Err(GitHostError::CliNotInstalled { provider }) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::CliNotInstalled { provider },
-            )));
+            return Ok(Err(PrError::CliNotInstalled { provider }));
         }
         Err(e) => return Err(ApiError::GitHost(e)),
     };
@@ -300,67 +407,24 @@ pub async fn create_pr(
 
     // Create the PR
     let pr_request = CreatePrRequest {
-        title: request.title.clone(),
-        body: request.body.clone(),
+        title: title.to_string(),
+        body,
         head_branch: workspace.branch.clone(),
         base_branch: base_branch.clone(),
-        draft: request.draft,
+        draft,
         head_repo_url: Some(push_remote_url),
     };
 
     match git_host
-        .create_pr(&repo_path, &target_remote_url, &pr_request)
+        .create_pr(repo_path, &target_remote_url, &pr_request)
         .await
     {
-        Ok(pr_info) => {
-            // Update the workspace with PR information
-            if let Err(e) = Merge::create_pr(
-                pool,
-                workspace.id,
-                workspace_repo.repo_id,
-                &base_branch,
-                pr_info.number,
-                &pr_info.url,
-            )
-            .await
-            {
-                tracing::error!("Failed to update workspace PR status: {}", e);
-            }
-
-            // Auto-open PR in browser
-            if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
-                tracing::warn!("Failed to open PR in browser: {}", e);
-            }
-
-            deployment
-                .track_if_analytics_allowed(
-                    "pr_created",
-                    serde_json::json!({
-                        "workspace_id": workspace.id.to_string(),
-                        "provider": format!("{:?}", provider),
-                    }),
-                )
-                .await;
-
-            // Trigger auto-description follow-up if enabled
-            if request.auto_generate_description
-                && let Err(e) = trigger_pr_description_follow_up(
-                    &deployment,
-                    &workspace,
-                    pr_info.number,
-                    &pr_info.url,
-                )
-                .await
-            {
-                tracing::warn!(
-                    "Failed to trigger PR description follow-up for attempt {}: {}",
-                    workspace.id,
-                    e
-                );
-            }
-
-            Ok(ResponseJson(ApiResponse::success(pr_info.url)))
-        }
+        Ok(info) => Ok(Ok(OpenedPr {
+            info,
+            base_branch,
+            provider,
+            git_host,
+        })),
         Err(e) => {
             tracing::error!(
                 "Failed to create PR for attempt {} using {:?}: {}",
@@ -369,20 +433,160 @@ pub async fn create_pr(
                 e
             );
             match &e {
-                GitHostError::CliNotInstalled { provider } => Ok(ResponseJson(
-                    ApiResponse::error_with_data(PrError::CliNotInstalled {
-                        provider: *provider,
-                    }),
-                )),
-                GitHostError::AuthFailed(_) => Ok(ResponseJson(ApiResponse::error_with_data(
-                    PrError::CliNotLoggedIn { provider },
-                ))),
+                GitHostError::CliNotInstalled { provider } => Ok(Err(PrError::CliNotInstalled {
+                    provider: *provider,
+                })),
+                GitHostError::AuthFailed(_) => Ok(Err(PrError::CliNotLoggedIn { provider })),
                 _ => Err(ApiError::GitHost(e)),
             }
         }
     }
 }
 
+/// Open a PR for every repo of the workspace with changes. Every repo is checked
+/// before any branch is pushed, and each PR's description links to the others.
+pub async fn create_workspace_prs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<CreateWorkspacePrsRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspacePr>, WorkspacePrError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    workflow::check_event(pool, &task, WorkflowEvent::PrOpened).await?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let changes = workspace_merge::repos_with_changes(
+        deployment.git(),
+        &workspace.branch,
+        Path::new(&container_ref),
+        repos,
+    )?;
+    if changes.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            WorkspacePrError::NoChanges,
+        )));
+    }
+    let problems =
+        workspace_merge::preflight(pool, deployment.git(), &workspace, &changes, false).await;
+    if !problems.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            WorkspacePrError::PreflightFailed { problems },
+        )));
+    }
+
+    let mut opened: Vec<(WorkspacePr, git_host::GitHostService)> = Vec::new();
+    for change in &changes {
+        let result = push_and_open_pr(
+            &deployment,
+            &workspace,
+            &change.repo,
+            &change.worktree_path,
+            &change.target_branch,
+            &request.title,
+            request.body.clone(),
+            request.draft,
+        )
+        .await;
+        let pr = match result {
+            Ok(Ok(pr)) => pr,
+            Ok(Err(error)) => {
+                link_workspace_prs(&opened, request.body.as_deref()).await;
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    WorkspacePrError::PrFailed {
+                        repo_id: change.repo.id,
+                        repo_name: change.repo.display_name.clone(),
+                        error,
+                        opened: opened.into_iter().map(|(pr, _)| pr).collect(),
+                    },
+                )));
+            }
+            Err(e) => {
+                link_workspace_prs(&opened, request.body.as_deref()).await;
+                return Err(e);
+            }
+        };
+
+        if let Err(e) = Merge::create_pr(
+            pool,
+            workspace.id,
+            change.repo.id,
+            &pr.base_branch,
+            pr.info.number,
+            &pr.info.url,
+        )
+        .await
+        {
+            tracing::error!("Failed to update workspace PR status: {}", e);
+        }
+        opened.push((
+            WorkspacePr {
+                repo_id: change.repo.id,
+                repo_name: change.repo.display_name.clone(),
+                pr_number: pr.info.number,
+                pr_url: pr.info.url,
+            },
+            pr.git_host,
+        ));
+    }
+    link_workspace_prs(&opened, request.body.as_deref()).await;
+
+    if let Err(e) = workflow::handle_event(pool, &task, WorkflowEvent::PrOpened).await {
+        tracing::warn!("Task {} status not updated on PR creation: {}", task.id, e);
+    }
+
+    let prs: Vec<WorkspacePr> = opened.into_iter().map(|(pr, _)| pr).collect();
+    for pr in &prs {
+        if let Err(e) = utils::browser::open_browser(&pr.pr_url).await {
+            tracing::warn!("Failed to open PR in browser: {}", e);
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_prs_created",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_count": prs.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(prs)))
+}
+
+/// Add links to the workspace's other PRs to each PR's description
+async fn link_workspace_prs(
+    opened: &[(WorkspacePr, git_host::GitHostService)],
+    body: Option<&str>,
+) {
+    if opened.len() < 2 {
+        return;
+    }
+    let prs: Vec<WorkspacePr> = opened.iter().map(|(pr, _)| pr.clone()).collect();
+    for (pr, git_host) in opened {
+        let description = workspace_merge::body_with_linked_prs(body, &pr.pr_url, &prs);
+        if let Err(e) = git_host
+            .update_pr_description(&pr.pr_url, &description)
+            .await
+        {
+            tracing::warn!(
+                "Failed to link PR {} to the workspace's other PRs: {}",
+                pr.pr_url,
+                e
+            );
+        }
+    }
+}
+
 pub async fn attach_existing_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -421,7 +625,8 @@ pub async fn attach_existing_pr(
         &git.resolve_remote_name_for_branch(&repo.path, &workspace_repo.target_branch)?,
     )?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote_url) {
+    let git_hosts_config = deployment.config().read().await.git_hosts.clone();
+    let git_host = match git_host::GitHostService::from_url(&remote_url, &git_hosts_config) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -481,26 +686,19 @@ pub async fn attach_existing_pr(
             .await?;
         }
 
-        // If PR is merged, check approval gate then mark task as done
-        if matches!(pr_info.status, MergeStatus::Merged) {
-            if task.status == TaskStatus::InReview {
-                let project = db::models::project::Project::find_by_id(pool, task.project_id)
-                    .await?
-                    .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
-
-                let approval_count =
-                    db::models::task_approval::TaskApproval::count_by_task_id(pool, task.id)
-                        .await?;
-
-                if approval_count < project.min_approvals_required {
-                    return Err(ApiError::BadRequest(format!(
-                        "Task requires {} approval(s) to complete, but has {}",
-                        project.min_approvals_required, approval_count
-                    )));
-                }
+        // Apply the workflow's transition for the PR's state; a merged PR must pass its guards
+        match pr_info.status {
+            MergeStatus::Open => {
+                workflow::handle_event(pool, &task, WorkflowEvent::PrOpened).await?;
+            }
+            MergeStatus::Merged => {
+                workflow::handle_event(pool, &task, WorkflowEvent::PrMerged).await?;
+                task_dependencies::spawn_start_unblocked_tasks(&deployment);
             }
+            _ => {}
+        }
 
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
+        if matches!(pr_info.status, MergeStatus::Merged) {
             if !workspace.pinned {
                 Workspace::set_archived(pool, workspace.id, true).await?;
             }
@@ -558,7 +756,8 @@ pub async fn get_pr_comments(
         &git.resolve_remote_name_for_branch(&repo.path, &workspace_repo.target_branch)?,
     )?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote_url) {
+    let git_hosts_config = deployment.config().read().await.git_hosts.clone();
+    let git_host = match git_host::GitHostService::from_url(&remote_url, &git_hosts_config) {
         Ok(host) => host,
         Err(GitHostError::CliNotInstalled { provider }) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
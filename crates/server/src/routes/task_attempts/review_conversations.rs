@@ -20,11 +20,15 @@ use db::models::{
         ReviewConversation, ReviewConversationError, ReviewConversationMessage,
         load_conversation_with_messages, load_conversations_with_messages,
     },
+    task::Task,
     workspace::Workspace,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use services::services::{
+    config::NotificationEvent, container::ContainerService, notification::Notification,
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -32,21 +36,21 @@ use uuid::Uuid;
 use crate::{DeploymentImpl, error::ApiError, middleware::get_user_id_from_headers};
 
 /// Response for creating a conversation (includes the initial message)
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct CreateConversationResponse {
     pub conversation: ConversationWithMessages,
 }
 
 /// Response for adding a message
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct AddMessageResponse {
     pub conversation: ConversationWithMessages,
 }
 
 /// Response for resolving a conversation
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ResolveConversationResponse {
     pub conversation: ConversationWithMessages,
@@ -209,6 +213,24 @@ pub async fn create_conversation(
                 )
                 .await;
 
+            let mut notification = Notification::new(
+                NotificationEvent::ReviewConversationOpened,
+                format!("Review Comment: {}", payload.file_path),
+                format!(
+                    "{}:{}\n{}",
+                    payload.file_path, payload.line_number, payload.initial_message
+                ),
+            )
+            .with_workspace(workspace.id);
+            if let Ok(Some(task)) = Task::find_by_id(pool, workspace.task_id).await {
+                notification = notification.with_task(&task);
+            }
+            deployment
+                .container()
+                .notification_service()
+                .notify(notification)
+                .await;
+
             Ok(ResponseJson(ApiResponse::success(
                 CreateConversationResponse {
                     conversation: full_conversation,
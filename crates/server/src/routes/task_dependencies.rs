@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::{delete, get, put},
+};
+use db::models::{
+    task::Task,
+    task_dependency::{
+        CreateTaskDependency, TaskAutoStart, TaskDependencies, TaskDependency, UpsertTaskAutoStart,
+    },
+};
+use deployment::Deployment;
+use services::services::container::ContainerService;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::try_get_authenticated_user};
+
+/// How often auto-starts are re-checked, to catch blockers finished outside the app
+/// (e.g. a PR merged on GitHub and picked up by the PR monitor)
+const AUTO_START_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Start any auto-start tasks that just became unblocked, without holding up the caller
+pub fn spawn_start_unblocked_tasks(deployment: &DeploymentImpl) {
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        if let Err(e) = deployment.container().start_unblocked_tasks().await {
+            tracing::error!("Failed to start unblocked tasks: {}", e);
+        }
+    });
+}
+
+/// Periodically start auto-start tasks whose blockers have finished
+pub fn spawn_auto_start_scheduler(deployment: &DeploymentImpl) -> tokio::task::JoinHandle<()> {
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(AUTO_START_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = deployment.container().start_unblocked_tasks().await {
+                tracing::error!("Failed to start unblocked tasks: {}", e);
+            }
+        }
+    })
+}
+
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let dependencies = TaskDependency::find_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+/// Mark the task as blocked by another task in the same project
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let pool = &deployment.db().pool;
+    TaskDependency::create(pool, &task, payload.blocked_by_task_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_added",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "blocked_by_task_id": payload.blocked_by_task_id.to_string(),
+            }),
+        )
+        .await;
+
+    let dependencies = TaskDependency::find_for_task(pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+pub async fn remove_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_task_id, blocked_by_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if TaskDependency::delete(pool, task.id, blocked_by_task_id).await? == 0 {
+        return Err(ApiError::BadRequest(
+            "Task is not blocked by that task".to_string(),
+        ));
+    }
+    // Removing the last open blocker may release an auto-start
+    spawn_start_unblocked_tasks(&deployment);
+
+    let dependencies = TaskDependency::find_for_task(pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+/// Start a workspace for the task with the given settings once its last blocker is done
+pub async fn set_task_auto_start(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Json(payload): Json<UpsertTaskAutoStart>,
+) -> Result<ResponseJson<ApiResponse<TaskAutoStart>>, ApiError> {
+    let user = try_get_authenticated_user(&deployment, &headers).await;
+    let auto_start = TaskAutoStart::upsert(
+        &deployment.db().pool,
+        task.id,
+        &payload,
+        user.as_ref().map(|u| u.id),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(auto_start)))
+}
+
+pub async fn delete_task_auto_start(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskAutoStart::delete(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(add_task_dependency),
+        )
+        .route(
+            "/dependencies/{blocked_by_task_id}",
+            delete(remove_task_dependency),
+        )
+        .route(
+            "/auto-start",
+            put(set_task_auto_start).delete(delete_task_auto_start),
+        )
+}
@@ -13,11 +13,10 @@ use axum::{
     routing::{delete, get, post, put},
 };
 use db::models::{
-    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
-    execution_process_logs::ExecutionProcessLogs,
+    audit_event::{AuditAction, AuditEvent, AuditTarget},
     image::TaskImage,
+    project_member::ProjectRole,
     repo::{Repo, RepoError},
-    session::Session,
     task::{
         CreateTask, Task, TaskHoldInfo, TaskUser, TaskWithAttemptStatus, TaskWithUsers, UpdateTask,
     },
@@ -25,24 +24,25 @@ use db::models::{
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
-use executors::{
-    logs::{NormalizedEntry, NormalizedEntryType, utils::patch::ConversationPatch},
-    profile::ExecutorProfileId,
-};
+use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService, share::ShareError, workspace_manager::WorkspaceManager,
+    container::ContainerService, share::ShareError, system_message, workflow,
+    workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::{api::oauth::LoginStatus, log_msg::LogMsg, response::ApiResponse};
+use utils::{api::oauth::LoginStatus, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
     error::ApiError,
-    middleware::{get_user_id, load_task_middleware, try_get_authenticated_user},
+    middleware::{
+        authorize_project_writes, get_user_id, load_task_middleware, record_audit_event,
+        require_project_role, try_get_authenticated_user,
+    },
     routes::task_attempts::WorkspaceRepoInput,
 };
 
@@ -130,6 +130,14 @@ pub async fn create_task(
         payload.project_id
     );
 
+    require_project_role(
+        &deployment,
+        &headers,
+        payload.project_id,
+        ProjectRole::Contributor,
+    )
+    .await?;
+
     let authenticated_user = try_get_authenticated_user(&deployment, &headers).await;
     let creator_user_id = get_user_id(&authenticated_user);
 
@@ -139,6 +147,15 @@ pub async fn create_task(
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::TaskCreated,
+        AuditTarget::task(task.project_id, task.id),
+        serde_json::json!({ "title": task.title, "status": task.status }),
+    )
+    .await;
+
     deployment
         .track_if_analytics_allowed(
             "task_created",
@@ -173,6 +190,14 @@ pub async fn create_task_and_start(
         ));
     }
 
+    require_project_role(
+        &deployment,
+        &headers,
+        payload.task.project_id,
+        ProjectRole::Contributor,
+    )
+    .await?;
+
     let pool = &deployment.db().pool;
 
     let authenticated_user = try_get_authenticated_user(&deployment, &headers).await;
@@ -185,6 +210,15 @@ pub async fn create_task_and_start(
         TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
     }
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::TaskCreated,
+        AuditTarget::task(task.project_id, task.id),
+        serde_json::json!({ "title": task.title, "status": task.status }),
+    )
+    .await;
+
     deployment
         .track_if_analytics_allowed(
             "task_created",
@@ -237,6 +271,19 @@ pub async fn create_task_and_start(
         .collect();
     WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::WorkspaceCreated,
+        AuditTarget::workspace(task.project_id, task.id, workspace.id),
+        serde_json::json!({
+            "branch": workspace.branch,
+            "executor_profile_id": payload.executor_profile_id,
+            "repos": workspace_repos,
+        }),
+    )
+    .await;
+
     let is_attempt_running = deployment
         .container()
         .start_workspace(&workspace, payload.executor_profile_id.clone())
@@ -278,9 +325,20 @@ pub async fn create_task_and_start(
 pub async fn update_task(
     Extension(existing_task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateTask>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithUsers>>, ApiError> {
     ensure_shared_task_auth(&existing_task, &deployment).await?;
+    let before = existing_task.clone();
+
+    // Status changes go through the project workflow's transitions and guards
+    let status = match payload.status {
+        Some(to) if to != existing_task.status => {
+            workflow::check_transition(&deployment.db().pool, &existing_task, &to).await?;
+            to
+        }
+        _ => existing_task.status.clone(),
+    };
 
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
@@ -289,7 +347,6 @@ pub async fn update_task(
         Some(s) => Some(s),                     // Non-empty string = update description
         None => existing_task.description,      // Field omitted = keep existing
     };
-    let status = existing_task.status;
     let parent_workspace_id = payload
         .parent_workspace_id
         .or(existing_task.parent_workspace_id);
@@ -317,6 +374,15 @@ pub async fn update_task(
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::TaskUpdated,
+        AuditTarget::task(task.project_id, task.id),
+        AuditEvent::diff(&before, &task),
+    )
+    .await;
+
     // If task has been shared, broadcast update
     if task.shared_task_id.is_some() {
         let Ok(publisher) = deployment.share_publisher() else {
@@ -333,6 +399,7 @@ pub async fn update_task(
 pub async fn delete_task(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
 ) -> Result<(StatusCode, ResponseJson<ApiResponse<()>>), ApiError> {
     let pool = &deployment.db().pool;
 
@@ -387,6 +454,19 @@ pub async fn delete_task(
         );
     }
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::TaskDeleted,
+        AuditTarget::task(task.project_id, task.id),
+        serde_json::json!({
+            "title": task.title,
+            "status": task.status,
+            "workspace_ids": attempts.iter().map(|w| w.id).collect::<Vec<_>>(),
+        }),
+    )
+    .await;
+
     deployment
         .track_if_analytics_allowed(
             "task_deleted",
@@ -460,50 +540,7 @@ async fn add_hold_message_to_history(
     task_id: Uuid,
     message: &str,
 ) -> Result<(), ApiError> {
-    // Get the latest workspace for this task
-    let workspaces = Workspace::fetch_all(pool, Some(task_id)).await?;
-    let Some(workspace) = workspaces.first() else {
-        // No workspace yet - no chat history to add to
-        return Ok(());
-    };
-
-    // Get the latest session for this workspace
-    let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await? else {
-        // No session yet - no chat history to add to
-        return Ok(());
-    };
-
-    // Get the latest coding agent execution process for this session
-    let Some(execution_process) = ExecutionProcess::find_latest_by_session_and_run_reason(
-        pool,
-        session.id,
-        &ExecutionProcessRunReason::CodingAgent,
-    )
-    .await?
-    else {
-        // No execution process yet - no chat history to add to
-        return Ok(());
-    };
-
-    // Create a system message entry
-    let entry = NormalizedEntry {
-        timestamp: Some(chrono::Utc::now().to_rfc3339()),
-        entry_type: NormalizedEntryType::SystemMessage,
-        content: message.to_string(),
-        metadata: None,
-    };
-
-    // Create a patch to add the entry
-    let patch = ConversationPatch::add_normalized_entry(999, entry);
-    if let Ok(json_line) = serde_json::to_string::<LogMsg>(&LogMsg::JsonPatch(patch)) {
-        let _ = ExecutionProcessLogs::append_log_line(
-            pool,
-            execution_process.id,
-            &format!("{json_line}\n"),
-        )
-        .await;
-    }
-
+    system_message::append_to_task_history(pool, task_id, message).await?;
     Ok(())
 }
 
@@ -558,6 +595,15 @@ pub async fn place_hold(
         held_at: chrono::Utc::now(),
     };
 
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::TaskHoldPlaced,
+        AuditTarget::task(task.project_id, task.id),
+        serde_json::json!({ "comment": comment }),
+    )
+    .await;
+
     // Add hold message to chat history
     let user_display = user
         .as_ref()
@@ -594,6 +640,24 @@ pub async fn release_hold(
 
     Task::release_hold(&deployment.db().pool, task.id).await?;
 
+    // The target is whoever placed the hold, so releasing someone else's hold stands out
+    let target = AuditTarget::task(task.project_id, task.id);
+    record_audit_event(
+        &deployment,
+        &headers,
+        AuditAction::TaskHoldReleased,
+        match task.hold_user_id {
+            Some(holder) => target.with_target(holder),
+            None => target,
+        },
+        serde_json::json!({
+            "hold_user_id": task.hold_user_id,
+            "hold_comment": task.hold_comment,
+            "hold_at": task.hold_at,
+        }),
+    )
+    .await;
+
     // Add release message to chat history
     let user_display = user
         .as_ref()
@@ -615,11 +679,16 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", delete(delete_task))
         .route("/hold", put(place_hold))
         .route("/hold", delete(release_hold))
-        .nest("/task-approvals", super::task_approvals::router());
+        .nest("/task-approvals", super::task_approvals::router())
+        .merge(super::task_dependencies::router());
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
         .merge(task_actions_router)
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authorize_project_writes,
+        ))
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
     let inner = Router::new()
@@ -6,21 +6,27 @@ use axum::{
         Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
+    http::{HeaderMap, HeaderValue, header::AUTHORIZATION},
     response::IntoResponse,
     routing::get,
 };
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
-use db::models::{workspace::Workspace, workspace_repo::WorkspaceRepo};
+use db::models::{
+    project_member::ProjectRole, workspace::Workspace, workspace_repo::WorkspaceRepo,
+};
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::require_workspace_role};
 
 #[derive(Debug, Deserialize)]
 pub struct TerminalQuery {
     pub workspace_id: Uuid,
+    /// Session token for multi-user servers, since browsers cannot set headers on a
+    /// websocket upgrade
+    pub token: Option<String>,
     #[serde(default = "default_cols")]
     pub cols: u16,
     #[serde(default = "default_rows")]
@@ -52,12 +58,23 @@ enum TerminalMessage {
 pub async fn terminal_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    mut headers: HeaderMap,
     Query(query): Query<TerminalQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let attempt = Workspace::find_by_id(&deployment.db().pool, query.workspace_id)
         .await?
         .ok_or_else(|| ApiError::BadRequest("Attempt not found".to_string()))?;
 
+    if let Some(token) = &query.token
+        && !headers.contains_key(AUTHORIZATION)
+    {
+        let value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .map_err(|_| ApiError::Unauthorized)?;
+        headers.insert(AUTHORIZATION, value);
+    }
+    // A shell in the worktree can do anything an agent can
+    require_workspace_role(&deployment, &headers, attempt.id, ProjectRole::Contributor).await?;
+
     let container_ref = attempt
         .container_ref
         .ok_or_else(|| ApiError::BadRequest("Attempt has no workspace directory".to_string()))?;
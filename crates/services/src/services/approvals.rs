@@ -9,7 +9,8 @@ use std::{
 use dashmap::DashMap;
 use db::models::{
     execution_process::ExecutionProcess,
-    task::{Task, TaskStatus},
+    project_workflow::WorkflowEvent,
+    tool_approval::{CreateToolApproval, ToolApproval, ToolApprovalResponse, ToolApprovalStatus},
 };
 use executors::{
     approvals::ToolCallMetadata,
@@ -29,6 +30,8 @@ use utils::{
 };
 use uuid::Uuid;
 
+use crate::services::workflow;
+
 #[derive(Debug)]
 struct PendingApproval {
     entry_index: usize,
@@ -48,6 +51,7 @@ pub struct ToolContext {
 
 #[derive(Clone)]
 pub struct Approvals {
+    pool: SqlitePool,
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalStatus>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
@@ -70,8 +74,9 @@ pub enum ApprovalError {
 }
 
 impl Approvals {
-    pub fn new(msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
+    pub fn new(pool: SqlitePool, msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
         Self {
+            pool,
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             msg_stores,
@@ -88,6 +93,7 @@ impl Approvals {
             .boxed()
             .shared();
         let req_id = request.id.clone();
+        record_request(&self.pool, &request).await;
 
         if let Some(store) = self.msg_store_by_id(&request.execution_process_id).await {
             // Find the matching tool use entry by name and input
@@ -137,16 +143,20 @@ impl Approvals {
         Ok((request, waiter))
     }
 
+    /// Answer a pending approval on behalf of `responded_by_user_id` (`None` on
+    /// single-user servers)
     #[tracing::instrument(skip(self, id, req))]
     pub async fn respond(
         &self,
-        pool: &SqlitePool,
         id: &str,
         req: ApprovalResponse,
+        responded_by_user_id: Option<Uuid>,
     ) -> Result<(ApprovalStatus, ToolContext), ApprovalError> {
+        let pool = &self.pool;
         if let Some((_, p)) = self.pending.remove(id) {
             self.completed.insert(id.to_string(), req.status.clone());
             let _ = p.response_tx.send(req.status.clone());
+            record_response(pool, id, &req.status, responded_by_user_id, None).await;
 
             if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
                 let status = ToolStatus::from_approval_status(&req.status).ok_or(
@@ -170,17 +180,17 @@ impl Approvals {
                 execution_process_id: p.execution_process_id,
             };
 
-            // If approved or denied, and task is still InReview, move back to InProgress
+            // If approved or denied, apply the workflow's approval-responded transition
             if matches!(
                 req.status,
                 ApprovalStatus::Approved | ApprovalStatus::Denied { .. }
             ) && let Ok(ctx) =
                 ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
-                && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
+                && let Err(e) =
+                    workflow::handle_event(pool, &ctx.task, WorkflowEvent::ApprovalResponded).await
             {
                 tracing::warn!(
-                    "Failed to update task status to InProgress after approval response: {}",
+                    "Failed to update task status after approval response: {}",
                     e
                 );
             }
@@ -200,6 +210,7 @@ impl Approvals {
         timeout_at: chrono::DateTime<chrono::Utc>,
         waiter: ApprovalWaiter,
     ) {
+        let pool = self.pool.clone();
         let pending = self.pending.clone();
         let completed = self.completed.clone();
         let msg_stores = self.msg_stores.clone();
@@ -225,6 +236,7 @@ impl Approvals {
                 if pending_approval.response_tx.send(status.clone()).is_err() {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
                 }
+                record_response(&pool, &id, &status, None, None).await;
 
                 let store = {
                     let map = msg_stores.read().await;
@@ -263,18 +275,18 @@ impl Approvals {
 
     pub(crate) async fn cancel(&self, id: &str) {
         if let Some((_, pending_approval)) = self.pending.remove(id) {
-            self.completed.insert(
-                id.to_string(),
-                ApprovalStatus::Denied {
-                    reason: Some("Cancelled".to_string()),
-                },
-            );
+            let status = ApprovalStatus::Denied {
+                reason: Some("Cancelled".to_string()),
+            };
+            self.completed.insert(id.to_string(), status.clone());
+            record_response(&self.pool, id, &status, None, None).await;
 
             if let Some(store) = self
                 .msg_store_by_id(&pending_approval.execution_process_id)
                 .await
                 && let Some(entry) = pending_approval.entry.with_tool_status(ToolStatus::Denied {
                     reason: Some("Cancelled".to_string()),
+                    rule: None,
                 })
             {
                 store.push_patch(ConversationPatch::replace(
@@ -287,6 +299,72 @@ impl Approvals {
         }
     }
 
+    /// Mark a tool call as decided by an approval policy, so the conversation and the
+    /// approval history show which rule let it run or refused it
+    pub async fn record_policy_decision(
+        &self,
+        request: &ApprovalRequest,
+        status: &ApprovalStatus,
+        rule: &str,
+    ) {
+        record_request(&self.pool, request).await;
+        record_response(
+            &self.pool,
+            &request.id,
+            status,
+            None,
+            Some(rule.to_string()),
+        )
+        .await;
+
+        let tool_status = match status {
+            ApprovalStatus::Denied { reason } => ToolStatus::Denied {
+                reason: reason.clone(),
+                rule: Some(rule.to_string()),
+            },
+            _ => ToolStatus::AutoApproved {
+                rule: rule.to_string(),
+            },
+        };
+        let execution_process_id = request.execution_process_id;
+        let tool_call_id = request.tool_call_id.as_str();
+        let Some(store) = self.msg_store_by_id(&execution_process_id).await else {
+            tracing::warn!(
+                "No msg_store found for execution_process_id: {}",
+                execution_process_id
+            );
+            return;
+        };
+        match find_matching_tool_use(store.clone(), tool_call_id)
+            .and_then(|(idx, entry)| Some((idx, entry.with_tool_status(tool_status)?)))
+        {
+            Some((idx, entry)) => store.push_patch(ConversationPatch::replace(idx, entry)),
+            None => tracing::warn!(
+                "No matching tool use entry found for policy decision: tool_call_id='{}', execution_process_id={}",
+                tool_call_id,
+                execution_process_id
+            ),
+        }
+    }
+
+    /// Close approvals left pending by a previous run of the server. Their waiters died
+    /// with it, and the agents that asked were children of that process and could not
+    /// receive an answer, so they are recorded as expired.
+    pub async fn expire_orphaned(&self) {
+        match ToolApproval::expire_pending(&self.pool).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Expired {} tool approvals left pending", count),
+            Err(e) => tracing::error!("Failed to expire pending tool approvals: {}", e),
+        }
+    }
+
+    /// The execution process a pending approval belongs to
+    pub fn pending_execution_process_id(&self, id: &str) -> Option<Uuid> {
+        self.pending
+            .get(id)
+            .map(|pending| pending.execution_process_id)
+    }
+
     /// Check which execution processes have pending approvals.
     /// Returns a set of execution_process_ids that have at least one pending approval.
     pub fn get_pending_execution_process_ids(
@@ -308,15 +386,53 @@ impl Approvals {
     }
 }
 
+/// Persist a new approval request. The in-memory state stays authoritative for the
+/// running agent, so a failed write is only logged.
+async fn record_request(pool: &SqlitePool, request: &ApprovalRequest) {
+    let data = CreateToolApproval {
+        id: request.id.clone(),
+        execution_process_id: request.execution_process_id,
+        tool_name: request.tool_name.clone(),
+        tool_call_id: request.tool_call_id.clone(),
+        tool_input: request.tool_input.clone(),
+        created_at: request.created_at,
+        timeout_at: request.timeout_at,
+    };
+    if let Err(e) = ToolApproval::create(pool, &data).await {
+        tracing::error!("Failed to record tool approval {}: {}", request.id, e);
+    }
+}
+
+async fn record_response(
+    pool: &SqlitePool,
+    id: &str,
+    status: &ApprovalStatus,
+    responded_by_user_id: Option<Uuid>,
+    policy_rule: Option<String>,
+) {
+    let (status, reason) = match status {
+        ApprovalStatus::Pending => return,
+        ApprovalStatus::Approved => (ToolApprovalStatus::Approved, None),
+        ApprovalStatus::Denied { reason } => (ToolApprovalStatus::Denied, reason.clone()),
+        ApprovalStatus::TimedOut => (ToolApprovalStatus::TimedOut, None),
+    };
+    let response = ToolApprovalResponse {
+        status,
+        reason,
+        responded_by_user_id,
+        policy_rule,
+    };
+    if let Err(e) = ToolApproval::respond(pool, id, &response).await {
+        tracing::error!("Failed to record response to tool approval {}: {}", id, e);
+    }
+}
+
 pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_id: Uuid) {
     if let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
-        && ctx.task.status == TaskStatus::InProgress
-        && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InReview).await
+        && let Err(e) =
+            workflow::handle_event(pool, &ctx.task, WorkflowEvent::ApprovalRequested).await
     {
-        tracing::warn!(
-            "Failed to update task status to InReview for approval request: {}",
-            e
-        );
+        tracing::warn!("Failed to update task status for approval request: {}", e);
     }
 }
 
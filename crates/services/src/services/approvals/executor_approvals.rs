@@ -1,14 +1,22 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use db::{self, DBService, models::execution_process::ExecutionProcess};
+use db::{
+    self, DBService,
+    models::{approval_policy::ApprovalDecision, execution_process::ExecutionProcess},
+};
 use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
 use serde_json::Value;
 use tokio_util::sync::CancellationToken;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approval_policy::{self, PolicyDecision},
+    approvals::Approvals,
+    config::NotificationEvent,
+    notification::{Notification, NotificationService},
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
@@ -42,8 +50,17 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_call_id: &str,
         cancel: CancellationToken,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
-
+        let policy = approval_policy::decide(
+            &self.db.pool,
+            self.execution_process_id,
+            tool_name,
+            &tool_input,
+        )
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to evaluate approval policy, asking instead: {}", e);
+            PolicyDecision::ask()
+        });
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
                 tool_name: tool_name.to_string(),
@@ -53,6 +70,27 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             self.execution_process_id,
         );
 
+        let rule = policy.rule.unwrap_or_else(|| "default".to_string());
+        let status = match policy.decision {
+            ApprovalDecision::Allow => Some(ApprovalStatus::Approved),
+            ApprovalDecision::Deny => Some(ApprovalStatus::Denied {
+                reason: Some(
+                    policy
+                        .reason
+                        .unwrap_or_else(|| format!("Denied by approval policy rule '{rule}'")),
+                ),
+            }),
+            ApprovalDecision::Ask => None,
+        };
+        if let Some(status) = status {
+            self.approvals
+                .record_policy_decision(&request, &status, &rule)
+                .await;
+            return Ok(status);
+        }
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+
         let (request, waiter) = self
             .approvals
             .create_with_waiter(request)
@@ -61,17 +99,25 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
 
         let approval_id = request.id.clone();
 
-        let task_name = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
+        let ctx = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
             .await
-            .map(|ctx| ctx.task.title)
-            .unwrap_or_else(|_| "Unknown task".to_string());
+            .ok();
+        let task_name = ctx
+            .as_ref()
+            .map(|ctx| ctx.task.title.clone())
+            .unwrap_or_else(|| "Unknown task".to_string());
 
-        self.notification_service
-            .notify(
-                &format!("Approval Needed: {}", task_name),
-                &format!("Tool '{}' requires approval", tool_name),
-            )
-            .await;
+        let mut notification = Notification::new(
+            NotificationEvent::ApprovalPending,
+            format!("Approval Needed: {}", task_name),
+            format!("Tool '{}' requires approval", tool_name),
+        );
+        if let Some(ctx) = &ctx {
+            notification = notification
+                .with_task(&ctx.task)
+                .with_workspace(ctx.workspace.id);
+        }
+        self.notification_service.notify(notification).await;
 
         let status = tokio::select! {
             _ = cancel.cancelled() => {
@@ -1,10 +1,13 @@
 //! Claude Code OAuth token rotation service.
 //!
 //! Manages rotation of Claude Code OAuth tokens across multiple users
-//! using a round-robin strategy (least recently used first).
+//! using a round-robin strategy (least recently used first). Tokens that hit
+//! their usage limit cool down until the limit resets, and tokens rejected by
+//! Claude are taken out of rotation until the user replaces them.
 
 use std::sync::Arc;
 
+use chrono::{DateTime, Duration, Utc};
 use db::{
     DBService,
     models::{
@@ -16,10 +19,12 @@ use db::{
     },
 };
 use thiserror::Error;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::services::token_encryption::{TokenCipher, TokenEncryptionError};
+
 #[derive(Debug, Error)]
 pub enum TokenRotationError {
     #[error("No tokens available for rotation")]
@@ -32,33 +37,30 @@ pub enum TokenRotationError {
     Database(#[from] sqlx::Error),
     #[error(transparent)]
     TokenError(#[from] ClaudeOAuthTokenError),
+    #[error(transparent)]
+    KeyError(#[from] TokenEncryptionError),
 }
 
-/// Simple token encoding/decoding for storage.
-/// In a controlled environment, we use base64 encoding with a version prefix.
-/// For production with untrusted storage, replace with proper encryption (AES-GCM).
-mod token_encoding {
-    use base64::{Engine as _, engine::general_purpose::STANDARD};
+/// Cooldown applied when Claude reports a usage limit without a reset time
+const DEFAULT_RATE_LIMIT_COOLDOWN: Duration = Duration::hours(1);
 
-    const VERSION_PREFIX: &str = "v1:";
+/// How long a token sits out after the API throttled it
+const RATE_LIMIT_BACKOFF: Duration = Duration::minutes(1);
 
-    pub fn encode(plaintext: &str) -> String {
-        format!("{}{}", VERSION_PREFIX, STANDARD.encode(plaintext))
-    }
+/// A decrypted token handed out for a single execution
+#[derive(Debug, Clone)]
+pub struct RotatedToken {
+    pub id: Uuid,
+    pub token: String,
+}
 
-    pub fn decode(encoded: &str) -> Result<String, String> {
-        if let Some(b64) = encoded.strip_prefix(VERSION_PREFIX) {
-            STANDARD
-                .decode(b64)
-                .map_err(|e| format!("base64 decode error: {e}"))
-                .and_then(|bytes| {
-                    String::from_utf8(bytes).map_err(|e| format!("utf8 decode error: {e}"))
-                })
-        } else {
-            // Legacy: assume raw token (for backwards compatibility during migration)
-            Ok(encoded.to_string())
-        }
-    }
+/// Outcome of re-encrypting the stored tokens
+#[derive(Debug, Default)]
+pub struct ReencryptReport {
+    /// Number of tokens rewritten with the current primary key
+    pub reencrypted: usize,
+    /// Tokens that no key in the ring could decrypt; they are left untouched
+    pub failed: Vec<Uuid>,
 }
 
 /// Service for managing Claude Code OAuth token rotation
@@ -67,19 +69,22 @@ pub struct ClaudeTokenRotationService {
     db: Arc<DBService>,
     /// Lock to ensure atomic rotation (prevents two concurrent requests getting same token)
     rotation_lock: Arc<Mutex<()>>,
+    /// Key ring used to encrypt tokens at rest; replaced when the key is rotated
+    cipher: Arc<RwLock<TokenCipher>>,
 }
 
 impl ClaudeTokenRotationService {
-    pub fn new(db: Arc<DBService>) -> Self {
+    pub fn new(db: Arc<DBService>, cipher: TokenCipher) -> Self {
         Self {
             db,
             rotation_lock: Arc::new(Mutex::new(())),
+            cipher: Arc::new(RwLock::new(cipher)),
         }
     }
 
     /// Get the next token to use, implementing round-robin rotation.
-    /// Returns None if no valid tokens are available.
-    pub async fn get_next_token(&self) -> Result<Option<String>, TokenRotationError> {
+    /// Returns None if no healthy tokens are available.
+    pub async fn get_next_token(&self) -> Result<Option<RotatedToken>, TokenRotationError> {
         let _lock = self.rotation_lock.lock().await;
 
         // Get next token using round-robin (least recently used)
@@ -89,9 +94,13 @@ impl ClaudeTokenRotationService {
             // Mark as used
             ClaudeOAuthToken::mark_used(&self.db.pool, token.id).await?;
 
-            // Decode and return
-            let decrypted = token_encoding::decode(&token.encrypted_token)
-                .map_err(TokenRotationError::DecryptionFailed)?;
+            // Decrypt and return
+            let decrypted = self
+                .cipher
+                .read()
+                .await
+                .decrypt(&token.encrypted_token)
+                .map_err(|e| TokenRotationError::DecryptionFailed(e.to_string()))?;
 
             info!(
                 token_id = %token.id,
@@ -99,13 +108,62 @@ impl ClaudeTokenRotationService {
                 "Rotated to Claude OAuth token"
             );
 
-            Ok(Some(decrypted))
+            Ok(Some(RotatedToken {
+                id: token.id,
+                token: decrypted,
+            }))
         } else {
             warn!("No Claude OAuth tokens available for rotation");
             Ok(None)
         }
     }
 
+    /// Put a token on cooldown after it hit its usage limit. `resets_at` is the unix
+    /// timestamp reported by Claude; a fixed cooldown is used when it is missing.
+    pub async fn mark_rate_limited(
+        &self,
+        token_id: Uuid,
+        resets_at: Option<i64>,
+    ) -> Result<(), TokenRotationError> {
+        let now = Utc::now();
+        let cooldown_until = resets_at
+            .and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0))
+            .filter(|reset| *reset > now)
+            .unwrap_or(now + DEFAULT_RATE_LIMIT_COOLDOWN);
+
+        ClaudeOAuthToken::mark_rate_limited(&self.db.pool, token_id, cooldown_until).await?;
+        warn!(
+            token_id = %token_id,
+            cooldown_until = %cooldown_until,
+            "Claude OAuth token hit its usage limit, cooling down"
+        );
+        Ok(())
+    }
+
+    /// Skip a token for a short while after the API throttled it. Unlike a usage limit
+    /// this is not counted against the token.
+    pub async fn back_off(&self, token_id: Uuid) -> Result<(), TokenRotationError> {
+        let cooldown_until = Utc::now() + RATE_LIMIT_BACKOFF;
+        ClaudeOAuthToken::set_cooldown(&self.db.pool, token_id, cooldown_until).await?;
+        info!(
+            token_id = %token_id,
+            cooldown_until = %cooldown_until,
+            "Claude OAuth token was rate limited, backing off"
+        );
+        Ok(())
+    }
+
+    /// Take a token out of rotation after Claude rejected it
+    pub async fn mark_invalid(
+        &self,
+        token_id: Uuid,
+        reason: &str,
+    ) -> Result<(), TokenRotationError> {
+        ClaudeOAuthToken::mark_invalid(&self.db.pool, token_id, reason).await?;
+        warn!(token_id = %token_id, reason, "Claude OAuth token was rejected, disabling it");
+        Ok(())
+    }
+
     /// Check if any valid tokens are available
     pub async fn has_available_tokens(&self) -> bool {
         match ClaudeOAuthToken::count_available(&self.db.pool).await {
@@ -135,8 +193,13 @@ impl ClaudeTokenRotationService {
         user_id: Uuid,
         raw_token: &str,
     ) -> Result<ClaudeOAuthTokenStatus, TokenRotationError> {
-        // Encode the token for storage
-        let encoded = token_encoding::encode(raw_token);
+        // Encrypt the token for storage
+        let encoded = self
+            .cipher
+            .read()
+            .await
+            .encrypt(raw_token)
+            .map_err(|e| TokenRotationError::EncryptionFailed(e.to_string()))?;
         let hint = generate_token_hint(raw_token);
 
         // Store in database (no expiration for now - Claude tokens are long-lived)
@@ -173,31 +236,74 @@ impl ClaudeTokenRotationService {
 
         Ok(statuses)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::token_encoding;
-
-    #[test]
-    fn test_encode_decode_roundtrip() {
-        let original = "my-secret-token-12345";
-        let encoded = token_encoding::encode(original);
-        let decoded = token_encoding::decode(&encoded).unwrap();
-        assert_eq!(original, decoded);
+    /// Re-encrypt every stored token that is not sealed with the current primary key,
+    /// including legacy `v1:` rows. Rows that cannot be decrypted are skipped and reported.
+    pub async fn reencrypt_all_tokens(&self) -> Result<ReencryptReport, TokenRotationError> {
+        let cipher = self.cipher.read().await;
+        self.reencrypt_with(&cipher).await
     }
 
-    #[test]
-    fn test_decode_legacy_token() {
-        // Legacy tokens without prefix should work
-        let legacy = "raw-token-without-prefix";
-        let decoded = token_encoding::decode(legacy).unwrap();
-        assert_eq!(legacy, decoded);
+    /// Generate a new primary key, re-encrypt all tokens with it and retire the old keys.
+    /// Only available when the key ring is stored in a key file.
+    pub async fn rotate_encryption_key(&self) -> Result<ReencryptReport, TokenRotationError> {
+        let mut cipher = self.cipher.write().await;
+
+        // Persist the new ring (new key + old keys) first so a crash mid-way never leaves
+        // rows encrypted with a key that only existed in memory.
+        let rotated = cipher.with_new_primary_key()?;
+        rotated.persist()?;
+
+        let report = self.reencrypt_with(&rotated).await?;
+
+        let retired = rotated.without_retired_keys();
+        retired.persist()?;
+        *cipher = retired;
+
+        info!(
+            key_id = cipher.primary_key_id(),
+            reencrypted = report.reencrypted,
+            failed = report.failed.len(),
+            "Rotated Claude OAuth token encryption key"
+        );
+
+        Ok(report)
     }
 
-    #[test]
-    fn test_encoded_has_version_prefix() {
-        let encoded = token_encoding::encode("test");
-        assert!(encoded.starts_with("v1:"));
+    /// Rewrite every token `cipher` wants re-encrypted in one transaction. A row that
+    /// cannot be decrypted does not stop the others; a failed write rolls back them all.
+    async fn reencrypt_with(
+        &self,
+        cipher: &TokenCipher,
+    ) -> Result<ReencryptReport, TokenRotationError> {
+        let tokens = ClaudeOAuthToken::find_all(&self.db.pool).await?;
+        let mut tx = self.db.pool.begin().await?;
+        let mut report = ReencryptReport::default();
+
+        for token in tokens {
+            if !cipher.needs_reencryption(&token.encrypted_token) {
+                continue;
+            }
+            let plaintext = match cipher.decrypt(&token.encrypted_token) {
+                Ok(plaintext) => plaintext,
+                Err(e) => {
+                    warn!(
+                        token_id = %token.id,
+                        user_id = %token.user_id,
+                        "Skipping Claude OAuth token that cannot be decrypted: {e}"
+                    );
+                    report.failed.push(token.id);
+                    continue;
+                }
+            };
+            let encrypted = cipher
+                .encrypt(&plaintext)
+                .map_err(|e| TokenRotationError::EncryptionFailed(e.to_string()))?;
+            ClaudeOAuthToken::update_encrypted_token(&mut *tx, token.id, &encrypted).await?;
+            report.reencrypted += 1;
+        }
+
+        tx.commit().await?;
+        Ok(report)
     }
 }
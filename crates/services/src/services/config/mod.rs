@@ -17,6 +17,12 @@ pub enum ConfigError {
 
 pub type Config = versions::v8::Config;
 pub type NotificationConfig = versions::v8::NotificationConfig;
+pub type NotificationChannelConfig = versions::v8::NotificationChannelConfig;
+pub type NotificationChannelKind = versions::v8::NotificationChannelKind;
+pub type NotificationEvent = versions::v8::NotificationEvent;
+pub type SmtpSecurity = versions::v8::SmtpSecurity;
+pub type ExecutionLimits = versions::v8::ExecutionLimits;
+pub type GitHostsConfig = versions::v8::GitHostsConfig;
 pub type EditorConfig = versions::v8::EditorConfig;
 pub type ThemeMode = versions::v8::ThemeMode;
 pub type SoundFile = versions::v8::SoundFile;
@@ -45,3 +51,170 @@ pub async fn save_config_to_file(
     std::fs::write(config_path, raw_config)?;
     Ok(())
 }
+
+/// Stands in for webhook header values and Slack webhook URLs in config responses.
+/// Sending it back unchanged keeps the stored value.
+pub const REDACTED_SECRET: &str = "<redacted>";
+
+/// The config as sent to clients, with stored credentials left out
+pub fn redact_secrets(config: &Config) -> Config {
+    let mut config = config.clone();
+    config.git_hosts.has_bitbucket_token = config.git_hosts.bitbucket_token.take().is_some();
+    for channel in &mut config.notification_channels {
+        match &mut channel.channel {
+            NotificationChannelKind::Webhook { headers, .. } => {
+                for value in headers.values_mut() {
+                    *value = REDACTED_SECRET.to_string();
+                }
+            }
+            NotificationChannelKind::Slack { webhook_url } => {
+                *webhook_url = REDACTED_SECRET.to_string();
+            }
+            NotificationChannelKind::Email { .. } => {}
+        }
+    }
+    config
+}
+
+/// Puts back the credentials a client left out because it was sent a config from
+/// [`redact_secrets`]
+pub fn keep_redacted_secrets(new: &mut Config, stored: &Config) {
+    let git_hosts = &mut new.git_hosts;
+    if git_hosts.bitbucket_token.is_none() && git_hosts.has_bitbucket_token {
+        git_hosts.bitbucket_token = stored.git_hosts.bitbucket_token.clone();
+    }
+    git_hosts.has_bitbucket_token = false;
+
+    for channel in &mut new.notification_channels {
+        // Channels are matched up by name, since that is all a client can keep stable
+        let stored = stored
+            .notification_channels
+            .iter()
+            .find(|c| c.name == channel.name)
+            .map(|c| &c.channel);
+        match (&mut channel.channel, stored) {
+            (
+                NotificationChannelKind::Webhook { headers, .. },
+                Some(NotificationChannelKind::Webhook {
+                    headers: stored_headers,
+                    ..
+                }),
+            ) => {
+                headers.retain(|name, value| {
+                    if value != REDACTED_SECRET {
+                        return true;
+                    }
+                    match stored_headers.get(name) {
+                        Some(stored_value) => {
+                            *value = stored_value.clone();
+                            true
+                        }
+                        None => false,
+                    }
+                });
+            }
+            (NotificationChannelKind::Webhook { headers, .. }, _) => {
+                headers.retain(|_, value| value != REDACTED_SECRET);
+            }
+            (
+                NotificationChannelKind::Slack { webhook_url },
+                Some(NotificationChannelKind::Slack {
+                    webhook_url: stored_url,
+                }),
+            ) if webhook_url == REDACTED_SECRET => {
+                *webhook_url = stored_url.clone();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_bitbucket_token(token: Option<&str>) -> Config {
+        let mut config = Config::default();
+        config.git_hosts.bitbucket_token = token.map(str::to_string);
+        config
+    }
+
+    #[test]
+    fn redacted_bitbucket_token_is_kept_on_save() {
+        let stored = with_bitbucket_token(Some("secret"));
+
+        let redacted = redact_secrets(&stored);
+        assert_eq!(redacted.git_hosts.bitbucket_token, None);
+        assert!(redacted.git_hosts.has_bitbucket_token);
+        assert!(!serde_json::to_string(&redacted).unwrap().contains("secret"));
+
+        let mut sent_back = redacted.clone();
+        keep_redacted_secrets(&mut sent_back, &stored);
+        assert_eq!(sent_back.git_hosts, stored.git_hosts);
+    }
+
+    fn channel(name: &str, channel: NotificationChannelKind) -> NotificationChannelConfig {
+        NotificationChannelConfig {
+            name: name.to_string(),
+            enabled: true,
+            channel,
+            events: Vec::new(),
+            users: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn redacted_notification_secrets_are_kept_on_save() {
+        let mut stored = Config::default();
+        stored.notification_channels = vec![
+            channel(
+                "hook",
+                NotificationChannelKind::Webhook {
+                    url: "https://example.com/hook".to_string(),
+                    headers: [("Authorization".to_string(), "Bearer secret".to_string())].into(),
+                },
+            ),
+            channel(
+                "slack",
+                NotificationChannelKind::Slack {
+                    webhook_url: "https://hooks.slack.com/services/secret".to_string(),
+                },
+            ),
+        ];
+
+        let redacted = redact_secrets(&stored);
+        assert!(!serde_json::to_string(&redacted).unwrap().contains("secret"));
+
+        let mut sent_back = redacted.clone();
+        keep_redacted_secrets(&mut sent_back, &stored);
+        assert_eq!(
+            sent_back.notification_channels,
+            stored.notification_channels
+        );
+
+        // A header added without a stored value to fall back to is dropped, not sent as-is
+        let mut added = redacted.clone();
+        if let NotificationChannelKind::Webhook { headers, .. } =
+            &mut added.notification_channels[0].channel
+        {
+            headers.insert("X-New".to_string(), REDACTED_SECRET.to_string());
+        }
+        keep_redacted_secrets(&mut added, &stored);
+        assert_eq!(added.notification_channels, stored.notification_channels);
+    }
+
+    #[test]
+    fn bitbucket_token_can_be_replaced_or_cleared() {
+        let stored = with_bitbucket_token(Some("secret"));
+
+        let mut replaced = redact_secrets(&stored);
+        replaced.git_hosts.bitbucket_token = Some("new".to_string());
+        keep_redacted_secrets(&mut replaced, &stored);
+        assert_eq!(replaced.git_hosts.bitbucket_token.as_deref(), Some("new"));
+
+        let mut cleared = redact_secrets(&stored);
+        cleared.git_hosts.has_bitbucket_token = false;
+        keep_redacted_secrets(&mut cleared, &stored);
+        assert_eq!(cleared.git_hosts.bitbucket_token, None);
+    }
+}
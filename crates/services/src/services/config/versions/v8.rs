@@ -1,5 +1,5 @@
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId, sandbox::SandboxConfig};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v7::{
@@ -23,6 +23,118 @@ pub enum SendMessageShortcut {
     Enter,
 }
 
+/// Events that can be delivered to notification channels
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    ExecutionCompleted,
+    ExecutionFailed,
+    ApprovalPending,
+    ReviewConversationOpened,
+    PrMerged,
+}
+
+/// How an SMTP connection is secured
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Implicit TLS, usually port 465
+    Tls,
+    /// Upgrade a plain connection with STARTTLS, usually port 587
+    #[default]
+    StartTls,
+    /// Unencrypted, only for local relays
+    None,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannelKind {
+    /// POST a JSON payload describing the event
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: std::collections::HashMap<String, String>,
+    },
+    /// Slack-compatible incoming webhook
+    Slack { webhook_url: String },
+    /// Email via SMTP
+    Email {
+        smtp_host: String,
+        smtp_port: u16,
+        #[serde(default)]
+        security: SmtpSecurity,
+        username: Option<String>,
+        /// Name of the environment variable holding the SMTP password
+        password_env: Option<String>,
+        from: String,
+        /// Fixed recipients, in addition to routed users
+        #[serde(default)]
+        to: Vec<String>,
+        /// Also email the task's creator and assignee, using their address from the users table
+        #[serde(default)]
+        notify_task_users: bool,
+    },
+}
+
+fn default_channel_enabled() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct NotificationChannelConfig {
+    pub name: String,
+    #[serde(default = "default_channel_enabled")]
+    pub enabled: bool,
+    pub channel: NotificationChannelKind,
+    /// Events delivered to this channel; empty means every event
+    #[serde(default)]
+    pub events: Vec<NotificationEvent>,
+    /// Only deliver events for tasks created by or assigned to these usernames;
+    /// empty means every task
+    #[serde(default)]
+    pub users: Vec<String>,
+}
+
+/// Caps on how many coding agents run at once. An execution over any cap waits in the
+/// execution queue and starts when a slot frees up. Unset caps are not enforced.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// Across all projects
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    /// Per executor, e.g. `{ "CODEX": 2 }`
+    #[serde(default)]
+    pub per_executor: std::collections::HashMap<BaseCodingAgent, u32>,
+    /// Within each project
+    #[serde(default)]
+    pub per_project: Option<u32>,
+    /// For each user who started the execution
+    #[serde(default)]
+    pub per_user: Option<u32>,
+}
+
+/// Git hosts that cannot be recognised from a remote URL alone, and credentials for hosts
+/// without a CLI to authenticate through
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct GitHostsConfig {
+    /// Hostnames of self-hosted GitLab instances, e.g. `gitlab.example.com`
+    #[serde(default)]
+    pub gitlab_hosts: Vec<String>,
+    /// Bitbucket account the token belongs to. Without it the token is sent as a bearer
+    /// token, as for repository and workspace access tokens.
+    #[serde(default)]
+    pub bitbucket_username: Option<String>,
+    /// Bitbucket API token or app password. Never sent to clients, which see
+    /// `has_bitbucket_token` instead.
+    #[serde(default)]
+    pub bitbucket_token: Option<String>,
+    /// Whether a Bitbucket token is stored. Only set in responses; sending it back with
+    /// `bitbucket_token: null` keeps the stored token.
+    #[serde(default)]
+    pub has_bitbucket_token: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -52,6 +164,19 @@ pub struct Config {
     pub commit_reminder: bool,
     #[serde(default)]
     pub send_message_shortcut: SendMessageShortcut,
+    /// Path to the key file used to encrypt stored Claude OAuth tokens.
+    /// `VK_TOKEN_ENCRYPTION_KEY` takes precedence when set.
+    #[serde(default)]
+    pub token_encryption_key_file: Option<String>,
+    #[serde(default)]
+    pub notification_channels: Vec<NotificationChannelConfig>,
+    #[serde(default)]
+    pub execution_limits: ExecutionLimits,
+    #[serde(default)]
+    pub git_hosts: GitHostsConfig,
+    /// Sandboxing and resource limits for agent, setup and dev server processes
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
 }
 
 impl Config {
@@ -79,6 +204,11 @@ impl Config {
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
             send_message_shortcut: SendMessageShortcut::default(),
+            token_encryption_key_file: None,
+            notification_channels: Vec::new(),
+            execution_limits: ExecutionLimits::default(),
+            git_hosts: GitHostsConfig::default(),
+            sandbox: SandboxConfig::default(),
         }
     }
 
@@ -131,6 +261,11 @@ impl Default for Config {
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
             send_message_shortcut: SendMessageShortcut::default(),
+            token_encryption_key_file: None,
+            notification_channels: Vec::new(),
+            execution_limits: ExecutionLimits::default(),
+            git_hosts: GitHostsConfig::default(),
+            sandbox: SandboxConfig::default(),
         }
     }
 }
@@ -0,0 +1,276 @@
+//! Hands a git operation stopped on conflicts to the workspace's coding agent.
+//!
+//! The agent gets a prompt listing each conflicted file with both sides of every
+//! conflict hunk. Once it finishes, the files are checked for leftover conflict markers:
+//! if none remain the operation is continued, otherwise it is rolled back.
+
+use std::path::Path;
+
+use super::git::ConflictOp;
+
+/// Most lines of one side of a hunk quoted in the prompt
+const MAX_SIDE_LINES: usize = 60;
+/// Most hunks quoted in the prompt; the agent finds the rest from the markers
+const MAX_PROMPT_HUNKS: usize = 40;
+
+/// One `<<<<<<<` .. `>>>>>>>` block of a conflicted file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// 1-based line of the `<<<<<<<` marker
+    pub start_line: usize,
+    /// 1-based line of the `>>>>>>>` marker
+    pub end_line: usize,
+    pub ours_label: String,
+    pub ours: Vec<String>,
+    /// The common ancestor, present with `merge.conflictStyle=diff3`
+    pub base: Option<Vec<String>>,
+    pub theirs_label: String,
+    pub theirs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConflictedFile {
+    pub path: String,
+    /// `None` if the file could not be read as text, e.g. it is binary or was deleted
+    pub hunks: Option<Vec<ConflictHunk>>,
+}
+
+enum Side {
+    Ours,
+    Base,
+    Theirs,
+}
+
+/// The label after a conflict marker made of seven `c`s, if `line` is one
+fn marker_label(line: &str, c: char) -> Option<&str> {
+    let rest = line.strip_prefix(&c.to_string().repeat(7))?;
+    if rest.is_empty() || rest.starts_with(' ') {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+pub fn parse_conflict_hunks(content: &str) -> Vec<ConflictHunk> {
+    let mut hunks = Vec::new();
+    let mut current: Option<(ConflictHunk, Side)> = None;
+    for (index, line) in content.lines().enumerate() {
+        if let Some(label) = marker_label(line, '<') {
+            let hunk = ConflictHunk {
+                start_line: index + 1,
+                ours_label: label.to_string(),
+                ..Default::default()
+            };
+            current = Some((hunk, Side::Ours));
+            continue;
+        }
+        let Some((hunk, side)) = current.as_mut() else {
+            continue;
+        };
+        if marker_label(line, '|').is_some() {
+            hunk.base = Some(Vec::new());
+            *side = Side::Base;
+        } else if line == "=======" {
+            *side = Side::Theirs;
+        } else if let Some(label) = marker_label(line, '>') {
+            hunk.end_line = index + 1;
+            hunk.theirs_label = label.to_string();
+            if let Some((hunk, _)) = current.take() {
+                hunks.push(hunk);
+            }
+        } else {
+            let lines = match side {
+                Side::Ours => &mut hunk.ours,
+                Side::Base => hunk.base.get_or_insert_with(Vec::new),
+                Side::Theirs => &mut hunk.theirs,
+            };
+            lines.push(line.to_string());
+        }
+    }
+    hunks
+}
+
+/// Whether any line opens or closes a conflict hunk. `=======` alone is not counted,
+/// as it also underlines Markdown headings.
+pub fn has_conflict_markers(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| marker_label(line, '<').is_some() || marker_label(line, '>').is_some())
+}
+
+pub fn read_conflicted_files(worktree_path: &Path, paths: &[String]) -> Vec<ConflictedFile> {
+    paths
+        .iter()
+        .map(|path| ConflictedFile {
+            path: path.clone(),
+            hunks: std::fs::read_to_string(worktree_path.join(path))
+                .ok()
+                .map(|content| parse_conflict_hunks(&content)),
+        })
+        .collect()
+}
+
+/// The files among `paths` that still contain conflict markers
+pub fn files_with_markers(worktree_path: &Path, paths: &[String]) -> Vec<String> {
+    paths
+        .iter()
+        .filter(|path| {
+            std::fs::read_to_string(worktree_path.join(path))
+                .is_ok_and(|content| has_conflict_markers(&content))
+        })
+        .cloned()
+        .collect()
+}
+
+fn op_name(op: &ConflictOp) -> &'static str {
+    match op {
+        ConflictOp::Rebase => "rebase",
+        ConflictOp::Merge => "merge",
+        ConflictOp::CherryPick => "cherry-pick",
+        ConflictOp::Revert => "revert",
+    }
+}
+
+/// What the "ours" and "theirs" sides of a hunk hold during `op`
+fn side_descriptions(op: &ConflictOp, target_branch: &str) -> (String, &'static str) {
+    match op {
+        ConflictOp::Rebase => (
+            format!("`{target_branch}` plus the task commits already replayed onto it"),
+            "the task commit being replayed",
+        ),
+        ConflictOp::Merge => (
+            "the task branch".to_string(),
+            "the branch being merged into it",
+        ),
+        ConflictOp::CherryPick => ("the task branch".to_string(), "the commit being picked"),
+        ConflictOp::Revert => ("the task branch".to_string(), "the commit being reverted"),
+    }
+}
+
+fn push_side(prompt: &mut String, title: &str, label: &str, lines: &[String]) {
+    let label = if label.is_empty() {
+        String::new()
+    } else {
+        format!(" (`{label}`)")
+    };
+    prompt.push_str(&format!("{title}{label}:\n```\n"));
+    for line in lines.iter().take(MAX_SIDE_LINES) {
+        prompt.push_str(line);
+        prompt.push('\n');
+    }
+    if lines.len() > MAX_SIDE_LINES {
+        prompt.push_str(&format!(
+            "... {} more lines\n",
+            lines.len() - MAX_SIDE_LINES
+        ));
+    }
+    prompt.push_str("```\n");
+}
+
+/// The follow-up prompt asking the agent to resolve the conflicts of `op`
+pub fn resolution_prompt(
+    op: &ConflictOp,
+    repo_name: &str,
+    target_branch: &str,
+    files: &[ConflictedFile],
+) -> String {
+    let name = op_name(op);
+    let (ours, theirs) = side_descriptions(op, target_branch);
+    let mut prompt = format!(
+        "A `git {name}` in the `{repo_name}` repository stopped on conflicts. Resolve every conflict below, keeping the intent of both sides. In each hunk, \"ours\" is {ours} and \"theirs\" is {theirs}.\n"
+    );
+
+    let mut quoted = 0;
+    let mut omitted = 0;
+    for file in files {
+        prompt.push_str(&format!("\n## `{}`\n\n", file.path));
+        let Some(hunks) = &file.hunks else {
+            prompt.push_str("Not a text file; keep one side's version or remove the file.\n");
+            continue;
+        };
+        if hunks.is_empty() {
+            prompt.push_str(
+                "No conflict markers: one side deleted or renamed the file while the other changed it. Keep the file, or delete it if the change is no longer needed.\n",
+            );
+            continue;
+        }
+        for hunk in hunks {
+            if quoted == MAX_PROMPT_HUNKS {
+                omitted += 1;
+                continue;
+            }
+            quoted += 1;
+            prompt.push_str(&format!(
+                "### Lines {}-{}\n\n",
+                hunk.start_line, hunk.end_line
+            ));
+            push_side(&mut prompt, "Ours", &hunk.ours_label, &hunk.ours);
+            if let Some(base) = &hunk.base {
+                push_side(&mut prompt, "Common ancestor", "", base);
+            }
+            push_side(&mut prompt, "Theirs", &hunk.theirs_label, &hunk.theirs);
+            prompt.push('\n');
+        }
+    }
+    if omitted > 0 {
+        prompt.push_str(&format!(
+            "\n{omitted} more conflicts are not shown; find them by their `<<<<<<<` markers.\n"
+        ));
+    }
+
+    prompt.push_str(&format!(
+        "\nEdit only the conflicted files, and leave no `<<<<<<<`, `=======` or `>>>>>>>` markers behind. Do not commit, and do not continue or abort the {name}: once you finish, the files are checked for leftover markers and the {name} is continued for you, or rolled back if any remain."
+    ));
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str = "fn main() {\n<<<<<<< HEAD\n    a();\n||||||| base\n    old();\n=======\n    b();\n    c();\n>>>>>>> 500f4cf (Add c)\n}\n";
+
+    #[test]
+    fn parses_both_sides_and_ancestor() {
+        let hunks = parse_conflict_hunks(CONFLICTED);
+        assert_eq!(
+            hunks,
+            vec![ConflictHunk {
+                start_line: 2,
+                end_line: 9,
+                ours_label: "HEAD".to_string(),
+                ours: vec!["    a();".to_string()],
+                base: Some(vec!["    old();".to_string()]),
+                theirs_label: "500f4cf (Add c)".to_string(),
+                theirs: vec!["    b();".to_string(), "    c();".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn markdown_underline_is_not_a_marker() {
+        assert!(has_conflict_markers(CONFLICTED));
+        assert!(!has_conflict_markers("Title\n=======\n\nText\n"));
+        assert!(has_conflict_markers("a\n>>>>>>> theirs\n"));
+    }
+
+    #[test]
+    fn prompt_quotes_each_side() {
+        let files = vec![
+            ConflictedFile {
+                path: "src/main.rs".to_string(),
+                hunks: Some(parse_conflict_hunks(CONFLICTED)),
+            },
+            ConflictedFile {
+                path: "logo.png".to_string(),
+                hunks: None,
+            },
+        ];
+        let prompt = resolution_prompt(&ConflictOp::Rebase, "app", "main", &files);
+        assert!(prompt.contains("`git rebase` in the `app` repository"));
+        assert!(prompt.contains("## `src/main.rs`\n\n### Lines 2-9"));
+        assert!(prompt.contains("Ours (`HEAD`):\n```\n    a();\n```"));
+        assert!(prompt.contains("Theirs (`500f4cf (Add c)`):\n```\n    b();\n    c();\n```"));
+        assert!(prompt.contains("## `logo.png`\n\nNot a text file"));
+    }
+}
@@ -1,7 +1,8 @@
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, LazyLock},
+    time::Duration,
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
@@ -9,7 +10,11 @@ use async_trait::async_trait;
 use db::{
     DBService,
     models::{
+        audit_event::{AuditAction, AuditEvent, AuditSource, AuditTarget, CreateAuditEvent},
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        conflict_resolution::{
+            ConflictResolution, ConflictResolutionStatus, CreateConflictResolution,
+        },
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
             ExecutionProcessRunReason, ExecutionProcessStatus,
@@ -18,10 +23,19 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        execution_queue::{ExecutionQueue, ExecutionSlot},
+        merge::Merge,
+        merge_queue::{MergeQueueEntry, MergeQueueStatus},
+        merge_verification::{CreateMergeVerification, MergeVerification},
+        project_budget::ProjectBudget,
+        project_repo::ProjectRepo,
+        project_workflow::WorkflowEvent,
         repo::Repo,
+        search::ConversationSearchIndex,
         session::{CreateSession, Session, SessionError},
-        task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
+        task::Task,
+        task_dependency::TaskAutoStart,
+        workspace::{CreateWorkspace, Workspace, WorkspaceError},
         workspace_repo::WorkspaceRepo,
     },
 };
@@ -32,6 +46,7 @@ use executors::profile::ExecutorConfigs;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
@@ -43,7 +58,10 @@ use futures::{StreamExt, future, stream::BoxStream};
 use json_patch::Patch;
 use sqlx::Error as SqlxError;
 use thiserror::Error;
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
@@ -52,13 +70,25 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    config::{ExecutionLimits, NotificationEvent},
+    conflict_resolution, execution_queue,
     git::{GitService, GitServiceError},
-    notification::NotificationService,
+    merge_queue,
+    merge_verification::{self, MergeVerificationError},
+    notification::{Notification, NotificationService},
+    search_index, workflow,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
+    workspace_start::{self, WorkspaceStartError},
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
 
+/// How many stored conversations the search backfill normalizes at once
+const SEARCH_BACKFILL_CONCURRENCY: usize = 4;
+
+/// Held while auto-starts are started, so two callers cannot start the same one twice
+static AUTO_START_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -81,6 +111,8 @@ pub enum ContainerError {
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
+    #[error("Project budget exceeded: {0}")]
+    BudgetExceeded(String),
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
@@ -128,7 +160,10 @@ pub trait ContainerService {
                     ExecutionProcess::find_by_session_id(&self.db().pool, session.id, false).await
                 {
                     for process in processes {
-                        if process.status == ExecutionProcessStatus::Running {
+                        if matches!(
+                            process.status,
+                            ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                        ) {
                             return Ok(true);
                         }
                     }
@@ -145,10 +180,11 @@ pub trait ContainerService {
     /// - Never when a setup script has no next_action (parallel mode)
     /// - The next action is None (no follow-up actions)
     fn should_finalize(&self, ctx: &ExecutionContext) -> bool {
-        // Never finalize DevServer processes
+        // Never finalize DevServer processes or merge verifications, which run
+        // alongside the task rather than as part of it
         if matches!(
             ctx.execution_process.run_reason,
-            ExecutionProcessRunReason::DevServer
+            ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::VerifyScript
         ) {
             return false;
         }
@@ -177,12 +213,13 @@ pub trait ContainerService {
         action.next_action.is_none()
     }
 
-    /// Finalize task execution by updating status to InReview and sending notifications
+    /// Finalize task execution by applying the workflow's agent-finished transition and
+    /// sending notifications
     async fn finalize_task(&self, ctx: &ExecutionContext) {
         if let Err(e) =
-            Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await
+            workflow::handle_event(&self.db().pool, &ctx.task, WorkflowEvent::AgentFinished).await
         {
-            tracing::error!("Failed to update task status to InReview: {e}");
+            tracing::error!("Failed to update task status after execution finished: {e}");
         }
 
         // Skip notification if process was intentionally killed by user
@@ -191,14 +228,20 @@ pub trait ContainerService {
         }
 
         let title = format!("Task Complete: {}", ctx.task.title);
-        let message = match ctx.execution_process.status {
-            ExecutionProcessStatus::Completed => format!(
-                "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+        let (event, message) = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed => (
+                NotificationEvent::ExecutionCompleted,
+                format!(
+                    "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
             ),
-            ExecutionProcessStatus::Failed => format!(
-                "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+            ExecutionProcessStatus::Failed => (
+                NotificationEvent::ExecutionFailed,
+                format!(
+                    "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
             ),
             _ => {
                 tracing::warn!(
@@ -208,7 +251,19 @@ pub trait ContainerService {
                 return;
             }
         };
-        self.notification_service().notify(&title, &message).await;
+        self.notification_service()
+            .notify(
+                Notification::new(event, title, message)
+                    .with_task(&ctx.task)
+                    .with_workspace(ctx.workspace.id),
+            )
+            .await;
+    }
+
+    /// Retry a failed coding agent execution as its executor profile's retry policy
+    /// allows. Returns whether a retry was scheduled.
+    async fn retry_failed_execution(&self, _ctx: &ExecutionContext) -> bool {
+        false
     }
 
     /// Cleanup executions marked as running in the db, call at startup
@@ -263,7 +318,7 @@ pub trait ContainerService {
             }
             // Process marked as failed
             tracing::info!("Marked orphaned execution process {} as failed", process.id);
-            // Update task status to InReview for coding agent and setup script failures
+            // Apply the agent-finished transition for coding agent and setup script failures
             if matches!(
                 process.run_reason,
                 ExecutionProcessRunReason::CodingAgent
@@ -275,12 +330,10 @@ pub trait ContainerService {
                     Workspace::find_by_id(&self.db().pool, session.workspace_id).await
                 && let Ok(Some(task)) = workspace.parent_task(&self.db().pool).await
                 && let Err(e) =
-                    Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await
+                    workflow::handle_event(&self.db().pool, &task, WorkflowEvent::AgentFinished)
+                        .await
             {
-                tracing::error!(
-                    "Failed to update task status to InReview for orphaned session: {}",
-                    e
-                );
+                tracing::error!("Failed to update task status for orphaned session: {}", e);
             }
         }
         Ok(())
@@ -338,6 +391,37 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Index the conversations of coding agent runs that were never fully indexed, from
+    /// their stored logs
+    async fn backfill_search_index(&self) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let execution_ids = ConversationSearchIndex::find_unindexed_executions(pool).await?;
+        if execution_ids.is_empty() {
+            return Ok(());
+        }
+
+        tracing::info!(
+            "Indexing the conversations of {} executions for search",
+            execution_ids.len()
+        );
+        futures::stream::iter(execution_ids)
+            .for_each_concurrent(SEARCH_BACKFILL_CONCURRENCY, |id| async move {
+                let result = match self.normalize_stored_logs(&id, false).await {
+                    Some(patches) => {
+                        search_index::index_stored_conversation(pool, id, patches).await
+                    }
+                    // Nothing to index; record that so it is not retried
+                    None => ConversationSearchIndex::mark_indexed(pool, id).await,
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Backfill: Failed to index execution {}: {}", id, e);
+                }
+            })
+            .await;
+
+        Ok(())
+    }
+
     /// Backfill repo names that were migrated with a sentinel placeholder.
     /// Also backfills dev_script_working_dir and agent_working_dir for single-repo projects.
     async fn backfill_repo_names(&self) -> Result<(), ContainerError> {
@@ -470,6 +554,656 @@ pub trait ContainerService {
         chained
     }
 
+    /// Run the verify script of each of the workspace's repos that has one, or only of
+    /// `repo_id`, against a temporary merge of the workspace branch into its target
+    /// branch. The runs belong to `session`, so their logs show with the workspace's
+    /// other processes.
+    async fn start_merge_verification(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        repo_id: Option<Uuid>,
+    ) -> Result<Vec<ExecutionProcess>, ContainerError> {
+        let pool = &self.db().pool;
+
+        // Merge every repo before starting any script, so a conflict starts nothing
+        let mut prepared = Vec::new();
+        for workspace_repo in WorkspaceRepo::find_by_workspace_id(pool, workspace.id).await? {
+            if repo_id.is_some_and(|repo_id| repo_id != workspace_repo.repo_id) {
+                continue;
+            }
+            let Some(script) = Repo::find_verify_script(pool, workspace_repo.repo_id).await? else {
+                continue;
+            };
+            let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+                .await?
+                .ok_or(SqlxError::RowNotFound)?;
+            let worktree_path = merge_verification::worktree_path(workspace.id, &repo);
+            let preview = match self.git().create_merge_preview(
+                &repo.path,
+                &worktree_path,
+                &workspace.branch,
+                &workspace_repo.target_branch,
+            ) {
+                Ok(preview) => preview,
+                Err(e) => {
+                    for (repo, worktree_path, ..) in &prepared {
+                        let _ = self.git().remove_merge_preview(&repo.path, worktree_path);
+                    }
+                    return Err(e.into());
+                }
+            };
+            prepared.push((repo, worktree_path, script, workspace_repo, preview));
+        }
+
+        let mut started = Vec::with_capacity(prepared.len());
+        let mut remaining = prepared.into_iter();
+        while let Some((repo, worktree_path, script, workspace_repo, preview)) = remaining.next() {
+            let result = async {
+                let execution_process = self
+                    .start_execution(
+                        workspace,
+                        session,
+                        &merge_verification::verify_action(&script, &worktree_path),
+                        &ExecutionProcessRunReason::VerifyScript,
+                    )
+                    .await?;
+                MergeVerification::create(
+                    pool,
+                    &CreateMergeVerification {
+                        execution_process_id: execution_process.id,
+                        workspace_id: workspace.id,
+                        repo_id: repo.id,
+                        task_commit: preview.task_commit,
+                        target_branch: workspace_repo.target_branch,
+                        target_commit: preview.target_commit,
+                        worktree_path: worktree_path.to_string_lossy().into_owned(),
+                    },
+                )
+                .await?;
+                Ok::<_, ContainerError>(execution_process)
+            }
+            .await;
+            match result {
+                Ok(execution_process) => started.push(execution_process),
+                Err(e) => {
+                    // Nothing will run in this repo's preview or any repo's after it
+                    let _ = self.git().remove_merge_preview(&repo.path, &worktree_path);
+                    for (repo, worktree_path, ..) in remaining {
+                        let _ = self.git().remove_merge_preview(&repo.path, &worktree_path);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(started)
+    }
+
+    /// Start a coding agent turn with `prompt` in the workspace's latest session, using
+    /// the executor profile of its last turn. Returns `None` when the workspace has no
+    /// agent session to continue.
+    async fn start_agent_follow_up(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        let pool = &self.db().pool;
+        let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await? else {
+            return Ok(None);
+        };
+        let Some(executor_profile_id) =
+            ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?
+        else {
+            return Ok(None);
+        };
+
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+        let action_type = match CodingAgentTurn::find_latest_session_info(pool, session.id).await? {
+            Some(info) => {
+                ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                    prompt,
+                    session_id: info.session_id,
+                    reset_to_message_id: None,
+                    executor_profile_id,
+                    working_dir,
+                })
+            }
+            None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id,
+                working_dir,
+            }),
+        };
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let action = ExecutorAction::new(
+            action_type,
+            self.cleanup_actions_for_repos(&repos).map(Box::new),
+        );
+
+        self.start_execution(
+            workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+        .map(Some)
+    }
+
+    /// Ask the workspace's agent to resolve the conflicts of the git operation stopped in
+    /// the repo's worktree. Returns `None` if nothing is in conflict or the workspace has
+    /// no agent session to follow up.
+    async fn start_conflict_resolution(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        let pool = &self.db().pool;
+        let container_ref = self.ensure_container_exists(workspace).await?;
+        let worktree_path = Path::new(&container_ref).join(&repo.name);
+        let Some(op) = self.git().detect_conflict_op(&worktree_path)? else {
+            return Ok(None);
+        };
+        let conflicted_files = self.git().get_conflicted_files(&worktree_path)?;
+        if conflicted_files.is_empty() {
+            return Ok(None);
+        }
+        let start_head = self.git().get_head_info(&worktree_path)?.oid;
+
+        let target_branch =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo.id)
+                .await?
+                .map(|workspace_repo| workspace_repo.target_branch)
+                .unwrap_or_default();
+        let prompt = conflict_resolution::resolution_prompt(
+            &op,
+            &repo.name,
+            &target_branch,
+            &conflict_resolution::read_conflicted_files(&worktree_path, &conflicted_files),
+        );
+        let Some(process) = self.start_agent_follow_up(workspace, prompt).await? else {
+            return Ok(None);
+        };
+        ConflictResolution::create(
+            pool,
+            &CreateConflictResolution {
+                execution_process_id: process.id,
+                workspace_id: workspace.id,
+                repo_id: repo.id,
+                conflicted_files,
+                start_head,
+            },
+        )
+        .await?;
+        Ok(Some(process))
+    }
+
+    /// Once an agent run resolving conflicts ends, continue the stopped operation if no
+    /// conflict markers remain, or roll it back. Returns whether the run was resolving
+    /// conflicts, in which case its changes are not committed like other agent turns.
+    async fn settle_conflict_resolution(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let Some(resolution) =
+            ConflictResolution::find_by_execution_process_id(pool, ctx.execution_process.id)
+                .await?
+        else {
+            return Ok(false);
+        };
+        if resolution.status != ConflictResolutionStatus::Running {
+            return Ok(true);
+        }
+
+        let (status, error_message) = self.continue_resolved_conflicts(ctx, &resolution);
+        if let Some(error_message) = &error_message {
+            tracing::info!(
+                "Conflict resolution {} in workspace {} ended {:?}: {}",
+                resolution.execution_process_id,
+                resolution.workspace_id,
+                status,
+                error_message
+            );
+        }
+        ConflictResolution::set_status(
+            pool,
+            resolution.execution_process_id,
+            status,
+            error_message.as_deref(),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Check the agent's work and continue or roll back the operation it was resolving
+    fn continue_resolved_conflicts(
+        &self,
+        ctx: &ExecutionContext,
+        resolution: &ConflictResolution,
+    ) -> (ConflictResolutionStatus, Option<String>) {
+        let Some(repo) = ctx.repos.iter().find(|repo| repo.id == resolution.repo_id) else {
+            return (
+                ConflictResolutionStatus::RolledBack,
+                Some("The repository is no longer part of the workspace".to_string()),
+            );
+        };
+        let Some(container_ref) = &ctx.workspace.container_ref else {
+            return (
+                ConflictResolutionStatus::RolledBack,
+                Some("The workspace has no worktree".to_string()),
+            );
+        };
+        let worktree_path = Path::new(container_ref).join(&repo.name);
+        let git = self.git();
+        // The agent may have finished the operation itself, or aborted it
+        if matches!(git.detect_conflict_op(&worktree_path), Ok(None)) {
+            return match Self::finished_past_start(git, &worktree_path, resolution) {
+                Ok(true) => (ConflictResolutionStatus::Resolved, None),
+                Ok(false) => (
+                    ConflictResolutionStatus::RolledBack,
+                    Some(
+                        "The operation ended without building on where it stopped; it was likely aborted"
+                            .to_string(),
+                    ),
+                ),
+                Err(e) => (ConflictResolutionStatus::RolledBack, Some(e.to_string())),
+            };
+        }
+
+        let failure = if ctx.execution_process.status != ExecutionProcessStatus::Completed {
+            "The agent did not finish resolving the conflicts".to_string()
+        } else {
+            // Files the agent staged no longer show as conflicted, so check the original
+            // list too
+            let mut paths = resolution.conflicted_files.0.clone();
+            paths.extend(git.get_conflicted_files(&worktree_path).unwrap_or_default());
+            paths.sort();
+            paths.dedup();
+            let remaining = conflict_resolution::files_with_markers(&worktree_path, &paths);
+            if !remaining.is_empty() {
+                format!("Conflict markers remain in {}", remaining.join(", "))
+            } else {
+                match git.continue_conflicts(&worktree_path) {
+                    Ok(()) => return (ConflictResolutionStatus::Resolved, None),
+                    Err(GitServiceError::MergeConflicts { message, .. }) => {
+                        return (ConflictResolutionStatus::Conflicted, Some(message));
+                    }
+                    Err(e) => e.to_string(),
+                }
+            }
+        };
+        let failure = match git.roll_back_conflicts(&worktree_path) {
+            Ok(()) => failure,
+            Err(e) => format!("{failure}; rolling back failed: {e}"),
+        };
+        (ConflictResolutionStatus::RolledBack, Some(failure))
+    }
+
+    /// Whether the worktree's HEAD is built on the commits the operation had replayed
+    /// when the run started. Aborting puts HEAD back where the operation began, which
+    /// is not. Runs from before the start was recorded are taken at their word.
+    fn finished_past_start(
+        git: &GitService,
+        worktree_path: &Path,
+        resolution: &ConflictResolution,
+    ) -> Result<bool, GitServiceError> {
+        let Some(start_head) = &resolution.start_head else {
+            return Ok(true);
+        };
+        let head = git.get_head_info(worktree_path)?.oid;
+        Ok(&head != start_head && git.is_ancestor(worktree_path, start_head, &head)?)
+    }
+
+    /// Follow up on a settled conflict resolution: start another run if git stopped on
+    /// further conflicts, and update the merge queue entry waiting on it
+    async fn follow_up_conflict_resolution(
+        &self,
+        execution_process_id: Uuid,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let Some(resolution) =
+            ConflictResolution::find_by_execution_process_id(pool, execution_process_id).await?
+        else {
+            return Ok(());
+        };
+        let entry =
+            MergeQueueEntry::find_by_resolution_execution_process_id(pool, execution_process_id)
+                .await?;
+        let error_message = resolution.error_message.as_deref();
+        match resolution.status {
+            ConflictResolutionStatus::Conflicted => {
+                let next = match (
+                    Workspace::find_by_id(pool, resolution.workspace_id).await?,
+                    Repo::find_by_id(pool, resolution.repo_id).await?,
+                ) {
+                    (Some(workspace), Some(repo)) => {
+                        match self.start_conflict_resolution(&workspace, &repo).await {
+                            Ok(next) => next,
+                            Err(e) => {
+                                tracing::error!(
+                                    "Failed to continue conflict resolution for workspace {}: {}",
+                                    workspace.id,
+                                    e
+                                );
+                                None
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+                if let Some(entry) = entry {
+                    match next {
+                        Some(process) => {
+                            MergeQueueEntry::set_conflicted(
+                                pool,
+                                entry.id,
+                                Some(process.id),
+                                error_message.unwrap_or_default(),
+                            )
+                            .await?
+                        }
+                        None => {
+                            MergeQueueEntry::set_status(
+                                pool,
+                                entry.id,
+                                MergeQueueStatus::Failed,
+                                error_message,
+                            )
+                            .await?
+                        }
+                    }
+                }
+            }
+            ConflictResolutionStatus::RolledBack => {
+                if let Some(entry) = entry {
+                    MergeQueueEntry::set_status(
+                        pool,
+                        entry.id,
+                        MergeQueueStatus::Failed,
+                        error_message,
+                    )
+                    .await?;
+                }
+            }
+            ConflictResolutionStatus::Running | ConflictResolutionStatus::Resolved => {}
+        }
+        Ok(())
+    }
+
+    /// Advance every merge queue until each is empty or waiting on a verify script or an
+    /// agent. Call whenever an entry is enqueued or an execution finishes.
+    async fn process_merge_queues(&self) -> Result<(), ContainerError> {
+        let _guard = merge_queue::MERGE_QUEUE_LOCK.lock().await;
+        loop {
+            let mut advanced = false;
+            for entry in MergeQueueEntry::find_heads(&self.db().pool).await? {
+                advanced |= self.advance_merge_queue_entry(&entry).await?;
+            }
+            if !advanced {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Take the entry at the front of its queue one step further: rebase it onto the
+    /// target branch, then verify or merge it. Returns whether the queue moved on.
+    async fn advance_merge_queue_entry(
+        &self,
+        entry: &MergeQueueEntry,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let Some(workspace) = Workspace::find_by_id(pool, entry.workspace_id).await? else {
+            MergeQueueEntry::delete(pool, entry.id).await?;
+            return Ok(true);
+        };
+        let Some(repo) = Repo::find_by_id(pool, entry.repo_id).await? else {
+            MergeQueueEntry::delete(pool, entry.id).await?;
+            return Ok(true);
+        };
+        let Some(task) = workspace.parent_task(pool).await? else {
+            MergeQueueEntry::delete(pool, entry.id).await?;
+            return Ok(true);
+        };
+        let target_changed =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo.id)
+                .await?
+                .is_none_or(|workspace_repo| workspace_repo.target_branch != entry.target_branch);
+        if target_changed {
+            return self
+                .fail_merge_queue_entry(
+                    entry,
+                    "The workspace's target branch changed after it was enqueued",
+                )
+                .await;
+        }
+
+        if entry.status == MergeQueueStatus::Verifying {
+            match merge_verification::check_verified(
+                pool,
+                self.git(),
+                &workspace,
+                &repo,
+                &entry.target_branch,
+            )
+            .await
+            {
+                Ok(()) => {
+                    return self
+                        .merge_queue_entry(entry, &workspace, &repo, &task)
+                        .await;
+                }
+                Err(MergeVerificationError::Running(_)) => return Ok(false),
+                Err(MergeVerificationError::Database(e)) => return Err(e.into()),
+                // A branch moved since the run started: rebase and verify again
+                Err(MergeVerificationError::NotVerified(_)) => {}
+                Err(e) => return self.fail_merge_queue_entry(entry, &e.to_string()).await,
+            }
+        }
+
+        // Rebasing while an agent works in the worktree would pull the branch out from
+        // under it; the entry is picked up again when the agent finishes
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?
+        {
+            return Ok(false);
+        }
+        if let Err(e) = workflow::check_event(pool, &task, WorkflowEvent::BranchMerged).await {
+            return self.fail_merge_queue_entry(entry, &e.to_string()).await;
+        }
+
+        let container_ref = match self.ensure_container_exists(&workspace).await {
+            Ok(container_ref) => container_ref,
+            Err(e) => return self.fail_merge_queue_entry(entry, &e.to_string()).await,
+        };
+        let worktree_path = Path::new(&container_ref).join(&repo.name);
+        match self.git().rebase_branch(
+            &repo.path,
+            &worktree_path,
+            &entry.target_branch,
+            &entry.target_branch,
+            &workspace.branch,
+        ) {
+            Ok(_) => {}
+            Err(GitServiceError::MergeConflicts { message, .. }) => {
+                let resolution = match self.start_conflict_resolution(&workspace, &repo).await {
+                    Ok(resolution) => resolution,
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to start conflict resolution for workspace {}: {}",
+                            workspace.id,
+                            e
+                        );
+                        None
+                    }
+                };
+                let message = match &resolution {
+                    Some(_) => message,
+                    None => format!(
+                        "{message} No agent could be started to resolve them; resolve them and enqueue the workspace again."
+                    ),
+                };
+                MergeQueueEntry::set_conflicted(
+                    pool,
+                    entry.id,
+                    resolution.map(|process| process.id),
+                    &message,
+                )
+                .await?;
+                return Ok(true);
+            }
+            Err(e) => return self.fail_merge_queue_entry(entry, &e.to_string()).await,
+        }
+
+        if Repo::find_verify_script(pool, repo.id).await?.is_none() {
+            return self
+                .merge_queue_entry(entry, &workspace, &repo, &task)
+                .await;
+        }
+        // Get or create a session for the verify script (system-initiated)
+        let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(session) => session,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession { executor: None },
+                    Uuid::new_v4(),
+                    workspace.id,
+                    None,
+                )
+                .await?
+            }
+        };
+        if let Err(e) = self
+            .start_merge_verification(&workspace, &session, Some(repo.id))
+            .await
+        {
+            return self.fail_merge_queue_entry(entry, &e.to_string()).await;
+        }
+        MergeQueueEntry::set_status(pool, entry.id, MergeQueueStatus::Verifying, None).await?;
+        Ok(false)
+    }
+
+    /// Merge the rebased (and verified) entry at the front of its queue and remove it
+    async fn merge_queue_entry(
+        &self,
+        entry: &MergeQueueEntry,
+        workspace: &Workspace,
+        repo: &Repo,
+        task: &Task,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let container_ref = match self.ensure_container_exists(workspace).await {
+            Ok(container_ref) => container_ref,
+            Err(e) => return self.fail_merge_queue_entry(entry, &e.to_string()).await,
+        };
+        let worktree_path = Path::new(&container_ref).join(&repo.name);
+        let merge_strategy = match entry.merge_strategy {
+            Some(strategy) => strategy,
+            None => ProjectRepo::find_merge_strategy(pool, task.project_id, repo.id).await?,
+        };
+
+        let merge_commit = match self.git().merge_changes_with_strategy(
+            &repo.path,
+            &worktree_path,
+            &workspace.branch,
+            &entry.target_branch,
+            &merge_queue::merge_commit_message(task),
+            merge_strategy,
+        ) {
+            Ok(merge_commit) => merge_commit,
+            // The target branch moved after the rebase: start over from the rebase
+            Err(GitServiceError::BranchesDiverged(_)) => {
+                MergeQueueEntry::set_status(pool, entry.id, MergeQueueStatus::Queued, None).await?;
+                return Ok(true);
+            }
+            Err(e) => return self.fail_merge_queue_entry(entry, &e.to_string()).await,
+        };
+
+        Merge::create_direct(
+            pool,
+            workspace.id,
+            repo.id,
+            &entry.target_branch,
+            &merge_commit,
+        )
+        .await?;
+        MergeQueueEntry::delete(pool, entry.id).await?;
+        tracing::info!(
+            "Merge queue merged workspace {} into {} of {}",
+            workspace.id,
+            entry.target_branch,
+            repo.name
+        );
+
+        let audit = CreateAuditEvent {
+            actor_user_id: None,
+            source: AuditSource::Api,
+            action: AuditAction::WorkspaceMerged,
+            target: AuditTarget::workspace(task.project_id, task.id, workspace.id)
+                .with_target(repo.id),
+            details: serde_json::json!({
+                "branch": workspace.branch,
+                "target_branch": entry.target_branch,
+                "merge_commit": merge_commit,
+                "merge_strategy": merge_strategy,
+                "merge_queue": true,
+            }),
+        };
+        if let Err(e) = AuditEvent::create(pool, audit).await {
+            tracing::error!("Failed to record audit event for queued merge: {}", e);
+        }
+        if let Err(e) = workflow::handle_event(pool, task, WorkflowEvent::BranchMerged).await {
+            tracing::error!("Failed to update task status after queued merge: {e}");
+        }
+        if !workspace.pinned {
+            Workspace::set_archived(pool, workspace.id, true).await?;
+        }
+        for dev_server in
+            ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id).await?
+        {
+            if let Err(e) = self
+                .stop_execution(&dev_server, ExecutionProcessStatus::Killed)
+                .await
+            {
+                tracing::error!(
+                    "Failed to stop dev server {} for merged workspace {}: {}",
+                    dev_server.id,
+                    workspace.id,
+                    e
+                );
+            }
+        }
+        if let Err(e) = self.start_unblocked_tasks().await {
+            tracing::error!("Failed to start unblocked tasks: {}", e);
+        }
+        Ok(true)
+    }
+
+    async fn fail_merge_queue_entry(
+        &self,
+        entry: &MergeQueueEntry,
+        message: &str,
+    ) -> Result<bool, ContainerError> {
+        tracing::warn!(
+            "Merge queue entry for workspace {} failed: {}",
+            entry.workspace_id,
+            message
+        );
+        MergeQueueEntry::set_status(
+            &self.db().pool,
+            entry.id,
+            MergeQueueStatus::Failed,
+            Some(message),
+        )
+        .await?;
+        Ok(true)
+    }
+
     async fn try_stop(&self, workspace: &Workspace, include_dev_server: bool) {
         // stop execution processes for this workspace's sessions
         let sessions = match Session::find_by_workspace_id(&self.db().pool, workspace.id).await {
@@ -488,7 +1222,18 @@ pub trait ContainerService {
                     {
                         continue;
                     }
-                    if process.status == ExecutionProcessStatus::Running {
+                    if process.status == ExecutionProcessStatus::Queued {
+                        if let Err(e) = self
+                            .cancel_queued_execution(process.id, ExecutionProcessStatus::Killed)
+                            .await
+                        {
+                            tracing::debug!(
+                                "Failed to cancel queued execution process {}: {}",
+                                process.id,
+                                e
+                            );
+                        }
+                    } else if process.status == ExecutionProcessStatus::Running {
                         self.stop_execution(&process, ExecutionProcessStatus::Killed)
                             .await
                             .unwrap_or_else(|e| {
@@ -549,6 +1294,9 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    /// Concurrency caps applied to coding agent executions
+    async fn execution_limits(&self) -> ExecutionLimits;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -628,158 +1376,206 @@ pub trait ContainerService {
             )
         } else {
             // Fallback: load from DB and normalize
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
+            self.normalize_stored_logs(id, true).await
+        }
+    }
 
-            let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
-                Ok(msgs) => msgs,
+    /// Normalize the logs stored for an execution. The worktree is recreated first if
+    /// `recreate_worktree` is set, so paths in the conversation resolve as they did
+    /// during the run.
+    async fn normalize_stored_logs(
+        &self,
+        id: &Uuid,
+        recreate_worktree: bool,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        let log_records =
+            match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                Ok(records) if !records.is_empty() => records,
+                Ok(_) => return None, // No logs exist
                 Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                    tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
                     return None;
                 }
             };
 
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
-                }
+        let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                return None;
             }
-            temp_store.push_finished();
+        };
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
+        // Create temporary store and populate
+        // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+        let temp_store = Arc::new(MsgStore::new());
+        for msg in raw_messages {
+            if matches!(
+                msg,
+                LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+            ) {
+                temp_store.push(msg);
+            }
+        }
+        temp_store.push_finished();
+
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Get the workspace to determine correct directory
+        let (workspace, _session) =
+            match process.parent_workspace_and_session(&self.db().pool).await {
+                Ok(Some((workspace, session))) => (workspace, session),
                 Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
+                    tracing::error!(
+                        "No workspace/session found for session ID: {}",
+                        process.session_id
+                    );
                     return None;
                 }
                 Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                    tracing::error!(
+                        "Failed to fetch workspace for session {}: {}",
+                        process.session_id,
+                        e
+                    );
                     return None;
                 }
             };
 
-            // Get the workspace to determine correct directory
-            let (workspace, _session) =
-                match process.parent_workspace_and_session(&self.db().pool).await {
-                    Ok(Some((workspace, session))) => (workspace, session),
-                    Ok(None) => {
-                        tracing::error!(
-                            "No workspace/session found for session ID: {}",
-                            process.session_id
-                        );
-                        return None;
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to fetch workspace for session {}: {}",
-                            process.session_id,
-                            e
-                        );
-                        return None;
-                    }
-                };
-
-            if let Err(err) = self.ensure_container_exists(&workspace).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for workspace {}: {}",
-                    workspace.id,
-                    err
-                );
-            }
+        if recreate_worktree && let Err(err) = self.ensure_container_exists(&workspace).await {
+            tracing::warn!(
+                "Failed to recreate worktree before log normalization for workspace {}: {}",
+                workspace.id,
+                err
+            );
+        }
 
-            let current_dir = self.workspace_to_current_dir(&workspace);
+        let current_dir = self.workspace_to_current_dir(&workspace);
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+        // Spawn normalizer on populated store
+        match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                #[cfg(feature = "qa-mode")]
+                {
+                    let executor = QaMockExecutor;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+                #[cfg(not(feature = "qa-mode"))]
+                {
+                    let executor = ExecutorConfigs::get_cached()
+                        .get_coding_agent_or_default(&request.executor_profile_id);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                 #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
+                {
                     let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
                 #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
+                {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+            }
+            #[cfg(feature = "qa-mode")]
+            ExecutorActionType::ReviewRequest(_request) => {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            ExecutorActionType::ReviewRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
+                    process.executor_action()
+                );
+                return None;
+            }
+        }
+        Some(
+            temp_store
+                .history_plus_stream()
+                .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
+    }
+
+    /// Current normalized conversation entries for an execution. Running processes
+    /// return what has been produced so far instead of waiting for completion.
+    async fn normalized_logs_snapshot(&self, id: &Uuid) -> Option<Vec<serde_json::Value>> {
+        let patches: Vec<Patch> = if let Some(store) = self.get_msg_store_by_id(id).await {
+            store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::JsonPatch(patch) => Some(patch),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            let mut stream = self.stream_normalized_logs(id).await?;
+            let mut patches = Vec::new();
+            let collect = async {
+                while let Some(Ok(msg)) = stream.next().await {
+                    match msg {
+                        LogMsg::JsonPatch(patch) => patches.push(patch),
+                        LogMsg::Finished => break,
+                        _ => {}
+                    }
                 }
+            };
+            if tokio::time::timeout(Duration::from_secs(10), collect)
+                .await
+                .is_err()
+            {
+                tracing::warn!("Timed out normalizing logs for execution {}", id);
+            }
+            patches
+        };
+
+        let mut conversation = serde_json::json!({ "entries": [] });
+        for patch in &patches {
+            if let Err(e) = json_patch::patch(&mut conversation, patch) {
+                tracing::debug!("Skipping normalized log patch for execution {}: {}", id, e);
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
-                    }))
-                    .boxed(),
-            )
+        }
+        match conversation["entries"].take() {
+            serde_json::Value::Array(entries) => Some(entries),
+            _ => Some(Vec::new()),
         }
     }
 
@@ -966,6 +1762,89 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Start a workspace for every task with an auto-start whose blockers are all done.
+    /// Auto-starts that are refused or fail to start are kept and retried on the next call.
+    async fn start_unblocked_tasks(&self) -> Result<Vec<Workspace>, ContainerError> {
+        let _guard = AUTO_START_LOCK.lock().await;
+        let pool = &self.db().pool;
+        let mut started = Vec::new();
+        for auto_start in TaskAutoStart::find_ready(pool).await? {
+            let Some(task) = Task::find_by_id(pool, auto_start.task_id).await? else {
+                continue;
+            };
+            // Held to the same checks as creating the workspace by hand. The auto-start
+            // is kept, so it goes ahead once the hold is released, the token added, etc.
+            let allowed = async {
+                workspace_start::ensure_task_startable(pool, &task).await?;
+                workspace_start::ensure_user_can_start(pool, &task, auto_start.requested_by_user_id)
+                    .await
+            }
+            .await;
+            match allowed {
+                Ok(()) => {}
+                Err(WorkspaceStartError::Database(e)) => return Err(e.into()),
+                Err(e @ WorkspaceStartError::OnHold(_)) => {
+                    tracing::debug!("Not auto-starting task {}: {}", task.id, e);
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Not auto-starting task {}: {}", task.id, e);
+                    continue;
+                }
+            }
+
+            let agent_working_dir = if let [repo] = auto_start.repos.as_slice() {
+                Repo::find_by_id(pool, repo.repo_id)
+                    .await?
+                    .map(|repo| repo.name)
+            } else {
+                None
+            };
+            let workspace_id = Uuid::new_v4();
+            let branch = self
+                .git_branch_from_workspace(&workspace_id, &task.title)
+                .await;
+            let workspace = Workspace::create(
+                pool,
+                &CreateWorkspace {
+                    branch,
+                    agent_working_dir,
+                },
+                workspace_id,
+                task.id,
+                auto_start.requested_by_user_id,
+            )
+            .await?;
+            WorkspaceRepo::create_many(pool, workspace.id, &auto_start.repos).await?;
+
+            // The auto-start is only used up once its workspace is running; a failed
+            // start removes the workspace again and leaves the auto-start to be retried
+            if let Err(e) = self
+                .start_workspace(&workspace, auto_start.executor_profile_id.clone())
+                .await
+            {
+                tracing::error!("Failed to auto-start task {}: {}", task.id, e);
+                // Reload it to see the container the failed start may have created
+                let workspace = Workspace::find_by_id(pool, workspace.id)
+                    .await?
+                    .unwrap_or(workspace);
+                if let Err(e) = self.delete(&workspace).await {
+                    tracing::error!(
+                        "Failed to clean up workspace {} of failed auto-start: {}",
+                        workspace.id,
+                        e
+                    );
+                }
+                Workspace::delete(pool, workspace.id).await?;
+                continue;
+            }
+            TaskAutoStart::delete(pool, task.id).await?;
+            tracing::info!("Auto-started task {} after its blockers finished", task.id);
+            started.push(workspace);
+        }
+        Ok(started)
+    }
+
     async fn start_execution(
         &self,
         workspace: &Workspace,
@@ -973,15 +1852,53 @@ pub trait ContainerService {
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
     ) -> Result<ExecutionProcess, ContainerError> {
-        // Update task status to InProgress when starting an execution
+        self.start_execution_with_retry_of(workspace, session, executor_action, run_reason, None)
+            .await
+    }
+
+    /// Start an execution. `retry_of` links an automatic retry to the failed run it
+    /// retries; the link is stored before the retry is launched, so a retry that fails
+    /// to start is counted against the retry policy too.
+    async fn start_execution_with_retry_of(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        executor_action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+        retry_of: Option<Uuid>,
+    ) -> Result<ExecutionProcess, ContainerError> {
         let task = workspace
             .parent_task(&self.db().pool)
             .await?
             .ok_or(SqlxError::RowNotFound)?;
-        if task.status != TaskStatus::InProgress
-            && run_reason != &ExecutionProcessRunReason::DevServer
+
+        // Refuse new agent work once the project's budget is used up. Dev servers and
+        // cleanup scripts do not consume tokens and still run.
+        if matches!(
+            run_reason,
+            ExecutionProcessRunReason::SetupScript | ExecutionProcessRunReason::CodingAgent
+        ) && let Some(status) =
+            ProjectBudget::status_for_project(&self.db().pool, task.project_id).await?
+            && status.exceeded
+        {
+            return Err(ContainerError::BudgetExceeded(format!(
+                "used {} tokens (${:.2}) this period",
+                status.usage.total_tokens,
+                status.usage.cost_usd.unwrap_or(0.0)
+            )));
+        }
+
+        // Apply the workflow's agent-started transition when starting an execution
+        if !matches!(
+            run_reason,
+            ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::VerifyScript
+        ) && let Err(e) =
+            workflow::handle_event(&self.db().pool, &task, WorkflowEvent::AgentStarted).await
         {
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
+            tracing::warn!(
+                "Task {} status not updated on execution start: {e}",
+                task.id
+            );
         }
         // Create new execution process record
         // Capture current HEAD per repository as the "before" commit for this execution
@@ -1016,13 +1933,37 @@ pub trait ContainerService {
             run_reason: run_reason.clone(),
         };
 
-        let execution_process = ExecutionProcess::create(
+        // Coding agents over a concurrency cap are queued instead of started. The record
+        // is created under the admission lock so the next decision counts it.
+        let limits = self.execution_limits().await;
+        let admission = execution_queue::ADMISSION_LOCK.lock().await;
+        let slot = (run_reason == &ExecutionProcessRunReason::CodingAgent).then(|| ExecutionSlot {
+            executor: executor_action.base_executor().map(|e| e.to_string()),
+            project_id: task.project_id,
+            user_id: session.initiated_by_user_id.or(workspace.owner_user_id),
+        });
+        let admitted = match &slot {
+            Some(slot) => {
+                let running = ExecutionQueue::running_slots(&self.db().pool).await?;
+                execution_queue::has_capacity(&limits, &running, slot)
+            }
+            None => true,
+        };
+        let mut execution_process = ExecutionProcess::create(
             &self.db().pool,
             &create_execution_process,
             Uuid::new_v4(),
             &repo_states,
         )
         .await?;
+        if let Some(retry_of) = retry_of {
+            ExecutionProcess::set_retry_of(&self.db().pool, execution_process.id, retry_of).await?;
+        }
+        if let Some(slot) = slot.filter(|_| !admitted) {
+            ExecutionQueue::enqueue(&self.db().pool, execution_process.id, &slot).await?;
+            execution_process.status = ExecutionProcessStatus::Queued;
+        }
+        drop(admission);
 
         Workspace::set_archived(&self.db().pool, workspace.id, false).await?;
 
@@ -1053,8 +1994,29 @@ pub trait ContainerService {
             .await?;
         }
 
+        if !admitted {
+            tracing::info!(
+                "Execution process {} queued: concurrency limit reached",
+                execution_process.id
+            );
+            return Ok(execution_process);
+        }
+
+        self.launch_execution(workspace, &execution_process, executor_action, task.id)
+            .await?;
+        Ok(execution_process)
+    }
+
+    /// Spawn the process for an execution record that is already marked running
+    async fn launch_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        task_id: Uuid,
+    ) -> Result<(), ContainerError> {
         if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
+            .start_execution_inner(workspace, execution_process, executor_action)
             .await
         {
             // Mark process as failed
@@ -1072,7 +2034,16 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            // Reload the task: its status changed when the execution started
+            if let Err(e) = workflow::handle_event_for_task(
+                &self.db().pool,
+                task_id,
+                WorkflowEvent::AgentFinished,
+            )
+            .await
+            {
+                tracing::warn!("Task {} status not updated after start error: {e}", task_id);
+            }
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
@@ -1108,6 +2079,14 @@ pub trait ContainerService {
                     .await;
                 }
             };
+
+            // A run that could not start, e.g. for missing credentials, is retried like
+            // one that failed
+            if let Ok(ctx) =
+                ExecutionProcess::load_context(&self.db().pool, execution_process.id).await
+            {
+                self.retry_failed_execution(&ctx).await;
+            }
             return Err(start_error);
         }
 
@@ -1131,6 +2110,11 @@ pub trait ContainerService {
                 _ => None,
             }
         {
+            search_index::spawn_conversation_indexer(
+                self.db().pool.clone(),
+                execution_process.id,
+                msg_store.clone(),
+            );
             #[cfg(feature = "qa-mode")]
             {
                 let executor = QaMockExecutor;
@@ -1154,7 +2138,99 @@ pub trait ContainerService {
         let db_stream_handle = self.spawn_stream_raw_logs_to_db(&execution_process.id);
         self.store_db_stream_handle(execution_process.id, db_stream_handle)
             .await;
-        Ok(execution_process)
+        Ok(())
+    }
+
+    /// Start queued executions, highest priority first, for as long as the limits allow
+    async fn start_queued_executions(&self) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let limits = self.execution_limits().await;
+
+        let mut claimed = Vec::new();
+        {
+            let _admission = execution_queue::ADMISSION_LOCK.lock().await;
+            let mut running = ExecutionQueue::running_slots(pool).await?;
+            for entry in ExecutionQueue::find_all(pool).await? {
+                let slot = entry.slot();
+                if !execution_queue::has_capacity(&limits, &running, &slot) {
+                    continue;
+                }
+                if ExecutionQueue::claim(pool, entry.execution_process_id).await? {
+                    running.push(slot);
+                    claimed.push(entry.execution_process_id);
+                }
+            }
+        }
+
+        for execution_process_id in claimed {
+            let ctx = match ExecutionProcess::load_context(pool, execution_process_id).await {
+                Ok(ctx) => ctx,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load queued execution process {}: {}",
+                        execution_process_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let started = async {
+                let action = ctx.execution_process.executor_action()?.clone();
+                // The workspace may have been cleaned up while the execution waited
+                self.ensure_container_exists(&ctx.workspace).await?;
+                let workspace = Workspace::find_by_id(pool, ctx.workspace.id)
+                    .await?
+                    .ok_or(SqlxError::RowNotFound)?;
+                self.launch_execution(&workspace, &ctx.execution_process, &action, ctx.task.id)
+                    .await
+            }
+            .await;
+            match started {
+                Ok(()) => {
+                    tracing::info!("Started queued execution process {}", execution_process_id)
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to start queued execution process {}: {}",
+                        execution_process_id,
+                        e
+                    );
+                    // launch_execution marks spawn failures itself; cover earlier failures
+                    if let Err(e) = ExecutionProcess::update_completion(
+                        pool,
+                        execution_process_id,
+                        ExecutionProcessStatus::Failed,
+                        None,
+                    )
+                    .await
+                    {
+                        tracing::error!("Failed to mark queued execution as failed: {}", e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Take a queued execution off the queue and mark it with `status`.
+    /// Returns false if the execution was not queued.
+    async fn cancel_queued_execution(
+        &self,
+        execution_process_id: Uuid,
+        status: ExecutionProcessStatus,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        if ExecutionQueue::remove(pool, execution_process_id).await? == 0 {
+            return Ok(false);
+        }
+        ExecutionProcess::update_completion(pool, execution_process_id, status, None).await?;
+        if let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
+            && let Err(e) =
+                workflow::handle_event(pool, &ctx.task, WorkflowEvent::AgentFinished).await
+        {
+            tracing::warn!("Task status not updated after cancelling queued execution: {e}");
+        }
+        Ok(true)
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -0,0 +1,152 @@
+//! Admission control for coding agent executions.
+//!
+//! An execution starts only if every configured cap still has room once it is counted;
+//! otherwise it waits in the persistent execution queue until a running agent finishes.
+
+use std::sync::LazyLock;
+
+use db::models::execution_queue::ExecutionSlot;
+use tokio::sync::Mutex;
+
+use crate::services::config::ExecutionLimits;
+
+/// Serializes admission decisions so two starts cannot both take the last free slot
+pub static ADMISSION_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// Whether `candidate` can start alongside the `running` executions under `limits`
+pub fn has_capacity(
+    limits: &ExecutionLimits,
+    running: &[ExecutionSlot],
+    candidate: &ExecutionSlot,
+) -> bool {
+    let under = |cap: Option<u32>, count: usize| cap.is_none_or(|cap| count < cap as usize);
+
+    if !under(limits.max_concurrent, running.len()) {
+        return false;
+    }
+    if let Some(executor) = &candidate.executor {
+        let cap = limits
+            .per_executor
+            .iter()
+            .find(|(agent, _)| agent.to_string() == *executor)
+            .map(|(_, cap)| *cap);
+        let count = running
+            .iter()
+            .filter(|slot| slot.executor.as_ref() == Some(executor))
+            .count();
+        if !under(cap, count) {
+            return false;
+        }
+    }
+    let project_count = running
+        .iter()
+        .filter(|slot| slot.project_id == candidate.project_id)
+        .count();
+    if !under(limits.per_project, project_count) {
+        return false;
+    }
+    if let Some(user_id) = candidate.user_id {
+        let user_count = running
+            .iter()
+            .filter(|slot| slot.user_id == Some(user_id))
+            .count();
+        if !under(limits.per_user, user_count) {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn slot(executor: &str, project_id: Uuid, user_id: Option<Uuid>) -> ExecutionSlot {
+        ExecutionSlot {
+            executor: Some(executor.to_string()),
+            project_id,
+            user_id,
+        }
+    }
+
+    #[test]
+    fn no_limits_admits_everything() {
+        let project = Uuid::new_v4();
+        let running = vec![slot("CLAUDE_CODE", project, None); 20];
+        assert!(has_capacity(
+            &ExecutionLimits::default(),
+            &running,
+            &slot("CLAUDE_CODE", project, None)
+        ));
+    }
+
+    #[test]
+    fn global_cap_counts_every_execution() {
+        let limits = ExecutionLimits {
+            max_concurrent: Some(2),
+            ..Default::default()
+        };
+        let running = vec![
+            slot("CLAUDE_CODE", Uuid::new_v4(), None),
+            slot("CODEX", Uuid::new_v4(), None),
+        ];
+        assert!(!has_capacity(
+            &limits,
+            &running,
+            &slot("AMP", Uuid::new_v4(), None)
+        ));
+        assert!(has_capacity(
+            &limits,
+            &running[..1],
+            &slot("AMP", Uuid::new_v4(), None)
+        ));
+    }
+
+    #[test]
+    fn executor_project_and_user_caps_only_count_matching_executions() {
+        let project = Uuid::new_v4();
+        let user = Uuid::new_v4();
+        let limits = ExecutionLimits {
+            per_executor: [(BaseCodingAgent::Codex, 1)].into_iter().collect(),
+            per_project: Some(2),
+            per_user: Some(1),
+            ..Default::default()
+        };
+        let running = vec![slot("CODEX", Uuid::new_v4(), None)];
+
+        // Another Codex run is over the executor cap, a Claude run is not
+        assert!(!has_capacity(
+            &limits,
+            &running,
+            &slot("CODEX", project, None)
+        ));
+        assert!(has_capacity(
+            &limits,
+            &running,
+            &slot("CLAUDE_CODE", project, None)
+        ));
+
+        let running = vec![
+            slot("CLAUDE_CODE", project, Some(user)),
+            slot("CLAUDE_CODE", project, None),
+        ];
+        assert!(!has_capacity(
+            &limits,
+            &running,
+            &slot("CLAUDE_CODE", project, None)
+        ));
+        assert!(!has_capacity(
+            &limits,
+            &running[..1],
+            &slot("CLAUDE_CODE", Uuid::new_v4(), Some(user))
+        ));
+        assert!(has_capacity(
+            &limits,
+            &running[..1],
+            &slot("CLAUDE_CODE", Uuid::new_v4(), Some(Uuid::new_v4()))
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, path::Path};
 
 use chrono::{DateTime, Utc};
+use db::models::merge::MergeStrategy;
 use git2::{
     BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
     Repository, Sort,
@@ -74,6 +75,13 @@ pub struct HeadInfo {
     pub oid: String,
 }
 
+/// The commits merged by [`GitService::create_merge_preview`]
+#[derive(Debug, Clone)]
+pub struct MergePreview {
+    pub task_commit: String,
+    pub target_commit: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct Commit(git2::Oid);
 
@@ -163,6 +171,13 @@ impl GitService {
         Repository::open(repo_path).map_err(GitServiceError::from)
     }
 
+    /// Whether a failed git CLI command stopped on conflicts
+    fn looks_like_conflict(stderr: &str) -> bool {
+        stderr.contains("could not apply")
+            || stderr.contains("CONFLICT")
+            || stderr.to_lowercase().contains("resolve all conflicts")
+    }
+
     /// Ensure local (repo-scoped) identity exists for CLI commits.
     /// Sets user.name/email only if missing in the repo config.
     fn ensure_cli_commit_identity(&self, repo_path: &Path) -> Result<(), GitServiceError> {
@@ -811,23 +826,56 @@ impl GitService {
         base_branch_name: &str,
         commit_message: &str,
     ) -> Result<String, GitServiceError> {
-        // Open the repositories
-        let task_repo = self.open_repo(task_worktree_path)?;
-        let base_repo = self.open_repo(base_worktree_path)?;
+        self.merge_changes_with_strategy(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            MergeStrategy::Squash,
+        )
+    }
 
+    /// Merge changes from a task branch into the base branch using `strategy`.
+    /// `commit_message` is used for the commit that squash and merge-commit merges
+    /// create. Returns the commit the base branch points to afterwards.
+    pub fn merge_changes_with_strategy(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        strategy: MergeStrategy,
+    ) -> Result<String, GitServiceError> {
         // Check if base branch is ahead of task branch - this indicates the base has moved
-        // ahead since the task was created, which should block the merge
+        // ahead since the task was created, which should block the merge unless the task
+        // branch is to be rebased onto it
         let (_, task_behind) =
             self.get_branch_status(base_worktree_path, task_branch_name, base_branch_name)?;
 
         if task_behind > 0 {
-            return Err(GitServiceError::BranchesDiverged(format!(
-                "Cannot merge: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'. The base branch has moved forward since the task was created.",
-            )));
+            if strategy != MergeStrategy::Rebase {
+                return Err(GitServiceError::BranchesDiverged(format!(
+                    "Cannot merge: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'. The base branch has moved forward since the task was created.",
+                )));
+            }
+            self.rebase_for_merge(
+                base_worktree_path,
+                task_worktree_path,
+                base_branch_name,
+                task_branch_name,
+            )?;
         }
 
+        // Open the repositories
+        let task_repo = self.open_repo(task_worktree_path)?;
+        let base_repo = self.open_repo(base_worktree_path)?;
+
         // Check where base branch is checked out (if anywhere)
-        match self.find_checkout_path_for_branch(base_worktree_path, base_branch_name)? {
+        let merged_sha = match self
+            .find_checkout_path_for_branch(base_worktree_path, base_branch_name)?
+        {
             Some(base_checkout_path) => {
                 // base branch is checked out somewhere - use CLI merge
                 let git_cli = GitCli::new();
@@ -847,26 +895,26 @@ impl GitService {
 
                 // Use CLI merge in base context
                 self.ensure_cli_commit_identity(&base_checkout_path)?;
-                let sha = git_cli
-                    .merge_squash_commit(
+                match strategy {
+                    MergeStrategy::Squash => git_cli.merge_squash_commit(
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
                         commit_message,
-                    )
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
-                    })?;
-
-                // Update task branch ref for continuity
-                let task_refname = format!("refs/heads/{task_branch_name}");
-                git_cli
-                    .update_ref(base_worktree_path, &task_refname, &sha)
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("git update-ref failed: {e}"))
-                    })?;
-
-                Ok(sha)
+                    ),
+                    MergeStrategy::MergeCommit => git_cli.merge_no_ff_commit(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                        commit_message,
+                    ),
+                    MergeStrategy::Rebase | MergeStrategy::FastForward => git_cli.merge_ff_only(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                    ),
+                }
+                .map_err(|e| GitServiceError::InvalidRepository(format!("CLI merge failed: {e}")))?
             }
             None => {
                 // base branch not checked out anywhere - use libgit2 pure ref operations
@@ -877,31 +925,131 @@ impl GitService {
                 let base_commit = base_branch.get().peel_to_commit()?;
                 let task_commit = task_branch.get().peel_to_commit()?;
 
-                // Create the squash commit in-memory (no checkout) and update the base branch ref
-                let signature = self.signature_with_fallback(&task_repo)?;
-                let squash_commit_id = self.perform_squash_merge(
-                    &task_repo,
-                    &base_commit,
-                    &task_commit,
-                    &signature,
-                    commit_message,
-                    base_branch_name,
-                )?;
+                // Create the commit in-memory (no checkout) and update the base branch ref
+                let merged_id = match strategy {
+                    MergeStrategy::Squash => {
+                        let signature = self.signature_with_fallback(&task_repo)?;
+                        self.perform_squash_merge(
+                            &task_repo,
+                            &base_commit,
+                            &task_commit,
+                            &signature,
+                            commit_message,
+                            base_branch_name,
+                        )?
+                    }
+                    MergeStrategy::MergeCommit => {
+                        let signature = self.signature_with_fallback(&task_repo)?;
+                        self.perform_merge_commit(
+                            &task_repo,
+                            &base_commit,
+                            &task_commit,
+                            &signature,
+                            commit_message,
+                            base_branch_name,
+                        )?
+                    }
+                    MergeStrategy::Rebase | MergeStrategy::FastForward => self
+                        .perform_fast_forward(
+                            &task_repo,
+                            &base_commit,
+                            &task_commit,
+                            base_branch_name,
+                        )?,
+                };
+                merged_id.to_string()
+            }
+        };
 
-                // Update the task branch to the new squash commit so follow-up
-                // work can continue from the merged state without conflicts.
-                let task_refname = format!("refs/heads/{task_branch_name}");
-                base_repo.reference(
-                    &task_refname,
-                    squash_commit_id,
-                    true,
-                    "Reset task branch after squash merge",
-                )?;
+        // Update the task branch to the new commit so follow-up work can continue from
+        // the merged state without conflicts. Its tree matches the task branch, so the
+        // task worktree stays as it is.
+        if strategy.creates_commit() {
+            let task_refname = format!("refs/heads/{task_branch_name}");
+            base_repo.reference(
+                &task_refname,
+                git2::Oid::from_str(&merged_sha)?,
+                true,
+                "Reset task branch after merge",
+            )?;
+        }
+
+        Ok(merged_sha)
+    }
 
-                Ok(squash_commit_id.to_string())
+    /// Rebase the task branch onto the base branch ahead of a merge. On conflicts the
+    /// rebase is aborted, leaving the task branch as it was.
+    fn rebase_for_merge(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        base_branch_name: &str,
+        task_branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        match self.rebase_branch(
+            base_worktree_path,
+            task_worktree_path,
+            base_branch_name,
+            base_branch_name,
+            task_branch_name,
+        ) {
+            Ok(_) => Ok(()),
+            Err(GitServiceError::MergeConflicts {
+                conflicted_files, ..
+            }) => {
+                GitCli::new()
+                    .abort_rebase(task_worktree_path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!(
+                            "git rebase --abort failed: {e}"
+                        ))
+                    })?;
+                Err(GitServiceError::MergeConflicts {
+                    message: format!(
+                        "Cannot merge: rebasing '{task_branch_name}' onto '{base_branch_name}' ran into conflicts, so the rebase was aborted and nothing was merged. Rebase the task branch and resolve the conflicts first."
+                    ),
+                    conflicted_files,
+                })
             }
+            Err(e) => Err(e),
         }
     }
+
+    /// Undo a merge made by [`Self::merge_changes_with_strategy`], moving the base branch
+    /// back to `base_oid` and the task branch back to `task_oid`. Where the base branch
+    /// is checked out, its worktree is moved with `git reset --keep`, which leaves local
+    /// changes alone.
+    pub fn undo_merge(
+        &self,
+        base_repo_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        base_oid: &str,
+        task_oid: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        match self.find_checkout_path_for_branch(base_repo_path, base_branch_name)? {
+            Some(base_checkout_path) => {
+                git.git(&base_checkout_path, ["reset", "--keep", base_oid])
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git reset --keep failed: {e}"))
+                    })?;
+            }
+            None => git.update_ref(
+                base_repo_path,
+                &format!("refs/heads/{base_branch_name}"),
+                base_oid,
+            )?,
+        }
+        // The task branch's tree is unchanged by the merge, so its worktree stays as it is
+        git.update_ref(
+            base_repo_path,
+            &format!("refs/heads/{task_branch_name}"),
+            task_oid,
+        )?;
+        Ok(())
+    }
+
     fn get_branch_status_inner(
         &self,
         repo: &Repository,
@@ -1086,6 +1234,19 @@ impl GitService {
         Ok(git.merge_base(worktree_path, target_branch, task_branch)?)
     }
 
+    /// Whether `ancestor` is `descendant` or in its history
+    pub fn is_ancestor(
+        &self,
+        repo_path: &Path,
+        ancestor: &str,
+        descendant: &str,
+    ) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let ancestor = git2::Oid::from_str(ancestor)?;
+        let descendant = git2::Oid::from_str(descendant)?;
+        Ok(ancestor == descendant || repo.graph_descendant_of(descendant, ancestor)?)
+    }
+
     /// Get the subject/summary line for a given commit OID
     pub fn get_commit_subject(
         &self,
@@ -1232,6 +1393,67 @@ impl GitService {
         Ok(())
     }
 
+    /// Check out the merge of `task_branch` into `target_branch`, uncommitted, in a
+    /// new detached worktree at `worktree_path`, replacing any worktree already there.
+    /// On conflicts the worktree is removed again.
+    pub fn create_merge_preview(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        task_branch: &str,
+        target_branch: &str,
+    ) -> Result<MergePreview, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let target_ref = Self::find_branch(&repo, target_branch)?.into_reference();
+        // Verify against the latest remote target, as a rebase onto it would
+        if target_ref.is_remote() {
+            self.fetch_branch_from_remote(&repo, &target_ref)?;
+        }
+        let preview = MergePreview {
+            task_commit: self.get_branch_oid(repo_path, task_branch)?,
+            target_commit: self.get_branch_oid(repo_path, target_branch)?,
+        };
+
+        self.remove_merge_preview(repo_path, worktree_path)?;
+        if let Some(parent) = worktree_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let git = GitCli::new();
+        git.worktree_add_detached(repo_path, worktree_path, &preview.target_commit)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        self.ensure_cli_commit_identity(worktree_path)?;
+
+        if let Err(e) = git.merge_no_commit(worktree_path, &preview.task_commit) {
+            let conflicted_files = git.get_conflicted_files(worktree_path).unwrap_or_default();
+            self.remove_merge_preview(repo_path, worktree_path)?;
+            if conflicted_files.is_empty() {
+                return Err(GitServiceError::GitCLI(e));
+            }
+            return Err(GitServiceError::MergeConflicts {
+                message: format!(
+                    "Merging '{task_branch}' into '{target_branch}' conflicts in: {}.",
+                    conflicted_files.join(", ")
+                ),
+                conflicted_files,
+            });
+        }
+        Ok(preview)
+    }
+
+    /// Remove a worktree created by [`Self::create_merge_preview`], if there is one
+    pub fn remove_merge_preview(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        if worktree_path.exists() && git.worktree_remove(repo_path, worktree_path, true).is_err() {
+            std::fs::remove_dir_all(worktree_path)?;
+        }
+        git.worktree_prune(repo_path)?;
+        Ok(())
+    }
+
     pub fn prune_worktrees(&self, repo_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
         git.worktree_prune(repo_path)
@@ -1350,6 +1572,66 @@ impl GitService {
         Ok(squash_commit_id)
     }
 
+    /// Create a merge commit of `task_commit` into `base_commit` without touching any
+    /// working tree and move the base branch to it
+    fn perform_merge_commit(
+        &self,
+        repo: &Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+        signature: &git2::Signature,
+        commit_message: &str,
+        base_branch_name: &str,
+    ) -> Result<git2::Oid, GitServiceError> {
+        let mut merge_opts = git2::MergeOptions::new();
+        merge_opts.find_renames(true);
+        merge_opts.fail_on_conflict(true);
+        let mut index = repo.merge_commits(base_commit, task_commit, Some(&merge_opts))?;
+        if index.has_conflicts() {
+            return Err(GitServiceError::MergeConflicts {
+                message: "Merge failed due to conflicts. Please resolve conflicts manually."
+                    .to_string(),
+                conflicted_files: vec![],
+            });
+        }
+
+        let tree_id = index.write_tree_to(repo)?;
+        let tree = repo.find_tree(tree_id)?;
+        let merge_commit_id = repo.commit(
+            None,
+            signature,
+            signature,
+            commit_message,
+            &tree,
+            &[base_commit, task_commit],
+        )?;
+
+        let refname = format!("refs/heads/{base_branch_name}");
+        repo.reference(&refname, merge_commit_id, true, "Merge commit")?;
+        Ok(merge_commit_id)
+    }
+
+    /// Move the base branch to `task_commit`, which must contain `base_commit`
+    fn perform_fast_forward(
+        &self,
+        repo: &Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+        base_branch_name: &str,
+    ) -> Result<git2::Oid, GitServiceError> {
+        if base_commit.id() != task_commit.id()
+            && !repo.graph_descendant_of(task_commit.id(), base_commit.id())?
+        {
+            return Err(GitServiceError::BranchesDiverged(format!(
+                "Cannot fast-forward '{base_branch_name}': it is not an ancestor of the task branch."
+            )));
+        }
+
+        let refname = format!("refs/heads/{base_branch_name}");
+        repo.reference(&refname, task_commit.id(), true, "Fast-forward merge")?;
+        Ok(task_commit.id())
+    }
+
     /// Rebase a worktree branch onto a new base
     pub fn rebase_branch(
         &self,
@@ -1391,10 +1673,7 @@ impl GitService {
             }
             Err(GitCliError::CommandFailed(stderr)) => {
                 // If the CLI indicates conflicts, return a concise, actionable error.
-                let looks_like_conflict = stderr.contains("could not apply")
-                    || stderr.contains("CONFLICT")
-                    || stderr.to_lowercase().contains("resolve all conflicts");
-                if looks_like_conflict {
+                if Self::looks_like_conflict(&stderr) {
                     // Determine current attempt branch name for clarity
                     let attempt_branch = worktree_repo
                         .head()
@@ -1550,6 +1829,54 @@ impl GitService {
         })
     }
 
+    /// Stage the worktree's tracked changes and continue the operation stopped on
+    /// conflicts (no-op if none). Fails with `MergeConflicts` if git stops again on the
+    /// conflicts of a later commit.
+    pub fn continue_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        let git = GitCli::new();
+        self.ensure_cli_commit_identity(worktree_path)?;
+        git.stage_tracked(worktree_path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        let (result, command) = match op {
+            ConflictOp::Rebase => (git.continue_rebase(worktree_path), "git rebase --continue"),
+            ConflictOp::Merge => (git.continue_merge(worktree_path), "git commit"),
+            ConflictOp::CherryPick => (
+                git.continue_cherry_pick(worktree_path),
+                "git cherry-pick --continue",
+            ),
+            ConflictOp::Revert => (git.continue_revert(worktree_path), "git revert --continue"),
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(GitCliError::CommandFailed(stderr)) if Self::looks_like_conflict(&stderr) => {
+                let conflicted_files = git.get_conflicted_files(worktree_path).unwrap_or_default();
+                Err(GitServiceError::MergeConflicts {
+                    message: format!(
+                        "{command} stopped on conflicts in: {}",
+                        conflicted_files.join(", ")
+                    ),
+                    conflicted_files,
+                })
+            }
+            Err(e) => Err(GitServiceError::InvalidRepository(format!(
+                "{command} failed: {e}"
+            ))),
+        }
+    }
+
+    /// Abort the operation stopped on conflicts, restoring the branch and worktree to
+    /// where they were before it began. Unlike [`Self::abort_conflicts`], a rebase
+    /// whose conflicts have been staged is aborted rather than quit.
+    pub fn roll_back_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        if self.is_rebase_in_progress(worktree_path)? {
+            return self.abort_rebase(worktree_path);
+        }
+        self.abort_conflicts(worktree_path)
+    }
+
     pub fn abort_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
         if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
@@ -148,6 +148,25 @@ impl GitCli {
         Ok(())
     }
 
+    /// Run `git -C <repo> worktree add --detach <path> <commit>`
+    pub fn worktree_add_detached(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let args: Vec<OsString> = vec![
+            "worktree".into(),
+            "add".into(),
+            "--detach".into(),
+            worktree_path.as_os_str().into(),
+            OsString::from(commit),
+        ];
+        self.git(repo_path, args)?;
+        Ok(())
+    }
+
     /// Run `git -C <repo> worktree remove <path>`
     pub fn worktree_remove(
         &self,
@@ -661,6 +680,44 @@ impl GitCli {
         self.git(worktree_path, ["rebase", "--quit"]).map(|_| ())
     }
 
+    /// Stage changes to tracked files, marking resolved conflicts as such
+    pub fn stage_tracked(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["add", "-u"]).map(|_| ())
+    }
+
+    /// Continue a rebase stopped on resolved conflicts, keeping each commit's message
+    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["rebase", "--continue"],
+            &Self::no_editor_env(),
+        )
+        .map(|_| ())
+    }
+
+    /// Commit a merge stopped on resolved conflicts with its prepared message
+    pub fn continue_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["commit", "--no-edit"]).map(|_| ())
+    }
+
+    pub fn continue_cherry_pick(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["cherry-pick", "--continue"],
+            &Self::no_editor_env(),
+        )
+        .map(|_| ())
+    }
+
+    pub fn continue_revert(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["revert", "--continue"],
+            &Self::no_editor_env(),
+        )
+        .map(|_| ())
+    }
+
     /// Return true if there are staged changes (index differs from HEAD)
     pub fn has_staged_changes(&self, repo_path: &Path) -> Result<bool, GitCliError> {
         // `git diff --cached --quiet` returns exit code 1 if there are differences
@@ -691,9 +748,15 @@ impl GitCli {
         message: &str,
     ) -> Result<String, GitCliError> {
         self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
-        self.git(repo_path, ["merge", "--squash", "--no-commit", from_branch])
-            .map(|_| ())?;
-        self.git(repo_path, ["commit", "-m", message]).map(|_| ())?;
+        let squashed = self
+            .git(repo_path, ["merge", "--squash", "--no-commit", from_branch])
+            .and_then(|_| self.git(repo_path, ["commit", "-m", message]));
+        if let Err(e) = squashed {
+            // A squash leaves no MERGE_HEAD to abort; drop what it staged instead,
+            // keeping unrelated unstaged changes
+            let _ = self.git(repo_path, ["reset", "--merge"]);
+            return Err(e);
+        }
         let sha = self
             .git(repo_path, ["rev-parse", "HEAD"])?
             .trim()
@@ -701,6 +764,46 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Merge `from_branch` into `base_branch` with a merge commit, even when a
+    /// fast-forward is possible. A failed merge is aborted, leaving `base_branch` as
+    /// it was.
+    pub fn merge_no_ff_commit(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        if let Err(e) = self.git(repo_path, ["merge", "--no-ff", "-m", message, from_branch]) {
+            let _ = self.abort_merge(repo_path);
+            return Err(e);
+        }
+        self.rev_parse(repo_path, "HEAD")
+    }
+
+    /// Fast-forward `base_branch` to `from_branch`. Fails without changing anything if
+    /// `base_branch` is not an ancestor of `from_branch`.
+    pub fn merge_ff_only(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        self.rev_parse(repo_path, "HEAD")
+    }
+
+    /// Merge `commit` into HEAD without committing, leaving the result in the index
+    /// and working tree. A failed merge is left in place so its conflicts can be
+    /// inspected.
+    pub fn merge_no_commit(&self, worktree_path: &Path, commit: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["merge", "--no-ff", "--no-commit", commit])
+            .map(|_| ())
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -817,6 +920,11 @@ impl GitCli {
         }
     }
 
+    /// Accept the prepared commit message instead of opening an editor
+    fn no_editor_env() -> [(OsString, OsString); 1] {
+        [(OsString::from("GIT_EDITOR"), OsString::from("true"))]
+    }
+
     /// Ensure `git` is available on PATH
     fn ensure_available(&self) -> Result<(), GitCliError> {
         let git = resolve_executable_path_blocking("git").ok_or(GitCliError::NotAvailable)?;
@@ -15,7 +15,9 @@ use serde::Deserialize;
 use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::git_host::types::{CreatePrRequest, UnifiedPrComment};
+use crate::services::git_host::types::{
+    CiCheck, CiChecksSummary, CiStatus, CreatePrRequest, UnifiedPrComment,
+};
 
 #[derive(Debug, Clone)]
 pub struct AzureRepoInfo {
@@ -105,6 +107,50 @@ struct AzRepoProject {
     name: String,
 }
 
+/// Response item from `az repos pr policy list`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPolicyEvaluation {
+    status: Option<String>,
+    configuration: Option<AzPolicyConfiguration>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPolicyConfiguration {
+    #[serde(rename = "type")]
+    policy_type: Option<AzPolicyType>,
+    settings: Option<AzPolicySettings>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPolicyType {
+    display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPolicySettings {
+    display_name: Option<String>,
+}
+
+/// Response item from `az pipelines runs list`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPipelineRun {
+    id: i64,
+    status: Option<String>,
+    result: Option<String>,
+    source_version: Option<String>,
+    definition: Option<AzPipelineDefinition>,
+}
+
+#[derive(Deserialize)]
+struct AzPipelineDefinition {
+    name: Option<String>,
+}
+
 #[derive(Debug, Error)]
 pub enum AzCliError {
     #[error("Azure CLI (`az`) executable not found or not runnable")]
@@ -333,6 +379,36 @@ impl AzCli {
         Self::parse_pr_response(&raw)
     }
 
+    /// Replace the description of a pull request.
+    pub fn update_pr_description(&self, pr_url: &str, body: &str) -> Result<(), AzCliError> {
+        let (_, pr_id) = Self::parse_pr_url(pr_url).ok_or_else(|| {
+            AzCliError::UnexpectedOutput(format!("Could not parse Azure DevOps PR URL: {pr_url}"))
+        })?;
+        let organization_url = Self::extract_organization_url(pr_url).ok_or_else(|| {
+            AzCliError::UnexpectedOutput(format!(
+                "Could not extract organization URL from: {pr_url}"
+            ))
+        })?;
+
+        self.run(
+            [
+                "repos",
+                "pr",
+                "update",
+                "--id",
+                &pr_id.to_string(),
+                "--organization",
+                &organization_url,
+                "--description",
+                body,
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+        Ok(())
+    }
+
     pub fn list_prs_for_branch(
         &self,
         organization_url: &str,
@@ -393,6 +469,83 @@ impl AzCli {
         Self::parse_pr_threads(&raw)
     }
 
+    /// Fetch the build and status policy evaluations for a pull request.
+    pub fn get_pr_checks(&self, pr_url: &str) -> Result<CiChecksSummary, AzCliError> {
+        let (organization, pr_id) = Self::parse_pr_url(pr_url).ok_or_else(|| {
+            AzCliError::UnexpectedOutput(format!("Could not parse Azure DevOps PR URL: {pr_url}"))
+        })?;
+
+        let org_url = format!("https://dev.azure.com/{}", organization);
+
+        let raw = self.run(
+            [
+                "repos",
+                "pr",
+                "policy",
+                "list",
+                "--id",
+                &pr_id.to_string(),
+                "--organization",
+                &org_url,
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+
+        Self::parse_policy_evaluations(&raw)
+    }
+
+    /// Fetch the pipeline runs triggered for a commit in the PR's project.
+    pub fn get_commit_checks(
+        &self,
+        pr_url: &str,
+        commit_sha: &str,
+    ) -> Result<CiChecksSummary, AzCliError> {
+        let organization_url = Self::extract_organization_url(pr_url).ok_or_else(|| {
+            AzCliError::UnexpectedOutput(format!(
+                "Could not extract organization URL from: {pr_url}"
+            ))
+        })?;
+        let project = Self::parse_pr_project(pr_url).ok_or_else(|| {
+            AzCliError::UnexpectedOutput(format!(
+                "Could not extract project from Azure DevOps PR URL: {pr_url}"
+            ))
+        })?;
+
+        let raw = self.run(
+            [
+                "pipelines",
+                "runs",
+                "list",
+                "--organization",
+                &organization_url,
+                "--project",
+                &project,
+                "--query-order",
+                "QueueTimeDesc",
+                "--top",
+                "50",
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+
+        Self::parse_pipeline_runs(&raw, commit_sha, &organization_url, &project)
+    }
+
+    /// Extract the (URL-decoded) project name, which precedes `/_git/` in PR URLs.
+    fn parse_pr_project(url: &str) -> Option<String> {
+        let parts: Vec<&str> = url.split('/').collect();
+        let git_idx = parts.iter().position(|&p| p == "_git")?;
+        let project = parts.get(git_idx.checked_sub(1)?)?;
+        if project.is_empty() || project.contains("visualstudio.com") {
+            return None;
+        }
+        urlencoding::decode(project).ok().map(|p| p.into_owned())
+    }
+
     /// Parse PR URL to extract organization and PR ID.
     ///
     /// Only extracts the minimal info needed for `az repos pr show`.
@@ -544,6 +697,100 @@ impl AzCli {
         Ok(comments)
     }
 
+    fn parse_policy_evaluations(raw: &str) -> Result<CiChecksSummary, AzCliError> {
+        let evaluations: Vec<AzPolicyEvaluation> =
+            serde_json::from_str(raw.trim()).map_err(|e| {
+                AzCliError::UnexpectedOutput(format!(
+                    "Failed to parse PR policy evaluations: {e}; raw: {raw}"
+                ))
+            })?;
+
+        let checks = evaluations
+            .into_iter()
+            .filter_map(|evaluation| {
+                let configuration = evaluation.configuration?;
+                let type_name = configuration
+                    .policy_type
+                    .and_then(|t| t.display_name)
+                    .unwrap_or_default();
+                // Only build and external status policies represent CI; reviewer and
+                // work item policies are not checks.
+                if !matches!(type_name.as_str(), "Build" | "Status") {
+                    return None;
+                }
+                let name = configuration
+                    .settings
+                    .and_then(|s| s.display_name)
+                    .filter(|n| !n.is_empty())
+                    .unwrap_or(type_name);
+                Some(CiCheck {
+                    name,
+                    status: Self::map_policy_status(evaluation.status.as_deref().unwrap_or("")),
+                    url: None,
+                })
+            })
+            .collect();
+
+        Ok(CiChecksSummary::from_checks(checks))
+    }
+
+    fn parse_pipeline_runs(
+        raw: &str,
+        commit_sha: &str,
+        organization_url: &str,
+        project: &str,
+    ) -> Result<CiChecksSummary, AzCliError> {
+        let runs: Vec<AzPipelineRun> = serde_json::from_str(raw.trim()).map_err(|e| {
+            AzCliError::UnexpectedOutput(format!("Failed to parse pipeline runs: {e}; raw: {raw}"))
+        })?;
+
+        let checks = runs
+            .into_iter()
+            .filter(|run| {
+                run.source_version
+                    .as_deref()
+                    .is_some_and(|sha| sha.eq_ignore_ascii_case(commit_sha))
+            })
+            .map(|run| CiCheck {
+                name: run
+                    .definition
+                    .and_then(|d| d.name)
+                    .unwrap_or_else(|| format!("Run {}", run.id)),
+                status: Self::map_pipeline_run(run.status.as_deref(), run.result.as_deref()),
+                url: Some(format!(
+                    "{}/{}/_build/results?buildId={}",
+                    organization_url,
+                    urlencoding::encode(project),
+                    run.id
+                )),
+            })
+            .collect();
+
+        Ok(CiChecksSummary::from_checks(checks))
+    }
+
+    /// Map an Azure DevOps policy evaluation status to CiStatus
+    fn map_policy_status(status: &str) -> CiStatus {
+        match status.to_lowercase().as_str() {
+            "queued" | "running" => CiStatus::Pending,
+            "approved" => CiStatus::Passed,
+            "rejected" | "broken" => CiStatus::Failed,
+            _ => CiStatus::None,
+        }
+    }
+
+    /// Map an Azure Pipelines run status/result pair to CiStatus
+    fn map_pipeline_run(status: Option<&str>, result: Option<&str>) -> CiStatus {
+        if !status.unwrap_or("").eq_ignore_ascii_case("completed") {
+            return CiStatus::Pending;
+        }
+        match result.unwrap_or("").to_lowercase().as_str() {
+            "succeeded" => CiStatus::Passed,
+            "failed" | "partiallysucceeded" | "canceled" => CiStatus::Failed,
+            _ => CiStatus::None,
+        }
+    }
+
     /// Map Azure DevOps PR status to MergeStatus
     fn map_azure_status(status: &str) -> MergeStatus {
         match status.to_lowercase().as_str() {
@@ -670,6 +917,72 @@ mod tests {
         assert_eq!(org_url, "https://myorg.visualstudio.com");
     }
 
+    #[test]
+    fn test_parse_pr_project() {
+        assert_eq!(
+            AzCli::parse_pr_project(
+                "https://dev.azure.com/myorg/My%20Project/_git/myrepo/pullrequest/123"
+            )
+            .as_deref(),
+            Some("My Project")
+        );
+        assert_eq!(
+            AzCli::parse_pr_project(
+                "https://myorg.visualstudio.com/myproject/_git/myrepo/pullrequest/456"
+            )
+            .as_deref(),
+            Some("myproject")
+        );
+        assert!(AzCli::parse_pr_project("https://dev.azure.com/myorg").is_none());
+    }
+
+    #[test]
+    fn test_parse_policy_evaluations_ignores_non_ci_policies() {
+        let raw = r#"[
+            {"status":"running","configuration":{"type":{"displayName":"Build"},"settings":{"displayName":"CI"}}},
+            {"status":"rejected","configuration":{"type":{"displayName":"Minimum number of reviewers"},"settings":{}}},
+            {"status":"approved","configuration":{"type":{"displayName":"Status"},"settings":{"displayName":""}}}
+        ]"#;
+        let summary = AzCli::parse_policy_evaluations(raw).unwrap();
+        assert_eq!(summary.checks.len(), 2);
+        assert_eq!(summary.checks[0].name, "CI");
+        assert_eq!(summary.checks[1].name, "Status");
+        assert_eq!(summary.status, CiStatus::Pending);
+    }
+
+    #[test]
+    fn test_parse_pipeline_runs_filters_by_commit() {
+        let raw = r#"[
+            {"id":10,"status":"inProgress","result":null,"sourceVersion":"abc123","definition":{"name":"deploy"}},
+            {"id":11,"status":"completed","result":"failed","sourceVersion":"def456","definition":{"name":"deploy"}}
+        ]"#;
+        let summary =
+            AzCli::parse_pipeline_runs(raw, "ABC123", "https://dev.azure.com/myorg", "myproject")
+                .unwrap();
+        assert_eq!(summary.checks.len(), 1);
+        assert_eq!(summary.status, CiStatus::Pending);
+        assert_eq!(
+            summary.checks[0].url.as_deref(),
+            Some("https://dev.azure.com/myorg/myproject/_build/results?buildId=10")
+        );
+    }
+
+    #[test]
+    fn test_map_pipeline_run() {
+        assert_eq!(
+            AzCli::map_pipeline_run(Some("completed"), Some("succeeded")),
+            CiStatus::Passed
+        );
+        assert_eq!(
+            AzCli::map_pipeline_run(Some("completed"), Some("canceled")),
+            CiStatus::Failed
+        );
+        assert_eq!(
+            AzCli::map_pipeline_run(Some("notStarted"), None),
+            CiStatus::Pending
+        );
+    }
+
     #[test]
     fn test_extract_organization_url_invalid() {
         assert!(AzCli::extract_organization_url("https://github.com/owner/repo").is_none());
@@ -14,7 +14,7 @@ use tracing::info;
 
 use super::{
     GitHostProvider,
-    types::{CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
+    types::{CiChecksSummary, CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
 };
 
 #[derive(Debug, Clone)]
@@ -127,6 +127,39 @@ impl GitHostProvider for AzureDevOpsProvider {
         .await
     }
 
+    async fn update_pr_description(&self, pr_url: &str, body: &str) -> Result<(), GitHostError> {
+        (|| async {
+            let cli = self.az_cli.clone();
+            let url = pr_url.to_string();
+            let body = body.to_string();
+
+            let result = task::spawn_blocking(move || cli.update_pr_description(&url, &body))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute Azure CLI for updating PR: {err}"
+                    ))
+                })?;
+            result.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err: &GitHostError| err.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
     async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
         (|| async {
             let cli = self.az_cli.clone();
@@ -246,6 +279,75 @@ impl GitHostProvider for AzureDevOpsProvider {
         .await
     }
 
+    async fn get_pr_checks(&self, pr_url: &str) -> Result<CiChecksSummary, GitHostError> {
+        (|| async {
+            let cli = self.az_cli.clone();
+            let url = pr_url.to_string();
+
+            let checks = task::spawn_blocking(move || cli.get_pr_checks(&url))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute Azure CLI for fetching PR checks: {err}"
+                    ))
+                })?;
+            checks.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err: &GitHostError| err.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn get_commit_checks(
+        &self,
+        pr_url: &str,
+        commit_sha: &str,
+    ) -> Result<CiChecksSummary, GitHostError> {
+        (|| async {
+            let cli = self.az_cli.clone();
+            let url = pr_url.to_string();
+            let sha = commit_sha.to_string();
+
+            let checks = task::spawn_blocking(move || cli.get_commit_checks(&url, &sha))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute Azure CLI for fetching commit checks: {err}"
+                    ))
+                })?;
+            checks.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err: &GitHostError| err.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::AzureDevOps
     }
@@ -0,0 +1,558 @@
+//! Minimal client for the Bitbucket Cloud REST API (`api.bitbucket.org/2.0`).
+//!
+//! Bitbucket has no CLI comparable to `gh` or `glab`, so requests are authenticated with
+//! the API token configured under `git_hosts` in the config.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::services::{
+    config::GitHostsConfig,
+    git_host::types::{CiCheck, CiChecksSummary, CiStatus, CreatePrRequest, UnifiedPrComment},
+};
+
+const API_BASE: &str = "https://api.bitbucket.org/2.0";
+
+/// Upper bound on pages followed for paginated listings
+const MAX_PAGES: usize = 10;
+
+/// A Bitbucket repository, identified by workspace and repository slug.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitbucketRepoInfo {
+    pub workspace: String,
+    pub repo_slug: String,
+}
+
+impl BitbucketRepoInfo {
+    fn api_url(&self) -> String {
+        format!(
+            "{API_BASE}/repositories/{}/{}",
+            self.workspace, self.repo_slug
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct BbPage<T> {
+    values: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BbPullRequest {
+    id: i64,
+    #[serde(default)]
+    state: String,
+    links: BbPullRequestLinks,
+    merge_commit: Option<BbCommit>,
+    updated_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct BbPullRequestLinks {
+    html: BbLink,
+}
+
+#[derive(Deserialize)]
+struct BbLink {
+    href: String,
+}
+
+#[derive(Deserialize)]
+struct BbCommit {
+    hash: String,
+}
+
+#[derive(Deserialize)]
+struct BbComment {
+    id: i64,
+    content: Option<BbContent>,
+    user: Option<BbUser>,
+    created_on: Option<DateTime<Utc>>,
+    #[serde(default)]
+    deleted: bool,
+    inline: Option<BbInline>,
+    links: Option<BbCommentLinks>,
+}
+
+#[derive(Deserialize)]
+struct BbContent {
+    raw: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BbUser {
+    nickname: Option<String>,
+    display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BbInline {
+    path: String,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct BbCommentLinks {
+    html: Option<BbLink>,
+}
+
+#[derive(Deserialize)]
+struct BbCommitStatus {
+    key: String,
+    name: Option<String>,
+    state: String,
+    url: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum BitbucketApiError {
+    #[error("Bitbucket credentials are not configured; set git_hosts.bitbucket_token")]
+    MissingCredentials,
+    #[error("Bitbucket request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Bitbucket API returned {status}: {body}")]
+    Status { status: StatusCode, body: String },
+    #[error("Bitbucket API returned unexpected output: {0}")]
+    UnexpectedOutput(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct BitbucketApi {
+    client: Client,
+    username: Option<String>,
+    token: Option<String>,
+}
+
+impl BitbucketApi {
+    pub fn new(config: &GitHostsConfig) -> Result<Self, BitbucketApiError> {
+        let client = Client::builder().timeout(Duration::from_secs(30)).build()?;
+        Ok(Self {
+            client,
+            username: config.bitbucket_username.clone(),
+            token: config.bitbucket_token.clone(),
+        })
+    }
+
+    fn request(&self, method: Method, url: &str) -> Result<RequestBuilder, BitbucketApiError> {
+        let token = self
+            .token
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .ok_or(BitbucketApiError::MissingCredentials)?;
+        let builder = self.client.request(method, url);
+        Ok(match self.username.as_deref().filter(|u| !u.is_empty()) {
+            Some(username) => builder.basic_auth(username, Some(token)),
+            None => builder.bearer_auth(token),
+        })
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        builder: RequestBuilder,
+    ) -> Result<T, BitbucketApiError> {
+        let response = builder.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(BitbucketApiError::Status { status, body });
+        }
+        let raw = response.text().await?;
+        serde_json::from_str(&raw)
+            .map_err(|err| BitbucketApiError::UnexpectedOutput(format!("{err}; raw: {raw}")))
+    }
+
+    /// Follow `next` links of a paginated listing, up to [`MAX_PAGES`] pages.
+    async fn get_all<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, BitbucketApiError> {
+        let mut page: BbPage<T> = self
+            .send(self.request(Method::GET, url)?.query(query))
+            .await?;
+        let mut values = std::mem::take(&mut page.values);
+        let mut pages = 1;
+        while let Some(next) = page.next.take()
+            && pages < MAX_PAGES
+        {
+            // `next` already carries the query string
+            page = self.send(self.request(Method::GET, &next)?).await?;
+            values.append(&mut page.values);
+            pages += 1;
+        }
+        Ok(values)
+    }
+
+    pub async fn create_pr(
+        &self,
+        request: &CreatePrRequest,
+        repo: &BitbucketRepoInfo,
+        head_repo: Option<&BitbucketRepoInfo>,
+    ) -> Result<PullRequestInfo, BitbucketApiError> {
+        let mut source = json!({ "branch": { "name": request.head_branch } });
+        if let Some(head) = head_repo.filter(|head| *head != repo) {
+            source["repository"] =
+                json!({ "full_name": format!("{}/{}", head.workspace, head.repo_slug) });
+        }
+        let body = json!({
+            "title": request.title,
+            "description": request.body.as_deref().unwrap_or(""),
+            "source": source,
+            "destination": { "branch": { "name": request.base_branch } },
+            "draft": request.draft.unwrap_or(false),
+        });
+
+        let pr: BbPullRequest = self
+            .send(
+                self.request(Method::POST, &format!("{}/pullrequests", repo.api_url()))?
+                    .json(&body),
+            )
+            .await?;
+        Ok(Self::pr_to_info(pr))
+    }
+
+    pub async fn update_pr_description(
+        &self,
+        pr_url: &str,
+        body: &str,
+    ) -> Result<(), BitbucketApiError> {
+        let (repo, id) = Self::parse_pr_url(pr_url)?;
+        let _: BbPullRequest = self
+            .send(
+                self.request(
+                    Method::PUT,
+                    &format!("{}/pullrequests/{id}", repo.api_url()),
+                )?
+                .json(&json!({ "description": body })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn view_pr(&self, pr_url: &str) -> Result<PullRequestInfo, BitbucketApiError> {
+        let (repo, id) = Self::parse_pr_url(pr_url)?;
+        let pr: BbPullRequest = self
+            .send(self.request(
+                Method::GET,
+                &format!("{}/pullrequests/{id}", repo.api_url()),
+            )?)
+            .await?;
+        Ok(Self::pr_to_info(pr))
+    }
+
+    /// Pull requests opened from a branch, in any state.
+    pub async fn list_prs_for_branch(
+        &self,
+        repo: &BitbucketRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, BitbucketApiError> {
+        let q = format!("source.branch.name=\"{}\"", branch.replace('"', "\\\""));
+        let prs: Vec<BbPullRequest> = self
+            .get_all(
+                &format!("{}/pullrequests", repo.api_url()),
+                &[
+                    ("q", q.as_str()),
+                    ("state", "OPEN"),
+                    ("state", "MERGED"),
+                    ("state", "DECLINED"),
+                    ("state", "SUPERSEDED"),
+                ],
+            )
+            .await?;
+        Ok(prs.into_iter().map(Self::pr_to_info).collect())
+    }
+
+    pub async fn get_pr_comments(
+        &self,
+        repo: &BitbucketRepoInfo,
+        pr_id: i64,
+    ) -> Result<Vec<UnifiedPrComment>, BitbucketApiError> {
+        let comments: Vec<BbComment> = self
+            .get_all(
+                &format!("{}/pullrequests/{pr_id}/comments", repo.api_url()),
+                &[("pagelen", "100")],
+            )
+            .await?;
+        Ok(Self::comments_to_unified(comments))
+    }
+
+    /// Build statuses reported on the pull request's source commit.
+    pub async fn get_pr_checks(&self, pr_url: &str) -> Result<CiChecksSummary, BitbucketApiError> {
+        let (repo, id) = Self::parse_pr_url(pr_url)?;
+        let statuses: Vec<BbCommitStatus> = self
+            .get_all(
+                &format!("{}/pullrequests/{id}/statuses", repo.api_url()),
+                &[("pagelen", "100")],
+            )
+            .await?;
+        Ok(Self::statuses_to_summary(statuses))
+    }
+
+    /// Build statuses reported on a commit of the pull request's repository.
+    pub async fn get_commit_checks(
+        &self,
+        pr_url: &str,
+        commit_sha: &str,
+    ) -> Result<CiChecksSummary, BitbucketApiError> {
+        let (repo, _) = Self::parse_pr_url(pr_url)?;
+        let statuses: Vec<BbCommitStatus> = self
+            .get_all(
+                &format!("{}/commit/{commit_sha}/statuses", repo.api_url()),
+                &[("pagelen", "100")],
+            )
+            .await?;
+        Ok(Self::statuses_to_summary(statuses))
+    }
+
+    /// Parse a remote URL (`https://[user@]bitbucket.org/workspace/repo.git` or
+    /// `git@bitbucket.org:workspace/repo.git`) into workspace and repository slug.
+    pub fn parse_remote_url(remote_url: &str) -> Option<BitbucketRepoInfo> {
+        let path = match remote_url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/')?.1,
+            None => remote_url.split_once(':')?.1,
+        };
+        let mut parts = path.trim_matches('/').trim_end_matches(".git").split('/');
+        let workspace = parts.next().filter(|s| !s.is_empty())?;
+        let repo_slug = parts.next().filter(|s| !s.is_empty())?;
+        Some(BitbucketRepoInfo {
+            workspace: workspace.to_string(),
+            repo_slug: repo_slug.to_string(),
+        })
+    }
+
+    /// Parse `https://bitbucket.org/{workspace}/{repo}/pull-requests/{id}`.
+    fn parse_pr_url(pr_url: &str) -> Result<(BitbucketRepoInfo, i64), BitbucketApiError> {
+        let parse = || {
+            let without_scheme = pr_url.split_once("://").map_or(pr_url, |(_, rest)| rest);
+            let path = without_scheme.split(['#', '?']).next()?;
+            let parts: Vec<&str> = path.split('/').collect();
+            if parts.get(3) != Some(&"pull-requests") {
+                return None;
+            }
+            Some((
+                BitbucketRepoInfo {
+                    workspace: parts[1].to_string(),
+                    repo_slug: parts[2].to_string(),
+                },
+                parts.get(4)?.parse().ok()?,
+            ))
+        };
+        parse().ok_or_else(|| {
+            BitbucketApiError::UnexpectedOutput(format!(
+                "Could not parse Bitbucket PR URL: {pr_url}"
+            ))
+        })
+    }
+
+    fn pr_to_info(pr: BbPullRequest) -> PullRequestInfo {
+        let status = Self::map_pr_state(&pr.state);
+        PullRequestInfo {
+            number: pr.id,
+            url: pr.links.html.href,
+            // Bitbucket has no merge timestamp; a merged PR is last updated by the merge
+            merged_at: matches!(status, MergeStatus::Merged)
+                .then_some(pr.updated_on)
+                .flatten(),
+            status,
+            merge_commit_sha: pr.merge_commit.map(|c| c.hash),
+        }
+    }
+
+    fn map_pr_state(state: &str) -> MergeStatus {
+        match state.to_ascii_uppercase().as_str() {
+            "OPEN" | "" => MergeStatus::Open,
+            "MERGED" => MergeStatus::Merged,
+            "DECLINED" | "SUPERSEDED" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        }
+    }
+
+    fn comments_to_unified(comments: Vec<BbComment>) -> Vec<UnifiedPrComment> {
+        comments
+            .into_iter()
+            .filter(|c| !c.deleted)
+            .map(|c| {
+                let author = c
+                    .user
+                    .and_then(|u| u.nickname.or(u.display_name))
+                    .unwrap_or_else(|| "unknown".to_string());
+                let body = c
+                    .content
+                    .and_then(|content| content.raw)
+                    .unwrap_or_default();
+                let created_at = c.created_on.unwrap_or_else(Utc::now);
+                let url = c.links.and_then(|l| l.html).map(|l| l.href);
+                match c.inline {
+                    Some(inline) => {
+                        // `to` is the line in the new file; comments on removed lines only have `from`
+                        let (line, side) = match (inline.to, inline.from) {
+                            (Some(line), _) => (Some(line), "RIGHT"),
+                            (None, Some(line)) => (Some(line), "LEFT"),
+                            (None, None) => (None, "RIGHT"),
+                        };
+                        UnifiedPrComment::Review {
+                            id: c.id,
+                            author,
+                            author_association: None,
+                            body,
+                            created_at,
+                            url,
+                            path: inline.path,
+                            line,
+                            side: Some(side.to_string()),
+                            diff_hunk: None,
+                        }
+                    }
+                    None => UnifiedPrComment::General {
+                        id: c.id.to_string(),
+                        author,
+                        author_association: None,
+                        body,
+                        created_at,
+                        url,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn statuses_to_summary(statuses: Vec<BbCommitStatus>) -> CiChecksSummary {
+        CiChecksSummary::from_checks(
+            statuses
+                .into_iter()
+                .map(|s| CiCheck {
+                    status: Self::map_status_state(&s.state),
+                    name: s.name.unwrap_or(s.key),
+                    url: s.url,
+                })
+                .collect(),
+        )
+    }
+
+    /// Map a commit build status state to a CiStatus.
+    fn map_status_state(state: &str) -> CiStatus {
+        match state.to_ascii_uppercase().as_str() {
+            "SUCCESSFUL" => CiStatus::Passed,
+            "INPROGRESS" => CiStatus::Pending,
+            "FAILED" | "STOPPED" => CiStatus::Failed,
+            _ => CiStatus::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(workspace: &str, repo_slug: &str) -> BitbucketRepoInfo {
+        BitbucketRepoInfo {
+            workspace: workspace.to_string(),
+            repo_slug: repo_slug.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        assert_eq!(
+            BitbucketApi::parse_remote_url("https://user@bitbucket.org/team/repo.git"),
+            Some(repo("team", "repo"))
+        );
+        assert_eq!(
+            BitbucketApi::parse_remote_url("git@bitbucket.org:team/repo.git"),
+            Some(repo("team", "repo"))
+        );
+        assert_eq!(
+            BitbucketApi::parse_remote_url("https://bitbucket.org/team"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_pr_url() {
+        let (parsed, id) =
+            BitbucketApi::parse_pr_url("https://bitbucket.org/team/repo/pull-requests/12/diff")
+                .unwrap();
+        assert_eq!(parsed, repo("team", "repo"));
+        assert_eq!(id, 12);
+        assert!(BitbucketApi::parse_pr_url("https://bitbucket.org/team/repo/src/main").is_err());
+    }
+
+    #[test]
+    fn test_pr_states() {
+        let raw = r#"{"values":[
+            {"id":1,"state":"OPEN","links":{"html":{"href":"https://bitbucket.org/t/r/pull-requests/1"}}},
+            {"id":2,"state":"MERGED","links":{"html":{"href":"https://bitbucket.org/t/r/pull-requests/2"}},
+             "merge_commit":{"hash":"abc123def456"},"updated_on":"2024-05-01T10:00:00.000000+00:00"},
+            {"id":3,"state":"DECLINED","links":{"html":{"href":"https://bitbucket.org/t/r/pull-requests/3"}},
+             "updated_on":"2024-05-01T10:00:00.000000+00:00"}
+        ]}"#;
+        let page: BbPage<BbPullRequest> = serde_json::from_str(raw).unwrap();
+        let prs: Vec<PullRequestInfo> = page
+            .values
+            .into_iter()
+            .map(BitbucketApi::pr_to_info)
+            .collect();
+        assert!(matches!(prs[0].status, MergeStatus::Open));
+        assert!(matches!(prs[1].status, MergeStatus::Merged));
+        assert!(prs[1].merged_at.is_some());
+        assert_eq!(prs[1].merge_commit_sha.as_deref(), Some("abc123def456"));
+        assert!(matches!(prs[2].status, MergeStatus::Closed));
+        assert!(prs[2].merged_at.is_none());
+    }
+
+    #[test]
+    fn test_comments_to_unified() {
+        let raw = r#"[
+            {"id":1,"content":{"raw":"Nice"},"user":{"nickname":"alice"},
+             "created_on":"2024-05-01T10:00:00+00:00",
+             "links":{"html":{"href":"https://bitbucket.org/t/r/pull-requests/1/_/diff#comment-1"}}},
+            {"id":2,"content":{"raw":"gone"},"deleted":true,"created_on":"2024-05-01T10:00:00+00:00"},
+            {"id":3,"content":{"raw":"Typo here"},"user":{"display_name":"Bob"},
+             "created_on":"2024-05-01T11:00:00+00:00",
+             "inline":{"path":"src/main.rs","from":null,"to":8}}
+        ]"#;
+        let comments: Vec<BbComment> = serde_json::from_str(raw).unwrap();
+        let unified = BitbucketApi::comments_to_unified(comments);
+        assert_eq!(unified.len(), 2);
+        assert!(matches!(
+            &unified[0],
+            UnifiedPrComment::General { author, .. } if author == "alice"
+        ));
+        match &unified[1] {
+            UnifiedPrComment::Review {
+                author,
+                path,
+                line,
+                side,
+                ..
+            } => {
+                assert_eq!(author, "Bob");
+                assert_eq!(path, "src/main.rs");
+                assert_eq!(*line, Some(8));
+                assert_eq!(side.as_deref(), Some("RIGHT"));
+            }
+            other => panic!("expected a review comment, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_statuses_to_summary() {
+        let raw = r#"[
+            {"key":"build","name":"Pipeline #4","state":"SUCCESSFUL","url":"https://ci/4"},
+            {"key":"deploy","state":"STOPPED"}
+        ]"#;
+        let statuses: Vec<BbCommitStatus> = serde_json::from_str(raw).unwrap();
+        let summary = BitbucketApi::statuses_to_summary(statuses);
+        assert_eq!(summary.status, CiStatus::Failed);
+        assert_eq!(summary.checks[0].name, "Pipeline #4");
+        assert_eq!(summary.checks[1].name, "deploy");
+    }
+}
@@ -0,0 +1,159 @@
+//! Bitbucket Cloud hosting service implementation.
+
+mod api;
+
+use std::{future::Future, path::Path, time::Duration};
+
+pub use api::BitbucketApi;
+use api::{BitbucketApiError, BitbucketRepoInfo};
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use db::models::merge::PullRequestInfo;
+use reqwest::StatusCode;
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    types::{CiChecksSummary, CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
+};
+use crate::services::config::GitHostsConfig;
+
+#[derive(Debug, Clone)]
+pub struct BitbucketProvider {
+    api: BitbucketApi,
+}
+
+impl BitbucketProvider {
+    pub fn new(config: &GitHostsConfig) -> Result<Self, GitHostError> {
+        Ok(Self {
+            api: BitbucketApi::new(config)?,
+        })
+    }
+
+    fn repo_info(remote_url: &str) -> Result<BitbucketRepoInfo, GitHostError> {
+        BitbucketApi::parse_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!(
+                "Could not parse Bitbucket remote URL: {remote_url}"
+            ))
+        })
+    }
+
+    /// Retry a Bitbucket API call with the same policy as the other providers.
+    async fn with_retry<T, F, Fut>(&self, f: F) -> Result<T, GitHostError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, BitbucketApiError>>,
+    {
+        (|| async { f().await.map_err(GitHostError::from) })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry())
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "Bitbucket API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+}
+
+impl From<BitbucketApiError> for GitHostError {
+    fn from(error: BitbucketApiError) -> Self {
+        match &error {
+            BitbucketApiError::MissingCredentials => GitHostError::AuthFailed(error.to_string()),
+            BitbucketApiError::Status { status, .. } => match *status {
+                StatusCode::UNAUTHORIZED => GitHostError::AuthFailed(error.to_string()),
+                StatusCode::FORBIDDEN => GitHostError::InsufficientPermissions(error.to_string()),
+                StatusCode::NOT_FOUND => GitHostError::RepoNotFoundOrNoAccess(error.to_string()),
+                _ => GitHostError::PullRequest(error.to_string()),
+            },
+            BitbucketApiError::Http(_) => GitHostError::PullRequest(error.to_string()),
+            BitbucketApiError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for BitbucketProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let repo = Self::repo_info(remote_url)?;
+        let head_repo = request
+            .head_repo_url
+            .as_deref()
+            .map(Self::repo_info)
+            .transpose()?;
+
+        let pr = self
+            .with_retry(|| self.api.create_pr(request, &repo, head_repo.as_ref()))
+            .await?;
+
+        info!(
+            "Created Bitbucket PR #{} for branch {}",
+            pr.number, request.head_branch
+        );
+        Ok(pr)
+    }
+
+    async fn update_pr_description(&self, pr_url: &str, body: &str) -> Result<(), GitHostError> {
+        self.with_retry(|| self.api.update_pr_description(pr_url, body))
+            .await
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        self.with_retry(|| self.api.view_pr(pr_url)).await
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let repo = Self::repo_info(remote_url)?;
+        self.with_retry(|| self.api.list_prs_for_branch(&repo, branch_name))
+            .await
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let repo = Self::repo_info(remote_url)?;
+        let mut comments = self
+            .with_retry(|| self.api.get_pr_comments(&repo, pr_number))
+            .await?;
+        comments.sort_by_key(|c| c.created_at());
+        Ok(comments)
+    }
+
+    async fn get_pr_checks(&self, pr_url: &str) -> Result<CiChecksSummary, GitHostError> {
+        self.with_retry(|| self.api.get_pr_checks(pr_url)).await
+    }
+
+    async fn get_commit_checks(
+        &self,
+        pr_url: &str,
+        commit_sha: &str,
+    ) -> Result<CiChecksSummary, GitHostError> {
+        self.with_retry(|| self.api.get_commit_checks(pr_url, commit_sha))
+            .await
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::Bitbucket
+    }
+}
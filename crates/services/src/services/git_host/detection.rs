@@ -1,6 +1,21 @@
 //! Git hosting provider detection from repository URLs.
 
 use super::types::ProviderKind;
+use crate::services::config::GitHostsConfig;
+
+/// Detect the git hosting provider from a remote or PR URL, recognising the self-hosted
+/// GitLab instances listed in `config` as well as the hosts [`detect_provider_from_url`] knows.
+pub fn detect_provider(url: &str, config: &GitHostsConfig) -> ProviderKind {
+    if let Some(host) = url_host(url)
+        && config
+            .gitlab_hosts
+            .iter()
+            .any(|h| h.trim().eq_ignore_ascii_case(&host))
+    {
+        return ProviderKind::GitLab;
+    }
+    detect_provider_from_url(url)
+}
 
 /// Detect the git hosting provider from a remote URL.
 ///
@@ -8,6 +23,8 @@ use super::types::ProviderKind;
 /// - GitHub.com: `https://github.com/owner/repo` or `git@github.com:owner/repo.git`
 /// - GitHub Enterprise: URLs containing `github.` (e.g., `https://github.company.com/owner/repo`)
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo` or legacy `https://org.visualstudio.com/...`
+/// - GitLab.com and self-hosted instances on a `gitlab.` host (e.g., `https://gitlab.company.com/group/repo`)
+/// - Bitbucket Cloud: `https://bitbucket.org/workspace/repo` or `git@bitbucket.org:workspace/repo.git`
 pub fn detect_provider_from_url(url: &str) -> ProviderKind {
     let url_lower = url.to_lowercase();
 
@@ -28,20 +45,50 @@ pub fn detect_provider_from_url(url: &str) -> ProviderKind {
         return ProviderKind::AzureDevOps;
     }
 
+    if url_lower.contains("gitlab.com") {
+        return ProviderKind::GitLab;
+    }
+
+    if url_lower.contains("bitbucket.org") {
+        return ProviderKind::Bitbucket;
+    }
+
     // GitHub Enterprise (contains "github." but not the Azure patterns above)
     if url_lower.contains("github.") {
         return ProviderKind::GitHub;
     }
 
+    // Self-hosted GitLab on a conventional hostname; others are listed in config
+    if url_host(&url_lower).is_some_and(|host| host.starts_with("gitlab.")) {
+        return ProviderKind::GitLab;
+    }
+
     ProviderKind::Unknown
 }
 
+/// Lowercased hostname of an HTTP(S), `ssh://` or scp-style (`git@host:path`) URL.
+fn url_host(url: &str) -> Option<String> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        // scp-style: user@host:path
+        None => url.split_once(':')?.0,
+    };
+    let authority = rest.split('/').next()?;
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host_port.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
 /// Detect the git hosting provider from a PR URL.
 ///
 /// Supports:
 /// - GitHub: `https://github.com/owner/repo/pull/123`
 /// - GitHub Enterprise: `https://github.company.com/owner/repo/pull/123`
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo/pullrequest/123`
+/// - GitLab: `https://gitlab.com/group/repo/-/merge_requests/123`
+/// - Bitbucket: `https://bitbucket.org/workspace/repo/pull-requests/123`
 #[cfg(test)]
 fn detect_provider_from_pr_url(pr_url: &str) -> ProviderKind {
     let url_lower = pr_url.to_lowercase();
@@ -59,6 +106,11 @@ fn detect_provider_from_pr_url(pr_url: &str) -> ProviderKind {
         return ProviderKind::AzureDevOps;
     }
 
+    // GitLab merge request: /-/merge_requests/ in the path
+    if url_lower.contains("/-/merge_requests/") {
+        return ProviderKind::GitLab;
+    }
+
     // Fall back to general URL detection
     detect_provider_from_url(pr_url)
 }
@@ -136,14 +188,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_gitlab() {
+        assert_eq!(
+            detect_provider_from_url("https://gitlab.com/group/subgroup/repo.git"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("git@gitlab.com:group/repo.git"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("https://gitlab.company.com/team/repo"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("ssh://git@gitlab.company.com:2222/team/repo.git"),
+            ProviderKind::GitLab
+        );
+    }
+
+    #[test]
+    fn test_bitbucket() {
+        assert_eq!(
+            detect_provider_from_url("https://user@bitbucket.org/workspace/repo.git"),
+            ProviderKind::Bitbucket
+        );
+        assert_eq!(
+            detect_provider_from_url("git@bitbucket.org:workspace/repo.git"),
+            ProviderKind::Bitbucket
+        );
+    }
+
+    #[test]
+    fn test_self_hosted_gitlab_from_config() {
+        let config = GitHostsConfig {
+            gitlab_hosts: vec!["git.company.com".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            detect_provider("https://git.company.com/team/repo.git", &config),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider("git@GIT.company.com:team/repo.git", &config),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider("https://github.com/owner/repo", &config),
+            ProviderKind::GitHub
+        );
+        assert_eq!(
+            detect_provider(
+                "https://git.company.com/team/repo.git",
+                &GitHostsConfig::default()
+            ),
+            ProviderKind::Unknown
+        );
+    }
+
     #[test]
     fn test_unknown_provider() {
         assert_eq!(
-            detect_provider_from_url("https://gitlab.com/owner/repo"),
+            detect_provider_from_url("https://git.company.com/owner/repo"),
             ProviderKind::Unknown
         );
         assert_eq!(
-            detect_provider_from_url("https://bitbucket.org/owner/repo"),
+            detect_provider_from_url("https://codeberg.org/owner/repo"),
             ProviderKind::Unknown
         );
     }
@@ -175,4 +286,16 @@ mod tests {
             ProviderKind::AzureDevOps
         );
     }
+
+    #[test]
+    fn test_pr_url_gitlab_and_bitbucket() {
+        assert_eq!(
+            detect_provider_from_pr_url("https://git.company.com/group/repo/-/merge_requests/7"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_pr_url("https://bitbucket.org/workspace/repo/pull-requests/3"),
+            ProviderKind::Bitbucket
+        );
+    }
 }
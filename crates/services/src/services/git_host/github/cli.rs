@@ -18,7 +18,8 @@ use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
 use crate::services::git_host::types::{
-    CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser,
+    CiCheck, CiChecksSummary, CiStatus, CreatePrRequest, PrComment, PrCommentAuthor,
+    PrReviewComment, ReviewCommentUser,
 };
 
 #[derive(Debug, Clone)]
@@ -97,6 +98,53 @@ struct GhPrResponse {
     merge_commit: Option<GhMergeCommit>,
 }
 
+/// Entry of `statusCheckRollup`: either a CheckRun or a commit StatusContext.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhStatusCheck {
+    name: Option<String>,
+    context: Option<String>,
+    status: Option<String>,
+    conclusion: Option<String>,
+    state: Option<String>,
+    details_url: Option<String>,
+    target_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhStatusCheckRollupWrapper {
+    #[serde(default)]
+    status_check_rollup: Vec<GhStatusCheck>,
+}
+
+#[derive(Deserialize)]
+struct GhCheckRun {
+    name: String,
+    status: Option<String>,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GhCheckRunsResponse {
+    #[serde(default)]
+    check_runs: Vec<GhCheckRun>,
+}
+
+#[derive(Deserialize)]
+struct GhCommitStatus {
+    context: String,
+    state: String,
+    target_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GhCombinedStatusResponse {
+    #[serde(default)]
+    statuses: Vec<GhCommitStatus>,
+}
+
 #[derive(Debug, Error)]
 pub enum GhCliError {
     #[error("GitHub CLI (`gh`) executable not found or not runnable")]
@@ -231,6 +279,25 @@ impl GhCli {
         Self::parse_pr_create_text(&raw)
     }
 
+    /// Replace the body of a pull request with `gh pr edit`.
+    pub fn edit_pr_body(&self, pr_url: &str, body: &str) -> Result<(), GhCliError> {
+        let mut body_file = NamedTempFile::new()
+            .map_err(|e| GhCliError::CommandFailed(format!("Failed to create temp file: {e}")))?;
+        body_file
+            .write_all(body.as_bytes())
+            .map_err(|e| GhCliError::CommandFailed(format!("Failed to write body: {e}")))?;
+
+        let args = [
+            OsString::from("pr"),
+            OsString::from("edit"),
+            OsString::from(pr_url),
+            OsString::from("--body-file"),
+            body_file.path().as_os_str().to_os_string(),
+        ];
+        self.run(args, None)?;
+        Ok(())
+    }
+
     /// Retrieve details for a pull request by URL.
     pub fn view_pr(&self, pr_url: &str) -> Result<PullRequestInfo, GhCliError> {
         let raw = self.run(
@@ -309,6 +376,63 @@ impl GhCli {
         )?;
         Self::parse_pr_review_comments(&raw)
     }
+
+    /// Fetch the check runs and status contexts attached to a pull request's head commit.
+    pub fn get_pr_checks(&self, pr_url: &str) -> Result<CiChecksSummary, GhCliError> {
+        let raw = self.run(["pr", "view", pr_url, "--json", "statusCheckRollup"], None)?;
+        Self::parse_pr_checks(&raw)
+    }
+
+    /// Fetch check runs and commit statuses for a commit in the PR's repository.
+    pub fn get_commit_checks(
+        &self,
+        pr_url: &str,
+        commit_sha: &str,
+    ) -> Result<CiChecksSummary, GhCliError> {
+        let (host, owner, repo) = Self::parse_pr_url(pr_url).ok_or_else(|| {
+            GhCliError::UnexpectedOutput(format!("Could not parse GitHub PR URL: {pr_url}"))
+        })?;
+
+        // A commit can have more check runs than fit on one page
+        let check_runs_raw = self.run(
+            [
+                "api",
+                "--paginate",
+                "--hostname",
+                &host,
+                &format!("repos/{owner}/{repo}/commits/{commit_sha}/check-runs?per_page=100"),
+            ],
+            None,
+        )?;
+        let statuses_raw = self.run(
+            [
+                "api",
+                "--hostname",
+                &host,
+                &format!("repos/{owner}/{repo}/commits/{commit_sha}/status"),
+            ],
+            None,
+        )?;
+
+        let mut checks = Self::parse_check_runs(&check_runs_raw)?;
+        checks.extend(Self::parse_combined_status(&statuses_raw)?);
+        Ok(CiChecksSummary::from_checks(checks))
+    }
+
+    /// Parse `https://{host}/{owner}/{repo}/pull/{number}` into host, owner and repo.
+    fn parse_pr_url(pr_url: &str) -> Option<(String, String, String)> {
+        let without_scheme = pr_url.split_once("://").map_or(pr_url, |(_, rest)| rest);
+        let parts: Vec<&str> = without_scheme.split('/').collect();
+        let pull_idx = parts.iter().position(|&p| p == "pull")?;
+        if pull_idx != 3 {
+            return None;
+        }
+        Some((
+            parts[0].to_string(),
+            parts[1].to_string(),
+            parts[2].to_string(),
+        ))
+    }
 }
 
 impl GhCli {
@@ -390,6 +514,101 @@ impl GhCli {
         }
     }
 
+    fn parse_pr_checks(raw: &str) -> Result<CiChecksSummary, GhCliError> {
+        let wrapper: GhStatusCheckRollupWrapper =
+            serde_json::from_str(raw.trim()).map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse gh pr view --json statusCheckRollup response: {err}; raw: {raw}"
+                ))
+            })?;
+
+        let checks = wrapper
+            .status_check_rollup
+            .into_iter()
+            .map(|c| {
+                let status = match c.state.as_deref() {
+                    Some(state) => Self::map_status_state(state),
+                    None => Self::map_check_run(c.status.as_deref(), c.conclusion.as_deref()),
+                };
+                CiCheck {
+                    name: c
+                        .name
+                        .or(c.context)
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    status,
+                    url: c.details_url.or(c.target_url),
+                }
+            })
+            .collect();
+
+        Ok(CiChecksSummary::from_checks(checks))
+    }
+
+    /// Parse a check-runs API response. `gh api --paginate` prints each page's JSON
+    /// object one after another, so there may be several.
+    fn parse_check_runs(raw: &str) -> Result<Vec<CiCheck>, GhCliError> {
+        let pages = serde_json::Deserializer::from_str(raw.trim())
+            .into_iter::<GhCheckRunsResponse>()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse check-runs API response: {err}; raw: {raw}"
+                ))
+            })?;
+
+        Ok(pages
+            .into_iter()
+            .flat_map(|page| page.check_runs)
+            .map(|c| CiCheck {
+                status: Self::map_check_run(c.status.as_deref(), c.conclusion.as_deref()),
+                name: c.name,
+                url: c.html_url,
+            })
+            .collect())
+    }
+
+    fn parse_combined_status(raw: &str) -> Result<Vec<CiCheck>, GhCliError> {
+        let resp: GhCombinedStatusResponse = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse commit status API response: {err}; raw: {raw}"
+            ))
+        })?;
+
+        Ok(resp
+            .statuses
+            .into_iter()
+            .map(|s| CiCheck {
+                status: Self::map_status_state(&s.state),
+                name: s.context,
+                url: s.target_url,
+            })
+            .collect())
+    }
+
+    /// Map a check run's status/conclusion pair (GraphQL or REST casing) to a CiStatus.
+    fn map_check_run(status: Option<&str>, conclusion: Option<&str>) -> CiStatus {
+        let status = status.unwrap_or("").to_ascii_uppercase();
+        if !status.is_empty() && status != "COMPLETED" {
+            return CiStatus::Pending;
+        }
+        match conclusion.unwrap_or("").to_ascii_uppercase().as_str() {
+            "SUCCESS" => CiStatus::Passed,
+            "NEUTRAL" | "SKIPPED" | "STALE" => CiStatus::None,
+            "" => CiStatus::Pending,
+            _ => CiStatus::Failed,
+        }
+    }
+
+    /// Map a commit status context state to a CiStatus.
+    fn map_status_state(state: &str) -> CiStatus {
+        match state.to_ascii_uppercase().as_str() {
+            "SUCCESS" => CiStatus::Passed,
+            "PENDING" | "EXPECTED" => CiStatus::Pending,
+            "FAILURE" | "ERROR" => CiStatus::Failed,
+            _ => CiStatus::None,
+        }
+    }
+
     fn parse_pr_comments(raw: &str) -> Result<Vec<PrComment>, GhCliError> {
         let wrapper: GhCommentsWrapper = serde_json::from_str(raw.trim()).map_err(|err| {
             GhCliError::UnexpectedOutput(format!(
@@ -446,3 +665,95 @@ impl GhCli {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pr_url() {
+        assert_eq!(
+            GhCli::parse_pr_url("https://github.com/owner/repo/pull/42"),
+            Some((
+                "github.com".to_string(),
+                "owner".to_string(),
+                "repo".to_string()
+            ))
+        );
+        assert_eq!(
+            GhCli::parse_pr_url("https://github.company.com/team/project/pull/7"),
+            Some((
+                "github.company.com".to_string(),
+                "team".to_string(),
+                "project".to_string()
+            ))
+        );
+        assert_eq!(GhCli::parse_pr_url("https://github.com/owner/repo"), None);
+    }
+
+    #[test]
+    fn test_parse_pr_checks_rollup() {
+        let raw = r#"{"statusCheckRollup":[
+            {"__typename":"CheckRun","name":"build","status":"COMPLETED","conclusion":"SUCCESS","detailsUrl":"https://ci/1"},
+            {"__typename":"CheckRun","name":"test","status":"IN_PROGRESS","conclusion":""},
+            {"__typename":"StatusContext","context":"ci/legacy","state":"SUCCESS","targetUrl":"https://ci/2"}
+        ]}"#;
+        let summary = GhCli::parse_pr_checks(raw).unwrap();
+        assert_eq!(summary.status, CiStatus::Pending);
+        assert_eq!(summary.checks.len(), 3);
+        assert_eq!(summary.checks[2].name, "ci/legacy");
+        assert_eq!(summary.checks[2].url.as_deref(), Some("https://ci/2"));
+    }
+
+    #[test]
+    fn test_parse_pr_checks_failure() {
+        let raw = r#"{"statusCheckRollup":[
+            {"__typename":"CheckRun","name":"build","status":"COMPLETED","conclusion":"FAILURE"},
+            {"__typename":"CheckRun","name":"docs","status":"COMPLETED","conclusion":"SKIPPED"}
+        ]}"#;
+        let summary = GhCli::parse_pr_checks(raw).unwrap();
+        assert_eq!(summary.status, CiStatus::Failed);
+        assert_eq!(summary.failed_checks().next().unwrap().name, "build");
+    }
+
+    #[test]
+    fn test_parse_pr_checks_empty() {
+        let summary = GhCli::parse_pr_checks(r#"{"statusCheckRollup":[]}"#).unwrap();
+        assert_eq!(summary.status, CiStatus::None);
+    }
+
+    #[test]
+    fn test_parse_commit_check_runs() {
+        let raw = r#"{"total_count":2,"check_runs":[
+            {"name":"deploy","status":"in_progress","conclusion":null,"html_url":"https://ci/3"},
+            {"name":"build","status":"completed","conclusion":"success","html_url":"https://ci/4"}
+        ]}"#;
+        let checks = GhCli::parse_check_runs(raw).unwrap();
+        assert_eq!(checks[0].status, CiStatus::Pending);
+        assert_eq!(checks[1].status, CiStatus::Passed);
+    }
+
+    #[test]
+    fn test_parse_paginated_check_runs() {
+        let raw = r#"{"total_count":3,"check_runs":[
+            {"name":"lint","status":"completed","conclusion":"success","html_url":null},
+            {"name":"build","status":"completed","conclusion":"success","html_url":null}
+        ]}{"total_count":3,"check_runs":[
+            {"name":"e2e","status":"completed","conclusion":"failure","html_url":null}
+        ]}"#;
+        let checks = GhCli::parse_check_runs(raw).unwrap();
+        assert_eq!(checks.len(), 3);
+        assert_eq!(checks[2].name, "e2e");
+        assert_eq!(checks[2].status, CiStatus::Failed);
+    }
+
+    #[test]
+    fn test_parse_combined_status() {
+        let raw = r#"{"state":"failure","statuses":[
+            {"context":"deploy/prod","state":"error","target_url":null}
+        ]}"#;
+        let checks = GhCli::parse_combined_status(raw).unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CiStatus::Failed);
+    }
+}
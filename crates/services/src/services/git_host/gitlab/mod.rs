@@ -0,0 +1,192 @@
+//! GitLab hosting service implementation, for gitlab.com and self-hosted instances.
+
+mod cli;
+
+use std::{path::Path, time::Duration};
+
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+pub use cli::GlabCli;
+use cli::{GitLabRepoInfo, GlabCliError};
+use db::models::merge::PullRequestInfo;
+use tokio::task;
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    types::{CiChecksSummary, CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
+};
+
+#[derive(Debug, Clone)]
+pub struct GitLabProvider {
+    glab_cli: GlabCli,
+}
+
+impl GitLabProvider {
+    pub fn new() -> Result<Self, GitHostError> {
+        Ok(Self {
+            glab_cli: GlabCli::new(),
+        })
+    }
+
+    fn repo_info(remote_url: &str) -> Result<GitLabRepoInfo, GitHostError> {
+        GlabCli::parse_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!("Could not parse GitLab remote URL: {remote_url}"))
+        })
+    }
+
+    /// Run a blocking `glab` call with the same retry policy as the other providers.
+    async fn with_retry<T, F>(&self, action: &str, f: F) -> Result<T, GitHostError>
+    where
+        T: Send + 'static,
+        F: Fn(GlabCli) -> Result<T, GlabCliError> + Clone + Send + 'static,
+    {
+        (|| async {
+            let cli = self.glab_cli.clone();
+            let f = f.clone();
+            task::spawn_blocking(move || f(cli))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitLab CLI for {action}: {err}"
+                    ))
+                })?
+                .map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitLab API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+}
+
+impl From<GlabCliError> for GitHostError {
+    fn from(error: GlabCliError) -> Self {
+        match &error {
+            GlabCliError::AuthFailed(msg) => GitHostError::AuthFailed(msg.clone()),
+            GlabCliError::NotAvailable => GitHostError::CliNotInstalled {
+                provider: ProviderKind::GitLab,
+            },
+            GlabCliError::CommandFailed(msg) => {
+                let lower = msg.to_ascii_lowercase();
+                if lower.contains("403") || lower.contains("forbidden") {
+                    GitHostError::InsufficientPermissions(msg.clone())
+                } else if lower.contains("404") || lower.contains("not found") {
+                    GitHostError::RepoNotFoundOrNoAccess(msg.clone())
+                } else {
+                    GitHostError::PullRequest(msg.clone())
+                }
+            }
+            GlabCliError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GitLabProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let repo = Self::repo_info(remote_url)?;
+        let head_repo = request
+            .head_repo_url
+            .as_deref()
+            .map(Self::repo_info)
+            .transpose()?;
+
+        let request_clone = request.clone();
+        let mr = self
+            .with_retry("merge request creation", move |cli| {
+                cli.create_mr(&request_clone, &repo, head_repo.as_ref())
+            })
+            .await?;
+
+        info!(
+            "Created GitLab MR !{} for branch {}",
+            mr.number, request.head_branch
+        );
+        Ok(mr)
+    }
+
+    async fn update_pr_description(&self, pr_url: &str, body: &str) -> Result<(), GitHostError> {
+        let url = pr_url.to_string();
+        let body = body.to_string();
+        self.with_retry("updating MR description", move |cli| {
+            cli.update_mr_description(&url, &body)
+        })
+        .await
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let url = pr_url.to_string();
+        self.with_retry("viewing MR", move |cli| cli.view_mr(&url))
+            .await
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let repo = Self::repo_info(remote_url)?;
+        let branch = branch_name.to_string();
+        self.with_retry("listing MRs", move |cli| {
+            cli.list_mrs_for_branch(&repo, &branch)
+        })
+        .await
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let repo = Self::repo_info(remote_url)?;
+        self.with_retry("fetching MR notes", move |cli| {
+            cli.get_mr_notes(&repo, pr_number)
+        })
+        .await
+    }
+
+    async fn get_pr_checks(&self, pr_url: &str) -> Result<CiChecksSummary, GitHostError> {
+        let url = pr_url.to_string();
+        self.with_retry("fetching MR pipeline jobs", move |cli| {
+            cli.get_mr_checks(&url)
+        })
+        .await
+    }
+
+    async fn get_commit_checks(
+        &self,
+        pr_url: &str,
+        commit_sha: &str,
+    ) -> Result<CiChecksSummary, GitHostError> {
+        let url = pr_url.to_string();
+        let sha = commit_sha.to_string();
+        self.with_retry("fetching commit pipelines", move |cli| {
+            cli.get_commit_checks(&url, &sha)
+        })
+        .await
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::GitLab
+    }
+}
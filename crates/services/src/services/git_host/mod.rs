@@ -2,20 +2,26 @@ mod detection;
 mod types;
 
 pub mod azure;
+pub mod bitbucket;
 pub mod github;
+pub mod gitlab;
 
 use std::path::Path;
 
 use async_trait::async_trait;
 use db::models::merge::PullRequestInfo;
-use detection::detect_provider_from_url;
+use detection::detect_provider;
 use enum_dispatch::enum_dispatch;
 pub use types::{
-    CreatePrRequest, GitHostError, PrComment, PrCommentAuthor, PrReviewComment, ProviderKind,
-    ReviewCommentUser, UnifiedPrComment,
+    CiCheck, CiChecksSummary, CiStatus, CreatePrRequest, GitHostError, PrComment, PrCommentAuthor,
+    PrReviewComment, ProviderKind, ReviewCommentUser, UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{
+    azure::AzureDevOpsProvider, bitbucket::BitbucketProvider, github::GitHubProvider,
+    gitlab::GitLabProvider,
+};
+use crate::services::config::GitHostsConfig;
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -27,6 +33,9 @@ pub trait GitHostProvider: Send + Sync {
         request: &CreatePrRequest,
     ) -> Result<PullRequestInfo, GitHostError>;
 
+    /// Replace the description of an existing PR.
+    async fn update_pr_description(&self, pr_url: &str, body: &str) -> Result<(), GitHostError>;
+
     async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError>;
 
     async fn list_prs_for_branch(
@@ -43,6 +52,17 @@ pub trait GitHostProvider: Send + Sync {
         pr_number: i64,
     ) -> Result<Vec<UnifiedPrComment>, GitHostError>;
 
+    /// CI checks (check runs, status contexts, build policies) reported for an open PR.
+    async fn get_pr_checks(&self, pr_url: &str) -> Result<CiChecksSummary, GitHostError>;
+
+    /// CI/CD checks reported for a commit of the PR's repository, e.g. the deploy
+    /// pipelines triggered by a merge commit.
+    async fn get_commit_checks(
+        &self,
+        pr_url: &str,
+        commit_sha: &str,
+    ) -> Result<CiChecksSummary, GitHostError>;
+
     fn provider_kind(&self) -> ProviderKind;
 }
 
@@ -50,13 +70,17 @@ pub trait GitHostProvider: Send + Sync {
 pub enum GitHostService {
     GitHub(GitHubProvider),
     AzureDevOps(AzureDevOpsProvider),
+    GitLab(GitLabProvider),
+    Bitbucket(BitbucketProvider),
 }
 
 impl GitHostService {
-    pub fn from_url(url: &str) -> Result<Self, GitHostError> {
-        match detect_provider_from_url(url) {
+    pub fn from_url(url: &str, config: &GitHostsConfig) -> Result<Self, GitHostError> {
+        match detect_provider(url, config) {
             ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
+            ProviderKind::GitLab => Ok(Self::GitLab(GitLabProvider::new()?)),
+            ProviderKind::Bitbucket => Ok(Self::Bitbucket(BitbucketProvider::new(config)?)),
             ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
     }
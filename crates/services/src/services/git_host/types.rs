@@ -8,6 +8,8 @@ use ts_rs::TS;
 pub enum ProviderKind {
     GitHub,
     AzureDevOps,
+    GitLab,
+    Bitbucket,
     Unknown,
 }
 
@@ -16,6 +18,8 @@ impl std::fmt::Display for ProviderKind {
         match self {
             ProviderKind::GitHub => write!(f, "GitHub"),
             ProviderKind::AzureDevOps => write!(f, "Azure DevOps"),
+            ProviderKind::GitLab => write!(f, "GitLab"),
+            ProviderKind::Bitbucket => write!(f, "Bitbucket"),
             ProviderKind::Unknown => write!(f, "Unknown"),
         }
     }
@@ -65,6 +69,56 @@ impl GitHostError {
     }
 }
 
+/// Aggregate state of CI checks or pipelines, for a single check or a whole set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CiStatus {
+    /// No checks reported, or only neutral/skipped ones.
+    None,
+    Pending,
+    Passed,
+    Failed,
+}
+
+/// A single check run, status context or pipeline run reported by the git host.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CiCheck {
+    pub name: String,
+    pub status: CiStatus,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CiChecksSummary {
+    pub status: CiStatus,
+    pub checks: Vec<CiCheck>,
+}
+
+impl CiChecksSummary {
+    /// Build a summary from individual checks.
+    /// Any failure wins, then anything still running, then passes.
+    pub fn from_checks(checks: Vec<CiCheck>) -> Self {
+        let status = if checks.iter().any(|c| c.status == CiStatus::Failed) {
+            CiStatus::Failed
+        } else if checks.iter().any(|c| c.status == CiStatus::Pending) {
+            CiStatus::Pending
+        } else if checks.iter().any(|c| c.status == CiStatus::Passed) {
+            CiStatus::Passed
+        } else {
+            CiStatus::None
+        };
+        Self { status, checks }
+    }
+
+    pub fn empty() -> Self {
+        Self::from_checks(Vec::new())
+    }
+
+    pub fn failed_checks(&self) -> impl Iterator<Item = &CiCheck> {
+        self.checks.iter().filter(|c| c.status == CiStatus::Failed)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct PrCommentAuthor {
     pub login: String,
@@ -138,3 +192,43 @@ impl UnifiedPrComment {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, status: CiStatus) -> CiCheck {
+        CiCheck {
+            name: name.to_string(),
+            status,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_ci_summary_failure_wins() {
+        let summary = CiChecksSummary::from_checks(vec![
+            check("build", CiStatus::Passed),
+            check("lint", CiStatus::Pending),
+            check("test", CiStatus::Failed),
+        ]);
+        assert_eq!(summary.status, CiStatus::Failed);
+        assert_eq!(summary.failed_checks().count(), 1);
+    }
+
+    #[test]
+    fn test_ci_summary_pending_before_passed() {
+        let summary = CiChecksSummary::from_checks(vec![
+            check("build", CiStatus::Passed),
+            check("deploy", CiStatus::Pending),
+        ]);
+        assert_eq!(summary.status, CiStatus::Pending);
+    }
+
+    #[test]
+    fn test_ci_summary_neutral_only_is_none() {
+        assert_eq!(CiChecksSummary::empty().status, CiStatus::None);
+        let summary = CiChecksSummary::from_checks(vec![check("optional", CiStatus::None)]);
+        assert_eq!(summary.status, CiStatus::None);
+    }
+}
@@ -89,9 +89,31 @@ impl LocalSessionService {
         &self,
         pool: &SqlitePool,
         user_id: Uuid,
+    ) -> Result<String, SessionError> {
+        self.create_session_for(pool, user_id, Duration::days(self.session_duration_days))
+            .await
+    }
+
+    /// Create a session that expires after `duration` instead of the configured default,
+    /// e.g. for a token handed to an agent for the length of one execution
+    pub async fn create_session_with_duration(
+        &self,
+        pool: &SqlitePool,
+        user_id: Uuid,
+        duration: std::time::Duration,
+    ) -> Result<String, SessionError> {
+        self.create_session_for(pool, user_id, Duration::seconds(duration.as_secs() as i64))
+            .await
+    }
+
+    async fn create_session_for(
+        &self,
+        pool: &SqlitePool,
+        user_id: Uuid,
+        lifetime: Duration,
     ) -> Result<String, SessionError> {
         let now = Utc::now();
-        let expires_at = now + Duration::days(self.session_duration_days);
+        let expires_at = now + lifetime;
 
         // Generate a random component for the session
         let token_string = Self::generate_token_string();
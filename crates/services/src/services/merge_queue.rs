@@ -0,0 +1,32 @@
+//! Serializes merges into a shared target branch.
+//!
+//! Each repo and target branch has a queue. The entry at its front is rebased onto the
+//! target branch, verified if the repo has a verify script, and merged; then the next
+//! entry goes. An entry whose rebase conflicts steps aside while the workspace's agent
+//! resolves the conflicts (see [`super::conflict_resolution`]), and joins the back of
+//! the queue once the rebase is continued.
+
+use std::sync::LazyLock;
+
+use db::models::task::Task;
+use tokio::sync::Mutex;
+
+/// Held while the queues are advanced, so two callers cannot merge the same entry
+pub static MERGE_QUEUE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// The commit message for merging `task`'s branch
+pub fn merge_commit_message(task: &Task) -> String {
+    let task_uuid_str = task.id.to_string();
+    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
+
+    let mut commit_message = format!("{} (vibe-kanban {})", task.title, first_uuid_section);
+
+    // Add description on next line if it exists
+    if let Some(description) = &task.description
+        && !description.trim().is_empty()
+    {
+        commit_message.push_str("\n\n");
+        commit_message.push_str(description);
+    }
+    commit_message
+}
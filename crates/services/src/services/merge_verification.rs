@@ -0,0 +1,129 @@
+//! Keeps a workspace from being merged, or a pull request from being opened for it,
+//! until its repos' verify scripts pass against the merged result.
+//!
+//! Each script runs in a temporary worktree holding the workspace branch merged into
+//! its target branch. A passing run only counts while neither branch has moved since.
+
+use std::path::{Path, PathBuf};
+
+use db::models::{
+    execution_process::ExecutionProcessStatus, merge_verification::MergeVerification, repo::Repo,
+    workspace::Workspace,
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::git::{GitService, GitServiceError};
+
+#[derive(Debug, Error)]
+pub enum MergeVerificationError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error("The verify script of {0} has not passed against the current branches")]
+    NotVerified(String),
+    #[error("The verify script of {0} is still running")]
+    Running(String),
+    #[error("The verify script of {0} failed")]
+    Failed(String),
+}
+
+/// Where the temporary merge of a workspace's repo is checked out
+pub fn worktree_path(workspace_id: Uuid, repo: &Repo) -> PathBuf {
+    utils::path::get_vibe_kanban_temp_dir()
+        .join("verify")
+        .join(workspace_id.to_string())
+        .join(&repo.name)
+}
+
+/// The action running `script` in the temporary merge at `worktree_path`
+pub fn verify_action(script: &str, worktree_path: &Path) -> ExecutorAction {
+    ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: script.to_string(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::VerifyScript,
+            working_dir: Some(worktree_path.to_string_lossy().into_owned()),
+        }),
+        None,
+    )
+}
+
+/// Check that `repo`'s verify script, if it has one, passed against the merge of the
+/// workspace branch at its current head into the current head of `target_branch`
+pub async fn check_verified(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    repo: &Repo,
+    target_branch: &str,
+) -> Result<(), MergeVerificationError> {
+    if Repo::find_verify_script(pool, repo.id).await?.is_none() {
+        return Ok(());
+    }
+    let Some(verification) = MergeVerification::find_latest(pool, workspace.id, repo.id).await?
+    else {
+        return Err(MergeVerificationError::NotVerified(
+            repo.display_name.clone(),
+        ));
+    };
+    if matches!(
+        verification.status,
+        ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+    ) {
+        return Err(MergeVerificationError::Running(repo.display_name.clone()));
+    }
+
+    let task_commit = git.get_branch_oid(&repo.path, &workspace.branch)?;
+    let target_commit = git.get_branch_oid(&repo.path, target_branch)?;
+    if verification.target_branch != target_branch
+        || verification.task_commit != task_commit
+        || verification.target_commit != target_commit
+    {
+        return Err(MergeVerificationError::NotVerified(
+            repo.display_name.clone(),
+        ));
+    }
+    if !verification.passed() {
+        return Err(MergeVerificationError::Failed(repo.display_name.clone()));
+    }
+    Ok(())
+}
+
+/// Remove the temporary merge a verification ran in, once its script has finished
+pub async fn remove_worktree(pool: &SqlitePool, git: &GitService, execution_process_id: Uuid) {
+    let verification =
+        match MergeVerification::find_by_execution_process_id(pool, execution_process_id).await {
+            Ok(Some(verification)) => verification,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load merge verification {}: {}",
+                    execution_process_id,
+                    e
+                );
+                return;
+            }
+        };
+    let repo = match Repo::find_by_id(pool, verification.repo_id).await {
+        Ok(Some(repo)) => repo,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Failed to load repo {}: {}", verification.repo_id, e);
+            return;
+        }
+    };
+    if let Err(e) = git.remove_merge_preview(&repo.path, Path::new(&verification.worktree_path)) {
+        tracing::warn!(
+            "Failed to remove verification worktree {}: {}",
+            verification.worktree_path,
+            e
+        );
+    }
+}
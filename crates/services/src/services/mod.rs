@@ -1,11 +1,14 @@
 pub mod analytics;
+pub mod approval_policy;
 pub mod approvals;
 pub mod auth;
 pub mod claude_token_rotation;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
 pub mod diff_stream;
 pub mod events;
+pub mod execution_queue;
 pub mod file_ranker;
 pub mod file_search;
 pub mod filesystem;
@@ -16,6 +19,8 @@ pub mod github_oauth;
 pub mod image;
 pub mod local_session;
 pub mod main_branch_sync;
+pub mod merge_queue;
+pub mod merge_verification;
 pub mod notification;
 pub mod oauth_credentials;
 pub mod pr_monitor;
@@ -25,6 +30,12 @@ pub mod qa_repos;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod search_index;
 pub mod share;
+pub mod system_message;
+pub mod token_encryption;
+pub mod workflow;
 pub mod workspace_manager;
+pub mod workspace_merge;
+pub mod workspace_start;
 pub mod worktree_manager;
@@ -1,25 +1,453 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use chrono::Utc;
+use db::{
+    DBService,
+    models::{task::Task, user::User},
+};
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use serde::Serialize;
+use thiserror::Error;
 use tokio::sync::RwLock;
+use uuid::Uuid;
 
-use crate::services::config::Config;
+use crate::services::config::{
+    Config, NotificationChannelConfig, NotificationChannelKind, NotificationEvent, SmtpSecurity,
+};
 
-/// Service for handling notifications.
+#[derive(Debug, Error)]
+pub enum NotificationError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("Webhook returned status {status}: {body}")]
+    WebhookStatus { status: u16, body: String },
+    #[error(transparent)]
+    Smtp(#[from] lettre::transport::smtp::Error),
+    #[error(transparent)]
+    Email(#[from] lettre::error::Error),
+    #[error("Invalid email address '{address}': {reason}")]
+    InvalidAddress { address: String, reason: String },
+    #[error("Environment variable {0} holding the SMTP password is not set")]
+    MissingPassword(String),
+    #[error("No recipients for email notification")]
+    NoRecipients,
+}
+
+/// A single event to deliver to the configured notification channels.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    pub task_id: Option<Uuid>,
+    pub task_title: Option<String>,
+    pub project_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub url: Option<String>,
+    creator_user_id: Option<Uuid>,
+    assignee_user_id: Option<Uuid>,
+}
+
+impl Notification {
+    pub fn new(
+        event: NotificationEvent,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            event,
+            title: title.into(),
+            message: message.into(),
+            task_id: None,
+            task_title: None,
+            project_id: None,
+            workspace_id: None,
+            url: None,
+            creator_user_id: None,
+            assignee_user_id: None,
+        }
+    }
+
+    pub fn with_task(mut self, task: &Task) -> Self {
+        self.task_id = Some(task.id);
+        self.task_title = Some(task.title.clone());
+        self.project_id = Some(task.project_id);
+        self.creator_user_id = task.creator_user_id;
+        self.assignee_user_id = task.assignee_user_id;
+        self
+    }
+
+    pub fn with_workspace(mut self, workspace_id: Uuid) -> Self {
+        self.workspace_id = Some(workspace_id);
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+/// JSON body posted to generic webhook channels
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPayload {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    pub task_id: Option<Uuid>,
+    pub task_title: Option<String>,
+    pub project_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub url: Option<String>,
+    /// Usernames of the task's creator and assignee
+    pub users: Vec<String>,
+    pub timestamp: String,
+}
+
+/// Users attached to the notified task, resolved from the `users` table
+#[derive(Debug, Clone)]
+struct TaskUsers {
+    users: Vec<User>,
+}
+
+impl TaskUsers {
+    fn usernames(&self) -> Vec<String> {
+        self.users.iter().map(|u| u.username.clone()).collect()
+    }
+
+    fn emails(&self) -> Vec<String> {
+        self.users.iter().filter_map(|u| u.email.clone()).collect()
+    }
+}
+
+/// Service for delivering notifications to the configured channels.
 ///
 /// Desktop notifications (sound, OS push) have been removed as the application
-/// is deployed as a headless SaaS service where they are non-functional.
-#[derive(Debug, Clone)]
+/// is deployed as a headless SaaS service; events are instead routed to the
+/// webhook, Slack and email channels listed in `Config::notification_channels`.
+#[derive(Clone)]
 pub struct NotificationService {
-    _config: Arc<RwLock<Config>>,
+    config: Arc<RwLock<Config>>,
+    db: DBService,
+    client: reqwest::Client,
 }
 
 impl NotificationService {
-    pub fn new(config: Arc<RwLock<Config>>) -> Self {
-        Self { _config: config }
+    pub fn new(config: Arc<RwLock<Config>>, db: DBService) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to build notification HTTP client, using defaults: {e}");
+                reqwest::Client::new()
+            });
+
+        Self { config, db, client }
     }
 
-    /// Notification handler — currently a no-op for SaaS deployment.
-    pub async fn notify(&self, title: &str, message: &str) {
-        tracing::debug!("Notification (no-op): {title} — {message}");
+    /// Deliver a notification to every enabled channel subscribed to its event.
+    /// Delivery happens in the background; failures are logged.
+    pub async fn notify(&self, notification: Notification) {
+        let channels: Vec<NotificationChannelConfig> = {
+            let config = self.config.read().await;
+            config
+                .notification_channels
+                .iter()
+                .filter(|c| channel_accepts_event(c, notification.event))
+                .cloned()
+                .collect()
+        };
+        if channels.is_empty() {
+            tracing::debug!(
+                "No notification channels for {:?}: {}",
+                notification.event,
+                notification.title
+            );
+            return;
+        }
+
+        let task_users = self.resolve_task_users(&notification).await;
+        let usernames = task_users.usernames();
+        let payload = build_payload(&notification, usernames.clone());
+
+        for channel in channels
+            .into_iter()
+            .filter(|c| channel_accepts_users(c, &usernames))
+        {
+            let client = self.client.clone();
+            let payload = payload.clone();
+            let task_emails = task_users.emails();
+            tokio::spawn(async move {
+                if let Err(e) = deliver(&client, &channel, &payload, task_emails).await {
+                    tracing::error!(
+                        "Failed to deliver {:?} notification to channel '{}': {}",
+                        payload.event,
+                        channel.name,
+                        e
+                    );
+                }
+            });
+        }
+    }
+
+    async fn resolve_task_users(&self, notification: &Notification) -> TaskUsers {
+        let mut ids = Vec::new();
+        for id in [notification.creator_user_id, notification.assignee_user_id]
+            .into_iter()
+            .flatten()
+        {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+
+        let mut users = Vec::new();
+        for id in ids {
+            match User::find_by_id(&self.db.pool, id).await {
+                Ok(Some(user)) => users.push(user),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to load user {} for notification: {}", id, e),
+            }
+        }
+        TaskUsers { users }
+    }
+}
+
+fn channel_accepts_event(channel: &NotificationChannelConfig, event: NotificationEvent) -> bool {
+    channel.enabled && (channel.events.is_empty() || channel.events.contains(&event))
+}
+
+fn channel_accepts_users(channel: &NotificationChannelConfig, usernames: &[String]) -> bool {
+    channel.users.is_empty()
+        || usernames
+            .iter()
+            .any(|name| channel.users.iter().any(|u| u.eq_ignore_ascii_case(name)))
+}
+
+fn build_payload(notification: &Notification, users: Vec<String>) -> NotificationPayload {
+    NotificationPayload {
+        event: notification.event,
+        title: notification.title.clone(),
+        message: notification.message.clone(),
+        task_id: notification.task_id,
+        task_title: notification.task_title.clone(),
+        project_id: notification.project_id,
+        workspace_id: notification.workspace_id,
+        url: notification.url.clone(),
+        users,
+        timestamp: Utc::now().to_rfc3339(),
+    }
+}
+
+fn slack_text(payload: &NotificationPayload) -> String {
+    match &payload.url {
+        Some(url) => format!("*{}*\n{}\n<{}>", payload.title, payload.message, url),
+        None => format!("*{}*\n{}", payload.title, payload.message),
+    }
+}
+
+fn email_body(payload: &NotificationPayload) -> String {
+    match &payload.url {
+        Some(url) => format!("{}\n\n{}", payload.message, url),
+        None => payload.message.clone(),
+    }
+}
+
+fn email_recipients(fixed: &[String], task_emails: Vec<String>, include_task: bool) -> Vec<String> {
+    let mut recipients: Vec<String> = Vec::new();
+    let extra = if include_task {
+        task_emails
+    } else {
+        Vec::new()
+    };
+    for address in fixed.iter().cloned().chain(extra) {
+        if !recipients.iter().any(|r| r.eq_ignore_ascii_case(&address)) {
+            recipients.push(address);
+        }
+    }
+    recipients
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, NotificationError> {
+    address.parse().map_err(
+        |e: lettre::address::AddressError| NotificationError::InvalidAddress {
+            address: address.to_string(),
+            reason: e.to_string(),
+        },
+    )
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    channel: &NotificationChannelConfig,
+    payload: &NotificationPayload,
+    task_emails: Vec<String>,
+) -> Result<(), NotificationError> {
+    match &channel.channel {
+        NotificationChannelKind::Webhook { url, headers } => {
+            let mut request = client.post(url).json(payload);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            check_response(request.send().await?).await
+        }
+        NotificationChannelKind::Slack { webhook_url } => {
+            let body = serde_json::json!({ "text": slack_text(payload) });
+            check_response(client.post(webhook_url).json(&body).send().await?).await
+        }
+        NotificationChannelKind::Email {
+            smtp_host,
+            smtp_port,
+            security,
+            username,
+            password_env,
+            from,
+            to,
+            notify_task_users,
+        } => {
+            let recipients = email_recipients(to, task_emails, *notify_task_users);
+            if recipients.is_empty() {
+                return Err(NotificationError::NoRecipients);
+            }
+
+            let mut builder = Message::builder()
+                .from(parse_mailbox(from)?)
+                .subject(&payload.title)
+                .header(ContentType::TEXT_PLAIN);
+            for recipient in &recipients {
+                builder = builder.to(parse_mailbox(recipient)?);
+            }
+            let email = builder.body(email_body(payload))?;
+
+            let mut transport = match security {
+                SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(smtp_host)?,
+                SmtpSecurity::StartTls => {
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host)?
+                }
+                SmtpSecurity::None => {
+                    AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(smtp_host)
+                }
+            }
+            .port(*smtp_port);
+            if let Some(username) = username {
+                let password = match password_env {
+                    Some(var) => std::env::var(var)
+                        .map_err(|_| NotificationError::MissingPassword(var.clone()))?,
+                    None => String::new(),
+                };
+                transport = transport.credentials(Credentials::new(username.clone(), password));
+            }
+
+            transport.build().send(email).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn check_response(response: reqwest::Response) -> Result<(), NotificationError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(NotificationError::WebhookStatus {
+        status: status.as_u16(),
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(events: Vec<NotificationEvent>, users: Vec<&str>) -> NotificationChannelConfig {
+        NotificationChannelConfig {
+            name: "test".to_string(),
+            enabled: true,
+            channel: NotificationChannelKind::Slack {
+                webhook_url: "https://hooks.example.com/x".to_string(),
+            },
+            events,
+            users: users.into_iter().map(String::from).collect(),
+        }
+    }
+
+    #[test]
+    fn empty_event_filter_accepts_everything() {
+        let c = channel(vec![], vec![]);
+        assert!(channel_accepts_event(&c, NotificationEvent::PrMerged));
+        assert!(channel_accepts_event(
+            &c,
+            NotificationEvent::ApprovalPending
+        ));
+    }
+
+    #[test]
+    fn event_filter_restricts_events() {
+        let c = channel(vec![NotificationEvent::ExecutionFailed], vec![]);
+        assert!(channel_accepts_event(
+            &c,
+            NotificationEvent::ExecutionFailed
+        ));
+        assert!(!channel_accepts_event(
+            &c,
+            NotificationEvent::ExecutionCompleted
+        ));
+    }
+
+    #[test]
+    fn disabled_channel_accepts_nothing() {
+        let mut c = channel(vec![], vec![]);
+        c.enabled = false;
+        assert!(!channel_accepts_event(&c, NotificationEvent::PrMerged));
+    }
+
+    #[test]
+    fn user_filter_matches_task_users() {
+        let c = channel(vec![], vec!["Alice"]);
+        assert!(channel_accepts_users(&c, &["alice".to_string()]));
+        assert!(!channel_accepts_users(&c, &["bob".to_string()]));
+        assert!(!channel_accepts_users(&c, &[]));
+        assert!(channel_accepts_users(&channel(vec![], vec![]), &[]));
+    }
+
+    #[test]
+    fn email_recipients_dedupe_and_respect_routing() {
+        let fixed = vec!["ops@example.com".to_string()];
+        let task = vec!["OPS@example.com".to_string(), "dev@example.com".to_string()];
+        assert_eq!(
+            email_recipients(&fixed, task.clone(), true),
+            vec!["ops@example.com", "dev@example.com"]
+        );
+        assert_eq!(
+            email_recipients(&fixed, task, false),
+            vec!["ops@example.com"]
+        );
+    }
+
+    #[test]
+    fn payload_serializes_event_in_snake_case() {
+        let workspace_id = Uuid::new_v4();
+        let notification = Notification::new(
+            NotificationEvent::ReviewConversationOpened,
+            "Review",
+            "New conversation",
+        )
+        .with_workspace(workspace_id)
+        .with_url("https://example.com/pr/1");
+        let payload = build_payload(&notification, vec!["alice".to_string()]);
+        let json = serde_json::to_value(&payload).unwrap();
+
+        assert_eq!(json["event"], "review_conversation_opened");
+        assert_eq!(json["workspace_id"], workspace_id.to_string());
+        assert_eq!(json["users"][0], "alice");
+        assert_eq!(
+            slack_text(&payload),
+            "*Review*\nNew conversation\n<https://example.com/pr/1>"
+        );
     }
 }
@@ -1,9 +1,10 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use db::{
     DBService,
     models::{
         merge::{Merge, MergeStatus, PrMerge},
+        project_workflow::{ProjectWorkflow, ProjectWorkflowError, WorkflowEvent},
         task::{Task, TaskStatus},
         workspace::{Workspace, WorkspaceError},
     },
@@ -11,12 +12,16 @@ use db::{
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
-use tokio::time::interval;
-use tracing::{debug, error, info};
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
 
 use crate::services::{
     analytics::AnalyticsContext,
-    git_host::{self, GitHostError, GitHostProvider},
+    config::{Config, NotificationEvent},
+    git_host::{self, CiChecksSummary, CiStatus, GitHostError, GitHostProvider},
+    notification::{Notification, NotificationService},
+    system_message,
+    workflow::{self, WorkflowError},
 };
 
 #[derive(Debug, Error)]
@@ -27,24 +32,34 @@ enum PrMonitorError {
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Workflow(#[from] WorkflowError),
+    #[error(transparent)]
+    ProjectWorkflow(#[from] ProjectWorkflowError),
 }
 
-/// Service to monitor PRs and update task status when they are merged
+/// Service to monitor PRs and drive task status from their CI/CD checks and merge state
 pub struct PrMonitorService {
     db: DBService,
+    config: Arc<RwLock<Config>>,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
+    notifications: NotificationService,
 }
 
 impl PrMonitorService {
     pub async fn spawn(
         db: DBService,
+        config: Arc<RwLock<Config>>,
         analytics: Option<AnalyticsContext>,
+        notifications: NotificationService,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
+            config,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
+            notifications,
         };
         tokio::spawn(async move {
             service.start().await;
@@ -70,13 +85,18 @@ impl PrMonitorService {
     /// Check all open PRs for updates with the provided GitHub token
     async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
         let open_prs = Merge::get_open_prs(&self.db.pool).await?;
+        let awaiting_deploy = Merge::get_merged_prs_awaiting_deploy(&self.db.pool).await?;
 
-        if open_prs.is_empty() {
+        if open_prs.is_empty() && awaiting_deploy.is_empty() {
             debug!("No open PRs to check");
             return Ok(());
         }
 
-        info!("Checking {} open PRs", open_prs.len());
+        info!(
+            "Checking {} open PRs and {} merged PRs awaiting deploy",
+            open_prs.len(),
+            awaiting_deploy.len()
+        );
 
         for pr_merge in open_prs {
             if let Err(e) = self.check_pr_status(&pr_merge).await {
@@ -86,12 +106,40 @@ impl PrMonitorService {
                 );
             }
         }
+
+        for pr_merge in awaiting_deploy {
+            if let Err(e) = self.check_deploy_status(&pr_merge).await {
+                error!(
+                    "Error checking deploy status of PR #{} for workspace {}: {}",
+                    pr_merge.pr_info.number, pr_merge.workspace_id, e
+                );
+            }
+        }
         Ok(())
     }
 
+    /// Check the deploy checks of a merged PR whose task is waiting on them
+    async fn check_deploy_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
+        let Some(workspace) = Workspace::find_by_id(&self.db.pool, pr_merge.workspace_id).await?
+        else {
+            return Ok(());
+        };
+        self.sync_deploy_checks(
+            pr_merge,
+            &workspace,
+            pr_merge.pr_info.merge_commit_sha.as_deref(),
+        )
+        .await
+    }
+
+    async fn git_host_for(&self, pr_url: &str) -> Result<git_host::GitHostService, GitHostError> {
+        let config = self.config.read().await;
+        git_host::GitHostService::from_url(pr_url, &config.git_hosts)
+    }
+
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        let git_host = git_host::GitHostService::from_url(&pr_merge.pr_info.url)?;
+        let git_host = self.git_host_for(&pr_merge.pr_info.url).await?;
         let pr_status = git_host.get_pr_status(&pr_merge.pr_info.url).await?;
 
         debug!(
@@ -99,37 +147,33 @@ impl PrMonitorService {
             pr_merge.pr_info.number, pr_status.status
         );
 
-        // Update the PR status in the database
-        if !matches!(&pr_status.status, MergeStatus::Open) {
-            // Update merge status with the latest information from git host
-            Merge::update_status(
-                &self.db.pool,
-                pr_merge.id,
-                pr_status.status.clone(),
-                pr_status.merge_commit_sha,
-            )
-            .await?;
-
-            // If the PR was merged, update the task status to done
-            if matches!(&pr_status.status, MergeStatus::Merged)
-                && let Some(workspace) =
-                    Workspace::find_by_id(&self.db.pool, pr_merge.workspace_id).await?
-            {
-                info!(
-                    "PR #{} was merged, updating task {} to done and archiving workspace",
-                    pr_merge.pr_info.number, workspace.task_id
-                );
-                Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+        let Some(workspace) = Workspace::find_by_id(&self.db.pool, pr_merge.workspace_id).await?
+        else {
+            return Ok(());
+        };
 
-                // Archive workspace unless pinned
-                if !workspace.pinned {
-                    Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
-                }
+        if matches!(&pr_status.status, MergeStatus::Open) {
+            return self.sync_pr_checks(&git_host, pr_merge, &workspace).await;
+        }
 
+        // Update merge status with the latest information from git host
+        Merge::update_status(
+            &self.db.pool,
+            pr_merge.id,
+            pr_status.status.clone(),
+            pr_status.merge_commit_sha.clone(),
+        )
+        .await?;
+
+        if matches!(&pr_status.status, MergeStatus::Merged) {
+            info!(
+                "PR #{} was merged for task {}",
+                pr_merge.pr_info.number, workspace.task_id
+            );
+
+            if let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await {
                 // Track analytics event
-                if let Some(analytics) = &self.analytics
-                    && let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await
-                {
+                if let Some(analytics) = &self.analytics {
                     analytics.analytics_service.track_event(
                         &analytics.user_id,
                         "pr_merged",
@@ -140,9 +184,209 @@ impl PrMonitorService {
                         })),
                     );
                 }
+
+                self.notifications
+                    .notify(
+                        Notification::new(
+                            NotificationEvent::PrMerged,
+                            format!("PR Merged: {}", task.title),
+                            format!(
+                                "PR #{} for '{}' was merged",
+                                pr_merge.pr_info.number, task.title
+                            ),
+                        )
+                        .with_task(&task)
+                        .with_workspace(workspace.id)
+                        .with_url(pr_merge.pr_info.url.clone()),
+                    )
+                    .await;
             }
+
+            self.sync_deploy_checks(pr_merge, &workspace, pr_status.merge_commit_sha.as_deref())
+                .await?;
         }
 
         Ok(())
     }
+
+    /// Apply the workflow's check transitions based on the checks running on an open PR
+    async fn sync_pr_checks(
+        &self,
+        git_host: &git_host::GitHostService,
+        pr_merge: &PrMerge,
+        workspace: &Workspace,
+    ) -> Result<(), PrMonitorError> {
+        let Some(task) = Task::find_by_id(&self.db.pool, workspace.task_id).await? else {
+            return Ok(());
+        };
+
+        // Only tasks the workflow moves on check results need the checks fetched
+        let events = [
+            WorkflowEvent::ChecksPending,
+            WorkflowEvent::ChecksPassed,
+            WorkflowEvent::ChecksFailed,
+        ];
+        let definition =
+            ProjectWorkflow::definition_for_project(&self.db.pool, task.project_id).await?;
+        if !events
+            .iter()
+            .any(|event| definition.find_automation(*event, &task.status).is_some())
+        {
+            return Ok(());
+        }
+
+        let checks = git_host.get_pr_checks(&pr_merge.pr_info.url).await?;
+        debug!(
+            "PR #{} checks: {:?} ({} total)",
+            pr_merge.pr_info.number,
+            checks.status,
+            checks.checks.len()
+        );
+
+        let event = match checks.status {
+            CiStatus::Pending => WorkflowEvent::ChecksPending,
+            CiStatus::Failed => WorkflowEvent::ChecksFailed,
+            CiStatus::Passed | CiStatus::None => WorkflowEvent::ChecksPassed,
+        };
+        let Some(status) = self.apply_event(&task, event).await? else {
+            return Ok(());
+        };
+        info!(
+            "PR #{} checks are {:?}, moved task {} to {}",
+            pr_merge.pr_info.number, checks.status, task.id, status
+        );
+
+        if event == WorkflowEvent::ChecksFailed {
+            let message = failed_checks_message(
+                &format!("CI checks failed on PR #{}", pr_merge.pr_info.number),
+                &status,
+                &checks,
+            );
+            system_message::append_to_task_history(&self.db.pool, task.id, &message).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a workflow event to the task. Guard failures leave the task where it is,
+    /// since the PR already changed on the git host.
+    async fn apply_event(
+        &self,
+        task: &Task,
+        event: WorkflowEvent,
+    ) -> Result<Option<TaskStatus>, PrMonitorError> {
+        match workflow::handle_event(&self.db.pool, task, event).await {
+            Ok(status) => Ok(status),
+            Err(WorkflowError::Database(e)) => Err(e.into()),
+            Err(e) => {
+                warn!(
+                    "Workflow did not move task {} on {:?}: {}",
+                    task.id, event, e
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Fire the workflow's deploy events for a merged PR: pending while deploy checks on
+    /// the merge commit run, then merged once they pass (or when there is nothing to wait for).
+    async fn sync_deploy_checks(
+        &self,
+        pr_merge: &PrMerge,
+        workspace: &Workspace,
+        merge_commit_sha: Option<&str>,
+    ) -> Result<(), PrMonitorError> {
+        let checks = match merge_commit_sha {
+            Some(sha) => {
+                let git_host = self.git_host_for(&pr_merge.pr_info.url).await?;
+                match git_host.get_commit_checks(&pr_merge.pr_info.url, sha).await {
+                    Ok(checks) => checks,
+                    Err(e) => {
+                        // Deploy checks are best-effort; don't leave the task stuck on errors
+                        warn!(
+                            "Failed to fetch deploy checks for PR #{}: {}",
+                            pr_merge.pr_info.number, e
+                        );
+                        CiChecksSummary::empty()
+                    }
+                }
+            }
+            None => CiChecksSummary::empty(),
+        };
+
+        let Some(task) = Task::find_by_id(&self.db.pool, workspace.task_id).await? else {
+            return Ok(());
+        };
+
+        match checks.status {
+            CiStatus::Pending => {
+                if let Some(status) = self
+                    .apply_event(&task, WorkflowEvent::DeployPending)
+                    .await?
+                {
+                    info!(
+                        "PR #{} deploy checks are running, moved task {} to {}",
+                        pr_merge.pr_info.number, task.id, status
+                    );
+                }
+            }
+            CiStatus::Failed => {
+                let Some(status) = self.apply_event(&task, WorkflowEvent::DeployFailed).await?
+                else {
+                    return Ok(());
+                };
+                info!(
+                    "PR #{} deploy checks failed, moved task {} to {}",
+                    pr_merge.pr_info.number, task.id, status
+                );
+                let message = failed_checks_message(
+                    &format!(
+                        "Deploy checks failed after merging PR #{}",
+                        pr_merge.pr_info.number
+                    ),
+                    &status,
+                    &checks,
+                );
+                system_message::append_to_task_history(&self.db.pool, workspace.task_id, &message)
+                    .await?;
+            }
+            CiStatus::Passed | CiStatus::None => {
+                if let Some(status) = self.apply_event(&task, WorkflowEvent::PrMerged).await? {
+                    info!(
+                        "PR #{} is merged and deployed, moved task {} to {}",
+                        pr_merge.pr_info.number, task.id, status
+                    );
+                }
+
+                // Archive workspace unless pinned
+                if !workspace.pinned {
+                    Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a system message saying where the task moved and listing the failed checks, with
+/// links where available
+fn failed_checks_message(headline: &str, status: &TaskStatus, checks: &CiChecksSummary) -> String {
+    let status = match status {
+        TaskStatus::Todo => "To Do",
+        TaskStatus::InProgress => "In Progress",
+        TaskStatus::InReview => "In Review",
+        TaskStatus::Ci => "CI",
+        TaskStatus::Cd => "CD",
+        TaskStatus::Done => "Done",
+        TaskStatus::Cancelled => "Cancelled",
+    };
+    let mut message = format!("{headline}. Task moved to {status}.");
+    for check in checks.failed_checks() {
+        match &check.url {
+            Some(url) => message.push_str(&format!("\n- {} ({})", check.name, url)),
+            None => message.push_str(&format!("\n- {}", check.name)),
+        }
+    }
+    message
 }
@@ -1,10 +1,13 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use db::models::{
     project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
+    project_archive::{ImportProject, ProjectArchive, ProjectArchiveError},
+    project_member::{ProjectMember, ProjectRole},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
 };
@@ -14,6 +17,7 @@ use uuid::Uuid;
 
 use super::{
     file_search::{FileSearchCache, SearchQuery},
+    image::{ImageError, ImageService},
     repo::{RepoError, RepoService},
 };
 
@@ -41,6 +45,10 @@ pub enum ProjectServiceError {
     GitError(String),
     #[error("Remote client error: {0}")]
     RemoteClient(String),
+    #[error(transparent)]
+    Archive(#[from] ProjectArchiveError),
+    #[error(transparent)]
+    Image(#[from] ImageError),
 }
 
 pub type Result<T> = std::result::Result<T, ProjectServiceError>;
@@ -111,6 +119,10 @@ impl ProjectService {
             ProjectRepo::create(pool, project.id, repo_entity.id).await?;
         }
 
+        if let Some(user_id) = creator_user_id {
+            ProjectMember::upsert(pool, project.id, user_id, ProjectRole::Owner).await?;
+        }
+
         Ok(project)
     }
 
@@ -212,6 +224,78 @@ impl ProjectService {
         Ok(rows_affected)
     }
 
+    /// The whole project as an archive, image files included
+    pub async fn export_project(
+        &self,
+        pool: &SqlitePool,
+        image_service: &ImageService,
+        project_id: Uuid,
+    ) -> Result<ProjectArchive> {
+        let mut archive = ProjectArchive::collect(pool, project_id).await?;
+        for image in &archive.images {
+            match tokio::fs::read(image_service.get_absolute_path(image)).await {
+                Ok(data) => {
+                    archive.image_data.insert(image.id, BASE64.encode(data));
+                }
+                Err(e) => tracing::warn!("Leaving image {} out of the export: {}", image.id, e),
+            }
+        }
+        Ok(archive)
+    }
+
+    /// Recreate an exported project on this server as a new project
+    pub async fn import_project(
+        &self,
+        pool: &SqlitePool,
+        repo_service: &RepoService,
+        image_service: &ImageService,
+        payload: ImportProject,
+        creator_user_id: Option<Uuid>,
+    ) -> Result<Project> {
+        let ImportProject {
+            archive,
+            repo_paths,
+        } = payload;
+        archive.check_version()?;
+
+        let mut local_repo_paths = HashMap::new();
+        for repo in &archive.repos {
+            let path = match repo_paths.get(&repo.id) {
+                Some(path) => repo_service.normalize_path(path)?,
+                None => repo.path.clone(),
+            };
+            repo_service.validate_git_repo_path(&path)?;
+            local_repo_paths.insert(repo.id, path);
+        }
+
+        let mut image_ids = HashMap::new();
+        for image in &archive.images {
+            let Some(data) = archive.image_data.get(&image.id) else {
+                continue;
+            };
+            let data = BASE64.decode(data).map_err(|_| ImageError::InvalidFormat)?;
+            let stored = image_service
+                .store_image(&data, &image.original_name)
+                .await?;
+            image_ids.insert(image.id, stored.id);
+        }
+
+        let project = archive
+            .restore(pool, &local_repo_paths, &image_ids, creator_user_id)
+            .await?;
+        if let Some(user_id) = creator_user_id {
+            ProjectMember::upsert(pool, project.id, user_id, ProjectRole::Owner).await?;
+        }
+
+        tracing::info!(
+            "Imported project {} as {} ({} tasks)",
+            archive.project.id,
+            project.id,
+            archive.tasks.len()
+        );
+        Ok(project)
+    }
+
     pub async fn get_repositories(&self, pool: &SqlitePool, project_id: Uuid) -> Result<Vec<Repo>> {
         let repos = ProjectRepo::find_repos_for_project(pool, project_id).await?;
         Ok(repos)
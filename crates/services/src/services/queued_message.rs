@@ -1,24 +1,17 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use chrono::{DateTime, Utc};
-use dashmap::DashMap;
-use db::models::scratch::DraftFollowUpData;
+use db::{
+    DBService,
+    models::{
+        queued_message::{QueuedMessage, QueuedMessageError},
+        scratch::DraftFollowUpData,
+    },
+};
 use serde::{Deserialize, Serialize};
+use tokio::sync::{RwLock, broadcast};
 use ts_rs::TS;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[ts(export)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
-    pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
-}
-
 /// Status of the queue for a session (for frontend display)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "status", rename_all = "snake_case")]
@@ -26,67 +19,162 @@ pub struct QueuedMessage {
 pub enum QueueStatus {
     /// No message queued
     Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+    /// Messages are queued and waiting for execution to complete
+    Queued {
+        /// The message that will run next
+        message: QueuedMessage,
+        /// Every queued message, in execution order
+        messages: Vec<QueuedMessage>,
+    },
+}
+
+impl QueueStatus {
+    pub fn from_messages(messages: Vec<QueuedMessage>) -> Self {
+        match messages.first() {
+            Some(first) => QueueStatus::Queued {
+                message: first.clone(),
+                messages,
+            },
+            None => QueueStatus::Empty,
+        }
+    }
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// Service for managing the persistent follow-up queue of each session.
+/// Messages are stored in SQLite and drained one at a time after each run;
+/// every change is broadcast to the session's subscribers.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    db: DBService,
+    channels: Arc<RwLock<HashMap<Uuid, broadcast::Sender<QueueStatus>>>>,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
+    pub fn new(db: DBService) -> Self {
         Self {
-            queue: Arc::new(DashMap::new()),
+            db,
+            channels: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
-            session_id,
-            data,
-            queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
-        queued
+    /// Append a message to the end of the session's queue
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::enqueue(&self.db.pool, session_id, &data).await?;
+        self.publish(session_id).await
+    }
+
+    /// Edit a queued message in place
+    pub async fn update_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        self.find_in_session(session_id, message_id).await?;
+        QueuedMessage::update_data(&self.db.pool, message_id, &data).await?;
+        self.publish(session_id).await
+    }
+
+    /// Remove a single queued message
+    pub async fn remove_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        self.find_in_session(session_id, message_id).await?;
+        QueuedMessage::delete(&self.db.pool, message_id).await?;
+        self.publish(session_id).await
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Set the execution order of the session's queue
+    pub async fn reorder(
+        &self,
+        session_id: Uuid,
+        ordered_ids: &[Uuid],
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::reorder(&self.db.pool, session_id, ordered_ids).await?;
+        self.publish(session_id).await
     }
 
-    /// Get the queued message for a session (if any)
-    pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+    /// Cancel every queued message for a session
+    pub async fn clear(&self, session_id: Uuid) -> Result<u64, QueuedMessageError> {
+        let removed = QueuedMessage::delete_by_session_id(&self.db.pool, session_id).await?;
+        if removed > 0 {
+            self.publish(session_id).await?;
+        }
+        Ok(removed)
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Take (remove and return) the next queued message for a session.
+    /// Used by finalization flow to consume the queue one message at a time.
+    pub async fn take_next(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, QueuedMessageError> {
+        let next = QueuedMessage::take_next(&self.db.pool, session_id).await?;
+        if next.is_some() {
+            self.publish(session_id).await?;
+        }
+        Ok(next)
     }
 
-    /// Check if a session has a queued message
-    pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+    /// Check if a session has any queued message
+    pub async fn has_queued(&self, session_id: Uuid) -> Result<bool, QueuedMessageError> {
+        Ok(QueuedMessage::exists_for_session(&self.db.pool, session_id).await?)
     }
 
     /// Get queue status for frontend display
-    pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
-            None => QueueStatus::Empty,
+    pub async fn get_status(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        let messages = QueuedMessage::find_by_session_id(&self.db.pool, session_id).await?;
+        Ok(QueueStatus::from_messages(messages))
+    }
+
+    /// Subscribe to queue changes for a session
+    pub async fn subscribe(&self, session_id: Uuid) -> broadcast::Receiver<QueueStatus> {
+        let mut channels = self.channels.write().await;
+        let sender = channels
+            .entry(session_id)
+            .or_insert_with(|| broadcast::channel(64).0);
+        sender.subscribe()
+    }
+
+    /// Drop the session's channel once its last subscriber has gone. Call after dropping a
+    /// receiver from [`Self::subscribe`], so channels don't outlive their subscribers.
+    pub async fn release(&self, session_id: Uuid) {
+        let mut channels = self.channels.write().await;
+        if channels
+            .get(&session_id)
+            .is_some_and(|sender| sender.receiver_count() == 0)
+        {
+            channels.remove(&session_id);
         }
     }
-}
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+    async fn find_in_session(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        QueuedMessage::find_by_id(&self.db.pool, message_id)
+            .await?
+            .filter(|m| m.session_id == session_id)
+            .ok_or(QueuedMessageError::NotFound)
+    }
+
+    /// Load the current queue and push it to subscribers
+    async fn publish(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        let status = self.get_status(session_id).await?;
+        let mut channels = self.channels.write().await;
+        if let Some(sender) = channels.get(&session_id)
+            && sender.send(status.clone()).is_err()
+        {
+            // No subscribers left
+            channels.remove(&session_id);
+        }
+        Ok(status)
     }
 }
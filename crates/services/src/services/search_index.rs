@@ -0,0 +1,306 @@
+//! Keeps the full-text search index of agent conversations up to date.
+//!
+//! Normalized conversation entries only exist in an execution's message store, so
+//! while a coding agent runs its patches are applied to a copy of the conversation and
+//! every few seconds the text of the entries they touched is written to the search
+//! index. Executions whose conversation was never fully indexed, such as those from
+//! before search existed or cut short by a restart, are indexed from their stored logs
+//! at startup.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use db::models::search::{ConversationEntryText, ConversationSearchIndex};
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
+use futures::{StreamExt, stream::BoxStream};
+use json_patch::Patch;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::task::JoinHandle;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+/// How long conversation changes wait before they are indexed
+const INDEX_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long re-normalizing stored logs may go without producing a patch before the
+/// conversation is taken to be complete
+const STORED_LOGS_IDLE: Duration = Duration::from_secs(2);
+
+/// Index the conversation of a coding agent execution as it grows, until the
+/// execution finishes
+pub fn spawn_conversation_indexer(
+    pool: SqlitePool,
+    execution_id: Uuid,
+    msg_store: Arc<MsgStore>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut stream = msg_store.history_plus_stream();
+        let mut ticker = tokio::time::interval(INDEX_INTERVAL);
+        let mut conversation = ConversationIndex::default();
+
+        loop {
+            tokio::select! {
+                msg = stream.next() => match msg {
+                    Some(Ok(LogMsg::JsonPatch(patch))) => conversation.apply(&patch),
+                    Some(Ok(LogMsg::Finished)) | None => break,
+                    Some(_) => {}
+                },
+                _ = ticker.tick(), if conversation.is_dirty() => {
+                    conversation.update_index(&pool, execution_id).await;
+                }
+            }
+        }
+        if conversation.update_index(&pool, execution_id).await
+            && let Err(e) = ConversationSearchIndex::mark_indexed(&pool, execution_id).await
+        {
+            tracing::warn!(
+                "Failed to mark execution {} as indexed: {}",
+                execution_id,
+                e
+            );
+        }
+    })
+}
+
+/// Index the whole conversation of a stopped execution from the normalized patches of
+/// its stored logs, replacing whatever was indexed for it before
+pub async fn index_stored_conversation(
+    pool: &SqlitePool,
+    execution_id: Uuid,
+    mut patches: BoxStream<'static, Result<LogMsg, std::io::Error>>,
+) -> Result<(), sqlx::Error> {
+    let mut conversation = ConversationIndex::default();
+    // Normalizers never signal that they are done, so stop once they go quiet
+    while let Ok(Some(msg)) = tokio::time::timeout(STORED_LOGS_IDLE, patches.next()).await {
+        match msg {
+            Ok(LogMsg::JsonPatch(patch)) => conversation.apply(&patch),
+            Ok(LogMsg::Finished) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+    let entries: Vec<ConversationEntryText> = conversation
+        .entries()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| searchable_text(index as i64, entry))
+        .collect();
+    ConversationSearchIndex::replace_entries(pool, execution_id, &entries).await
+}
+
+/// A conversation rebuilt from its patches as they arrive, with the entries changed
+/// since the index was last updated
+#[derive(Default)]
+struct ConversationIndex {
+    conversation: Value,
+    /// Entries touched by patches not yet written to the index
+    changed: BTreeSet<usize>,
+    /// What the index holds for this execution
+    indexed: HashMap<i64, ConversationEntryText>,
+}
+
+impl ConversationIndex {
+    fn entries(&self) -> &[Value] {
+        self.conversation["entries"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn is_dirty(&self) -> bool {
+        !self.changed.is_empty()
+    }
+
+    /// Apply a patch and note the entries it touched. Adding or removing an entry
+    /// shifts the ones after it, so those count as touched too.
+    fn apply(&mut self, patch: &Patch) {
+        if self.conversation.is_null() {
+            self.conversation = serde_json::json!({ "entries": [] });
+        }
+        let len_before = self.entries().len();
+        if let Err(e) = json_patch::patch(&mut self.conversation, patch) {
+            tracing::debug!("Skipping conversation patch for the search index: {}", e);
+            return;
+        }
+        let len_after = self.entries().len();
+
+        let Ok(Value::Array(ops)) = serde_json::to_value(patch) else {
+            return;
+        };
+        for op in &ops {
+            let Some(index) = op
+                .get("path")
+                .and_then(Value::as_str)
+                .and_then(|path| path.strip_prefix("/entries/"))
+                .and_then(|index| index.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            match op.get("op").and_then(Value::as_str) {
+                Some("replace") => {
+                    self.changed.insert(index);
+                }
+                _ => self.changed.extend(index..len_before.max(len_after)),
+            }
+        }
+    }
+
+    /// Write the touched entries to the index. Returns whether the index is now up to
+    /// date with the conversation.
+    async fn update_index(&mut self, pool: &SqlitePool, execution_id: Uuid) -> bool {
+        let mut upserts = Vec::new();
+        let mut removed = Vec::new();
+        for &index in &self.changed {
+            let text = self
+                .entries()
+                .get(index)
+                .and_then(|entry| searchable_text(index as i64, entry));
+            let indexed = self.indexed.get(&(index as i64));
+            match text {
+                Some(text) if indexed != Some(&text) => upserts.push(text),
+                Some(_) => {}
+                None if indexed.is_some() => removed.push(index as i64),
+                None => {}
+            }
+        }
+
+        if let Err(e) = ConversationSearchIndex::upsert_entries(pool, execution_id, &upserts).await
+        {
+            tracing::warn!(
+                "Failed to update search index for execution {}: {}",
+                execution_id,
+                e
+            );
+            return false;
+        }
+        if let Err(e) = ConversationSearchIndex::remove_entries(pool, execution_id, &removed).await
+        {
+            tracing::warn!(
+                "Failed to update search index for execution {}: {}",
+                execution_id,
+                e
+            );
+            return false;
+        }
+        for index in removed {
+            self.indexed.remove(&index);
+        }
+        for text in upserts {
+            self.indexed.insert(text.entry_index, text);
+        }
+        self.changed.clear();
+        true
+    }
+}
+
+/// The text to index for a conversation entry: assistant messages, tool calls with
+/// the command they ran, and errors. Other entries are not searchable.
+fn searchable_text(entry_index: i64, entry: &Value) -> Option<ConversationEntryText> {
+    if entry.get("type")?.as_str()? != "NORMALIZED_ENTRY" {
+        return None;
+    }
+    let entry: NormalizedEntry = serde_json::from_value(entry.get("content")?.clone()).ok()?;
+
+    let (entry_type, content) = match &entry.entry_type {
+        NormalizedEntryType::AssistantMessage => ("assistant_message", entry.content),
+        NormalizedEntryType::ErrorMessage { .. } => ("error_message", entry.content),
+        NormalizedEntryType::ToolUse {
+            action_type: ActionType::CommandRun { command, .. },
+            ..
+        } if !entry.content.contains(command.as_str()) => {
+            ("tool_use", format!("{command}\n{}", entry.content))
+        }
+        NormalizedEntryType::ToolUse { .. } => ("tool_use", entry.content),
+        _ => return None,
+    };
+    if content.trim().is_empty() {
+        return None;
+    }
+    Some(ConversationEntryText {
+        entry_index,
+        entry_type: entry_type.to_string(),
+        content,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{ToolStatus, utils::ConversationPatch};
+    use serde_json::json;
+
+    use super::*;
+
+    fn patch_value(entry: NormalizedEntry) -> Value {
+        json!({ "type": "NORMALIZED_ENTRY", "content": entry })
+    }
+
+    #[test]
+    fn indexes_commands_messages_and_errors_only() {
+        let command = patch_value(NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "cargo test -p db".to_string(),
+                    result: None,
+                },
+                status: ToolStatus::Created,
+            },
+            content: "Run the db tests".to_string(),
+            metadata: None,
+        });
+        let text = searchable_text(3, &command).unwrap();
+        assert_eq!(text.entry_index, 3);
+        assert_eq!(text.entry_type, "tool_use");
+        assert_eq!(text.content, "cargo test -p db\nRun the db tests");
+
+        let thinking = patch_value(NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::Thinking,
+            content: "Hmm".to_string(),
+            metadata: None,
+        });
+        assert!(searchable_text(4, &thinking).is_none());
+        assert!(searchable_text(5, &json!({ "type": "STDOUT", "content": "hi" })).is_none());
+    }
+
+    fn message(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn tracks_only_the_entries_patches_touch() {
+        let mut conversation = ConversationIndex::default();
+        for (index, content) in ["one", "two", "three"].into_iter().enumerate() {
+            conversation.apply(&ConversationPatch::add_normalized_entry(
+                index,
+                message(content),
+            ));
+        }
+        assert_eq!(conversation.changed, BTreeSet::from([0, 1, 2]));
+
+        conversation.changed.clear();
+        conversation.apply(&ConversationPatch::replace(1, message("two, edited")));
+        assert_eq!(conversation.changed, BTreeSet::from([1]));
+
+        // Removing an entry shifts every entry after it
+        conversation.changed.clear();
+        conversation.apply(&ConversationPatch::remove(0));
+        assert_eq!(conversation.changed, BTreeSet::from([0, 1, 2]));
+        assert_eq!(conversation.entries().len(), 2);
+        assert_eq!(
+            searchable_text(0, &conversation.entries()[0])
+                .unwrap()
+                .content,
+            "two, edited"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Helpers for surfacing system messages in a task's conversation history.
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    execution_process_logs::ExecutionProcessLogs,
+    session::Session,
+    workspace::{Workspace, WorkspaceError},
+};
+use executors::logs::{NormalizedEntry, NormalizedEntryType, utils::patch::ConversationPatch};
+use sqlx::SqlitePool;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// Append a system message to the chat history of the task's latest coding agent run.
+///
+/// Does nothing if the task has no workspace, session or coding agent process yet.
+pub async fn append_to_task_history(
+    pool: &SqlitePool,
+    task_id: Uuid,
+    message: &str,
+) -> Result<(), WorkspaceError> {
+    // Get the latest workspace for this task
+    let workspaces = Workspace::fetch_all(pool, Some(task_id)).await?;
+    let Some(workspace) = workspaces.first() else {
+        return Ok(());
+    };
+
+    // Get the latest session for this workspace
+    let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await? else {
+        return Ok(());
+    };
+
+    // Get the latest coding agent execution process for this session
+    let Some(execution_process) = ExecutionProcess::find_latest_by_session_and_run_reason(
+        pool,
+        session.id,
+        &ExecutionProcessRunReason::CodingAgent,
+    )
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let entry = NormalizedEntry {
+        timestamp: Some(chrono::Utc::now().to_rfc3339()),
+        entry_type: NormalizedEntryType::SystemMessage,
+        content: message.to_string(),
+        metadata: None,
+    };
+
+    let patch = ConversationPatch::add_normalized_entry(999, entry);
+    if let Ok(json_line) = serde_json::to_string::<LogMsg>(&LogMsg::JsonPatch(patch)) {
+        let _ = ExecutionProcessLogs::append_log_line(
+            pool,
+            execution_process.id,
+            &format!("{json_line}\n"),
+        )
+        .await;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,442 @@
+//! Authenticated encryption for secrets stored in the database.
+//!
+//! Values are sealed with AES-256-GCM and stored as
+//! `v2:<key id>:<base64(nonce || ciphertext)>`. The key id is derived from the key
+//! itself, so several keys can be held at once: the first key of the ring encrypts,
+//! every key can decrypt. Older `v1:` (base64 only) and raw values are still readable
+//! so existing rows can be migrated in place.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use aes_gcm::{
+    Aes256Gcm, Key, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng},
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use utils::assets::asset_dir;
+
+/// Environment variable holding the key ring: base64-encoded 32-byte keys separated by
+/// commas or newlines, newest (encrypting) key first.
+pub const TOKEN_ENCRYPTION_KEY_ENV: &str = "VK_TOKEN_ENCRYPTION_KEY";
+
+const V1_PREFIX: &str = "v1:";
+const V2_PREFIX: &str = "v2:";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Error)]
+pub enum TokenEncryptionError {
+    #[error("Invalid encryption key: {0}")]
+    InvalidKey(String),
+    #[error("No encryption key matches key id {0}")]
+    UnknownKey(String),
+    #[error("Malformed encrypted value: {0}")]
+    Malformed(String),
+    #[error("Decryption failed: value was tampered with or the key is wrong")]
+    DecryptionFailed,
+    #[error("Encryption failed")]
+    EncryptionFailed,
+    #[error(
+        "Key rotation is only supported for file-backed keys; update VK_TOKEN_ENCRYPTION_KEY instead"
+    )]
+    RotationUnsupported,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Where the key ring was loaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    Env,
+    File(PathBuf),
+}
+
+struct RingKey {
+    id: String,
+    bytes: [u8; KEY_LEN],
+    cipher: Aes256Gcm,
+}
+
+impl RingKey {
+    fn new(bytes: [u8; KEY_LEN]) -> Self {
+        Self {
+            id: key_id(&bytes),
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)),
+            bytes,
+        }
+    }
+}
+
+/// An ordered set of AES-256-GCM keys. The first key is used for encryption.
+pub struct TokenCipher {
+    keys: Vec<RingKey>,
+    source: KeySource,
+}
+
+impl std::fmt::Debug for TokenCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenCipher")
+            .field(
+                "key_ids",
+                &self.keys.iter().map(|k| &k.id).collect::<Vec<_>>(),
+            )
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+/// Default key file, generated on first start when no key is configured
+pub fn default_key_path() -> PathBuf {
+    asset_dir().join("token_encryption.key")
+}
+
+impl TokenCipher {
+    /// Load the key ring from `VK_TOKEN_ENCRYPTION_KEY`, falling back to the configured key
+    /// file, and finally to a key file in the asset directory that is created if missing.
+    pub fn load(key_file: Option<&Path>) -> Result<Self, TokenEncryptionError> {
+        if let Ok(raw) = std::env::var(TOKEN_ENCRYPTION_KEY_ENV)
+            && !raw.trim().is_empty()
+        {
+            return Self::from_key_ring(&raw, KeySource::Env);
+        }
+
+        match key_file {
+            Some(path) => {
+                let raw = fs::read_to_string(path)?;
+                Self::from_key_ring(&raw, KeySource::File(path.to_path_buf()))
+            }
+            None => {
+                let path = default_key_path();
+                if path.exists() {
+                    let raw = fs::read_to_string(&path)?;
+                    return Self::from_key_ring(&raw, KeySource::File(path));
+                }
+                tracing::warn!(
+                    "No token encryption key configured; generating one at {}. Set {} or \
+                     `token_encryption_key_file` to keep the key away from the database.",
+                    path.display(),
+                    TOKEN_ENCRYPTION_KEY_ENV
+                );
+                let cipher = Self {
+                    keys: vec![RingKey::new(generate_key())],
+                    source: KeySource::File(path),
+                };
+                cipher.persist()?;
+                Ok(cipher)
+            }
+        }
+    }
+
+    /// Parse a key ring of base64-encoded keys separated by commas or newlines.
+    pub fn from_key_ring(raw: &str, source: KeySource) -> Result<Self, TokenEncryptionError> {
+        let keys = raw
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(parse_key)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if keys.is_empty() {
+            return Err(TokenEncryptionError::InvalidKey(
+                "key ring is empty".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            keys: keys.into_iter().map(RingKey::new).collect(),
+            source,
+        })
+    }
+
+    pub fn source(&self) -> &KeySource {
+        &self.source
+    }
+
+    /// Identifier of the key new values are encrypted with
+    pub fn primary_key_id(&self) -> &str {
+        &self.keys[0].id
+    }
+
+    /// Encrypt a value with the primary key into the `v2:` format.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, TokenEncryptionError> {
+        let key = &self.keys[0];
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = key
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| TokenEncryptionError::EncryptionFailed)?;
+
+        let mut payload = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        payload.extend_from_slice(&nonce);
+        payload.extend_from_slice(&ciphertext);
+
+        Ok(format!(
+            "{V2_PREFIX}{}:{}",
+            key.id,
+            STANDARD.encode(payload)
+        ))
+    }
+
+    /// Decrypt a stored value. Accepts `v2:` values sealed with any key in the ring,
+    /// as well as legacy `v1:` and raw values.
+    pub fn decrypt(&self, stored: &str) -> Result<String, TokenEncryptionError> {
+        if let Some(rest) = stored.strip_prefix(V2_PREFIX) {
+            let (id, b64) = rest.split_once(':').ok_or_else(|| {
+                TokenEncryptionError::Malformed("missing key id separator".to_string())
+            })?;
+            let key = self
+                .keys
+                .iter()
+                .find(|k| k.id == id)
+                .ok_or_else(|| TokenEncryptionError::UnknownKey(id.to_string()))?;
+
+            let payload = STANDARD.decode(b64).map_err(|e| {
+                TokenEncryptionError::Malformed(format!("base64 decode error: {e}"))
+            })?;
+            if payload.len() <= NONCE_LEN {
+                return Err(TokenEncryptionError::Malformed(
+                    "payload too short".to_string(),
+                ));
+            }
+            let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+            let plaintext = key
+                .cipher
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|_| TokenEncryptionError::DecryptionFailed)?;
+
+            return String::from_utf8(plaintext)
+                .map_err(|e| TokenEncryptionError::Malformed(format!("utf8 decode error: {e}")));
+        }
+
+        if let Some(b64) = stored.strip_prefix(V1_PREFIX) {
+            return STANDARD
+                .decode(b64)
+                .map_err(|e| TokenEncryptionError::Malformed(format!("base64 decode error: {e}")))
+                .and_then(|bytes| {
+                    String::from_utf8(bytes).map_err(|e| {
+                        TokenEncryptionError::Malformed(format!("utf8 decode error: {e}"))
+                    })
+                });
+        }
+
+        // Legacy: assume raw token
+        Ok(stored.to_string())
+    }
+
+    /// Whether a stored value should be rewritten: anything that is not `v2:` under the
+    /// primary key.
+    pub fn needs_reencryption(&self, stored: &str) -> bool {
+        stored
+            .strip_prefix(V2_PREFIX)
+            .and_then(|rest| rest.split_once(':'))
+            .is_none_or(|(id, _)| id != self.primary_key_id())
+    }
+
+    /// Build a new ring with a freshly generated primary key, keeping the current keys
+    /// so existing values stay readable until they are re-encrypted.
+    pub fn with_new_primary_key(&self) -> Result<Self, TokenEncryptionError> {
+        if self.source == KeySource::Env {
+            return Err(TokenEncryptionError::RotationUnsupported);
+        }
+        let mut keys = vec![RingKey::new(generate_key())];
+        keys.extend(self.keys.iter().map(|k| RingKey::new(k.bytes)));
+        Ok(Self {
+            keys,
+            source: self.source.clone(),
+        })
+    }
+
+    /// Drop every key except the primary one.
+    pub fn without_retired_keys(&self) -> Self {
+        Self {
+            keys: vec![RingKey::new(self.keys[0].bytes)],
+            source: self.source.clone(),
+        }
+    }
+
+    /// Write the key ring back to its key file (no-op for env-provided keys).
+    pub fn persist(&self) -> Result<(), TokenEncryptionError> {
+        let KeySource::File(path) = &self.source else {
+            return Ok(());
+        };
+
+        let contents = self
+            .keys
+            .iter()
+            .map(|k| STANDARD.encode(k.bytes))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write a new file that is private from the start and move it over the old one,
+        // so the keys are never readable by others, nor left half-written
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+        if let Err(e) = fs::remove_file(&tmp_path)
+            && e.kind() != io::ErrorKind::NotFound
+        {
+            return Err(e.into());
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(format!("{contents}\n").as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+}
+
+fn parse_key(encoded: &str) -> Result<[u8; KEY_LEN], TokenEncryptionError> {
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|e| TokenEncryptionError::InvalidKey(format!("not valid base64: {e}")))?;
+    bytes.try_into().map_err(|bytes: Vec<u8>| {
+        TokenEncryptionError::InvalidKey(format!("expected {KEY_LEN} bytes, got {}", bytes.len()))
+    })
+}
+
+fn generate_key() -> [u8; KEY_LEN] {
+    let key = Aes256Gcm::generate_key(&mut OsRng);
+    let mut bytes = [0u8; KEY_LEN];
+    bytes.copy_from_slice(&key);
+    bytes
+}
+
+/// Short, non-secret identifier for a key
+fn key_id(key: &[u8; KEY_LEN]) -> String {
+    let digest = Sha256::digest(key);
+    digest[..4].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher_with(keys: &[[u8; KEY_LEN]]) -> TokenCipher {
+        let ring = keys
+            .iter()
+            .map(|k| STANDARD.encode(k))
+            .collect::<Vec<_>>()
+            .join(",");
+        TokenCipher::from_key_ring(&ring, KeySource::Env).unwrap()
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let cipher = cipher_with(&[[7u8; KEY_LEN]]);
+        let encrypted = cipher.encrypt("my-secret-token-12345").unwrap();
+        assert!(encrypted.starts_with("v2:"));
+        assert!(!encrypted.contains("my-secret-token"));
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), "my-secret-token-12345");
+    }
+
+    #[test]
+    fn test_encryption_uses_random_nonce() {
+        let cipher = cipher_with(&[[7u8; KEY_LEN]]);
+        assert_ne!(
+            cipher.encrypt("token").unwrap(),
+            cipher.encrypt("token").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decrypt_legacy_formats() {
+        let cipher = cipher_with(&[[7u8; KEY_LEN]]);
+        assert_eq!(cipher.decrypt("v1:dGVzdA==").unwrap(), "test");
+        assert_eq!(cipher.decrypt("raw-token").unwrap(), "raw-token");
+        assert!(cipher.needs_reencryption("v1:dGVzdA=="));
+        assert!(cipher.needs_reencryption("raw-token"));
+    }
+
+    #[test]
+    fn test_tampered_value_is_rejected() {
+        let cipher = cipher_with(&[[7u8; KEY_LEN]]);
+        let encrypted = cipher.encrypt("token").unwrap();
+        let (prefix, b64) = encrypted.rsplit_once(':').unwrap();
+        let mut payload = STANDARD.decode(b64).unwrap();
+        let last = payload.len() - 1;
+        payload[last] ^= 1;
+        let tampered = format!("{prefix}:{}", STANDARD.encode(payload));
+        assert!(matches!(
+            cipher.decrypt(&tampered),
+            Err(TokenEncryptionError::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_old_key_still_decrypts_after_rotation() {
+        let old = cipher_with(&[[1u8; KEY_LEN]]);
+        let encrypted = old.encrypt("token").unwrap();
+
+        let rotated = cipher_with(&[[2u8; KEY_LEN], [1u8; KEY_LEN]]);
+        assert_eq!(rotated.decrypt(&encrypted).unwrap(), "token");
+        assert!(rotated.needs_reencryption(&encrypted));
+        assert!(!rotated.needs_reencryption(&rotated.encrypt("token").unwrap()));
+
+        let new_only = cipher_with(&[[2u8; KEY_LEN]]);
+        assert!(matches!(
+            new_only.decrypt(&encrypted),
+            Err(TokenEncryptionError::UnknownKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        let short = STANDARD.encode([0u8; 16]);
+        assert!(matches!(
+            TokenCipher::from_key_ring(&short, KeySource::Env),
+            Err(TokenEncryptionError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_persisted_key_file_is_private() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys");
+        fs::write(&path, "stale\n").unwrap();
+
+        let cipher = TokenCipher::from_key_ring(
+            &STANDARD.encode([1u8; KEY_LEN]),
+            KeySource::File(path.clone()),
+        )
+        .unwrap();
+        cipher.persist().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n", STANDARD.encode([1u8; KEY_LEN]))
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_env_keys_cannot_be_rotated_in_place() {
+        let cipher = cipher_with(&[[1u8; KEY_LEN]]);
+        assert!(matches!(
+            cipher.with_new_primary_key(),
+            Err(TokenEncryptionError::RotationUnsupported)
+        ));
+    }
+}
@@ -0,0 +1,138 @@
+//! Enforces each project's task-status workflow.
+//!
+//! Every status change, whether requested by a user or triggered by an event, goes
+//! through this module so the project's transitions and guards apply uniformly.
+
+use db::models::{
+    project::Project,
+    project_workflow::{
+        ProjectWorkflow, ProjectWorkflowError, WorkflowDefinition, WorkflowEvent, WorkflowGuard,
+    },
+    review_conversation::ReviewConversation,
+    task::{Task, TaskStatus},
+    task_approval::TaskApproval,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum WorkflowError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Definition(#[from] ProjectWorkflowError),
+    #[error("Moving a task from {from} to {to} is not allowed by the project workflow")]
+    TransitionNotAllowed { from: TaskStatus, to: TaskStatus },
+    #[error("Task requires {required} approval(s) to move to {to}, but has {actual}")]
+    InsufficientApprovals {
+        to: TaskStatus,
+        required: i64,
+        actual: i64,
+    },
+    #[error("Task is on hold and cannot move to {0}")]
+    OnHold(TaskStatus),
+    #[error("Task has {count} unresolved review conversation(s) and cannot move to {to}")]
+    UnresolvedConversations { to: TaskStatus, count: i64 },
+}
+
+/// Check that the project workflow allows moving `task` to `to` right now
+pub async fn check_transition(
+    pool: &SqlitePool,
+    task: &Task,
+    to: &TaskStatus,
+) -> Result<(), WorkflowError> {
+    let definition = ProjectWorkflow::definition_for_project(pool, task.project_id).await?;
+    check_with_definition(pool, &definition, task, to).await
+}
+
+/// The status `event` would move `task` to, after checking it is allowed.
+/// Returns `None` when the workflow has no automation for the event.
+pub async fn check_event(
+    pool: &SqlitePool,
+    task: &Task,
+    event: WorkflowEvent,
+) -> Result<Option<TaskStatus>, WorkflowError> {
+    let definition = ProjectWorkflow::definition_for_project(pool, task.project_id).await?;
+    let Some(automation) = definition.find_automation(event, &task.status) else {
+        return Ok(None);
+    };
+    if automation.to == task.status {
+        return Ok(None);
+    }
+    check_with_definition(pool, &definition, task, &automation.to).await?;
+    Ok(Some(automation.to.clone()))
+}
+
+/// Apply the project's automation for `event` to `task`.
+/// Returns the new status if the task moved.
+pub async fn handle_event(
+    pool: &SqlitePool,
+    task: &Task,
+    event: WorkflowEvent,
+) -> Result<Option<TaskStatus>, WorkflowError> {
+    let next = check_event(pool, task, event).await?;
+    if let Some(status) = &next {
+        Task::update_status(pool, task.id, status.clone()).await?;
+    }
+    Ok(next)
+}
+
+/// [`handle_event`] for callers that only have the task id
+pub async fn handle_event_for_task(
+    pool: &SqlitePool,
+    task_id: Uuid,
+    event: WorkflowEvent,
+) -> Result<Option<TaskStatus>, WorkflowError> {
+    match Task::find_by_id(pool, task_id).await? {
+        Some(task) => handle_event(pool, &task, event).await,
+        None => Ok(None),
+    }
+}
+
+async fn check_with_definition(
+    pool: &SqlitePool,
+    definition: &WorkflowDefinition,
+    task: &Task,
+    to: &TaskStatus,
+) -> Result<(), WorkflowError> {
+    let transition = definition
+        .find_transition(&task.status, to)
+        .ok_or_else(|| WorkflowError::TransitionNotAllowed {
+            from: task.status.clone(),
+            to: to.clone(),
+        })?;
+
+    for guard in &transition.guards {
+        match guard {
+            WorkflowGuard::Approvals => {
+                let project = Project::find_by_id(pool, task.project_id)
+                    .await?
+                    .ok_or(sqlx::Error::RowNotFound)?;
+                let actual = TaskApproval::count_by_task_id(pool, task.id).await?;
+                if actual < project.min_approvals_required {
+                    return Err(WorkflowError::InsufficientApprovals {
+                        to: to.clone(),
+                        required: project.min_approvals_required,
+                        actual,
+                    });
+                }
+            }
+            WorkflowGuard::NoHold => {
+                if task.is_on_hold() {
+                    return Err(WorkflowError::OnHold(to.clone()));
+                }
+            }
+            WorkflowGuard::ConversationsResolved => {
+                let count = ReviewConversation::count_unresolved_by_task_id(pool, task.id).await?;
+                if count > 0 {
+                    return Err(WorkflowError::UnresolvedConversations {
+                        to: to.clone(),
+                        count,
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,315 @@
+//! Merges every repo of a workspace, or opens a pull request for each, as one step.
+//!
+//! All repos with changes are checked before any is touched, so a problem in one stops
+//! the operation up front. A merge that still fails part way rolls back the repos
+//! already merged. Pull requests cannot be taken back; instead each one links to the
+//! others in its description.
+
+use std::path::{Path, PathBuf};
+
+use db::models::{
+    merge::MergeStrategy, merge_queue::MergeQueueEntry, repo::Repo, workspace::Workspace,
+    workspace_repo::RepoWithTargetBranch,
+};
+use git2::BranchType;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    git::{GitService, GitServiceError},
+    merge_verification,
+};
+
+/// A workspace repo whose branch has commits its target branch does not
+#[derive(Debug, Clone)]
+pub struct RepoChange {
+    pub repo: Repo,
+    pub target_branch: String,
+    pub target_is_remote: bool,
+    pub worktree_path: PathBuf,
+    /// Commits on the target branch that the workspace branch does not have
+    pub commits_behind: usize,
+}
+
+/// Why a repo cannot be merged, or have a pull request opened, yet
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PreflightProblem {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub message: String,
+}
+
+/// One repo's merge, as planned from a [`RepoChange`]
+#[derive(Debug, Clone)]
+pub struct PlannedMerge {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub repo_path: PathBuf,
+    pub worktree_path: PathBuf,
+    pub target_branch: String,
+    pub strategy: MergeStrategy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MergedRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub merge_commit: String,
+    pub merge_strategy: MergeStrategy,
+}
+
+/// A pull request opened for one of a workspace's repos
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkspacePr {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    #[ts(type = "number")]
+    pub pr_number: i64,
+    pub pr_url: String,
+}
+
+#[derive(Debug, Error)]
+#[error("Merging {repo_name} failed: {error}")]
+pub struct PartialMergeError {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub error: GitServiceError,
+    /// The repos merged before the failure whose merge was undone
+    pub rolled_back: Vec<String>,
+    /// The repos merged before the failure whose merge could not be undone
+    pub not_rolled_back: Vec<String>,
+}
+
+/// The workspace's repos with commits not yet in their target branch. A remote target
+/// branch is fetched first.
+pub fn repos_with_changes(
+    git: &GitService,
+    workspace_branch: &str,
+    workspace_path: &Path,
+    repos: Vec<RepoWithTargetBranch>,
+) -> Result<Vec<RepoChange>, GitServiceError> {
+    let mut changes = Vec::new();
+    for RepoWithTargetBranch {
+        repo,
+        target_branch,
+    } in repos
+    {
+        let target_is_remote =
+            git.find_branch_type(&repo.path, &target_branch)? == BranchType::Remote;
+        let (ahead, behind) = if target_is_remote {
+            git.get_remote_branch_status(&repo.path, workspace_branch, Some(&target_branch))?
+        } else {
+            git.get_branch_status(&repo.path, workspace_branch, &target_branch)?
+        };
+        if ahead == 0 {
+            continue;
+        }
+        changes.push(RepoChange {
+            worktree_path: workspace_path.join(&repo.name),
+            repo,
+            target_branch,
+            target_is_remote,
+            commits_behind: behind,
+        });
+    }
+    Ok(changes)
+}
+
+/// Everything that would stop `changes` from being merged (`merging`) or opened as pull
+/// requests: a remote target branch or a pending merge queue entry for a merge, a git
+/// operation stopped on conflicts, uncommitted changes, a target branch that has moved
+/// on, or a verify script that has not passed. A merge must hold
+/// [`super::merge_queue::MERGE_QUEUE_LOCK`] from this check until it is done.
+pub async fn preflight(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    changes: &[RepoChange],
+    merging: bool,
+) -> Vec<PreflightProblem> {
+    let mut problems = Vec::new();
+    for change in changes {
+        let mut problem = |message: String| {
+            problems.push(PreflightProblem {
+                repo_id: change.repo.id,
+                repo_name: change.repo.display_name.clone(),
+                message,
+            })
+        };
+
+        if merging && change.target_is_remote {
+            problem(format!(
+                "Cannot merge directly into the remote branch '{}'; open pull requests instead",
+                change.target_branch
+            ));
+        }
+        if merging {
+            match MergeQueueEntry::find(pool, workspace.id, change.repo.id).await {
+                Ok(Some(entry)) if entry.is_pending() => problem(format!(
+                    "In the merge queue for '{}'; take it out of the queue to merge directly",
+                    entry.target_branch
+                )),
+                Ok(_) => {}
+                Err(e) => problem(e.to_string()),
+            }
+        }
+        match git.detect_conflict_op(&change.worktree_path) {
+            Ok(Some(_)) => {
+                problem("A git operation is stopped on conflicts; resolve or abort it".to_string())
+            }
+            Ok(None) => {}
+            Err(e) => problem(e.to_string()),
+        }
+        match git.is_worktree_clean(&change.worktree_path) {
+            Ok(true) => {}
+            Ok(false) => problem("Has uncommitted changes; commit or discard them".to_string()),
+            Err(e) => problem(e.to_string()),
+        }
+        if change.commits_behind > 0 {
+            problem(format!(
+                "'{}' is {} commits ahead of the workspace branch; rebase first",
+                change.target_branch, change.commits_behind
+            ));
+        }
+        if let Err(e) = merge_verification::check_verified(
+            pool,
+            git,
+            workspace,
+            &change.repo,
+            &change.target_branch,
+        )
+        .await
+        {
+            problem(e.to_string());
+        }
+    }
+    problems
+}
+
+/// Merge the workspace branch into each repo's target branch, in order. If one fails,
+/// the merges already made are undone before the error is returned.
+pub fn merge_all(
+    git: &GitService,
+    workspace_branch: &str,
+    commit_message: &str,
+    merges: &[PlannedMerge],
+) -> Result<Vec<MergedRepo>, PartialMergeError> {
+    // The branch tips to roll back to, for each repo merged so far
+    let mut undo: Vec<(&PlannedMerge, String, String)> = Vec::new();
+    let mut merged = Vec::new();
+    for merge in merges {
+        let result = git
+            .get_branch_oid(&merge.repo_path, &merge.target_branch)
+            .and_then(|target_oid| {
+                let task_oid = git.get_branch_oid(&merge.repo_path, workspace_branch)?;
+                let merge_commit = git.merge_changes_with_strategy(
+                    &merge.repo_path,
+                    &merge.worktree_path,
+                    workspace_branch,
+                    &merge.target_branch,
+                    commit_message,
+                    merge.strategy,
+                )?;
+                Ok((target_oid, task_oid, merge_commit))
+            });
+        match result {
+            Ok((target_oid, task_oid, merge_commit)) => {
+                undo.push((merge, target_oid, task_oid));
+                merged.push(MergedRepo {
+                    repo_id: merge.repo_id,
+                    target_branch: merge.target_branch.clone(),
+                    merge_commit,
+                    merge_strategy: merge.strategy,
+                });
+            }
+            Err(error) => {
+                let mut rolled_back = Vec::new();
+                let mut not_rolled_back = Vec::new();
+                for (done, target_oid, task_oid) in undo.into_iter().rev() {
+                    match git.undo_merge(
+                        &done.repo_path,
+                        workspace_branch,
+                        &done.target_branch,
+                        &target_oid,
+                        &task_oid,
+                    ) {
+                        Ok(()) => rolled_back.push(done.repo_name.clone()),
+                        Err(e) => {
+                            tracing::error!(
+                                "Failed to roll back the merge of {} into {}: {}",
+                                done.repo_name,
+                                done.target_branch,
+                                e
+                            );
+                            not_rolled_back.push(done.repo_name.clone());
+                        }
+                    }
+                }
+                return Err(PartialMergeError {
+                    repo_id: merge.repo_id,
+                    repo_name: merge.repo_name.clone(),
+                    error,
+                    rolled_back,
+                    not_rolled_back,
+                });
+            }
+        }
+    }
+    Ok(merged)
+}
+
+/// `body` followed by links to every pull request opened for the workspace, marking the
+/// one at `pr_url` as this one
+pub fn body_with_linked_prs(body: Option<&str>, pr_url: &str, prs: &[WorkspacePr]) -> String {
+    let mut linked = String::new();
+    if let Some(body) = body.map(str::trim_end).filter(|body| !body.is_empty()) {
+        linked.push_str(body);
+        linked.push_str("\n\n---\n\n");
+    }
+    linked
+        .push_str("This change spans several repositories; merge these pull requests together:\n");
+    for pr in prs {
+        let this = if pr.pr_url == pr_url {
+            " (this pull request)"
+        } else {
+            ""
+        };
+        linked.push_str(&format!("\n- {}: {}{this}", pr.repo_name, pr.pr_url));
+    }
+    linked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr(repo_name: &str, pr_number: i64) -> WorkspacePr {
+        WorkspacePr {
+            repo_id: Uuid::new_v4(),
+            repo_name: repo_name.to_string(),
+            pr_number,
+            pr_url: format!("https://github.com/acme/{repo_name}/pull/{pr_number}"),
+        }
+    }
+
+    #[test]
+    fn links_every_pr_after_the_body() {
+        let prs = vec![pr("app", 12), pr("api", 7)];
+        let body = body_with_linked_prs(Some("Adds login.\n"), &prs[1].pr_url, &prs);
+        assert_eq!(
+            body,
+            "Adds login.\n\n---\n\nThis change spans several repositories; merge these pull requests together:\n\n- app: https://github.com/acme/app/pull/12\n- api: https://github.com/acme/api/pull/7 (this pull request)"
+        );
+    }
+
+    #[test]
+    fn empty_body_is_left_out() {
+        let prs = vec![pr("app", 12), pr("api", 7)];
+        let body = body_with_linked_prs(Some("  "), &prs[0].pr_url, &prs);
+        assert!(body.starts_with("This change spans several repositories"));
+        assert!(body.contains("/app/pull/12 (this pull request)"));
+    }
+}
@@ -0,0 +1,98 @@
+//! Checks that apply to every way of starting a workspace on a task, so a dependency
+//! auto-start is refused for the same reasons as a user creating the workspace.
+
+use db::models::{
+    claude_oauth_token::ClaudeOAuthToken,
+    project_member::{ProjectMember, ProjectRole},
+    task::Task,
+    task_dependency::TaskDependency,
+    user::User,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::services::local_session::LocalSessionService;
+
+#[derive(Debug, Error)]
+pub enum WorkspaceStartError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Task is on hold: {0}. Release the hold before starting a new workspace session.")]
+    OnHold(String),
+    #[error(
+        "Task is blocked by {0}. Finish the blocking tasks before starting a new workspace session."
+    )]
+    Blocked(String),
+    #[error("No usable Claude OAuth token is configured")]
+    ClaudeTokenRequired,
+    #[error("{0}")]
+    Forbidden(String),
+}
+
+/// Refuse to start work on `task` while it is on hold or any task it depends on is not
+/// done yet
+pub async fn ensure_task_startable(
+    pool: &SqlitePool,
+    task: &Task,
+) -> Result<(), WorkspaceStartError> {
+    if task.is_on_hold() {
+        return Err(WorkspaceStartError::OnHold(
+            task.hold_comment
+                .clone()
+                .unwrap_or_else(|| "No reason given".to_string()),
+        ));
+    }
+
+    let blockers = TaskDependency::find_unresolved_blockers(pool, task.id).await?;
+    if !blockers.is_empty() {
+        let titles = blockers
+            .iter()
+            .map(|blocker| format!("'{}'", blocker.title))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(WorkspaceStartError::Blocked(titles));
+    }
+    Ok(())
+}
+
+/// Whether `user_id` has a Claude OAuth token that has not expired
+pub async fn user_has_valid_claude_token(
+    pool: &SqlitePool,
+    user_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    Ok(ClaudeOAuthToken::find_by_user_id(pool, user_id)
+        .await?
+        .is_some_and(|token| !token.to_status().is_expired))
+}
+
+/// Refuse to start work on `task` for a user who is not a contributor in its project or
+/// has no usable Claude token, for starts made without a request to check (such as an
+/// auto-start). Passes when local auth is not configured.
+pub async fn ensure_user_can_start(
+    pool: &SqlitePool,
+    task: &Task,
+    user_id: Option<Uuid>,
+) -> Result<(), WorkspaceStartError> {
+    if LocalSessionService::from_env().is_none() {
+        return Ok(());
+    }
+    let Some(user_id) = user_id else {
+        return Err(WorkspaceStartError::Forbidden(
+            "No user to start the workspace for".to_string(),
+        ));
+    };
+
+    if !User::is_admin(pool, user_id).await? {
+        let role = ProjectMember::find_role(pool, task.project_id, user_id).await?;
+        if !role.is_some_and(|role| role >= ProjectRole::Contributor) {
+            return Err(WorkspaceStartError::Forbidden(
+                "The user is no longer a contributor in the project".to_string(),
+            ));
+        }
+    }
+    if !user_has_valid_claude_token(pool, user_id).await? {
+        return Err(WorkspaceStartError::ClaudeTokenRequired);
+    }
+    Ok(())
+}
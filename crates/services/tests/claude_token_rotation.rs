@@ -0,0 +1,184 @@
+use std::{fs, path::Path, sync::Arc};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use db::{DBService, models::claude_oauth_token::ClaudeOAuthToken};
+use services::services::{
+    claude_token_rotation::ClaudeTokenRotationService,
+    token_encryption::{KeySource, TokenCipher, TokenEncryptionError},
+};
+use sqlx::SqlitePool;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+async fn setup_pool() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+    pool
+}
+
+/// A file-backed key ring holding a single key built from `seed`
+fn file_cipher(key_path: &Path, seed: u8) -> TokenCipher {
+    TokenCipher::from_key_ring(
+        &STANDARD.encode([seed; 32]),
+        KeySource::File(key_path.to_path_buf()),
+    )
+    .unwrap()
+}
+
+fn service(pool: &SqlitePool, cipher: TokenCipher) -> ClaudeTokenRotationService {
+    ClaudeTokenRotationService::new(Arc::new(DBService { pool: pool.clone() }), cipher)
+}
+
+/// Store `encrypted_token` for a new user, with `hint` as the token hint
+async fn store_token(pool: &SqlitePool, encrypted_token: &str, hint: &str) -> ClaudeOAuthToken {
+    let user_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO users (id, github_id, username, email) VALUES ($1, $2, $3, $4)")
+        .bind(user_id)
+        .bind(Uuid::new_v4().as_u128() as i64)
+        .bind(format!("user-{user_id}"))
+        .bind(format!("{user_id}@test.com"))
+        .execute(pool)
+        .await
+        .unwrap();
+    ClaudeOAuthToken::upsert(pool, user_id, encrypted_token, Some(hint), None)
+        .await
+        .unwrap()
+}
+
+async fn stored(pool: &SqlitePool, id: Uuid) -> String {
+    ClaudeOAuthToken::find_by_id(pool, id)
+        .await
+        .unwrap()
+        .unwrap()
+        .encrypted_token
+}
+
+#[tokio::test]
+async fn test_legacy_tokens_are_migrated_to_v2() {
+    let pool = setup_pool().await;
+    let dir = TempDir::new().unwrap();
+    let key_path = dir.path().join("token.key");
+    let cipher = file_cipher(&key_path, 1);
+    let primary = cipher.primary_key_id().to_string();
+
+    let v1 = store_token(
+        &pool,
+        &format!("v1:{}", STANDARD.encode("sk-v1")),
+        "...k-v1",
+    )
+    .await;
+    let raw = store_token(&pool, "sk-raw", "...-raw").await;
+
+    let report = service(&pool, cipher).reencrypt_all_tokens().await.unwrap();
+    assert_eq!(report.reencrypted, 2);
+    assert!(report.failed.is_empty());
+
+    let check = file_cipher(&key_path, 1);
+    for (token, plaintext) in [(v1, "sk-v1"), (raw, "sk-raw")] {
+        let encrypted = stored(&pool, token.id).await;
+        assert!(encrypted.starts_with(&format!("v2:{primary}:")));
+        assert_eq!(check.decrypt(&encrypted).unwrap(), plaintext);
+    }
+}
+
+#[tokio::test]
+async fn test_undecryptable_tokens_are_skipped_and_reported() {
+    let pool = setup_pool().await;
+    let dir = TempDir::new().unwrap();
+    let cipher = file_cipher(&dir.path().join("token.key"), 1);
+
+    let good = store_token(
+        &pool,
+        &format!("v1:{}", STANDARD.encode("sk-good")),
+        "...good",
+    )
+    .await;
+    let corrupt = store_token(&pool, "v2:deadbeef:AAAAAAAAAAAAAAAAAAAAAAAA", "...rupt").await;
+
+    let report = service(&pool, cipher).reencrypt_all_tokens().await.unwrap();
+    assert_eq!(report.reencrypted, 1);
+    assert_eq!(report.failed, vec![corrupt.id]);
+    assert!(stored(&pool, good.id).await.starts_with("v2:"));
+    assert_eq!(
+        stored(&pool, corrupt.id).await,
+        "v2:deadbeef:AAAAAAAAAAAAAAAAAAAAAAAA"
+    );
+}
+
+#[tokio::test]
+async fn test_rotation_retires_the_old_key() {
+    let pool = setup_pool().await;
+    let dir = TempDir::new().unwrap();
+    let key_path = dir.path().join("token.key");
+    let old = file_cipher(&key_path, 1);
+    old.persist().unwrap();
+
+    let token = store_token(&pool, &old.encrypt("sk-rotate").unwrap(), "...tate").await;
+    let rotation = service(&pool, file_cipher(&key_path, 1));
+
+    let report = rotation.rotate_encryption_key().await.unwrap();
+    assert_eq!(report.reencrypted, 1);
+    assert!(report.failed.is_empty());
+
+    // Only the new key is left in the key file, and it is the one the token uses now
+    let ring = fs::read_to_string(&key_path).unwrap();
+    assert_eq!(ring.lines().count(), 1);
+    let new = TokenCipher::from_key_ring(&ring, KeySource::File(key_path.clone())).unwrap();
+    assert_ne!(new.primary_key_id(), old.primary_key_id());
+
+    let encrypted = stored(&pool, token.id).await;
+    assert_eq!(new.decrypt(&encrypted).unwrap(), "sk-rotate");
+    assert!(matches!(
+        old.decrypt(&encrypted),
+        Err(TokenEncryptionError::UnknownKey(_))
+    ));
+    assert_eq!(
+        rotation.get_next_token().await.unwrap().unwrap().token,
+        "sk-rotate"
+    );
+}
+
+#[tokio::test]
+async fn test_failed_write_rolls_back_every_token() {
+    let pool = setup_pool().await;
+    let dir = TempDir::new().unwrap();
+    let key_path = dir.path().join("token.key");
+    let old = file_cipher(&key_path, 1);
+    old.persist().unwrap();
+
+    let first = store_token(&pool, &old.encrypt("sk-first").unwrap(), "...irst").await;
+    let second = store_token(&pool, &old.encrypt("sk-second").unwrap(), "fail").await;
+    let before = [
+        stored(&pool, first.id).await,
+        stored(&pool, second.id).await,
+    ];
+    sqlx::query(
+        r#"CREATE TRIGGER fail_reencrypt BEFORE UPDATE OF encrypted_token ON claude_oauth_tokens
+           WHEN OLD.token_hint = 'fail'
+           BEGIN SELECT RAISE(ABORT, 'disk I/O error'); END"#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let rotation = service(&pool, file_cipher(&key_path, 1));
+    assert!(rotation.rotate_encryption_key().await.is_err());
+
+    // Neither token was rewritten, and both still decrypt with the key in use
+    let after = [
+        stored(&pool, first.id).await,
+        stored(&pool, second.id).await,
+    ];
+    assert_eq!(before, after);
+    let mut plaintexts = vec![
+        rotation.get_next_token().await.unwrap().unwrap().token,
+        rotation.get_next_token().await.unwrap().unwrap().token,
+    ];
+    plaintexts.sort();
+    assert_eq!(plaintexts, ["sk-first", "sk-second"]);
+
+    // The old key is kept in the key file until a rotation succeeds
+    let ring = fs::read_to_string(&key_path).unwrap();
+    let reloaded = TokenCipher::from_key_ring(&ring, KeySource::File(key_path.clone())).unwrap();
+    assert_eq!(reloaded.decrypt(&after[0]).unwrap(), "sk-first");
+}
@@ -4,9 +4,14 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use db::models::merge::MergeStrategy;
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
-use services::services::git::{GitCli, GitCliError, GitService};
+use services::services::{
+    git::{GitCli, GitCliError, GitService, GitServiceError},
+    workspace_merge::{self, PlannedMerge},
+};
 use tempfile::TempDir;
+use uuid::Uuid;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
 
 fn write_file<P: AsRef<Path>>(base: P, rel: &str, content: &str) {
@@ -713,6 +718,107 @@ fn rebase_refuses_to_abort_existing_rebase() {
     // Note: We do not auto-abort; user should resolve or abort explicitly
 }
 
+#[test]
+fn continue_conflicts_finishes_resolved_rebase() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let service = GitService::new();
+    let res = service.rebase_branch(
+        &repo_path,
+        &worktree_path,
+        "new-base",
+        "old-base",
+        "feature",
+    );
+    assert!(matches!(res, Err(GitServiceError::MergeConflicts { .. })));
+
+    // Resolve without staging; continuing stages tracked changes itself
+    write_file(
+        &worktree_path,
+        "conflict.txt",
+        "new-base and feature version\n",
+    );
+    service
+        .continue_conflicts(&worktree_path)
+        .expect("continue should succeed");
+
+    assert_eq!(service.detect_conflict_op(&worktree_path).unwrap(), None);
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    assert_eq!(wt_repo.head().unwrap().shorthand(), Some("feature"));
+    let head = wt_repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.message(), Some("feature conflicting change"));
+    let new_base = service.get_branch_oid(&repo_path, "new-base").unwrap();
+    assert_eq!(head.parent_id(0).unwrap().to_string(), new_base);
+}
+
+#[test]
+fn roll_back_conflicts_aborts_staged_rebase() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let service = GitService::new();
+    let before = service.get_branch_oid(&repo_path, "feature").unwrap();
+    let _ = service
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on conflicts");
+
+    // Staged resolutions leave no conflicted files, which `abort_conflicts` would quit
+    write_file(&worktree_path, "conflict.txt", "half resolved\n");
+    add_path(&worktree_path, "conflict.txt");
+    service
+        .roll_back_conflicts(&worktree_path)
+        .expect("roll back should succeed");
+
+    assert_eq!(service.detect_conflict_op(&worktree_path).unwrap(), None);
+    assert_eq!(
+        service.get_branch_oid(&repo_path, "feature").unwrap(),
+        before
+    );
+    let content = fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "feature version\n");
+}
+
+#[test]
+fn only_a_finished_rebase_builds_on_where_it_stopped() {
+    for finish in [true, false] {
+        let td = TempDir::new().unwrap();
+        let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+        let service = GitService::new();
+        let _ = service
+            .rebase_branch(
+                &repo_path,
+                &worktree_path,
+                "new-base",
+                "old-base",
+                "feature",
+            )
+            .expect_err("rebase should stop on conflicts");
+        let stopped_at = service.get_head_info(&worktree_path).unwrap().oid;
+
+        if finish {
+            write_file(&worktree_path, "conflict.txt", "resolved\n");
+            service.continue_conflicts(&worktree_path).unwrap();
+        } else {
+            service.roll_back_conflicts(&worktree_path).unwrap();
+        }
+
+        assert_eq!(service.detect_conflict_op(&worktree_path).unwrap(), None);
+        let head = service.get_head_info(&worktree_path).unwrap().oid;
+        assert_eq!(
+            head != stopped_at
+                && service
+                    .is_ancestor(&worktree_path, &stopped_at, &head)
+                    .unwrap(),
+            finish
+        );
+    }
+}
+
 #[test]
 fn rebase_fast_forwards_when_no_unique_commits() {
     let td = TempDir::new().unwrap();
@@ -1406,3 +1512,285 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn merge_commit_strategy_keeps_task_commits() {
+    // libgit2 path: main is not checked out anywhere
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let before_main = s.get_branch_oid(&repo_path, "main").unwrap();
+    let feature_tip = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    let sha = s
+        .merge_changes_with_strategy(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "Merge feature",
+            MergeStrategy::MergeCommit,
+        )
+        .expect("merge commit should succeed");
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let merge_commit = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    let parents: Vec<String> = merge_commit.parent_ids().map(|id| id.to_string()).collect();
+    assert_eq!(parents, vec![before_main, feature_tip]);
+    assert_eq!(merge_commit.message(), Some("Merge feature"));
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), sha);
+    // The task branch continues from the merge commit
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), sha);
+}
+
+#[test]
+fn fast_forward_strategy_moves_base_to_task_tip() {
+    // CLI path: main is checked out in the main repo
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    let s = GitService::new();
+    let feature_tip = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    let sha = s
+        .merge_changes_with_strategy(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "unused",
+            MergeStrategy::FastForward,
+        )
+        .expect("fast-forward should succeed");
+
+    assert_eq!(sha, feature_tip);
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), feature_tip);
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "feature").unwrap(),
+        feature_tip
+    );
+    assert!(repo_path.join("feat.txt").exists());
+}
+
+#[test]
+fn rebase_strategy_rebases_onto_moved_base() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "base_ahead.txt", "base ahead\n");
+    commit_all(&repo, "base ahead commit");
+    let s = GitService::new();
+    let before_main = s.get_branch_oid(&repo_path, "main").unwrap();
+
+    let sha = s
+        .merge_changes_with_strategy(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "unused",
+            MergeStrategy::Rebase,
+        )
+        .expect("rebase merge should succeed");
+
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), sha);
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), sha);
+    let merged = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    assert_eq!(merged.summary(), Some("feature commit"));
+    assert!(
+        repo.graph_descendant_of(merged.id(), git2::Oid::from_str(&before_main).unwrap())
+            .unwrap()
+    );
+    assert!(repo_path.join("base_ahead.txt").exists());
+    assert!(repo_path.join("feat.txt").exists());
+}
+
+#[test]
+fn rebase_strategy_conflict_leaves_branches_unchanged() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_direct_conflict_repo(&td);
+    let s = GitService::new();
+    let before_main = s.get_branch_oid(&repo_path, "main").unwrap();
+    let before_feature = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    let res = s.merge_changes_with_strategy(
+        &repo_path,
+        &worktree_path,
+        "feature",
+        "main",
+        "unused",
+        MergeStrategy::Rebase,
+    );
+
+    assert!(matches!(res, Err(GitServiceError::MergeConflicts { .. })));
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), before_main);
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "feature").unwrap(),
+        before_feature
+    );
+    let git = GitCli::new();
+    assert!(!git.is_rebase_in_progress(&worktree_path).unwrap());
+}
+
+#[test]
+fn merge_preview_checks_out_uncommitted_merge() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, _worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "base_ahead.txt", "base ahead\n");
+    commit_all(&repo, "base ahead commit");
+    let s = GitService::new();
+    let before_main = s.get_branch_oid(&repo_path, "main").unwrap();
+    let before_feature = s.get_branch_oid(&repo_path, "feature").unwrap();
+    let preview_path = td.path().join("verify").join("repo");
+
+    let preview = s
+        .create_merge_preview(&repo_path, &preview_path, "feature", "main")
+        .expect("merge preview should succeed");
+
+    assert_eq!(preview.target_commit, before_main);
+    assert_eq!(preview.task_commit, before_feature);
+    assert!(preview_path.join("base_ahead.txt").exists());
+    assert!(preview_path.join("feat.txt").exists());
+    // Nothing was committed: both branches and the preview's HEAD are unchanged
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), before_main);
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "feature").unwrap(),
+        before_feature
+    );
+    assert_eq!(s.get_head_info(&preview_path).unwrap().oid, before_main);
+
+    s.remove_merge_preview(&repo_path, &preview_path).unwrap();
+    assert!(!preview_path.exists());
+}
+
+#[test]
+fn merge_preview_conflict_removes_worktree() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, _worktree_path) = setup_direct_conflict_repo(&td);
+    let s = GitService::new();
+    let preview_path = td.path().join("verify").join("repo");
+
+    let res = s.create_merge_preview(&repo_path, &preview_path, "feature", "main");
+
+    match res {
+        Err(GitServiceError::MergeConflicts {
+            conflicted_files, ..
+        }) => assert_eq!(conflicted_files, vec!["conflict.txt".to_string()]),
+        other => panic!("expected merge conflicts, got {other:?}"),
+    }
+    assert!(!preview_path.exists());
+}
+
+#[test]
+fn undo_merge_restores_both_branches() {
+    // libgit2 path: main is not checked out anywhere
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let before_main = s.get_branch_oid(&repo_path, "main").unwrap();
+    let before_feature = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    s.merge_changes_with_strategy(
+        &repo_path,
+        &worktree_path,
+        "feature",
+        "main",
+        "Squash feature",
+        MergeStrategy::Squash,
+    )
+    .expect("squash merge should succeed");
+    assert_ne!(s.get_branch_oid(&repo_path, "main").unwrap(), before_main);
+
+    s.undo_merge(&repo_path, "feature", "main", &before_main, &before_feature)
+        .expect("undo should succeed");
+
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), before_main);
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "feature").unwrap(),
+        before_feature
+    );
+    assert!(s.is_worktree_clean(&worktree_path).unwrap());
+}
+
+#[test]
+fn undo_merge_moves_checked_out_base_worktree_back() {
+    // CLI path: main is checked out in the main repo
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    let s = GitService::new();
+    let before_main = s.get_branch_oid(&repo_path, "main").unwrap();
+    let before_feature = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    s.merge_changes_with_strategy(
+        &repo_path,
+        &worktree_path,
+        "feature",
+        "main",
+        "Merge feature",
+        MergeStrategy::MergeCommit,
+    )
+    .expect("merge commit should succeed");
+    assert!(repo_path.join("feat.txt").exists());
+
+    s.undo_merge(&repo_path, "feature", "main", &before_main, &before_feature)
+        .expect("undo should succeed");
+
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), before_main);
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "feature").unwrap(),
+        before_feature
+    );
+    assert!(!repo_path.join("feat.txt").exists());
+    assert!(s.is_worktree_clean(&repo_path).unwrap());
+}
+
+#[test]
+fn merge_all_rolls_back_earlier_repos_when_one_fails() {
+    let app = TempDir::new().unwrap();
+    let api = TempDir::new().unwrap();
+    let (app_path, app_worktree) = setup_repo_with_worktree(&app);
+    let (api_path, api_worktree) = setup_repo_with_worktree(&api);
+    // api's main is checked out with staged changes, so its merge is refused
+    let api_repo = Repository::open(&api_path).unwrap();
+    checkout_branch(&api_repo, "main");
+    write_file(&api_path, "staged.txt", "staged\n");
+    add_path(&api_path, "staged.txt");
+    let s = GitService::new();
+    let before_main = s.get_branch_oid(&app_path, "main").unwrap();
+    let before_feature = s.get_branch_oid(&app_path, "feature").unwrap();
+
+    let planned = |name: &str, repo_path: &Path, worktree_path: &Path| PlannedMerge {
+        repo_id: Uuid::new_v4(),
+        repo_name: name.to_string(),
+        repo_path: repo_path.to_path_buf(),
+        worktree_path: worktree_path.to_path_buf(),
+        target_branch: "main".to_string(),
+        strategy: MergeStrategy::Squash,
+    };
+    let merges = vec![
+        planned("app", &app_path, &app_worktree),
+        planned("api", &api_path, &api_worktree),
+    ];
+    let err = workspace_merge::merge_all(&s, "feature", "Add feature", &merges)
+        .expect_err("api merge should fail");
+
+    assert_eq!(err.repo_name, "api");
+    assert!(matches!(err.error, GitServiceError::WorktreeDirty(..)));
+    assert_eq!(err.rolled_back, vec!["app".to_string()]);
+    assert!(err.not_rolled_back.is_empty());
+    assert_eq!(s.get_branch_oid(&app_path, "main").unwrap(), before_main);
+    assert_eq!(
+        s.get_branch_oid(&app_path, "feature").unwrap(),
+        before_feature
+    );
+}
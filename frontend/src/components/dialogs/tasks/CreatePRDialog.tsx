@@ -189,7 +189,11 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
                 ? 'GitHub'
                 : result.error.provider === 'azure_dev_ops'
                   ? 'Azure DevOps'
-                  : 'Git host';
+                  : result.error.provider === 'git_lab'
+                    ? 'GitLab'
+                    : result.error.provider === 'bitbucket'
+                      ? 'Bitbucket'
+                      : 'Git host';
             const action =
               result.error.type === 'cli_not_installed'
                 ? 'not installed'
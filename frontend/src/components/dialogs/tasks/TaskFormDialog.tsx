@@ -177,6 +177,7 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
             parent_workspace_id: null,
             image_ids: images.length > 0 ? images.map((img) => img.id) : null,
             assignee_user_id: value.assigneeUserId,
+            status: null,
           },
         },
         { onSuccess: () => modal.remove() }
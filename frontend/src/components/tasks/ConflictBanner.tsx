@@ -11,6 +11,8 @@ export type Props = Readonly<{
   op?: ConflictOp | null;
   onResolve?: () => void;
   enableResolve: boolean;
+  onResolveWithAgent?: () => void;
+  enableResolveWithAgent?: boolean;
   enableAbort: boolean;
 }>;
 
@@ -44,6 +46,8 @@ export function ConflictBanner({
   op,
   onResolve,
   enableResolve,
+  onResolveWithAgent,
+  enableResolveWithAgent = false,
   enableAbort,
 }: Props) {
   const { full: opTitle, lower: opTitleLower } = getOperationTitle(op);
@@ -103,6 +107,17 @@ export function ConflictBanner({
             Resolve conflicts
           </Button>
         )}
+        {onResolveWithAgent && (
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={onResolveWithAgent}
+            disabled={!enableResolveWithAgent}
+            title={`The agent resolves the conflicts, then the ${opTitleLower} is continued, or aborted if any remain`}
+          >
+            Resolve with agent
+          </Button>
+        )}
         <Button
           size="sm"
           variant="outline"
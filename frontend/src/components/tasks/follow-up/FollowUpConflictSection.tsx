@@ -28,7 +28,10 @@ export function FollowUpConflictSection({
   );
   const op = repoWithConflicts?.conflict_op ?? null;
   const repoId = repoWithConflicts?.repo_id;
-  const { abortConflicts } = useAttemptConflicts(workspaceId, repoId);
+  const { abortConflicts, resolveConflictsWithAgent } = useAttemptConflicts(
+    workspaceId,
+    repoId
+  );
 
   // write using setAborting and read through abortingRef in async handlers
   const [aborting, setAborting] = useState(false);
@@ -36,6 +39,7 @@ export function FollowUpConflictSection({
   useEffect(() => {
     abortingRef.current = aborting;
   }, [aborting]);
+  const [resolvingWithAgent, setResolvingWithAgent] = useState(false);
 
   if (!repoWithConflicts) return null;
 
@@ -60,7 +64,19 @@ export function FollowUpConflictSection({
             setAborting(false);
           }
         }}
-        enableAbort={enableAbort && !aborting}
+        onResolveWithAgent={async () => {
+          if (!workspaceId || resolvingWithAgent) return;
+          try {
+            setResolvingWithAgent(true);
+            await resolveConflictsWithAgent();
+          } catch (e) {
+            console.error('Failed to start resolving conflicts with agent', e);
+          } finally {
+            setResolvingWithAgent(false);
+          }
+        }}
+        enableResolveWithAgent={enableAbort && !aborting && !resolvingWithAgent}
+        enableAbort={enableAbort && !aborting && !resolvingWithAgent}
       />
       {/* Conflict instructions preview (non-editable) */}
       {conflictResolutionInstructions && enableResolve && (
@@ -26,6 +26,7 @@ const RUN_REASON_LABELS: Record<ExecutionProcessRunReason, string> = {
   setupscript: 'Setup Script',
   cleanupscript: 'Cleanup Script',
   devserver: 'Dev Server',
+  verifyscript: 'Verify Script',
 };
 
 const RUN_REASON_ICONS: Record<ExecutionProcessRunReason, typeof TerminalIcon> =
@@ -34,9 +35,11 @@ const RUN_REASON_ICONS: Record<ExecutionProcessRunReason, typeof TerminalIcon> =
     setupscript: GearIcon,
     cleanupscript: GearIcon,
     devserver: GlobeIcon,
+    verifyscript: GearIcon,
   };
 
 const STATUS_COLORS: Record<ExecutionProcessStatus, string> = {
+  queued: 'bg-low',
   running: 'bg-info',
   completed: 'bg-success',
   failed: 'bg-destructive',
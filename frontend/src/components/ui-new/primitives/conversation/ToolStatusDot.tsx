@@ -16,10 +16,17 @@ export function ToolStatusDot({ status, className }: ToolStatusDotProps) {
     statusType === 'denied' ||
     statusType === 'timed_out';
   const isPending =
-    statusType === 'created' || statusType === 'pending_approval';
+    statusType === 'created' ||
+    statusType === 'pending_approval' ||
+    statusType === 'auto_approved';
+  // Calls decided by an approval policy name the rule that decided them
+  const rule = 'rule' in status ? status.rule : undefined;
 
   return (
-    <span className={cn('inline-flex', className)}>
+    <span
+      className={cn('inline-flex', className)}
+      title={rule ? `Approval policy rule: ${rule}` : undefined}
+    >
       <span
         className={cn(
           'size-1.5 rounded-full',
@@ -7,6 +7,7 @@ import '@xterm/xterm/css/xterm.css';
 import { useTheme } from '@/components/ThemeProvider';
 import { getTerminalTheme } from '@/utils/terminalTheme';
 import { useTerminal } from '@/contexts/TerminalContext';
+import { getAuthToken } from '@/lib/api';
 
 interface XTermInstanceProps {
   tabId: string;
@@ -37,7 +38,9 @@ export function XTermInstance({
   const endpoint = useMemo(() => {
     const protocol = window.location.protocol === 'https:' ? 'https:' : 'http:';
     const host = window.location.host;
-    return `${protocol}//${host}/api/terminal/ws?workspace_id=${workspaceId}&cols=${initialSizeRef.current.cols}&rows=${initialSizeRef.current.rows}`;
+    const token = getAuthToken();
+    const tokenParam = token ? `&token=${encodeURIComponent(token)}` : '';
+    return `${protocol}//${host}/api/terminal/ws?workspace_id=${workspaceId}&cols=${initialSizeRef.current.cols}&rows=${initialSizeRef.current.rows}${tokenParam}`;
   }, [workspaceId]);
 
   const fitTerminal = useCallback(() => {
@@ -20,7 +20,13 @@ import {
   Repo,
   RepoWithTargetBranch,
   CreateProject,
+  ImportProject,
   CreateProjectRepo,
+  ProjectRepoMergeSettings,
+  RepoVerifySettings,
+  MergeQueueEntry,
+  MergeVerification,
+  ConflictResolution,
   UpdateRepo,
   SearchMode,
   SearchResult,
@@ -29,9 +35,36 @@ import {
   Tag,
   TagSearchParams,
   TaskApprovalWithUser,
+  TaskDependencies,
+  CreateTaskDependency,
+  TaskAutoStart,
+  UpsertTaskAutoStart,
   TaskWithAttemptStatus,
   UpdateProject,
   UpdateTask,
+  WorkflowDefinition,
+  ProjectBudgetStatus,
+  UpsertProjectBudget,
+  ExecutionProcessStatusReason,
+  ExecutionTokenUsage,
+  ExecutionQueueEntry,
+  QueuedExecution,
+  QueuedMerge,
+  UpdateQueuePriority,
+  ProjectAccess,
+  ProjectMember,
+  ProjectMemberWithUser,
+  SetProjectMemberRole,
+  SetUserAdmin,
+  AuditAction,
+  AuditEventPage,
+  SearchHit,
+  ToolApproval,
+  ApprovalPolicy,
+  UpsertApprovalPolicy,
+  AuditSource,
+  TokenUsageRollup,
+  UsageGroupBy,
   UpdateTag,
   UserSystemInfo,
   McpServerQuery,
@@ -78,9 +111,16 @@ import {
   QueueStatus,
   PrCommentsResponse,
   MergeTaskAttemptRequest,
+  MergeWorkspaceRequest,
+  MergedRepo,
+  WorkspaceMergeError,
+  CreateWorkspacePrsRequest,
+  WorkspacePr,
+  WorkspacePrError,
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
+  ResolveConflictsRequest,
   Session,
   SessionWithInitiator,
   Workspace,
@@ -120,7 +160,7 @@ export class ApiError<E = unknown> extends Error {
 const AUTH_TOKEN_KEY = 'vk_auth_token';
 
 // Get auth token from localStorage
-function getAuthToken(): string | null {
+export function getAuthToken(): string | null {
   if (typeof window === 'undefined') return null;
   return localStorage.getItem(AUTH_TOKEN_KEY);
 }
@@ -289,6 +329,74 @@ export const projectsApi = {
     return handleApiResponse<void>(response);
   },
 
+  /** The whole project, history included, as an archive file */
+  exportArchive: async (id: string): Promise<Blob> => {
+    const response = await makeRequest(`/api/projects/${id}/export`);
+    if (!response.ok) {
+      const errorText = await response.text();
+      throw new ApiError(
+        `Failed to export project: ${errorText}`,
+        response.status,
+        response
+      );
+    }
+    return response.blob();
+  },
+
+  importArchive: async (data: ImportProject): Promise<Project> => {
+    const response = await makeRequest('/api/projects/import', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Project>(response);
+  },
+
+  getWorkflow: async (id: string): Promise<WorkflowDefinition> => {
+    const response = await makeRequest(`/api/projects/${id}/workflow`);
+    return handleApiResponse<WorkflowDefinition>(response);
+  },
+
+  updateWorkflow: async (
+    id: string,
+    data: WorkflowDefinition
+  ): Promise<WorkflowDefinition> => {
+    const response = await makeRequest(`/api/projects/${id}/workflow`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<WorkflowDefinition>(response);
+  },
+
+  resetWorkflow: async (id: string): Promise<WorkflowDefinition> => {
+    const response = await makeRequest(`/api/projects/${id}/workflow`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<WorkflowDefinition>(response);
+  },
+
+  getBudget: async (id: string): Promise<ProjectBudgetStatus | null> => {
+    const response = await makeRequest(`/api/projects/${id}/budget`);
+    return handleApiResponse<ProjectBudgetStatus | null>(response);
+  },
+
+  updateBudget: async (
+    id: string,
+    data: UpsertProjectBudget
+  ): Promise<ProjectBudgetStatus | null> => {
+    const response = await makeRequest(`/api/projects/${id}/budget`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectBudgetStatus | null>(response);
+  },
+
+  deleteBudget: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${id}/budget`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
   searchFiles: async (
     id: string,
     query: string,
@@ -336,6 +444,31 @@ export const projectsApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getRepositoryMergeSettings: async (
+    projectId: string,
+    repoId: string
+  ): Promise<ProjectRepoMergeSettings> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/repositories/${repoId}/merge-settings`
+    );
+    return handleApiResponse<ProjectRepoMergeSettings>(response);
+  },
+
+  updateRepositoryMergeSettings: async (
+    projectId: string,
+    repoId: string,
+    data: ProjectRepoMergeSettings
+  ): Promise<ProjectRepoMergeSettings> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/repositories/${repoId}/merge-settings`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectRepoMergeSettings>(response);
+  },
 };
 
 // Task Management APIs
@@ -426,6 +559,51 @@ export const tasksApi = {
     return handleApiResponse<void>(response);
   },
 
+  getDependencies: async (taskId: string): Promise<TaskDependencies> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`);
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  addDependency: async (
+    taskId: string,
+    data: CreateTaskDependency
+  ): Promise<TaskDependencies> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  removeDependency: async (
+    taskId: string,
+    blockedByTaskId: string
+  ): Promise<TaskDependencies> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/dependencies/${blockedByTaskId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  setAutoStart: async (
+    taskId: string,
+    data: UpsertTaskAutoStart
+  ): Promise<TaskAutoStart> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/auto-start`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskAutoStart>(response);
+  },
+
+  deleteAutoStart: async (taskId: string): Promise<void> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/auto-start`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
   linkToLocal: async (data: SharedTaskDetails): Promise<Task | null> => {
     const response = await makeRequest(`/api/shared-tasks/link-to-local`, {
       method: 'POST',
@@ -626,6 +804,53 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  mergeWorkspace: async (
+    attemptId: string,
+    data: MergeWorkspaceRequest
+  ): Promise<Result<MergedRepo[], WorkspaceMergeError>> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge/all`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<MergedRepo[], WorkspaceMergeError>(
+      response
+    );
+  },
+
+  enqueueMerge: async (
+    attemptId: string,
+    data: MergeTaskAttemptRequest
+  ): Promise<MergeQueueEntry> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-queue`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<MergeQueueEntry>(response);
+  },
+
+  getMergeQueue: async (attemptId: string): Promise<MergeQueueEntry[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-queue`
+    );
+    return handleApiResponse<MergeQueueEntry[]>(response);
+  },
+
+  dequeueMerge: async (attemptId: string, repoId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-queue/${repoId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
   push: async (
     attemptId: string,
     data: PushTaskAttemptRequest
@@ -710,6 +935,29 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  resolveConflicts: async (
+    attemptId: string,
+    data: ResolveConflictsRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
+  getConflictResolutions: async (
+    attemptId: string
+  ): Promise<ConflictResolution[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolutions`
+    );
+    return handleApiResponse<ConflictResolution[]>(response);
+  },
+
   createPR: async (
     attemptId: string,
     data: CreatePrApiRequest
@@ -721,6 +969,22 @@ export const attemptsApi = {
     return handleApiResponseAsResult<string, PrError>(response);
   },
 
+  createWorkspacePRs: async (
+    attemptId: string,
+    data: CreateWorkspacePrsRequest
+  ): Promise<Result<WorkspacePr[], WorkspacePrError>> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr/all`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<WorkspacePr[], WorkspacePrError>(
+      response
+    );
+  },
+
   startDevServer: async (attemptId: string): Promise<ExecutionProcess[]> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/start-dev-server`,
@@ -769,6 +1033,27 @@ export const attemptsApi = {
     );
   },
 
+  runVerifyScript: async (
+    attemptId: string
+  ): Promise<Result<ExecutionProcess[], RunScriptError>> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/run-verify-script`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponseAsResult<ExecutionProcess[], RunScriptError>(
+      response
+    );
+  },
+
+  getVerifications: async (attemptId: string): Promise<MergeVerification[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/verifications`
+    );
+    return handleApiResponse<MergeVerification[]>(response);
+  },
+
   getPrComments: async (
     attemptId: string,
     repoId: string
@@ -957,6 +1242,216 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getTokenUsage: async (processId: string): Promise<ExecutionTokenUsage[]> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/token-usage`
+    );
+    return handleApiResponse<ExecutionTokenUsage[]>(response);
+  },
+
+  getStatusReason: async (
+    processId: string
+  ): Promise<ExecutionProcessStatusReason | null> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/status-reason`
+    );
+    return handleApiResponse<ExecutionProcessStatusReason | null>(response);
+  },
+
+  getApprovals: async (processId: string): Promise<ToolApproval[]> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/approvals`
+    );
+    return handleApiResponse<ToolApproval[]>(response);
+  },
+};
+
+// Token usage APIs
+export const usageApi = {
+  getRollup: async (query: {
+    group_by?: UsageGroupBy;
+    project_id?: string;
+    task_id?: string;
+    user_id?: string;
+    since?: string;
+    until?: string;
+  }): Promise<TokenUsageRollup[]> => {
+    const params = new URLSearchParams();
+    for (const [key, value] of Object.entries(query)) {
+      if (value) params.set(key, value);
+    }
+    const response = await makeRequest(`/api/usage?${params.toString()}`);
+    return handleApiResponse<TokenUsageRollup[]>(response);
+  },
+};
+
+// Audit log APIs
+export type AuditQuery = {
+  actor_user_id?: string;
+  project_id?: string;
+  task_id?: string;
+  workspace_id?: string;
+  action?: AuditAction;
+  source?: AuditSource;
+  since?: string;
+  until?: string;
+};
+
+const auditParams = (query: Record<string, string | number | undefined>) => {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(query)) {
+    if (value !== undefined && value !== '') params.set(key, String(value));
+  }
+  return params.toString();
+};
+
+export const auditApi = {
+  list: async (
+    query: AuditQuery & { limit?: number; offset?: number }
+  ): Promise<AuditEventPage> => {
+    const response = await makeRequest(`/api/audit?${auditParams(query)}`);
+    return handleApiResponse<AuditEventPage>(response);
+  },
+
+  /** All matching events as JSON Lines, for compliance reviews */
+  export: async (query: AuditQuery): Promise<Blob> => {
+    const response = await makeRequest(
+      `/api/audit/export?${auditParams(query)}`
+    );
+    if (!response.ok) {
+      const errorText = await response.text();
+      throw new ApiError(
+        `Failed to export audit log: ${errorText}`,
+        response.status,
+        response
+      );
+    }
+    return response.blob();
+  },
+};
+
+// Search API
+export const searchApi = {
+  /** Tasks, agent conversation entries and review comments matching `q` */
+  search: async (
+    q: string,
+    options: { projectId?: string; limit?: number } = {}
+  ): Promise<SearchHit[]> => {
+    const params = new URLSearchParams({ q });
+    if (options.projectId) params.set('project_id', options.projectId);
+    if (options.limit !== undefined) params.set('limit', String(options.limit));
+    const response = await makeRequest(`/api/search?${params.toString()}`);
+    return handleApiResponse<SearchHit[]>(response);
+  },
+};
+
+// Approval Policy APIs
+export const approvalPoliciesApi = {
+  /** All policies, or only the project's when `projectId` is given */
+  list: async (projectId?: string): Promise<ApprovalPolicy[]> => {
+    const query = projectId ? `?project_id=${encodeURIComponent(projectId)}` : '';
+    const response = await makeRequest(`/api/approval-policies${query}`);
+    return handleApiResponse<ApprovalPolicy[]>(response);
+  },
+
+  upsert: async (data: UpsertApprovalPolicy): Promise<ApprovalPolicy> => {
+    const response = await makeRequest('/api/approval-policies', {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ApprovalPolicy>(response);
+  },
+
+  delete: async (policyId: string): Promise<void> => {
+    const response = await makeRequest(`/api/approval-policies/${policyId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Execution Queue APIs
+export const executionQueueApi = {
+  list: async (projectId?: string): Promise<QueuedExecution[]> => {
+    const query = projectId ? `?project_id=${encodeURIComponent(projectId)}` : '';
+    const response = await makeRequest(`/api/execution-queue${query}`);
+    return handleApiResponse<QueuedExecution[]>(response);
+  },
+
+  setPriority: async (
+    executionProcessId: string,
+    data: UpdateQueuePriority
+  ): Promise<ExecutionQueueEntry> => {
+    const response = await makeRequest(
+      `/api/execution-queue/${executionProcessId}/priority`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionQueueEntry>(response);
+  },
+};
+
+export const mergeQueueApi = {
+  list: async (projectId?: string): Promise<QueuedMerge[]> => {
+    const query = projectId ? `?project_id=${encodeURIComponent(projectId)}` : '';
+    const response = await makeRequest(`/api/merge-queue${query}`);
+    return handleApiResponse<QueuedMerge[]>(response);
+  },
+};
+
+// Project membership and roles
+export const projectMembersApi = {
+  list: async (projectId: string): Promise<ProjectMemberWithUser[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/members`);
+    return handleApiResponse<ProjectMemberWithUser[]>(response);
+  },
+
+  getMyAccess: async (projectId: string): Promise<ProjectAccess> => {
+    const response = await makeRequest(`/api/projects/${projectId}/access`);
+    return handleApiResponse<ProjectAccess>(response);
+  },
+
+  setRole: async (
+    projectId: string,
+    userId: string,
+    data: SetProjectMemberRole
+  ): Promise<ProjectMember> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/members/${userId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectMember>(response);
+  },
+
+  remove: async (projectId: string, userId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/members/${userId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  listAdmins: async (): Promise<string[]> => {
+    const response = await makeRequest('/api/users/admins');
+    return handleApiResponse<string[]>(response);
+  },
+
+  setAdmin: async (
+    userId: string,
+    data: SetUserAdmin
+  ): Promise<SetUserAdmin> => {
+    const response = await makeRequest(`/api/users/${userId}/admin`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<SetUserAdmin>(response);
+  },
 };
 
 // File System APIs
@@ -998,6 +1493,11 @@ export const repoApi = {
     return handleApiResponse<Repo>(response);
   },
 
+  getVerifySettings: async (repoId: string): Promise<RepoVerifySettings> => {
+    const response = await makeRequest(`/api/repos/${repoId}/verify-settings`);
+    return handleApiResponse<RepoVerifySettings>(response);
+  },
+
   register: async (data: {
     path: string;
     display_name?: string;
@@ -1279,6 +1779,20 @@ export const approvalsApi = {
 
     return handleApiResponse<ApprovalStatus>(res);
   },
+
+  /** Approvals the user answered, newest first */
+  listForUser: async (
+    userId: string,
+    page: { limit?: number; offset?: number } = {}
+  ): Promise<ToolApproval[]> => {
+    const params = new URLSearchParams();
+    if (page.limit !== undefined) params.set('limit', String(page.limit));
+    if (page.offset !== undefined) params.set('offset', String(page.offset));
+    const response = await makeRequest(
+      `/api/users/${userId}/approvals?${params}`
+    );
+    return handleApiResponse<ToolApproval[]>(response);
+  },
 };
 
 // OAuth API
@@ -1488,7 +2002,7 @@ export const queueApi = {
   },
 
   /**
-   * Cancel a queued follow-up message
+   * Cancel every queued follow-up message
    */
   cancel: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
@@ -1497,6 +2011,54 @@ export const queueApi = {
     return handleApiResponse<QueueStatus>(response);
   },
 
+  /**
+   * Edit a queued message in place
+   */
+  update: async (
+    sessionId: string,
+    messageId: string,
+    data: { message: string; executor_profile_id: ExecutorProfileId }
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Remove a single queued message
+   */
+  remove: async (sessionId: string, messageId: string): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Set the execution order of the queued messages
+   */
+  reorder: async (
+    sessionId: string,
+    messageIds: string[]
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/reorder`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ message_ids: messageIds }),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
   /**
    * Get the current queue status for a session
    */
@@ -1504,6 +2066,9 @@ export const queueApi = {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`);
     return handleApiResponse<QueueStatus>(response);
   },
+
+  getStreamUrl: (sessionId: string): string =>
+    `/api/sessions/${sessionId}/queue/ws`,
 };
 
 // Local Auth API types
@@ -14,7 +14,19 @@ export type UserData = { user_id: string, first_name: string | null, last_name:
 
 export type User = { id: string, github_id: number, username: string, email: string | null, display_name: string | null, avatar_url: string | null, created_at: Date, updated_at: Date, };
 
-export type ClaudeOAuthTokenStatus = { has_token: boolean, token_hint: string | null, created_at: Date | null, expires_at: Date | null, last_used_at: Date | null, is_expired: boolean, };
+export type ClaudeOAuthTokenStatus = { has_token: boolean, token_hint: string | null, created_at: Date | null, expires_at: Date | null, last_used_at: Date | null, is_expired: boolean, 
+/**
+ * When the token becomes usable again after hitting a usage limit
+ */
+cooldown_until: Date | null, is_invalid: boolean, invalid_reason: string | null, 
+/**
+ * Number of executions the token has been handed out for
+ */
+use_count: number, 
+/**
+ * Number of times the token hit a usage limit
+ */
+rate_limited_count: number, last_rate_limited_at: Date | null, };
 
 export type UserTokenStatus = { user_id: string, username: string, display_name: string | null, avatar_url: string | null, token_status: ClaudeOAuthTokenStatus, };
 
@@ -24,6 +36,16 @@ export type UpsertClaudeTokenRequest = {
  */
 token: string, };
 
+export type ReencryptClaudeTokensResponse = { 
+/**
+ * Number of stored tokens that were rewritten with the current key
+ */
+reencrypted: number, 
+/**
+ * Tokens that could not be decrypted and were left as they are
+ */
+failed_token_ids: Array<string>, };
+
 export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, creator_user_id: string | null, min_approvals_required: number, 
 /**
  * Hex color for the project header (e.g., "#FF5733")
@@ -54,14 +76,231 @@ score: number, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
+export type ImportProject = { 
+/**
+ * A file written by the export endpoint
+ */
+archive: unknown, 
+/**
+ * Path on this machine for archived repos, keyed by their id in the archive.
+ * Repos left out are expected at their archived path.
+ */
+repo_paths: { [key in string]?: string }, };
+
 export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, };
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, verify_script?: string | null, };
+
+export type RepoVerifySettings = { 
+/**
+ * Script that must pass against the merge of a workspace branch into its target
+ * branch before the workspace can be merged or a pull request opened
+ */
+verify_script: string | null, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
+export type ProjectRepoMergeSettings = { 
+/**
+ * Used by local merges that don't ask for a strategy
+ */
+merge_strategy: MergeStrategy, };
+
+export type WorkflowGuard = "approvals" | "no_hold" | "conversations_resolved";
+
+export type WorkflowEvent = "agent_started" | "agent_finished" | "approval_requested" | "approval_responded" | "pr_opened" | "checks_pending" | "checks_passed" | "checks_failed" | "deploy_pending" | "deploy_failed" | "pr_merged" | "branch_merged";
+
+export type WorkflowTransition = { 
+/**
+ * Statuses the transition may start from; empty means any status
+ */
+from: Array<TaskStatus>, to: TaskStatus, guards: Array<WorkflowGuard>, };
+
+export type WorkflowAutomation = { event: WorkflowEvent, 
+/**
+ * Statuses the automation applies to; empty means any status
+ */
+from: Array<TaskStatus>, to: TaskStatus, };
+
+export type WorkflowDefinition = { transitions: Array<WorkflowTransition>, automations: Array<WorkflowAutomation>, };
+
+export type BudgetPeriod = "total" | "monthly";
+
+export type ProjectBudget = { project_id: string, max_tokens: number | null, max_cost_usd: number | null, period: BudgetPeriod, created_at: Date, updated_at: Date, };
+
+export type UpsertProjectBudget = { max_tokens: number | null, max_cost_usd: number | null, period: BudgetPeriod, };
+
+export type ProjectBudgetStatus = { budget: ProjectBudget, 
+/**
+ * Usage in the budget's current period
+ */
+usage: TokenUsageRollup, 
+/**
+ * Whether new executions are currently refused
+ */
+exceeded: boolean, };
+
+export type ExecutionQueueEntry = { execution_process_id: string, 
+/**
+ * Higher priorities start first; equal priorities start in arrival order
+ */
+priority: number, executor: string | null, project_id: string, user_id: string | null, created_at: Date, };
+
+export type QueuedExecution = { session_id: string, workspace_id: string, task_id: string, task_title: string, execution_process_id: string, 
+/**
+ * Higher priorities start first; equal priorities start in arrival order
+ */
+priority: number, executor: string | null, project_id: string, user_id: string | null, created_at: Date, };
+
+export type UpdateQueuePriority = { priority: number, };
+
+export type ProjectRole = "viewer" | "contributor" | "maintainer" | "owner";
+
+export type ProjectMember = { project_id: string, user_id: string, role: ProjectRole, created_at: Date, updated_at: Date, };
+
+export type ProjectMemberWithUser = { username: string, display_name: string | null, avatar_url: string | null, project_id: string, user_id: string, role: ProjectRole, created_at: Date, updated_at: Date, };
+
+export type SetProjectMemberRole = { role: ProjectRole, };
+
+export type ProjectAccess = { 
+/**
+ * The caller's role, or null if they are not a member
+ */
+role: ProjectRole | null, 
+/**
+ * Server admins pass every role check and manage memberships
+ */
+is_admin: boolean, };
+
+export type SetUserAdmin = { is_admin: boolean, };
+
+export type AuditSource = "api" | "mcp";
+
+export type AuditAction = "project_created" | "project_updated" | "project_deleted" | "project_repository_added" | "project_repository_removed" | "project_repository_updated" | "project_workflow_updated" | "project_workflow_reset" | "project_budget_updated" | "project_budget_deleted" | "project_exported" | "project_imported" | "task_created" | "task_updated" | "task_deleted" | "task_hold_placed" | "task_hold_released" | "task_approved" | "task_unapproved" | "workspace_created" | "workspace_updated" | "workspace_deleted" | "workspace_merged" | "merge_queued" | "workspace_rebased" | "branch_pushed" | "branch_force_pushed" | "branch_renamed" | "target_branch_changed" | "execution_stopped" | "claude_token_upserted" | "claude_token_deleted" | "claude_token_key_rotated" | "approval_policy_updated" | "approval_policy_deleted";
+
+export type AuditEvent = { id: string, 
+/**
+ * `null` on single-user servers and for actions taken by the server itself
+ */
+actor_user_id: string | null, 
+/**
+ * Self-reported by the caller and not verified
+ */
+source: AuditSource, action: AuditAction, project_id: string | null, task_id: string | null, workspace_id: string | null, 
+/**
+ * The affected object when it is not the project, task or workspace itself,
+ * e.g. a repository or the user whose hold was released
+ */
+target_id: string | null, details: JsonValue, created_at: Date, };
+
+export type AuditEventPage = { events: Array<AuditEvent>, 
+/**
+ * Number of events matching the filter across all pages
+ */
+total: number, limit: number, offset: number, };
+
+export type SearchHitKind = "task" | "conversation_entry" | "review_message";
+
+export type SearchHit = { kind: SearchHitKind, project_id: string, task_id: string, task_title: string, workspace_id: string | null, session_id: string | null, execution_process_id: string | null, 
+/**
+ * Index of the entry in the execution's normalized conversation
+ */
+entry_index: number | null, 
+/**
+ * `type` of the matched conversation entry, e.g. `assistant_message`
+ */
+entry_type: string | null, review_conversation_id: string | null, 
+/**
+ * The matched text around the search terms
+ */
+snippet: string, 
+/**
+ * Lower is a better match
+ */
+rank: number, };
+
+export type ApprovalDecision = "allow" | "deny" | "ask";
+
+export type ApprovalRule = { 
+/**
+ * Shown in the conversation next to each decision the rule makes
+ */
+name: string, decision: ApprovalDecision, 
+/**
+ * Regex the whole tool name must match, case-insensitively (e.g. `bash|shell`)
+ */
+tool_name: string | null, 
+/**
+ * JSON pointer into the tool input that `input_pattern` is matched against
+ * (e.g. `/command`). When unset, every string in the input is searched.
+ */
+input_field: string | null, 
+/**
+ * Regex searched for in the tool input (e.g. `rm\s+-rf` or `\.env\b`). Allow rules
+ * must match a whole value instead (e.g. `cargo test( [\w-]+)*`), and never approve
+ * one containing shell operators such as `;`, `|` or `$(`.
+ */
+input_pattern: string | null, 
+/**
+ * Reason given to the agent when the rule denies a call
+ */
+reason: string | null, };
+
+export type ApprovalPolicyDefinition = { rules: Array<ApprovalRule>, default_decision: ApprovalDecision, };
+
+export type ApprovalPolicy = { id: string, project_id: string | null, 
+/**
+ * Executor the policy applies to (e.g. `CLAUDE_CODE`)
+ */
+executor: string | null, 
+/**
+ * Executor profile variant (e.g. `PLAN`); requires `executor`
+ */
+variant: string | null, definition: ApprovalPolicyDefinition, created_at: Date, updated_at: Date, };
+
+export type UpsertApprovalPolicy = { project_id: string | null, executor: string | null, variant: string | null, definition: ApprovalPolicyDefinition, };
+
+export type ToolApprovalStatus = "pending" | "approved" | "denied" | "timed_out" | "expired";
+
+export type ToolApproval = { id: string, execution_process_id: string, tool_name: string, tool_call_id: string, tool_input: JsonValue, status: ToolApprovalStatus, reason: string | null, 
+/**
+ * The user who answered; `null` for policy decisions, timeouts and single-user
+ * servers
+ */
+responded_by_user_id: string | null, 
+/**
+ * The approval policy rule that answered without asking anyone
+ */
+policy_rule: string | null, created_at: Date, timeout_at: Date, responded_at: Date | null, 
+/**
+ * Time from the request to its answer
+ */
+latency_ms: number | null, };
+
+export type ExecutionTokenUsage = { id: string, execution_process_id: string, model: string, input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_creation_tokens: number, 
+/**
+ * Cost reported by the agent; `null` when the agent does not report costs
+ */
+cost_usd: number | null, created_at: Date, updated_at: Date, };
+
+export type UsageGroupBy = "model" | "task" | "project" | "user" | "day";
+
+export type TokenUsageRollup = { 
+/**
+ * Task, project or user id, depending on the grouping
+ */
+group_id: string | null, 
+/**
+ * Model name, task title, project name, username or day (`YYYY-MM-DD`)
+ */
+label: string | null, execution_count: number, input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_creation_tokens: number, total_tokens: number, 
+/**
+ * Sum of the costs agents reported; `null` when none reported a cost
+ */
+cost_usd: number | null, };
+
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
@@ -94,7 +333,11 @@ export type UpdateTask = { title: string | null, description: string | null, par
 /**
  * Set to Some(user_id) to assign, or None to not change, or Some(null) to unassign
  */
-assignee_user_id: string | null | null, };
+assignee_user_id: string | null | null, 
+/**
+ * Move the task to this status; must be allowed by the project workflow
+ */
+status: TaskStatus | null, };
 
 export type TaskApproval = { id: string, task_id: string, user_id: string, created_at: Date, };
 
@@ -102,6 +345,18 @@ export type TaskApprovalUser = { id: string, username: string, avatar_url: strin
 
 export type TaskApprovalWithUser = { user: TaskApprovalUser, id: string, task_id: string, user_id: string, created_at: Date, };
 
+export type TaskDependency = { task_id: string, blocked_by_task_id: string, created_at: Date, };
+
+export type CreateTaskDependency = { blocked_by_task_id: string, };
+
+export type DependencyTask = { id: string, title: string, status: TaskStatus, };
+
+export type TaskAutoStart = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, requested_by_user_id: string | null, created_at: Date, updated_at: Date, };
+
+export type UpsertTaskAutoStart = { executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, };
+
+export type TaskDependencies = { blocked_by: Array<DependencyTask>, blocking: Array<DependencyTask>, auto_start: TaskAutoStart | null, };
+
 export type PlaceHoldRequest = { 
 /**
  * The comment explaining why the hold is being placed
@@ -154,9 +409,14 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed" }
+
+/**
+ * Why an execution failed or was stopped, when its status and exit code alone don't say
+ */
+export type ExecutionProcessStatusReason = "memory_limit_exceeded" | "pids_limit_exceeded" | "max_runtime_exceeded" | "idle_timeout";
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "verifyscript";
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
@@ -166,11 +426,70 @@ export type DirectMerge = { id: string, workspace_id: string, repo_id: string, m
 
 export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
 
+export type MergeVerification = { execution_process_id: string, workspace_id: string, repo_id: string, 
+/**
+ * Head of the workspace branch that was merged
+ */
+task_commit: string, target_branch: string, 
+/**
+ * Head of the target branch it was merged into
+ */
+target_commit: string, 
+/**
+ * Status of the script's execution process
+ */
+status: ExecutionProcessStatus, exit_code: number | null, created_at: string, };
+
+export type MergeQueueStatus = "queued" | "verifying" | "conflicted" | "failed";
+
+export type MergeQueueEntry = { id: string, workspace_id: string, repo_id: string, target_branch: string, 
+/**
+ * Defaults to the repository's merge strategy in the project
+ */
+merge_strategy: MergeStrategy | null, status: MergeQueueStatus, error_message: string | null, 
+/**
+ * The agent run resolving the conflicts of a `conflicted` entry
+ */
+resolution_execution_process_id: string | null, 
+/**
+ * Lower positions merge first
+ */
+position: number, enqueued_at: string, updated_at: string, };
+
+export type QueuedMerge = { project_id: string, task_id: string, task_title: string, branch: string, id: string, workspace_id: string, repo_id: string, target_branch: string, 
+/**
+ * Defaults to the repository's merge strategy in the project
+ */
+merge_strategy: MergeStrategy | null, status: MergeQueueStatus, error_message: string | null, 
+/**
+ * The agent run resolving the conflicts of a `conflicted` entry
+ */
+resolution_execution_process_id: string | null, 
+/**
+ * Lower positions merge first
+ */
+position: number, enqueued_at: string, updated_at: string, };
+
+export type ConflictResolutionStatus = "running" | "resolved" | "conflicted" | "rolled_back";
+
+export type ConflictResolution = { execution_process_id: string, workspace_id: string, repo_id: string, 
+/**
+ * The files in conflict when the run started
+ */
+conflicted_files: Array<string>, 
+/**
+ * The worktree's HEAD when the run started; `None` for runs from before it was
+ * recorded
+ */
+start_head: string | null, status: ConflictResolutionStatus, error_message: string | null, created_at: string, updated_at: string, };
+
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
+export type MergeStrategy = "squash" | "merge_commit" | "rebase" | "fast_forward";
+
 export type PullRequestInfo = { number: number, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
-export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
+export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" } | { "status": "auto_approved", rule: string, };
 
 export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue, tool_call_id: string, };
 
@@ -284,10 +603,28 @@ export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: st
 
 export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
 
-export type MergeTaskAttemptRequest = { repo_id: string, };
+export type MergeTaskAttemptRequest = { repo_id: string, 
+/**
+ * Defaults to the repository's merge strategy in the project
+ */
+merge_strategy?: MergeStrategy, };
 
 export type PushTaskAttemptRequest = { repo_id: string, };
 
+export type MergeWorkspaceRequest = { 
+/**
+ * Defaults to each repository's merge strategy in the project
+ */
+merge_strategy?: MergeStrategy, };
+
+export type WorkspaceMergeError = { "type": "no_changes" } | { "type": "preflight_failed", problems: Array<PreflightProblem>, } | { "type": "merge_failed", repo_id: string, repo_name: string, message: string, rolled_back: Array<string>, not_rolled_back: Array<string>, };
+
+export type PreflightProblem = { repo_id: string, repo_name: string, message: string, };
+
+export type MergedRepo = { repo_id: string, target_branch: string, merge_commit: string, merge_strategy: MergeStrategy, };
+
+export type WorkspacePr = { repo_id: string, repo_name: string, pr_number: number, pr_url: string, };
+
 export type RenameBranchRequest = { new_branch_name: string, };
 
 export type RenameBranchResponse = { branch: string, };
@@ -300,6 +637,8 @@ export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id:
 
 export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
+export type CreateWorkspacePrsRequest = { title: string, body: string | null, draft: boolean | null, };
+
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: number, hash: string, created_at: string, updated_at: string, };
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: number | null, format: string | null, proxy_url: string | null, };
@@ -318,12 +657,16 @@ export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: strin
 
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
 
 export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
 
+export type WorkspacePrError = { "type": "no_changes" } | { "type": "preflight_failed", problems: Array<PreflightProblem>, } | { "type": "pr_failed", repo_id: string, repo_name: string, error: PrError, opened: Array<WorkspacePr>, };
+
 export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
 
 export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: number | null, pr_status: MergeStatus | null, };
@@ -338,7 +681,7 @@ export type GetPrCommentsQuery = { repo_id: string, };
 
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: number, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: number | null, side: string | null, diff_hunk: string | null, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "git_lab" | "bitbucket" | "unknown";
 
 export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -444,10 +787,122 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, language: UiLanguage, git_branch_prefix: string, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, send_message_shortcut: SendMessageShortcut, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, language: UiLanguage, git_branch_prefix: string, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, send_message_shortcut: SendMessageShortcut, 
+/**
+ * Path to the key file used to encrypt stored Claude OAuth tokens.
+ * `VK_TOKEN_ENCRYPTION_KEY` takes precedence when set.
+ */
+token_encryption_key_file: string | null, notification_channels: Array<NotificationChannelConfig>, execution_limits: ExecutionLimits, git_hosts: GitHostsConfig, 
+/**
+ * Sandboxing and resource limits for agent, setup and dev server processes
+ */
+sandbox: SandboxConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
+export type NotificationChannelConfig = { name: string, enabled: boolean, channel: NotificationChannelKind, 
+/**
+ * Events delivered to this channel; empty means every event
+ */
+events: Array<NotificationEvent>, 
+/**
+ * Only deliver events for tasks created by or assigned to these usernames;
+ * empty means every task
+ */
+users: Array<string>, };
+
+export type NotificationChannelKind = { "type": "webhook", url: string, headers: { [key in string]?: string }, } | { "type": "slack", webhook_url: string, } | { "type": "email", smtp_host: string, smtp_port: number, security: SmtpSecurity, username: string | null, 
+/**
+ * Name of the environment variable holding the SMTP password
+ */
+password_env: string | null, from: string, 
+/**
+ * Fixed recipients, in addition to routed users
+ */
+to: Array<string>, 
+/**
+ * Also email the task's creator and assignee, using their address from the users table
+ */
+notify_task_users: boolean, };
+
+export type NotificationEvent = "execution_completed" | "execution_failed" | "approval_pending" | "review_conversation_opened" | "pr_merged";
+
+export type SmtpSecurity = "tls" | "start_tls" | "none";
+
+export type ExecutionLimits = { 
+/**
+ * Across all projects
+ */
+max_concurrent: number | null, 
+/**
+ * Per executor, e.g. `{ "CODEX": 2 }`
+ */
+per_executor: { [key in BaseCodingAgent]?: number }, 
+/**
+ * Within each project
+ */
+per_project: number | null, 
+/**
+ * For each user who started the execution
+ */
+per_user: number | null, };
+
+export type GitHostsConfig = { 
+/**
+ * Hostnames of self-hosted GitLab instances, e.g. `gitlab.example.com`
+ */
+gitlab_hosts: Array<string>, 
+/**
+ * Bitbucket account the token belongs to. Without it the token is sent as a bearer
+ * token, as for repository and workspace access tokens.
+ */
+bitbucket_username: string | null, 
+/**
+ * Bitbucket API token or app password. Never sent to clients, which see
+ * `has_bitbucket_token` instead.
+ */
+bitbucket_token: string | null, 
+/**
+ * Whether a Bitbucket token is stored. Only set in responses; sending it back with
+ * `bitbucket_token: null` keeps the stored token.
+ */
+has_bitbucket_token: boolean, };
+
+export type SandboxConfig = { 
+/**
+ * Run agent, setup, cleanup and dev server processes in the sandbox
+ */
+enabled: boolean, 
+/**
+ * Network access for sandboxed processes; executor profiles can override it
+ */
+network: SandboxNetwork, 
+/**
+ * Memory limit per execution in MiB
+ */
+memory_max_mb: number | null, 
+/**
+ * CPU limit per execution as a percentage of one core, e.g. 200 for two cores
+ */
+cpu_max_percent: number | null, 
+/**
+ * Maximum number of processes and threads per execution
+ */
+pids_max: number | null, 
+/**
+ * cgroup v2 directory delegated to the server, under which each execution gets
+ * its own cgroup. Only used when a limit is set.
+ */
+cgroup_parent: string | null, 
+/**
+ * Paths outside the workspace that sandboxed processes may write to, such as
+ * the directories agents keep their sessions and caches in. `~` is expanded.
+ * Paths in the server's data directory are left read-only.
+ */
+writable_paths: Array<string>, };
+
+export type SandboxNetwork = "allow" | "deny";
+
 export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
 
 export type EditorConfig = { editor_type: EditorType, custom_command: string | null, remote_ssh_host: string | null, remote_ssh_user: string | null, };
@@ -464,11 +919,15 @@ export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
-export type QueuedMessage = { 
+export type QueuedMessage = { id: string, 
 /**
  * The session this message is queued for
  */
 session_id: string, 
+/**
+ * Order within the session's queue; lower runs first
+ */
+position: number, 
 /**
  * The follow-up data (message + variant)
  */
@@ -476,9 +935,17 @@ data: DraftFollowUpData,
 /**
  * Timestamp when the message was queued
  */
-queued_at: string, };
+queued_at: string, updated_at: string, };
 
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+export type QueueStatus = { "status": "empty" } | { "status": "queued", 
+/**
+ * The message that will run next
+ */
+message: QueuedMessage, 
+/**
+ * Every queued message, in execution order
+ */
+messages: Array<QueuedMessage>, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
@@ -488,12 +955,13 @@ export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest | { "type": "ReviewRequest" } & ReviewRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "VerifyScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
  * Optional relative path to execute the script in (relative to container_ref).
- * If None, uses the container_ref directory directly.
+ * If None, uses the container_ref directory directly. An absolute path is used
+ * as is.
  */
 working_dir: string | null, };
 
@@ -525,19 +993,33 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
+export type RetryPolicy = { 
+/**
+ * Runs per profile, counting the first; 1 moves straight on to the fallbacks
+ */
+max_attempts: number, 
+/**
+ * Delay before retrying on the same profile, doubled for each further retry
+ */
+backoff_seconds: number, 
+/**
+ * Profiles to try, in order, once a profile's attempts are used up
+ */
+fallback_profiles: Array<ExecutorProfileId>, };
+
 export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -549,19 +1031,19 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, variant?: string | null, mode?: string | null, 
 /**
  * Auto-approve agent actions
  */
-auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, sandbox_network?: SandboxNetwork | null, max_runtime_minutes?: number | null, max_idle_minutes?: number | null, continue_after_timeout?: boolean | null, max_timeout_continuations?: number | null, retry?: RetryPolicy | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
@@ -611,7 +1093,21 @@ export type NormalizedEntry = { timestamp: string | null, entry_type: Normalized
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo;
 
-export type TokenUsageInfo = { total_tokens: number, model_context_window: number, };
+export type TokenUsageInfo = { total_tokens: number, model_context_window: number, 
+/**
+ * Tokens consumed by the execution so far, per model. Empty when the agent does not report it.
+ */
+usage: Array<ModelTokenUsage>, };
+
+export type ModelTokenUsage = { model: string, 
+/**
+ * Input tokens that were neither read from nor written to the prompt cache
+ */
+input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_creation_tokens: number, 
+/**
+ * Cost reported by the agent, if it reports one
+ */
+cost_usd: number | null, };
 
 export type FileChange = { "action": "write", content: string, } | { "action": "delete" } | { "action": "rename", new_path: string, } | { "action": "edit", 
 /**
@@ -627,7 +1123,7 @@ export type ActionType = { "action": "file_read", path: string, } | { "action":
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 
-export type NormalizedEntryError = { "type": "setup_required" } | { "type": "other" };
+export type NormalizedEntryError = { "type": "setup_required" } | { "type": "usage_limit_reached", resets_at: number | null, } | { "type": "rate_limited" } | { "type": "authentication_failed" } | { "type": "other" };
 
 export type ToolResult = { type: ToolResultValueType, 
 /**
@@ -637,7 +1133,11 @@ value: JsonValue, };
 
 export type ToolResultValueType = { "type": "markdown" } | { "type": "json" };
 
-export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
+export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, 
+/**
+ * The approval policy rule that denied the call without asking anyone
+ */
+rule?: string, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
 
 export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
 